@@ -0,0 +1,56 @@
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::mpsc::{self, RecvTimeoutError};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+/// Debounced saves on a background thread: a burst of changes is written once,
+/// `delay` after the last of them.
+pub struct Autosave {
+    tx: mpsc::Sender<()>,
+    dirty: Arc<AtomicBool>,
+    /// Held while a save runs, so [`Autosave::take_pending`] waits for it.
+    saving: Arc<Mutex<()>>,
+}
+
+impl Autosave {
+    pub fn spawn(delay: Duration, save: impl Fn() + Send + 'static) -> Self {
+        let (tx, rx) = mpsc::channel::<()>();
+        let dirty = Arc::new(AtomicBool::new(false));
+        let saving = Arc::new(Mutex::new(()));
+
+        let (pending, lock) = (dirty.clone(), saving.clone());
+        std::thread::Builder::new()
+            .name("pigeon-autosave".to_string())
+            .spawn(move || {
+                while rx.recv().is_ok() {
+                    loop {
+                        match rx.recv_timeout(delay) {
+                            Ok(()) => continue,
+                            Err(RecvTimeoutError::Timeout) => break,
+                            Err(RecvTimeoutError::Disconnected) => return,
+                        }
+                    }
+                    let _guard = lock.lock().unwrap();
+                    if pending.swap(false, Ordering::SeqCst) {
+                        save();
+                    }
+                }
+            })
+            .expect("failed to spawn autosave thread");
+
+        Self { tx, dirty, saving }
+    }
+
+    /// Note a change; the save runs once changes stop for the delay.
+    pub fn schedule(&self) {
+        self.dirty.store(true, Ordering::SeqCst);
+        let _ = self.tx.send(());
+    }
+
+    /// Cancel the pending save, if any, and return whether there was one, so the
+    /// caller can save right away instead. Waits for a save already running.
+    pub fn take_pending(&self) -> bool {
+        let _guard = self.saving.lock().unwrap();
+        self.dirty.swap(false, Ordering::SeqCst)
+    }
+}
@@ -0,0 +1,61 @@
+use anyhow::{bail, Result};
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+
+/// Size of the little-endian `u64` length prefix on every returned buffer.
+const PREFIX_LEN: usize = std::mem::size_of::<u64>();
+
+/// Wire encoding for the buffer-based FFI functions, chosen per call.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Encoding {
+    Json,
+    /// MessagePack with named fields, so payloads keep the JSON field names.
+    MessagePack,
+}
+
+impl TryFrom<u32> for Encoding {
+    type Error = anyhow::Error;
+
+    fn try_from(value: u32) -> Result<Self> {
+        match value {
+            0 => Ok(Self::Json),
+            1 => Ok(Self::MessagePack),
+            other => bail!("unknown encoding: {other} (expected 0 = JSON, 1 = MessagePack)"),
+        }
+    }
+}
+
+impl Encoding {
+    pub fn decode<T: DeserializeOwned>(self, bytes: &[u8]) -> Result<T> {
+        Ok(match self {
+            Self::Json => serde_json::from_slice(bytes)?,
+            Self::MessagePack => rmp_serde::from_slice(bytes)?,
+        })
+    }
+
+    pub fn encode<T: Serialize>(self, value: &T) -> Result<Vec<u8>> {
+        Ok(match self {
+            Self::Json => serde_json::to_vec(value)?,
+            Self::MessagePack => rmp_serde::to_vec_named(value)?,
+        })
+    }
+}
+
+/// Hand `payload` to the host as `[len: u64 LE][payload]`; free with [`free_buffer`].
+pub fn into_buffer(payload: Vec<u8>) -> *mut u8 {
+    let mut buf = Vec::with_capacity(PREFIX_LEN + payload.len());
+    buf.extend_from_slice(&(payload.len() as u64).to_le_bytes());
+    buf.extend_from_slice(&payload);
+    Box::into_raw(buf.into_boxed_slice()) as *mut u8
+}
+
+/// Release a buffer created by [`into_buffer`].
+///
+/// # Safety
+/// `ptr` must come from [`into_buffer`] and not have been freed already.
+pub unsafe fn free_buffer(ptr: *mut u8) {
+    let mut prefix = [0u8; PREFIX_LEN];
+    unsafe { std::ptr::copy_nonoverlapping(ptr, prefix.as_mut_ptr(), PREFIX_LEN) };
+    let total = PREFIX_LEN + u64::from_le_bytes(prefix) as usize;
+    drop(unsafe { Box::from_raw(std::ptr::slice_from_raw_parts_mut(ptr, total)) });
+}
@@ -0,0 +1,657 @@
+use anyhow::{anyhow, bail, Context, Result};
+use base64::Engine;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use std::str::FromStr;
+
+/// A named set of requests exchanged with other tools.
+///
+/// Each request serializes to the same shape as the FFI request payload, so
+/// hosts can pass items straight to `pigeon_send_request`.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Collection {
+    pub name: String,
+    #[serde(default)]
+    pub requests: Vec<CollectionRequest>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CollectionRequest {
+    #[serde(default)]
+    pub name: String,
+    pub method: String,
+    pub url: String,
+    #[serde(default)]
+    pub headers: Vec<CollectionHeader>,
+    pub body: Option<CollectionBody>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CollectionHeader {
+    pub key: String,
+    pub value: String,
+    #[serde(default = "default_true")]
+    pub enabled: bool,
+}
+
+fn default_true() -> bool {
+    true
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CollectionBody {
+    #[serde(default)]
+    pub content_type: String,
+    #[serde(default)]
+    pub content: String,
+}
+
+/// Supported interchange formats.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CollectionKind {
+    Curl,
+    Postman,
+    OpenApi,
+}
+
+impl FromStr for CollectionKind {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s.trim().to_ascii_lowercase().as_str() {
+            "curl" => Ok(Self::Curl),
+            "postman" => Ok(Self::Postman),
+            "openapi" | "swagger" => Ok(Self::OpenApi),
+            other => bail!("unknown collection kind: {other} (expected curl, postman or openapi)"),
+        }
+    }
+}
+
+/// Parse `data` in the given format into a collection.
+pub fn import(kind: CollectionKind, data: &str) -> Result<Collection> {
+    match kind {
+        CollectionKind::Curl => import_curl(data),
+        CollectionKind::Postman => import_postman(data),
+        CollectionKind::OpenApi => import_openapi(data),
+    }
+}
+
+/// Render a collection in the given format.
+pub fn export(kind: CollectionKind, collection: &Collection) -> Result<String> {
+    match kind {
+        CollectionKind::Curl => Ok(export_curl(collection)),
+        CollectionKind::Postman => Ok(serde_json::to_string_pretty(&export_postman(collection))?),
+        CollectionKind::OpenApi => Ok(serde_json::to_string_pretty(&export_openapi(collection))?),
+    }
+}
+
+impl CollectionRequest {
+    fn default_name(&self) -> String {
+        let path = reqwest::Url::parse(&self.url)
+            .map(|u| u.path().to_string())
+            .unwrap_or_else(|_| self.url.clone());
+        format!("{} {}", self.method, path)
+    }
+
+    /// Move a `Content-Type` header into the body, which is where the send path reads it.
+    fn hoist_content_type(&mut self) {
+        let Some(body) = self.body.as_mut() else {
+            return;
+        };
+        if let Some(pos) = self
+            .headers
+            .iter()
+            .position(|h| h.key.eq_ignore_ascii_case("content-type"))
+        {
+            let header = self.headers.remove(pos);
+            body.content_type = header.value;
+        }
+    }
+}
+
+// cURL
+
+fn import_curl(data: &str) -> Result<Collection> {
+    let words = shell_words(data)?;
+    let mut words = words.into_iter().peekable();
+    if words.peek().map(String::as_str) == Some("curl") {
+        words.next();
+    }
+
+    let mut method: Option<String> = None;
+    let mut url: Option<String> = None;
+    let mut headers = Vec::new();
+    let mut data_parts: Vec<String> = Vec::new();
+    let mut content_type: Option<String> = None;
+    let mut as_query = false;
+
+    while let Some(word) = words.next() {
+        // Normalize `--opt=value` and attached short options like `-XPOST`.
+        let (flag, inline) = match word.split_once('=') {
+            Some((f, v)) if f.starts_with("--") => (f.to_string(), Some(v.to_string())),
+            _ => match attached_short_option(&word) {
+                Some((f, v)) => (f.to_string(), Some(v.to_string())),
+                None => (word.clone(), None),
+            },
+        };
+
+        let mut value = |name: &str| -> Result<String> {
+            inline
+                .clone()
+                .or_else(|| words.next())
+                .ok_or_else(|| anyhow!("missing value for {name}"))
+        };
+
+        match flag.as_str() {
+            "-X" | "--request" => method = Some(value(&flag)?.to_ascii_uppercase()),
+            "-H" | "--header" => {
+                let raw = value(&flag)?;
+                if let Some((k, v)) = raw.split_once(':') {
+                    headers.push(CollectionHeader {
+                        key: k.trim().to_string(),
+                        value: v.trim().to_string(),
+                        enabled: true,
+                    });
+                }
+            }
+            "-d" | "--data" | "--data-raw" | "--data-binary" | "--data-ascii"
+            | "--data-urlencode" => data_parts.push(value(&flag)?),
+            "--json" => {
+                data_parts.push(value(&flag)?);
+                content_type = Some("application/json".to_string());
+            }
+            "-u" | "--user" => {
+                let credentials = base64::engine::general_purpose::STANDARD.encode(value(&flag)?);
+                headers.push(CollectionHeader {
+                    key: "Authorization".to_string(),
+                    value: format!("Basic {credentials}"),
+                    enabled: true,
+                });
+            }
+            "-A" | "--user-agent" => headers.push(CollectionHeader {
+                key: "User-Agent".to_string(),
+                value: value(&flag)?,
+                enabled: true,
+            }),
+            "-b" | "--cookie" => headers.push(CollectionHeader {
+                key: "Cookie".to_string(),
+                value: value(&flag)?,
+                enabled: true,
+            }),
+            "-e" | "--referer" => headers.push(CollectionHeader {
+                key: "Referer".to_string(),
+                value: value(&flag)?,
+                enabled: true,
+            }),
+            "--url" => url = Some(value(&flag)?),
+            "-I" | "--head" => method = Some("HEAD".to_string()),
+            "-G" | "--get" => as_query = true,
+            "-F" | "--form" => bail!("multipart form fields (-F) are not supported"),
+            // Options that take a value we don't model.
+            "-o" | "--output" | "-m" | "--max-time" | "--connect-timeout" | "-x" | "--proxy"
+            | "-w" | "--write-out" | "--retry" | "--cacert" | "--cert" | "--key" => {
+                value(&flag)?;
+            }
+            f if f.starts_with('-') => {}
+            _ => {
+                if url.is_none() {
+                    url = Some(word);
+                }
+            }
+        }
+    }
+
+    let mut url = url.ok_or_else(|| anyhow!("no URL found in curl command"))?;
+    let data = data_parts.join("&");
+    let mut body = None;
+
+    if !data.is_empty() {
+        if as_query {
+            url.push(if url.contains('?') { '&' } else { '?' });
+            url.push_str(&data);
+        } else {
+            body = Some(CollectionBody {
+                content_type: content_type
+                    .unwrap_or_else(|| "application/x-www-form-urlencoded".to_string()),
+                content: data,
+            });
+        }
+    }
+
+    let method = method.unwrap_or_else(|| if body.is_some() { "POST" } else { "GET" }.to_string());
+
+    let mut request = CollectionRequest {
+        name: String::new(),
+        method,
+        url,
+        headers,
+        body,
+    };
+    request.hoist_content_type();
+    request.name = request.default_name();
+
+    Ok(Collection {
+        name: "cURL import".to_string(),
+        requests: vec![request],
+    })
+}
+
+/// A short option with its value attached, split apart: `-XPOST` into `-X` and `POST`.
+fn attached_short_option(word: &str) -> Option<(&str, &str)> {
+    let mut chars = word.strip_prefix('-')?.char_indices();
+    let (_, flag) = chars.next()?;
+    let (at, _) = chars.next()?;
+    matches!(flag, 'X' | 'H' | 'd' | 'u' | 'A' | 'b' | 'e').then(|| word.split_at(at + 1))
+}
+
+fn export_curl(collection: &Collection) -> String {
+    collection
+        .requests
+        .iter()
+        .map(|r| {
+            let mut parts = vec![
+                "curl".to_string(),
+                "-X".to_string(),
+                r.method.clone(),
+                shell_quote(&r.url),
+            ];
+            for h in r.headers.iter().filter(|h| h.enabled) {
+                parts.push("-H".to_string());
+                parts.push(shell_quote(&format!("{}: {}", h.key, h.value)));
+            }
+            if let Some(body) = &r.body {
+                if !body.content_type.is_empty() {
+                    parts.push("-H".to_string());
+                    parts.push(shell_quote(&format!("Content-Type: {}", body.content_type)));
+                }
+                if !body.content.is_empty() {
+                    parts.push("--data-raw".to_string());
+                    parts.push(shell_quote(&body.content));
+                }
+            }
+            format!("# {}\n{}", r.name, parts.join(" "))
+        })
+        .collect::<Vec<_>>()
+        .join("\n\n")
+}
+
+fn shell_quote(s: &str) -> String {
+    format!("'{}'", s.replace('\'', r"'\''"))
+}
+
+/// Split a command line into words using POSIX shell quoting rules
+/// (single/double quotes, backslash escapes and line continuations).
+fn shell_words(input: &str) -> Result<Vec<String>> {
+    let mut words = Vec::new();
+    let mut current = String::new();
+    let mut in_word = false;
+    let mut chars = input.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        match c {
+            '\\' => match chars.next() {
+                Some('\n') => {}
+                Some('\r') if chars.peek() == Some(&'\n') => {
+                    chars.next();
+                }
+                Some(n) => {
+                    current.push(n);
+                    in_word = true;
+                }
+                None => {}
+            },
+            '\'' => {
+                in_word = true;
+                loop {
+                    match chars.next() {
+                        Some('\'') => break,
+                        Some(n) => current.push(n),
+                        None => bail!("unterminated single quote"),
+                    }
+                }
+            }
+            '"' => {
+                in_word = true;
+                loop {
+                    match chars.next() {
+                        Some('"') => break,
+                        Some('\\') => match chars.next() {
+                            Some(n @ ('"' | '\\' | '$' | '`')) => current.push(n),
+                            Some('\n') => {}
+                            Some(n) => {
+                                current.push('\\');
+                                current.push(n);
+                            }
+                            None => bail!("unterminated double quote"),
+                        },
+                        Some(n) => current.push(n),
+                        None => bail!("unterminated double quote"),
+                    }
+                }
+            }
+            c if c.is_whitespace() => {
+                if in_word {
+                    words.push(std::mem::take(&mut current));
+                    in_word = false;
+                }
+            }
+            c => {
+                current.push(c);
+                in_word = true;
+            }
+        }
+    }
+
+    if in_word {
+        words.push(current);
+    }
+
+    Ok(words)
+}
+
+// Postman (collection format v2.1)
+
+const POSTMAN_SCHEMA: &str = "https://schema.getpostman.com/json/collection/v2.1.0/collection.json";
+
+fn import_postman(data: &str) -> Result<Collection> {
+    let doc: Value = serde_json::from_str(data).context("invalid Postman collection JSON")?;
+    let name = doc["info"]["name"]
+        .as_str()
+        .unwrap_or("Postman import")
+        .to_string();
+
+    let mut requests = Vec::new();
+    if let Some(items) = doc["item"].as_array() {
+        collect_postman_items(items, "", &mut requests);
+    }
+
+    Ok(Collection { name, requests })
+}
+
+/// Flatten nested Postman folders; folder names become a `Folder / Item` prefix.
+fn collect_postman_items(items: &[Value], prefix: &str, out: &mut Vec<CollectionRequest>) {
+    for item in items {
+        let name = item["name"].as_str().unwrap_or_default();
+        let full_name = if prefix.is_empty() {
+            name.to_string()
+        } else {
+            format!("{prefix} / {name}")
+        };
+
+        if let Some(children) = item["item"].as_array() {
+            collect_postman_items(children, &full_name, out);
+            continue;
+        }
+
+        let req = &item["request"];
+        // A request may be shortened to just its URL string.
+        let url = match req {
+            Value::String(s) => s.clone(),
+            _ => match &req["url"] {
+                Value::String(s) => s.clone(),
+                v => v["raw"].as_str().unwrap_or_default().to_string(),
+            },
+        };
+
+        let headers = req["header"]
+            .as_array()
+            .map(|hs| {
+                hs.iter()
+                    .map(|h| CollectionHeader {
+                        key: h["key"].as_str().unwrap_or_default().to_string(),
+                        value: h["value"].as_str().unwrap_or_default().to_string(),
+                        enabled: !h["disabled"].as_bool().unwrap_or(false),
+                    })
+                    .collect()
+            })
+            .unwrap_or_default();
+
+        let body = match req["body"]["mode"].as_str() {
+            Some("raw") => Some(CollectionBody {
+                content_type: match req["body"]["options"]["raw"]["language"].as_str() {
+                    Some("json") => "application/json",
+                    Some("xml") => "application/xml",
+                    Some("html") => "text/html",
+                    Some("javascript") => "application/javascript",
+                    Some("text") => "text/plain",
+                    _ => "",
+                }
+                .to_string(),
+                content: req["body"]["raw"].as_str().unwrap_or_default().to_string(),
+            }),
+            Some("urlencoded") => Some(CollectionBody {
+                content_type: "application/x-www-form-urlencoded".to_string(),
+                content: req["body"]["urlencoded"]
+                    .as_array()
+                    .map(|fields| {
+                        fields
+                            .iter()
+                            .filter(|f| !f["disabled"].as_bool().unwrap_or(false))
+                            .map(|f| {
+                                format!(
+                                    "{}={}",
+                                    f["key"].as_str().unwrap_or_default(),
+                                    f["value"].as_str().unwrap_or_default()
+                                )
+                            })
+                            .collect::<Vec<_>>()
+                            .join("&")
+                    })
+                    .unwrap_or_default(),
+            }),
+            _ => None,
+        };
+
+        let mut request = CollectionRequest {
+            name: full_name,
+            method: req["method"].as_str().unwrap_or("GET").to_ascii_uppercase(),
+            url,
+            headers,
+            body,
+        };
+        request.hoist_content_type();
+        if request.name.is_empty() {
+            request.name = request.default_name();
+        }
+        out.push(request);
+    }
+}
+
+fn export_postman(collection: &Collection) -> Value {
+    let items = collection
+        .requests
+        .iter()
+        .map(|r| {
+            let mut headers = r
+                .headers
+                .iter()
+                .map(|h| json!({ "key": h.key, "value": h.value, "disabled": !h.enabled }))
+                .collect::<Vec<_>>();
+
+            let mut request = json!({
+                "method": r.method,
+                "url": { "raw": r.url },
+            });
+
+            if let Some(body) = &r.body {
+                if !body.content_type.is_empty() {
+                    headers.push(json!({ "key": "Content-Type", "value": body.content_type }));
+                }
+                request["body"] = json!({ "mode": "raw", "raw": body.content });
+            }
+            request["header"] = Value::Array(headers);
+
+            json!({ "name": r.name, "request": request })
+        })
+        .collect::<Vec<_>>();
+
+    json!({
+        "info": { "name": collection.name, "schema": POSTMAN_SCHEMA },
+        "item": items,
+    })
+}
+
+// OpenAPI (3.x, plus Swagger 2.0 server info), JSON documents only
+
+const OPENAPI_METHODS: &[&str] = &[
+    "get", "put", "post", "delete", "options", "head", "patch", "trace",
+];
+
+fn import_openapi(data: &str) -> Result<Collection> {
+    let doc: Value = serde_json::from_str(data).context("invalid OpenAPI JSON document")?;
+    let name = doc["info"]["title"]
+        .as_str()
+        .unwrap_or("OpenAPI import")
+        .to_string();
+
+    let base_url = if doc.get("swagger").is_some() {
+        let scheme = doc["schemes"][0].as_str().unwrap_or("https");
+        format!(
+            "{scheme}://{}{}",
+            doc["host"].as_str().unwrap_or("localhost"),
+            doc["basePath"].as_str().unwrap_or("")
+        )
+    } else {
+        doc["servers"][0]["url"].as_str().unwrap_or("").to_string()
+    };
+    let base_url = base_url.trim_end_matches('/');
+
+    let paths = doc["paths"]
+        .as_object()
+        .ok_or_else(|| anyhow!("OpenAPI document has no paths"))?;
+
+    let mut requests = Vec::new();
+    for (path, item) in paths {
+        for method in OPENAPI_METHODS {
+            let Some(op) = item.get(*method) else {
+                continue;
+            };
+            let method = method.to_ascii_uppercase();
+
+            let name = op["summary"]
+                .as_str()
+                .or_else(|| op["operationId"].as_str())
+                .map(str::to_string)
+                .unwrap_or_else(|| format!("{method} {path}"));
+
+            let headers = op["parameters"]
+                .as_array()
+                .map(|params| {
+                    params
+                        .iter()
+                        .filter(|p| p["in"].as_str() == Some("header"))
+                        .map(|p| CollectionHeader {
+                            key: p["name"].as_str().unwrap_or_default().to_string(),
+                            value: example_string(&p["example"]),
+                            enabled: p["required"].as_bool().unwrap_or(false),
+                        })
+                        .collect()
+                })
+                .unwrap_or_default();
+
+            let body = op["requestBody"]["content"]
+                .as_object()
+                .and_then(|content| content.iter().next())
+                .map(|(content_type, media)| {
+                    let example = if media["example"].is_null() {
+                        media["examples"]
+                            .as_object()
+                            .and_then(|e| e.values().next())
+                            .map(|e| e["value"].clone())
+                            .unwrap_or(Value::Null)
+                    } else {
+                        media["example"].clone()
+                    };
+                    CollectionBody {
+                        content_type: content_type.clone(),
+                        content: example_string(&example),
+                    }
+                });
+
+            requests.push(CollectionRequest {
+                name,
+                method,
+                url: format!("{base_url}{path}"),
+                headers,
+                body,
+            });
+        }
+    }
+
+    Ok(Collection { name, requests })
+}
+
+fn example_string(value: &Value) -> String {
+    match value {
+        Value::Null => String::new(),
+        Value::String(s) => s.clone(),
+        other => serde_json::to_string_pretty(other).unwrap_or_default(),
+    }
+}
+
+/// Build a minimal OpenAPI 3 document. Requests sharing a path and method are
+/// merged, with the last one winning.
+fn export_openapi(collection: &Collection) -> Value {
+    let mut servers: Vec<String> = Vec::new();
+    let mut paths = serde_json::Map::new();
+
+    for r in &collection.requests {
+        let (server, path) = match reqwest::Url::parse(&r.url) {
+            Ok(u) => (u.origin().ascii_serialization(), u.path().to_string()),
+            Err(_) => (String::new(), r.url.clone()),
+        };
+        if !server.is_empty() && !servers.contains(&server) {
+            servers.push(server);
+        }
+
+        let mut op = json!({
+            "summary": r.name,
+            "responses": { "default": { "description": "Response" } },
+        });
+
+        let params = r
+            .headers
+            .iter()
+            .filter(|h| !h.key.eq_ignore_ascii_case("content-type"))
+            .map(|h| {
+                json!({
+                    "name": h.key,
+                    "in": "header",
+                    "required": h.enabled,
+                    "schema": { "type": "string" },
+                    "example": h.value,
+                })
+            })
+            .collect::<Vec<_>>();
+        if !params.is_empty() {
+            op["parameters"] = Value::Array(params);
+        }
+
+        if let Some(body) = &r.body {
+            let content_type = if body.content_type.is_empty() {
+                "text/plain"
+            } else {
+                body.content_type.as_str()
+            };
+            let example = serde_json::from_str::<Value>(&body.content)
+                .unwrap_or_else(|_| Value::String(body.content.clone()));
+            op["requestBody"] = json!({ "content": { content_type: { "example": example } } });
+        }
+
+        let entry = paths
+            .entry(path)
+            .or_insert_with(|| Value::Object(Default::default()));
+        entry[r.method.to_ascii_lowercase()] = op;
+    }
+
+    json!({
+        "openapi": "3.0.3",
+        "info": { "title": collection.name, "version": "1.0.0" },
+        "servers": servers.iter().map(|url| json!({ "url": url })).collect::<Vec<_>>(),
+        "paths": paths,
+    })
+}
@@ -0,0 +1,203 @@
+use anyhow::{anyhow, Result};
+use chrono::{DateTime, Utc};
+use cookie_store::{Cookie, CookieDomain, CookieExpiration, CookieStore, RawCookie};
+use reqwest::header::HeaderValue;
+use reqwest::Url;
+use serde::{Deserialize, Serialize};
+use std::io::{BufReader, BufWriter};
+use std::path::Path;
+use std::sync::RwLock;
+
+/// Cookie jar shared by every request sent through the FFI.
+///
+/// Unlike `reqwest::cookie::Jar`, the underlying store stays reachable so the
+/// host can list and edit cookies.
+#[derive(Debug, Default)]
+pub struct CookieJar(RwLock<CookieStore>);
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CookieInfo {
+    pub name: String,
+    pub value: String,
+    pub domain: String,
+    pub path: String,
+    pub secure: bool,
+    pub http_only: bool,
+    /// Sent only to `domain` itself, not its subdomains (no `Domain` attribute).
+    pub host_only: bool,
+    /// `None` for session cookies.
+    pub expires: Option<DateTime<Utc>>,
+}
+
+impl From<&Cookie<'_>> for CookieInfo {
+    fn from(c: &Cookie<'_>) -> Self {
+        let expires = match &c.expires {
+            CookieExpiration::AtUtc(at) => DateTime::from_timestamp(at.unix_timestamp(), 0),
+            CookieExpiration::SessionEnd => None,
+        };
+
+        CookieInfo {
+            name: c.name().to_string(),
+            value: c.value().to_string(),
+            domain: String::from(&c.domain),
+            path: String::from(&c.path),
+            secure: c.secure().unwrap_or(false),
+            http_only: c.http_only().unwrap_or(false),
+            host_only: matches!(c.domain, CookieDomain::HostOnly(_)),
+            expires,
+        }
+    }
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct NewCookie {
+    /// URL the cookie is set "from"; used for domain/path defaults and validation.
+    pub url: String,
+    pub name: String,
+    pub value: String,
+    pub domain: Option<String>,
+    pub path: Option<String>,
+    #[serde(default)]
+    pub secure: bool,
+    #[serde(default)]
+    pub http_only: bool,
+    /// `None` (or omitted) for a session cookie.
+    pub expires: Option<DateTime<Utc>>,
+}
+
+/// Identifies a cookie in the jar, as reported by [`CookieJar::list`].
+#[derive(Debug, Deserialize)]
+pub struct CookieKey {
+    pub domain: String,
+    pub path: String,
+    pub name: String,
+}
+
+impl CookieJar {
+    /// Load persistent cookies saved by [`CookieJar::save`]; a missing file yields an empty jar.
+    pub fn load(path: &Path) -> Result<Self> {
+        if !path.exists() {
+            return Ok(Self::default());
+        }
+        let file = std::fs::File::open(path)?;
+        let store = cookie_store::serde::json::load(BufReader::new(file))
+            .map_err(|e| anyhow!("Failed to load cookies from {}: {e}", path.display()))?;
+        Ok(Self(RwLock::new(store)))
+    }
+
+    /// Write persistent, unexpired cookies to `path` (session cookies are skipped).
+    pub fn save(&self, path: &Path) -> Result<()> {
+        let mut writer = BufWriter::new(std::fs::File::create(path)?);
+        cookie_store::serde::json::save(&self.0.read().unwrap(), &mut writer)
+            .map_err(|e| anyhow!("Failed to save cookies to {}: {e}", path.display()))?;
+        Ok(())
+    }
+
+    /// List unexpired cookies, optionally restricted to a domain (and its subdomains).
+    pub fn list(&self, domain: Option<&str>) -> Vec<CookieInfo> {
+        let domain = domain
+            .map(|d| d.trim().trim_start_matches('.').to_ascii_lowercase())
+            .filter(|d| !d.is_empty());
+
+        let store = self.0.read().unwrap();
+        store
+            .iter_unexpired()
+            .filter(|c| match &domain {
+                Some(d) => {
+                    let cookie_domain = String::from(&c.domain);
+                    cookie_domain == *d || cookie_domain.ends_with(&format!(".{d}"))
+                }
+                None => true,
+            })
+            .map(CookieInfo::from)
+            .collect()
+    }
+
+    /// Cookies that would be attached to a request to `url`.
+    pub fn for_url(&self, url: &str) -> Result<Vec<CookieInfo>> {
+        let url = Url::parse(url)?;
+        let store = self.0.read().unwrap();
+        Ok(store
+            .matches(&url)
+            .into_iter()
+            .map(CookieInfo::from)
+            .collect())
+    }
+
+    /// Insert or replace a cookie as if it had been received from `cookie.url`.
+    pub fn set(&self, cookie: NewCookie) -> Result<()> {
+        let url = Url::parse(&cookie.url)?;
+
+        let mut builder = RawCookie::build((cookie.name, cookie.value))
+            .secure(cookie.secure)
+            .http_only(cookie.http_only);
+        if let Some(domain) = cookie.domain {
+            builder = builder.domain(domain);
+        }
+        if let Some(path) = cookie.path {
+            builder = builder.path(path);
+        }
+
+        let mut parsed = Cookie::try_from_raw_cookie(&builder.build(), &url)
+            .map_err(|e| anyhow!("Failed to set cookie: {e}"))?
+            .into_owned();
+        if let Some(expires) = cookie.expires {
+            let seconds = (expires - Utc::now()).num_seconds();
+            if seconds <= 0 {
+                return Err(anyhow!("Failed to set cookie: expiry is in the past"));
+            }
+            parsed.expires = CookieExpiration::from(seconds as u64);
+        }
+
+        self.0
+            .write()
+            .unwrap()
+            .insert(parsed, &url)
+            .map_err(|e| anyhow!("Failed to set cookie: {e}"))?;
+
+        Ok(())
+    }
+
+    /// Remove one cookie, returning whether it was in the jar.
+    pub fn remove(&self, key: &CookieKey) -> bool {
+        self.0
+            .write()
+            .unwrap()
+            .remove(&key.domain, &key.path, &key.name)
+            .is_some()
+    }
+
+    /// Remove every cookie from the jar.
+    pub fn clear(&self) {
+        self.0.write().unwrap().clear();
+    }
+}
+
+impl reqwest::cookie::CookieStore for CookieJar {
+    fn set_cookies(&self, cookie_headers: &mut dyn Iterator<Item = &HeaderValue>, url: &Url) {
+        let cookies = cookie_headers
+            .filter_map(|v| v.to_str().ok())
+            .filter_map(|s| RawCookie::parse(s.to_owned()).ok());
+
+        self.0.write().unwrap().store_response_cookies(cookies, url);
+    }
+
+    fn cookies(&self, url: &Url) -> Option<HeaderValue> {
+        let header = self
+            .0
+            .read()
+            .unwrap()
+            .get_request_values(url)
+            .map(|(name, value)| format!("{name}={value}"))
+            .collect::<Vec<_>>()
+            .join("; ");
+
+        if header.is_empty() {
+            return None;
+        }
+
+        HeaderValue::from_str(&header).ok()
+    }
+}
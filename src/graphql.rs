@@ -0,0 +1,282 @@
+use anyhow::{bail, Result};
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+
+use crate::{FfiAuth, FfiBody, FfiHeader, FfiRequest, FfiResponse};
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GraphqlRequest {
+    pub url: String,
+    pub query: String,
+    #[serde(default)]
+    pub variables: Option<Value>,
+    #[serde(default)]
+    pub operation_name: Option<String>,
+    #[serde(default)]
+    pub headers: Vec<FfiHeader>,
+    #[serde(default)]
+    pub auth: Option<FfiAuth>,
+}
+
+/// HTTP response plus the unpacked GraphQL envelope.
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GraphqlResponse {
+    pub status: u16,
+    pub status_text: String,
+    pub headers: Vec<(String, String)>,
+    pub duration_ms: u64,
+    /// `data` from the envelope (`null` when absent).
+    pub data: Value,
+    /// `errors[]` from the envelope; transport and decoding failures are
+    /// reported here too, as `{"message": "..."}` entries.
+    pub errors: Vec<Value>,
+    /// True when the server returned data alongside errors.
+    pub partial: bool,
+    /// Raw response body, for display or debugging.
+    pub body: String,
+}
+
+impl GraphqlRequest {
+    /// Wrap the query in a standard `POST` JSON envelope.
+    pub fn into_ffi_request(self) -> FfiRequest {
+        let mut envelope = json!({ "query": self.query });
+        if let Some(variables) = self.variables {
+            envelope["variables"] = variables;
+        }
+        if let Some(operation_name) = self.operation_name {
+            envelope["operationName"] = Value::String(operation_name);
+        }
+
+        let mut headers = self.headers;
+        if !headers
+            .iter()
+            .any(|h| h.enabled && h.key.eq_ignore_ascii_case("accept"))
+        {
+            headers.push(FfiHeader {
+                key: "Accept".to_string(),
+                value: "application/graphql-response+json, application/json".to_string(),
+                enabled: true,
+                secret: false,
+            });
+        }
+
+        FfiRequest {
+            method: "POST".to_string(),
+            url: self.url,
+            headers,
+            body: Some(FfiBody {
+                content_type: "application/json".to_string(),
+                content: envelope.to_string(),
+            }),
+            auth: self.auth,
+            space: None,
+        }
+    }
+}
+
+impl From<FfiResponse> for GraphqlResponse {
+    fn from(response: FfiResponse) -> Self {
+        let (data, errors) = if response.status == 0 {
+            (Value::Null, vec![json!({ "message": response.body })])
+        } else {
+            match serde_json::from_str::<Value>(&response.body) {
+                Ok(mut envelope) => {
+                    let data = envelope
+                        .get_mut("data")
+                        .map(Value::take)
+                        .unwrap_or(Value::Null);
+                    let errors = match envelope.get_mut("errors").map(Value::take) {
+                        Some(Value::Array(errors)) => errors,
+                        Some(Value::Null) | None => vec![],
+                        Some(other) => vec![other],
+                    };
+                    (data, errors)
+                }
+                Err(e) => (
+                    Value::Null,
+                    vec![
+                        json!({ "message": format!("response is not a GraphQL JSON envelope: {e}") }),
+                    ],
+                ),
+            }
+        };
+
+        Self {
+            partial: !data.is_null() && !errors.is_empty(),
+            status: response.status,
+            status_text: response.status_text,
+            headers: response.headers,
+            duration_ms: response.duration_ms,
+            data,
+            errors,
+            body: response.body,
+        }
+    }
+}
+
+/// Introspection query for [`Schema`]: types with their fields, arguments,
+/// input fields and enum values. Type references are unwrapped four levels
+/// deep, enough for e.g. `[[Int!]!]!`.
+pub const INTROSPECTION_QUERY: &str = "query IntrospectionQuery {
+  __schema {
+    queryType { name }
+    mutationType { name }
+    subscriptionType { name }
+    types {
+      kind name description
+      fields(includeDeprecated: true) {
+        name description isDeprecated
+        args { name description defaultValue type { ...TypeRef } }
+        type { ...TypeRef }
+      }
+      inputFields { name description defaultValue type { ...TypeRef } }
+      enumValues(includeDeprecated: true) { name }
+      possibleTypes { name }
+    }
+  }
+}
+fragment TypeRef on __Type {
+  kind name
+  ofType { kind name ofType { kind name ofType { kind name ofType { kind name } } } }
+}";
+
+/// Where to run [`INTROSPECTION_QUERY`]: the endpoint plus any headers and auth it needs.
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SchemaRequest {
+    pub url: String,
+    #[serde(default)]
+    pub headers: Vec<FfiHeader>,
+    #[serde(default)]
+    pub auth: Option<FfiAuth>,
+}
+
+impl SchemaRequest {
+    pub fn into_graphql_request(self) -> GraphqlRequest {
+        GraphqlRequest {
+            url: self.url,
+            query: INTROSPECTION_QUERY.to_string(),
+            variables: None,
+            operation_name: Some("IntrospectionQuery".to_string()),
+            headers: self.headers,
+            auth: self.auth,
+        }
+    }
+}
+
+/// An introspected schema, flattened for editors: type references are written
+/// as in GraphQL (`[User!]!`) and the built-in `__` types are left out.
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Schema {
+    pub query_type: Option<String>,
+    pub mutation_type: Option<String>,
+    pub subscription_type: Option<String>,
+    pub types: Vec<SchemaType>,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SchemaType {
+    pub name: String,
+    /// `OBJECT`, `INTERFACE`, `UNION`, `ENUM`, `INPUT_OBJECT` or `SCALAR`.
+    pub kind: String,
+    pub description: Option<String>,
+    pub fields: Vec<SchemaField>,
+    pub input_fields: Vec<SchemaInput>,
+    pub enum_values: Vec<String>,
+    /// Members of a union or implementations of an interface.
+    pub possible_types: Vec<String>,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SchemaField {
+    pub name: String,
+    #[serde(rename = "type")]
+    pub type_ref: String,
+    pub description: Option<String>,
+    pub args: Vec<SchemaInput>,
+    pub deprecated: bool,
+}
+
+/// An argument or input object field.
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SchemaInput {
+    pub name: String,
+    #[serde(rename = "type")]
+    pub type_ref: String,
+    pub description: Option<String>,
+    pub default_value: Option<String>,
+}
+
+impl Schema {
+    /// Read the `data` of an [`INTROSPECTION_QUERY`] response.
+    pub fn from_introspection(data: &Value) -> Result<Self> {
+        let Some(schema) = data.get("__schema").filter(|s| s.is_object()) else {
+            bail!("response has no __schema; is introspection disabled on this server?");
+        };
+        let root = |key: &str| text(&schema[key]["name"]);
+        Ok(Self {
+            query_type: root("queryType"),
+            mutation_type: root("mutationType"),
+            subscription_type: root("subscriptionType"),
+            types: list(&schema["types"])
+                .filter_map(|t| {
+                    let name = text(&t["name"])?;
+                    (!name.starts_with("__")).then(|| SchemaType {
+                        name,
+                        kind: text(&t["kind"]).unwrap_or_default(),
+                        description: text(&t["description"]),
+                        fields: list(&t["fields"])
+                            .map(|f| SchemaField {
+                                name: text(&f["name"]).unwrap_or_default(),
+                                type_ref: type_ref(&f["type"]),
+                                description: text(&f["description"]),
+                                args: list(&f["args"]).map(input).collect(),
+                                deprecated: f["isDeprecated"].as_bool().unwrap_or(false),
+                            })
+                            .collect(),
+                        input_fields: list(&t["inputFields"]).map(input).collect(),
+                        enum_values: list(&t["enumValues"])
+                            .filter_map(|v| text(&v["name"]))
+                            .collect(),
+                        possible_types: list(&t["possibleTypes"])
+                            .filter_map(|v| text(&v["name"]))
+                            .collect(),
+                    })
+                })
+                .collect(),
+        })
+    }
+}
+
+fn text(value: &Value) -> Option<String> {
+    value.as_str().map(str::to_string)
+}
+
+/// Items of a JSON array; nothing for `null` or anything else.
+fn list(value: &Value) -> impl Iterator<Item = &Value> {
+    value.as_array().into_iter().flatten()
+}
+
+fn input(value: &Value) -> SchemaInput {
+    SchemaInput {
+        name: text(&value["name"]).unwrap_or_default(),
+        type_ref: type_ref(&value["type"]),
+        description: text(&value["description"]),
+        default_value: text(&value["defaultValue"]),
+    }
+}
+
+/// A `__Type` reference written as in GraphQL, e.g. `[User!]!`.
+fn type_ref(value: &Value) -> String {
+    match value["kind"].as_str() {
+        Some("NON_NULL") => format!("{}!", type_ref(&value["ofType"])),
+        Some("LIST") => format!("[{}]", type_ref(&value["ofType"])),
+        _ => text(&value["name"]).unwrap_or_default(),
+    }
+}
@@ -0,0 +1,401 @@
+use anyhow::{anyhow, bail, Context, Result};
+use chrono::{DateTime, Utc};
+use rusqlite::types::Value as SqlValue;
+use rusqlite::{params, params_from_iter, Connection};
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+use std::sync::Mutex;
+use uuid::Uuid;
+
+use crate::{FfiRequest, FfiResponse};
+
+/// Entries returned by a query that doesn't set `limit`.
+const DEFAULT_LIMIT: u32 = 100;
+
+const SCHEMA: &str = "
+CREATE TABLE IF NOT EXISTS history (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    space_id TEXT,
+    space TEXT,
+    timestamp_ms INTEGER NOT NULL,
+    method TEXT NOT NULL,
+    url TEXT NOT NULL,
+    request TEXT NOT NULL,
+    sent TEXT,
+    status INTEGER NOT NULL,
+    status_text TEXT NOT NULL,
+    headers TEXT NOT NULL,
+    body TEXT NOT NULL,
+    duration_ms INTEGER NOT NULL,
+    pinned INTEGER NOT NULL DEFAULT 0
+);
+CREATE INDEX IF NOT EXISTS history_timestamp ON history (timestamp_ms);
+CREATE INDEX IF NOT EXISTS history_space ON history (space_id, timestamp_ms);
+";
+
+/// Columns added after the table was first created, with their definitions, for
+/// databases that predate them.
+const ADDED_COLUMNS: &[(&str, &str)] = &[
+    ("sent", "TEXT"),
+    ("pinned", "INTEGER NOT NULL DEFAULT 0"),
+    ("body_base64", "TEXT"),
+    ("failure", "TEXT"),
+];
+
+/// Schema version this build writes, kept in SQLite's `user_version`.
+const SCHEMA_VERSION: u32 = 2;
+
+type Migration = fn(&Connection) -> rusqlite::Result<()>;
+
+/// `MIGRATIONS[n]` upgrades a database of version `n` to `n + 1`; each runs in a
+/// transaction with the version bump. Later versions only add [`ADDED_COLUMNS`].
+const MIGRATIONS: &[Migration] = &[unversioned, add_missing_columns];
+
+/// A sent request and the response it got.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct HistoryEntry {
+    pub id: i64,
+    pub space_id: Option<Uuid>,
+    /// Space name when the request was sent.
+    pub space: Option<String>,
+    pub timestamp: DateTime<Utc>,
+    /// The request as `on_request` hooks left it, before secrets and auth were applied.
+    pub request: FfiRequest,
+    /// The request as sent, with variables substituted, so it can be sent again as-is.
+    /// Fields holding secrets keep their `{{secret:...}}` placeholder, and headers
+    /// added by auth providers are left out. `None` for entries recorded before
+    /// snapshots were kept.
+    pub sent: Option<FfiRequest>,
+    pub response: FfiResponse,
+    /// Kept as a saved response: exempt from [`Retention`] pruning.
+    pub pinned: bool,
+}
+
+/// Filters for [`HistoryStore::query`]; unset fields match everything.
+#[derive(Debug, Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct HistoryQuery {
+    /// Space id, or its name when the request was sent.
+    pub space: Option<String>,
+    /// Sent at or after this time.
+    pub since: Option<DateTime<Utc>>,
+    /// Sent before this time.
+    pub until: Option<DateTime<Utc>>,
+    pub status: Option<StatusFilter>,
+    /// Case-insensitive method, e.g. `"post"`.
+    pub method: Option<String>,
+    /// Substring of the request URL, to pick an endpoint.
+    pub url: Option<String>,
+    /// Text in the URL, the request (headers and body) or the response body,
+    /// ignoring ASCII case (as SQLite's `lower` does).
+    pub text: Option<String>,
+    /// Only saved (`true`) or only unsaved (`false`) responses.
+    pub pinned: Option<bool>,
+    /// Most entries to return, newest first (100 by default).
+    pub limit: Option<u32>,
+}
+
+/// `404` for one status, `"4xx"` for a class (`0xx` is transport failures), or
+/// `"error"` for failures and 4xx/5xx responses alike.
+#[derive(Debug, Deserialize)]
+#[serde(untagged)]
+pub enum StatusFilter {
+    Exact(u16),
+    Class(String),
+}
+
+/// How much unpinned history to keep; unset limits keep everything.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct Retention {
+    /// Most unpinned entries kept, newest first.
+    pub max_entries: Option<u32>,
+    /// Unpinned entries older than this many days are dropped.
+    pub max_age_days: Option<u32>,
+}
+
+/// Request history kept in SQLite under the data directory.
+pub struct HistoryStore {
+    conn: Mutex<Connection>,
+}
+
+impl HistoryStore {
+    /// Open (and create, if needed) the database at `path`.
+    pub fn open(path: &Path) -> Result<Self> {
+        let conn = Connection::open(path)
+            .with_context(|| format!("Failed to open history: {}", path.display()))?;
+        migrate(&conn)
+            .with_context(|| format!("Failed to initialize history: {}", path.display()))?;
+        Ok(Self {
+            conn: Mutex::new(conn),
+        })
+    }
+
+    /// Record `response` to `request`, sent now as `sent` in space `space` (`(id, name)`).
+    pub fn record(
+        &self,
+        space: Option<(Uuid, String)>,
+        request: &FfiRequest,
+        sent: &FfiRequest,
+        response: &FfiResponse,
+    ) -> Result<()> {
+        let (space_id, space) = space.unzip();
+        self.conn.lock().unwrap().execute(
+            "INSERT INTO history (space_id, space, timestamp_ms, method, url, request, sent,
+                 status, status_text, headers, body, body_base64, duration_ms, failure)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13, ?14)",
+            params![
+                space_id.map(|id| id.to_string()),
+                space,
+                Utc::now().timestamp_millis(),
+                request.method.to_ascii_uppercase(),
+                request.url,
+                serde_json::to_string(request)?,
+                serde_json::to_string(sent)?,
+                response.status,
+                response.status_text,
+                serde_json::to_string(&response.headers)?,
+                response.body,
+                response.body_base64,
+                response.duration_ms as i64,
+                response
+                    .failure
+                    .map(|f| serde_json::to_string(&f))
+                    .transpose()?,
+            ],
+        )?;
+        Ok(())
+    }
+
+    /// Entries matching `query`, newest first.
+    pub fn query(&self, query: &HistoryQuery) -> Result<Vec<HistoryEntry>> {
+        let mut clauses: Vec<&str> = Vec::new();
+        let mut values: Vec<SqlValue> = Vec::new();
+
+        if let Some(space) = &query.space {
+            clauses.push("(space_id = ? OR space = ?)");
+            values.push(SqlValue::Text(space.clone()));
+            values.push(SqlValue::Text(space.clone()));
+        }
+        if let Some(since) = query.since {
+            clauses.push("timestamp_ms >= ?");
+            values.push(SqlValue::Integer(since.timestamp_millis()));
+        }
+        if let Some(until) = query.until {
+            clauses.push("timestamp_ms < ?");
+            values.push(SqlValue::Integer(until.timestamp_millis()));
+        }
+        match &query.status {
+            Some(StatusFilter::Exact(status)) => {
+                clauses.push("status = ?");
+                values.push(SqlValue::Integer((*status).into()));
+            }
+            Some(StatusFilter::Class(class)) if class.eq_ignore_ascii_case("error") => {
+                clauses.push("(status = 0 OR status >= 400)");
+            }
+            Some(StatusFilter::Class(class)) => {
+                let digit = class
+                    .strip_suffix("xx")
+                    .or_else(|| class.strip_suffix("XX"))
+                    .and_then(|d| d.parse::<i64>().ok())
+                    .filter(|d| (0..=9).contains(d))
+                    .ok_or_else(|| {
+                        anyhow!("invalid status filter `{class}`, expected e.g. 4xx or error")
+                    })?;
+                clauses.push("status >= ? AND status < ?");
+                values.push(SqlValue::Integer(digit * 100));
+                values.push(SqlValue::Integer(digit * 100 + 100));
+            }
+            None => {}
+        }
+        if let Some(method) = &query.method {
+            clauses.push("method = ?");
+            values.push(SqlValue::Text(method.to_ascii_uppercase()));
+        }
+        if let Some(url) = &query.url {
+            clauses.push("instr(url, ?) > 0");
+            values.push(SqlValue::Text(url.clone()));
+        }
+        if let Some(text) = query.text.as_deref().filter(|t| !t.is_empty()) {
+            clauses.push(
+                "(instr(lower(url), ?) > 0 OR instr(lower(request), ?) > 0
+                  OR instr(lower(coalesce(body, '')), ?) > 0)",
+            );
+            let text = text.to_ascii_lowercase();
+            values.extend(std::iter::repeat_n(SqlValue::Text(text), 3));
+        }
+        if let Some(pinned) = query.pinned {
+            clauses.push("pinned = ?");
+            values.push(SqlValue::Integer(pinned.into()));
+        }
+        values.push(SqlValue::Integer(
+            query.limit.unwrap_or(DEFAULT_LIMIT).into(),
+        ));
+
+        let filter = if clauses.is_empty() {
+            String::new()
+        } else {
+            format!("WHERE {}", clauses.join(" AND "))
+        };
+        let sql = format!(
+            "SELECT id, space_id, space, timestamp_ms, request,
+                    status, status_text, headers, body, duration_ms, sent, pinned,
+                    body_base64, failure
+             FROM history {filter} ORDER BY timestamp_ms DESC, id DESC LIMIT ?"
+        );
+
+        let conn = self.conn.lock().unwrap();
+        let mut statement = conn.prepare(&sql)?;
+        let entries = statement
+            .query_map(params_from_iter(values), |row| {
+                Ok(HistoryEntry {
+                    id: row.get(0)?,
+                    space_id: row
+                        .get::<_, Option<String>>(1)?
+                        .and_then(|id| id.parse().ok()),
+                    space: row.get(2)?,
+                    timestamp: DateTime::from_timestamp_millis(row.get(3)?).unwrap_or_default(),
+                    request: json_column(row, 4)?,
+                    sent: row
+                        .get::<_, Option<String>>(10)?
+                        .map(|_| json_column(row, 10))
+                        .transpose()?,
+                    response: FfiResponse {
+                        status: row.get(5)?,
+                        status_text: row.get(6)?,
+                        headers: json_column(row, 7)?,
+                        body: row.get(8)?,
+                        body_base64: row.get(12)?,
+                        duration_ms: row.get::<_, i64>(9)?.max(0) as u64,
+                        failure: row
+                            .get::<_, Option<String>>(13)?
+                            .map(|_| json_column(row, 13))
+                            .transpose()?,
+                    },
+                    pinned: row.get(11)?,
+                })
+            })?
+            .collect::<rusqlite::Result<_>>()?;
+        Ok(entries)
+    }
+
+    /// Pin entry `id` as a saved response, or unpin it. An error if there's no such entry.
+    pub fn set_pinned(&self, id: i64, pinned: bool) -> Result<()> {
+        let changed = self.conn.lock().unwrap().execute(
+            "UPDATE history SET pinned = ?1 WHERE id = ?2",
+            params![pinned, id],
+        )?;
+        if changed == 0 {
+            bail!("unknown history entry: {id}");
+        }
+        Ok(())
+    }
+
+    /// Delete entry `id`, pinned or not. An error if there's no such entry.
+    pub fn delete(&self, id: i64) -> Result<()> {
+        let deleted = self
+            .conn
+            .lock()
+            .unwrap()
+            .execute("DELETE FROM history WHERE id = ?1", params![id])?;
+        if deleted == 0 {
+            bail!("unknown history entry: {id}");
+        }
+        Ok(())
+    }
+
+    /// Delete the entries of `space` (id or name), or of every space for `None`.
+    /// Pinned entries are kept. Returns how many were deleted.
+    pub fn clear(&self, space: Option<&str>) -> Result<usize> {
+        let conn = self.conn.lock().unwrap();
+        let deleted = match space {
+            Some(space) => conn.execute(
+                "DELETE FROM history WHERE pinned = 0 AND (space_id = ?1 OR space = ?1)",
+                params![space],
+            )?,
+            None => conn.execute("DELETE FROM history WHERE pinned = 0", [])?,
+        };
+        Ok(deleted)
+    }
+
+    /// Delete unpinned entries beyond `retention`'s limits. Returns how many were deleted.
+    pub fn prune(&self, retention: &Retention) -> Result<usize> {
+        let conn = self.conn.lock().unwrap();
+        let mut deleted = 0;
+        if let Some(days) = retention.max_age_days {
+            let cutoff = Utc::now() - chrono::Duration::days(days.into());
+            deleted += conn.execute(
+                "DELETE FROM history WHERE pinned = 0 AND timestamp_ms < ?1",
+                params![cutoff.timestamp_millis()],
+            )?;
+        }
+        if let Some(max) = retention.max_entries {
+            deleted += conn.execute(
+                "DELETE FROM history WHERE pinned = 0 AND id NOT IN (
+                     SELECT id FROM history WHERE pinned = 0
+                     ORDER BY timestamp_ms DESC, id DESC LIMIT ?1
+                 )",
+                params![max],
+            )?;
+        }
+        Ok(deleted)
+    }
+}
+
+/// Bring the database up to [`SCHEMA_VERSION`]. One written by a newer build is
+/// an error, so it isn't written to with a schema it doesn't match.
+fn migrate(conn: &Connection) -> Result<()> {
+    let version: u32 = conn.query_row("PRAGMA user_version", [], |row| row.get(0))?;
+    if version > SCHEMA_VERSION {
+        bail!(
+            "history schema version {version} is newer than this build supports \
+             ({SCHEMA_VERSION}); update pigeon to open it"
+        );
+    }
+    for (from, migration) in MIGRATIONS.iter().enumerate().skip(version as usize) {
+        let to = from + 1;
+        conn.execute_batch("BEGIN")?;
+        let migrated = migration(conn)
+            .and_then(|()| conn.execute_batch(&format!("PRAGMA user_version = {to}")));
+        if let Err(e) = migrated {
+            let _ = conn.execute_batch("ROLLBACK");
+            return Err(e).with_context(|| {
+                format!("Failed to migrate history from schema version {from} to {to}")
+            });
+        }
+        conn.execute_batch("COMMIT")?;
+    }
+    Ok(())
+}
+
+/// 0 → 1: new databases, and those from before versioning, which may lack
+/// columns added since.
+fn unversioned(conn: &Connection) -> rusqlite::Result<()> {
+    conn.execute_batch(SCHEMA)?;
+    add_missing_columns(conn)
+}
+
+fn add_missing_columns(conn: &Connection) -> rusqlite::Result<()> {
+    let existing: Vec<String> = conn
+        .prepare("SELECT name FROM pragma_table_info('history')")?
+        .query_map([], |row| row.get(0))?
+        .collect::<rusqlite::Result<_>>()?;
+    for (name, definition) in ADDED_COLUMNS {
+        if !existing.iter().any(|column| column == name) {
+            conn.execute_batch(&format!(
+                "ALTER TABLE history ADD COLUMN {name} {definition}"
+            ))?;
+        }
+    }
+    Ok(())
+}
+
+fn json_column<T: serde::de::DeserializeOwned>(
+    row: &rusqlite::Row,
+    index: usize,
+) -> rusqlite::Result<T> {
+    let text: String = row.get(index)?;
+    serde_json::from_str(&text).map_err(|e| {
+        rusqlite::Error::FromSqlConversionFailure(index, rusqlite::types::Type::Text, Box::new(e))
+    })
+}
@@ -1,317 +1,3949 @@
-#[deprecated]
-#[allow(dead_code)]
-mod model;
-
+mod autosave;
+mod codec;
+mod collection;
+mod cookies;
+mod graphql;
+mod history;
+mod logging;
 mod lua;
+mod migrate;
+mod mock;
+mod model;
+mod paths;
+mod profiles;
+mod runner;
+mod session;
+mod settings;
+mod store;
+mod template;
+mod undo;
+mod vault;
+mod watcher;
 
+use autosave::Autosave;
+use codec::Encoding;
+use collection::{Collection, CollectionKind};
+use cookies::{CookieJar, CookieKey, NewCookie};
+use graphql::{GraphqlRequest, GraphqlResponse, Schema, SchemaRequest};
+use history::{HistoryQuery, HistoryStore, Retention};
+use logging::{Level, LogCallback};
+use lua::hooks::WorkspaceEvent;
 use lua::LuaRuntime;
+use mock::MockConfig;
+use model::Workspace;
+use runner::RunPlan;
 use serde::{Deserialize, Serialize};
+use session::Session;
+use settings::Overrides;
+use std::collections::BTreeMap;
 use std::ffi::{c_char, CStr, CString};
 use std::panic::AssertUnwindSafe;
-use std::sync::OnceLock;
+use std::path::Path;
+use std::sync::atomic::{AtomicBool, AtomicU64, AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex, OnceLock, RwLock};
+use store::Storage;
+use undo::UndoStack;
+use vault::Vault;
+
+/// Feature names reported by `pigeon_capabilities`. Extend when adding a new FFI surface.
+const CAPABILITIES: &[&str] = &[
+    "auth-providers",
+    "batch",
+    "collections",
+    "commands",
+    "config",
+    "config-watch",
+    "cookies",
+    "cookies-edit",
+    "formatters",
+    "graphql",
+    "graphql-schema",
+    "history",
+    "history-delete",
+    "history-pins",
+    "keychain",
+    "keymap",
+    "log-callback",
+    "lua-console",
+    "lua-eval",
+    "mock-server",
+    "onboarding",
+    "msgpack",
+    "plugins",
+    "profiles",
+    "request-preview",
+    "run-collection",
+    "saved-requests",
+    "secret-storage",
+    "session",
+    "settings",
+    "shutdown",
+    "status",
+    "space-create",
+    "space-overrides",
+    "themes",
+    "workspace",
+    "workspace-bulk",
+    "workspace-duplicate",
+    "workspace-export",
+    "workspace-order",
+    "workspace-remove",
+    "workspace-search",
+    "workspace-storage",
+    "workspace-undo",
+    "workspace-update",
+];
+
+// Runtimes and the HTTP client live in resettable slots so `pigeon_shutdown` can
+// drop them; they are lazily recreated if the library is used again afterwards.
+static TOKIO_RUNTIME: RwLock<Option<Arc<tokio::runtime::Runtime>>> = RwLock::new(None);
+static LUA_RUNTIME: RwLock<Option<Arc<LuaRuntime>>> = RwLock::new(None);
+static HTTP_CLIENT: RwLock<Option<reqwest::Client>> = RwLock::new(None);
+static COOKIE_JAR: OnceLock<Arc<CookieJar>> = OnceLock::new();
+/// Built from these when the shared client is (re)created; `None` until a config or
+/// settings change computes them.
+static HTTP_OPTIONS: Mutex<Option<HttpOptions>> = Mutex::new(None);
+/// Settings changed in a host's settings view, layered over `config.lua`.
+static SETTINGS: OnceLock<Mutex<Overrides>> = OnceLock::new();
+static WORKSPACE: OnceLock<Mutex<Workspace>> = OnceLock::new();
+static WORKSPACE_AUTOSAVE: OnceLock<Autosave> = OnceLock::new();
+/// Where the workspace is saved; follows `workspace.storage` once a config is loaded.
+static WORKSPACE_STORAGE: Mutex<Option<Storage>> = Mutex::new(None);
+/// Host edits to the workspace, for `pigeon_workspace_undo`/`_redo`. Lock after `WORKSPACE`.
+static WORKSPACE_UNDO: Mutex<UndoStack> = Mutex::new(UndoStack::new());
+/// Nothing had been saved when the workspace was first loaded, so it holds the sample
+/// data; cleared by `pigeon_workspace_setup` or once a saved one is found.
+static FIRST_RUN: AtomicBool = AtomicBool::new(false);
+/// The workspace in storage failed to load, so it isn't saved over until it loads
+/// (on the next config reload) or the storage moves.
+static WORKSPACE_UNREADABLE: AtomicBool = AtomicBool::new(false);
+static HISTORY: OnceLock<Option<HistoryStore>> = OnceLock::new();
+/// Limits from `history.max_entries` / `history.max_age_days`, applied after each send.
+static HISTORY_RETENTION: Mutex<Retention> = Mutex::new(Retention {
+    max_entries: None,
+    max_age_days: None,
+});
+/// Key for secret workspace values; `None` while locked. Lock after `WORKSPACE`.
+static VAULT: Mutex<Option<Vault>> = Mutex::new(None);
+
+/// Profiles layered over `config.lua`, in order; kept across reloads.
+static PROFILES: RwLock<Vec<String>> = RwLock::new(Vec::new());
+
+/// Bumped every time a config is loaded or reloaded, so hosts can poll for changes.
+static CONFIG_GENERATION: AtomicU64 = AtomicU64::new(0);
+
+/// Requests being sent right now, from any source (hosts, Lua, collection runs).
+static IN_FLIGHT: AtomicUsize = AtomicUsize::new(0);
+/// Response bytes received so far by the requests in [`IN_FLIGHT`].
+static RECEIVED: AtomicU64 = AtomicU64::new(0);
+/// The collection run in progress, if any, for `pigeon_status`.
+static COLLECTION_RUN: Mutex<Option<RunProgress>> = Mutex::new(None);
+
+#[derive(Debug, Clone, Serialize)]
+struct RunProgress {
+    name: String,
+    done: usize,
+    total: usize,
+}
+
+/// Counts one request in [`IN_FLIGHT`] and its body bytes in [`RECEIVED`] until
+/// dropped, however the send ends.
+struct InFlight {
+    received: u64,
+}
+
+impl InFlight {
+    fn start() -> Self {
+        IN_FLIGHT.fetch_add(1, Ordering::Relaxed);
+        InFlight { received: 0 }
+    }
+
+    fn receive(&mut self, bytes: usize) {
+        self.received += bytes as u64;
+        RECEIVED.fetch_add(bytes as u64, Ordering::Relaxed);
+    }
+}
+
+impl Drop for InFlight {
+    fn drop(&mut self) {
+        IN_FLIGHT.fetch_sub(1, Ordering::Relaxed);
+        RECEIVED.fetch_sub(self.received, Ordering::Relaxed);
+    }
+}
+
+/// Woken by `pigeon_shutdown` to cancel in-flight requests.
+static SHUTDOWN: tokio::sync::Notify = tokio::sync::Notify::const_new();
+
+const COOKIES_FILE: &str = "cookies.json";
+const SETTINGS_FILE: &str = "settings.json";
+const SESSION_FILE: &str = "session.json";
+const WORKSPACE_FILE: &str = "workspace.json";
+/// Default home of the `workspace.storage = "directory"` layout, in the data directory.
+const WORKSPACE_DIR: &str = "workspace";
+const HISTORY_FILE: &str = "history.db";
+/// How long the workspace must stay unchanged before it is written to disk.
+const AUTOSAVE_DELAY: std::time::Duration = std::time::Duration::from_millis(500);
+
+/// Master passphrase for secret workspace values, for when no host prompts for it (e.g. CI).
+const PASSPHRASE_ENV: &str = "PIGEON_PASSPHRASE";
+
+/// Placeholder prefix resolved through the Lua secrets command, as in `{{secret:API_KEY}}`.
+const SECRET_PREFIX: &str = "secret:";
+
+fn get_tokio_runtime() -> Arc<tokio::runtime::Runtime> {
+    if let Some(rt) = TOKIO_RUNTIME.read().unwrap().as_ref() {
+        return rt.clone();
+    }
+
+    TOKIO_RUNTIME
+        .write()
+        .unwrap()
+        .get_or_insert_with(|| {
+            Arc::new(
+                tokio::runtime::Builder::new_multi_thread()
+                    .enable_all()
+                    .build()
+                    .expect("Failed to create Tokio runtime"),
+            )
+        })
+        .clone()
+}
+
+fn get_lua_runtime() -> Option<Arc<LuaRuntime>> {
+    LUA_RUNTIME.read().unwrap().clone()
+}
+
+/// Cookie jar shared by all requests, seeded from the data directory on first use.
+fn get_cookie_jar() -> &'static Arc<CookieJar> {
+    COOKIE_JAR.get_or_init(|| {
+        let jar = paths::data_dir()
+            .map_err(anyhow::Error::msg)
+            .and_then(|dir| CookieJar::load(&dir.join(COOKIES_FILE)))
+            .unwrap_or_else(|e| {
+                logging::emit(
+                    Level::Error,
+                    "cookies.load_error",
+                    serde_json::json!({ "error": format!("{e:#}") }),
+                );
+                CookieJar::default()
+            });
+        Arc::new(jar)
+    })
+}
+
+/// Settings view overrides, loaded from the data directory on first use.
+fn get_settings() -> &'static Mutex<Overrides> {
+    SETTINGS.get_or_init(|| {
+        let overrides = paths::data_dir()
+            .map_err(anyhow::Error::msg)
+            .and_then(|dir| Overrides::load(&dir.join(SETTINGS_FILE)))
+            .unwrap_or_else(|e| {
+                logging::emit(
+                    Level::Error,
+                    "settings.load_error",
+                    serde_json::json!({ "error": format!("{e:#}") }),
+                );
+                Overrides::default()
+            });
+        Mutex::new(overrides)
+    })
+}
+
+/// A setting's value: the settings view's override if there is one, else what
+/// `config.lua` sets (`null` without a config).
+fn setting(lua: Option<&LuaRuntime>, path: &str) -> anyhow::Result<serde_json::Value> {
+    if let Some(value) = get_settings().lock().unwrap().get(path) {
+        return Ok(value.clone());
+    }
+    match lua {
+        Some(lua) => lua.config_get(path),
+        None => Ok(serde_json::Value::Null),
+    }
+}
+
+/// Workspace shared by the host and `pigeon.workspace`, loaded from the data
+/// directory on first use. A config loaded later may move it, see
+/// [`follow_workspace_storage`].
+fn get_workspace() -> &'static Mutex<Workspace> {
+    WORKSPACE.get_or_init(|| {
+        let workspace = paths::data_dir()
+            .map_err(anyhow::Error::msg)
+            .and_then(|dir| {
+                let storage = Storage::File(dir.join(WORKSPACE_FILE));
+                FIRST_RUN.store(!storage.exists(), Ordering::Relaxed);
+                let loaded = load_workspace(&storage);
+                *WORKSPACE_STORAGE.lock().unwrap() = Some(storage);
+                loaded
+            })
+            .unwrap_or_else(|e| {
+                logging::emit(
+                    Level::Error,
+                    "workspace.load_error",
+                    serde_json::json!({ "error": format!("{e:#}") }),
+                );
+                Workspace::default()
+            });
+        Mutex::new(workspace)
+    })
+}
+
+/// Load the workspace from `storage`. On failure the files are left as they are
+/// and saving stops (see [`WORKSPACE_UNREADABLE`]), e.g. until a merge conflict
+/// in one of them is resolved.
+fn load_workspace(storage: &Storage) -> anyhow::Result<Workspace> {
+    let loaded = storage.load();
+    WORKSPACE_UNREADABLE.store(loaded.is_err(), Ordering::Relaxed);
+    let mut workspace = loaded?;
+    open_secrets(&mut workspace);
+    Ok(workspace)
+}
+
+/// Unlock `vault` with `PIGEON_PASSPHRASE`, if it is locked and that is set.
+fn unlock_from_env(vault: &mut Option<Vault>, workspace: &mut Workspace) {
+    let Some(passphrase) = std::env::var(PASSPHRASE_ENV)
+        .ok()
+        .filter(|_| vault.is_none())
+    else {
+        return;
+    };
+    let values = workspace.secret_values_mut();
+    match Vault::unlock(&passphrase, values.iter().map(|v| v.as_str())) {
+        Ok(unlocked) => *vault = Some(unlocked),
+        Err(e) => logging::emit(
+            Level::Error,
+            "secrets.unlock_error",
+            serde_json::json!({ "error": format!("{e:#}") }),
+        ),
+    }
+}
+
+/// Decrypt `workspace`'s secret values if secrets are unlocked; they stay
+/// encrypted (and can't be sent) until `pigeon_secrets_unlock` otherwise.
+fn open_secrets(workspace: &mut Workspace) {
+    let mut vault = VAULT.lock().unwrap();
+    unlock_from_env(&mut vault, workspace);
+    let Some(vault) = vault.as_mut() else {
+        return;
+    };
+    for value in workspace.secret_values_mut() {
+        if !vault::is_sealed(value) {
+            continue;
+        }
+        match vault.open(value) {
+            Ok(plain) => *value = plain,
+            Err(e) => logging::emit(
+                Level::Error,
+                "secrets.open_error",
+                serde_json::json!({ "error": format!("{e:#}") }),
+            ),
+        }
+    }
+}
+
+/// Encrypt `workspace`'s secret values for writing it out. Fails while secrets
+/// are locked if any of them isn't encrypted yet.
+fn seal_secrets(workspace: &mut Workspace) -> anyhow::Result<()> {
+    let mut vault = VAULT.lock().unwrap();
+    unlock_from_env(&mut vault, workspace);
+    for value in workspace.secret_values_mut() {
+        if value.is_empty() || vault::is_sealed(value) {
+            continue;
+        }
+        let Some(vault) = vault.as_mut() else {
+            anyhow::bail!(
+                "secret values can't be saved while secrets are locked; \
+                 set a passphrase with pigeon_secrets_unlock or {PASSPHRASE_ENV}"
+            );
+        };
+        *value = vault.seal(value)?;
+    }
+    Ok(())
+}
+
+/// The storage `pigeon.config` asks for: with `workspace.storage = "directory"`
+/// the workspace is kept as a tree under `workspace.path` (relative to the config
+/// directory; `workspace/` in the data directory when unset), otherwise in one file.
+fn configured_storage(lua: &LuaRuntime) -> anyhow::Result<Storage> {
+    let data_dir = paths::data_dir().map_err(anyhow::Error::msg)?;
+    let storage = lua.config_get("workspace.storage")?;
+    if storage.as_str() != Some("directory") {
+        return Ok(Storage::File(data_dir.join(WORKSPACE_FILE)));
+    }
+    Ok(match lua.config_get("workspace.path")? {
+        serde_json::Value::String(path) => Storage::Directory(lua.config_dir().join(path)),
+        _ => Storage::Directory(data_dir.join(WORKSPACE_DIR)),
+    })
+}
+
+/// Switch to the storage a freshly (re)loaded config asks for. A workspace already
+/// saved there replaces the one in memory; otherwise the current one is written
+/// there, e.g. to move it into a Git repository. A workspace that failed to load
+/// is loaded again, even from the same storage.
+fn follow_workspace_storage(lua: &LuaRuntime) {
+    let report = |e: anyhow::Error| {
+        logging::emit(
+            Level::Error,
+            "workspace.storage_error",
+            serde_json::json!({ "error": format!("{e:#}") }),
+        );
+    };
+    let storage = match configured_storage(lua) {
+        Ok(storage) => storage,
+        Err(e) => return report(e),
+    };
+    let workspace = get_workspace();
+    let mut current = WORKSPACE_STORAGE.lock().unwrap();
+    let retry = WORKSPACE_UNREADABLE.load(Ordering::Relaxed);
+    if current.as_ref() == Some(&storage) && !retry {
+        return;
+    }
+    if storage.exists() {
+        match load_workspace(&storage) {
+            Ok(loaded) => {
+                *workspace.lock().unwrap() = loaded;
+                FIRST_RUN.store(false, Ordering::Relaxed);
+            }
+            Err(e) => {
+                // Saving stays off; the next reload tries again.
+                *current = Some(storage);
+                return report(e);
+            }
+        }
+    } else {
+        WORKSPACE_UNREADABLE.store(false, Ordering::Relaxed);
+    }
+    logging::emit(
+        Level::Info,
+        "workspace.storage",
+        serde_json::json!({ "path": storage.path() }),
+    );
+    *current = Some(storage);
+    drop(current);
+    workspace_changed();
+}
+
+/// Take up the history limits of a freshly (re)loaded config or changed settings,
+/// and prune to them.
+fn follow_history_retention(lua: Option<&LuaRuntime>) {
+    let limit = |path: &str| match setting(lua, path) {
+        Ok(value) => value.as_f64().map(|n| n.max(0.0) as u32),
+        Err(e) => {
+            log_lua_error("history.retention_error", &e);
+            None
+        }
+    };
+    let retention = Retention {
+        max_entries: limit("history.max_entries"),
+        max_age_days: limit("history.max_age_days"),
+    };
+    *HISTORY_RETENTION.lock().unwrap() = retention;
+    prune_history(&retention);
+}
+
+fn prune_history(retention: &Retention) {
+    if *retention == Retention::default() {
+        return;
+    }
+    let Some(history) = get_history() else {
+        return;
+    };
+    if let Err(e) = history.prune(retention) {
+        logging::emit(
+            Level::Error,
+            "history.prune_error",
+            serde_json::json!({ "error": format!("{e:#}") }),
+        );
+    }
+}
+
+/// Apply a host edit called `label` to the workspace so it can be undone, then
+/// schedule a save. Nothing is recorded when `edit` fails.
+fn edit_workspace<T>(
+    label: String,
+    edit: impl FnOnce(&mut Workspace) -> anyhow::Result<T>,
+) -> anyhow::Result<T> {
+    let value = {
+        let mut workspace = get_workspace().lock().unwrap();
+        let before = workspace.clone();
+        let value = edit(&mut workspace)?;
+        WORKSPACE_UNDO.lock().unwrap().push(label, before);
+        value
+    };
+    workspace_changed();
+    Ok(value)
+}
+
+/// Schedule a debounced write of the workspace after it changed.
+fn workspace_changed() {
+    WORKSPACE_AUTOSAVE
+        .get_or_init(|| {
+            Autosave::spawn(AUTOSAVE_DELAY, || {
+                if let Err(e) = save_workspace() {
+                    logging::emit(
+                        Level::Error,
+                        "workspace.save_error",
+                        serde_json::json!({ "error": format!("{e:#}") }),
+                    );
+                }
+            })
+        })
+        .schedule();
+}
+
+/// Write what the `pigeon.on_workspace_save` hooks make of the current workspace.
+///
+/// The in-memory workspace is left as is, so hooks can e.g. strip secrets from the file.
+fn save_workspace() -> anyhow::Result<()> {
+    // Not locked while hooks run, since they may call `pigeon.workspace`.
+    let mut workspace = get_workspace().lock().unwrap().clone();
+    if let Some(lua) = get_lua_runtime() {
+        workspace = apply_workspace_hooks(&lua, WorkspaceEvent::Save, workspace);
+    }
+    write_workspace(&workspace)
+}
+
+/// Write `workspace` to its storage, with secret values encrypted. Fails while
+/// the workspace saved there can't be loaded.
+fn write_workspace(workspace: &Workspace) -> anyhow::Result<()> {
+    let storage = WORKSPACE_STORAGE.lock().unwrap().clone();
+    if WORKSPACE_UNREADABLE.load(Ordering::Relaxed) {
+        let path = storage.as_ref().map(|s| s.path().display().to_string());
+        anyhow::bail!(
+            "not saving over the workspace at {}, which failed to load; fix it and reload the config",
+            path.unwrap_or_default()
+        );
+    }
+    let mut workspace = workspace.clone();
+    seal_secrets(&mut workspace)?;
+    match storage {
+        Some(storage) => storage.save(&workspace),
+        None => {
+            let dir = paths::data_dir().map_err(anyhow::Error::msg)?;
+            workspace.save(&dir.join(WORKSPACE_FILE))
+        }
+    }
+}
+
+/// Request history in the data directory, or `None` if it can't be opened.
+fn get_history() -> Option<&'static HistoryStore> {
+    HISTORY
+        .get_or_init(|| {
+            paths::data_dir()
+                .map_err(anyhow::Error::msg)
+                .and_then(|dir| HistoryStore::open(&dir.join(HISTORY_FILE)))
+                .inspect_err(|e| {
+                    logging::emit(
+                        Level::Error,
+                        "history.open_error",
+                        serde_json::json!({ "error": format!("{e:#}") }),
+                    );
+                })
+                .ok()
+        })
+        .as_ref()
+}
+
+/// Add `response` to the history, filed under the space named by `request.space`
+/// (id or name), or the first space that isn't archived when unset. `sent` is the request as resolved
+/// for sending (see [`sent_snapshot`]).
+fn record_history(request: &FfiRequest, sent: &FfiRequest, response: &FfiResponse) {
+    let Some(history) = get_history() else {
+        return;
+    };
+    let space = {
+        let workspace = get_workspace().lock().unwrap();
+        let space = match &request.space {
+            Some(key) => workspace.find_space(key),
+            None => workspace.spaces.iter().find(|s| !s.archived),
+        };
+        space.map(|s| (s.id, s.name.clone()))
+    };
+    let secrets = secret_values();
+    let (request, sent) = (
+        mask_secrets(request, &secrets),
+        mask_secrets(sent, &secrets),
+    );
+    if let (None, Some(key)) = (&space, &request.space) {
+        logging::emit(
+            Level::Warn,
+            "history.unknown_space",
+            serde_json::json!({ "space": key }),
+        );
+    }
+    if let Err(e) = history.record(space, &request, &sent, response) {
+        logging::emit(
+            Level::Error,
+            "history.record_error",
+            serde_json::json!({ "error": format!("{e:#}") }),
+        );
+    }
+    let retention = *HISTORY_RETENTION.lock().unwrap();
+    prune_history(&retention);
+}
+
+/// The `http.*` and `tls.*` settings the shared HTTP client is built with.
+#[derive(Debug, Clone, Default)]
+struct HttpOptions {
+    timeout: Option<std::time::Duration>,
+    proxy: Option<String>,
+    /// Accept invalid and self-signed certificates.
+    insecure: bool,
+    /// PEM file with an extra root certificate to trust.
+    ca_file: Option<String>,
+}
+
+impl HttpOptions {
+    fn read(lua: Option<&LuaRuntime>) -> Self {
+        let get = |path: &str| match setting(lua, path) {
+            Ok(value) => value,
+            Err(e) => {
+                log_lua_error("http.settings_error", &e);
+                serde_json::Value::Null
+            }
+        };
+        let text = |path: &str| {
+            get(path)
+                .as_str()
+                .map(str::trim)
+                .filter(|s| !s.is_empty())
+                .map(String::from)
+        };
+        Self {
+            timeout: get("http.timeout")
+                .as_f64()
+                .filter(|secs| *secs > 0.0)
+                .and_then(|secs| std::time::Duration::try_from_secs_f64(secs).ok()),
+            proxy: text("http.proxy"),
+            insecure: get("tls.insecure").as_bool().unwrap_or(false),
+            ca_file: text("tls.ca_file"),
+        }
+    }
+
+    fn build_client(&self) -> anyhow::Result<reqwest::Client> {
+        let mut builder = reqwest::Client::builder().cookie_provider(get_cookie_jar().clone());
+        if let Some(timeout) = self.timeout {
+            builder = builder.timeout(timeout);
+        }
+        if let Some(proxy) = &self.proxy {
+            let proxy = reqwest::Proxy::all(proxy)
+                .map_err(|e| anyhow::anyhow!("invalid http.proxy `{proxy}`: {e}"))?;
+            builder = builder.proxy(proxy);
+        }
+        if self.insecure {
+            builder = builder.danger_accept_invalid_certs(true);
+        }
+        if let Some(path) = &self.ca_file {
+            let pem = std::fs::read(path)
+                .map_err(|e| anyhow::anyhow!("Failed to read tls.ca_file {path}: {e}"))?;
+            let certificate = reqwest::Certificate::from_pem(&pem)
+                .map_err(|e| anyhow::anyhow!("invalid certificate in tls.ca_file {path}: {e}"))?;
+            builder = builder.add_root_certificate(certificate);
+        }
+        Ok(builder.build()?)
+    }
+}
+
+/// Take up the HTTP settings of a freshly (re)loaded config or changed settings;
+/// the shared client is rebuilt with them for the next request.
+fn follow_http_settings(lua: Option<&LuaRuntime>) {
+    *HTTP_OPTIONS.lock().unwrap() = Some(HttpOptions::read(lua));
+    HTTP_CLIENT.write().unwrap().take();
+}
+
+/// Shared HTTP client so connections and cookies persist across requests.
+fn get_http_client() -> reqwest::Client {
+    if let Some(client) = HTTP_CLIENT.read().unwrap().as_ref() {
+        return client.clone();
+    }
+
+    HTTP_CLIENT
+        .write()
+        .unwrap()
+        .get_or_insert_with(|| {
+            let options = HTTP_OPTIONS
+                .lock()
+                .unwrap()
+                .clone()
+                .unwrap_or_else(|| HttpOptions::read(None));
+            // A bad setting shouldn't stop every request, so fall back to the defaults.
+            options.build_client().unwrap_or_else(|e| {
+                logging::emit(
+                    Level::Error,
+                    "http.client_error",
+                    serde_json::json!({ "error": format!("{e:#}") }),
+                );
+                reqwest::Client::builder()
+                    .cookie_provider(get_cookie_jar().clone())
+                    .build()
+                    .expect("Failed to create HTTP client")
+            })
+        })
+        .clone()
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct FfiRequest {
+    method: String,
+    url: String,
+    #[serde(default)]
+    headers: Vec<FfiHeader>,
+    body: Option<FfiBody>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    auth: Option<FfiAuth>,
+    /// Id or name of the space whose history records the response; the first
+    /// space when unset.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    space: Option<String>,
+}
+
+/// Query strings are encoded like `pigeon.encode.url`, except that `{{placeholder}}`
+/// braces are kept so placeholders still resolve at send time.
+const QUERY_ENCODE_SET: &percent_encoding::AsciiSet = &percent_encoding::NON_ALPHANUMERIC
+    .remove(b'-')
+    .remove(b'_')
+    .remove(b'.')
+    .remove(b'~')
+    .remove(b'{')
+    .remove(b'}');
+
+impl From<&model::Request> for FfiRequest {
+    fn from(request: &model::Request) -> Self {
+        let query: Vec<String> = request
+            .params
+            .iter()
+            .filter(|p| p.enabled && !p.key.is_empty())
+            .map(|p| {
+                let encode = |s: &str| {
+                    percent_encoding::utf8_percent_encode(s, QUERY_ENCODE_SET).to_string()
+                };
+                format!("{}={}", encode(&p.key), encode(&p.value))
+            })
+            .collect();
+        let mut url = request.url.clone();
+        if !query.is_empty() {
+            url.push(if url.contains('?') { '&' } else { '?' });
+            url.push_str(&query.join("&"));
+        }
+
+        Self {
+            method: request.method.clone(),
+            url,
+            headers: request
+                .headers
+                .iter()
+                .map(|h| FfiHeader {
+                    key: h.key.clone(),
+                    value: h.value.clone(),
+                    enabled: h.enabled,
+                    secret: false,
+                })
+                .collect(),
+            body: request.body.as_ref().map(|b| FfiBody {
+                content_type: b.content_type.clone(),
+                content: b.content.clone(),
+            }),
+            auth: request.auth.as_ref().map(|a| FfiAuth {
+                kind: a.kind.clone(),
+                params: a.params.clone(),
+            }),
+            space: None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct FfiHeader {
+    key: String,
+    value: String,
+    #[serde(default = "default_true")]
+    enabled: bool,
+    /// Holds a secret value: front ends mask it and history keeps it masked.
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    secret: bool,
+}
+
+fn default_true() -> bool {
+    true
+}
+
+/// Auth applied while the request is built, resolved by `type` against the auth
+/// providers registered from Lua with `pigeon.register_auth`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct FfiAuth {
+    #[serde(rename = "type")]
+    kind: String,
+    #[serde(default)]
+    params: BTreeMap<String, String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct FfiBody {
+    #[serde(default)]
+    content_type: String,
+    #[serde(default)]
+    content: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct FfiResponse {
+    status: u16,
+    status_text: String,
+    /// One `[name, value]` pair per header value, so repeated headers such as
+    /// `Set-Cookie` are kept. Names appear in the order first received, with
+    /// repeated values grouped under their name. Non-UTF-8 values are decoded lossily.
+    headers: Vec<(String, String)>,
+    body: String,
+    /// The body's raw bytes, base64-encoded, when they aren't valid UTF-8 (images
+    /// and other binary data). `body` then holds a lossy decoding.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    body_base64: Option<String>,
+    duration_ms: u64,
+    /// Why no response came back, on `status: 0` errors.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    failure: Option<FailureKind>,
+}
+
+/// Classes of send failures, so hosts can explain them and suggest fixes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+enum FailureKind {
+    /// The host name didn't resolve.
+    Dns,
+    /// Nothing listens on the host and port.
+    ConnectionRefused,
+    /// Any other failure to connect, e.g. an unreachable network.
+    Connect,
+    /// The TLS handshake failed, e.g. on an untrusted or expired certificate.
+    Tls,
+    Timeout,
+    /// The URL couldn't be parsed or has no host.
+    InvalidUrl,
+    /// Stopped by `pigeon_shutdown`.
+    Cancelled,
+    /// Everything else, including hook, secret and signing errors.
+    Other,
+}
+
+impl FfiResponse {
+    /// Response-shaped error (`status: 0`) so callers can always parse one format.
+    fn error(message: impl Into<String>) -> Self {
+        Self::failed(FailureKind::Other, message)
+    }
+
+    fn failed(kind: FailureKind, message: impl Into<String>) -> Self {
+        Self {
+            status: 0,
+            status_text: "Error".to_string(),
+            headers: vec![],
+            body: message.into(),
+            body_base64: None,
+            duration_ms: 0,
+            failure: Some(kind),
+        }
+    }
+
+    /// The error for a send of `url` that reqwest gave up on, with its full cause
+    /// chain (reqwest's own message only says which URL failed).
+    fn from_send_error(e: &reqwest::Error, url: &str) -> Self {
+        let mut message = format!("request failed: {e}");
+        let mut source = std::error::Error::source(e);
+        while let Some(cause) = source {
+            message.push_str(&format!(": {cause}"));
+            source = cause.source();
+        }
+        Self::failed(classify_send_error(e, url), message)
+    }
+}
+
+fn classify_send_error(e: &reqwest::Error, url: &str) -> FailureKind {
+    if e.is_timeout() {
+        return FailureKind::Timeout;
+    }
+    // Also raised for invalid header names or values, which aren't the URL's fault.
+    if e.is_builder() {
+        let invalid_url = reqwest::Url::parse(url).map_or(true, |u| u.host().is_none());
+        return if invalid_url {
+            FailureKind::InvalidUrl
+        } else {
+            FailureKind::Other
+        };
+    }
+    let mut source = std::error::Error::source(e);
+    while let Some(cause) = source {
+        if let Some(io) = cause.downcast_ref::<std::io::Error>() {
+            match io.kind() {
+                std::io::ErrorKind::ConnectionRefused => return FailureKind::ConnectionRefused,
+                std::io::ErrorKind::TimedOut => return FailureKind::Timeout,
+                _ => {}
+            }
+        }
+        // hyper-util and the TLS backends don't expose typed errors for these.
+        let text = cause.to_string().to_lowercase();
+        if text.contains("dns error") || text.contains("failed to lookup address") {
+            return FailureKind::Dns;
+        }
+        if ["certificate", "tls", "ssl", "handshake"]
+            .iter()
+            .any(|word| text.contains(word))
+        {
+            return FailureKind::Tls;
+        }
+        source = cause.source();
+    }
+    if e.is_connect() {
+        FailureKind::Connect
+    } else {
+        FailureKind::Other
+    }
+}
+
+/// Decode a response body as `reqwest::Response::text` does, honouring the
+/// Content-Type charset, and keep the bytes too when they aren't UTF-8.
+fn decode_body(content_type: Option<&str>, bytes: &[u8]) -> (String, Option<String>) {
+    use base64::Engine;
+
+    let encoding = content_type
+        .and_then(|value| {
+            value.split(';').skip(1).find_map(|param| {
+                let (name, value) = param.split_once('=')?;
+                name.trim()
+                    .eq_ignore_ascii_case("charset")
+                    .then(|| value.trim().trim_matches('"'))
+            })
+        })
+        .and_then(|label| encoding_rs::Encoding::for_label(label.as_bytes()))
+        .unwrap_or(encoding_rs::UTF_8);
+    let (text, _, _) = encoding.decode(bytes);
+    let raw = std::str::from_utf8(bytes)
+        .is_err()
+        .then(|| base64::engine::general_purpose::STANDARD.encode(bytes));
+    (text.into_owned(), raw)
+}
+
+fn json_error(message: impl Into<String>) -> String {
+    serde_json::to_string(&FfiResponse::error(message))
+    .unwrap_or_else(|_| "{\"status\":0,\"statusText\":\"Error\",\"headers\":[],\"body\":\"serialization error\",\"durationMs\":0}".to_string())
+}
+
+/// `{"error": "..."}` payload used by the non-request FFI functions.
+fn json_result_error(message: impl Into<String>) -> String {
+    serde_json::json!({ "error": message.into() }).to_string()
+}
+
+fn json_success() -> String {
+    r#"{"success": true}"#.to_string()
+}
+
+/// Read an optional C string argument; NULL maps to `None`.
+///
+/// # Safety
+/// `ptr` must be either NULL or point to a valid NUL-terminated C string.
+unsafe fn optional_c_str<'a>(ptr: *const c_char, name: &str) -> Result<Option<&'a str>, String> {
+    if ptr.is_null() {
+        return Ok(None);
+    }
+    unsafe { CStr::from_ptr(ptr) }
+        .to_str()
+        .map(Some)
+        .map_err(|e| format!("{name} is not valid utf-8: {e}"))
+}
+
+/// Read a required C string argument.
+///
+/// # Safety
+/// `ptr` must be either NULL or point to a valid NUL-terminated C string.
+unsafe fn required_c_str<'a>(ptr: *const c_char, name: &str) -> Result<&'a str, String> {
+    unsafe { optional_c_str(ptr, name) }?.ok_or_else(|| format!("{name} is null"))
+}
+
+fn log_lua_error(event: &str, error: &anyhow::Error) {
+    let message = format!("{error:#}");
+    logging::emit(Level::Error, event, serde_json::json!({ "error": message }));
+    lua::console::record(Level::Error, event, message);
+}
+
+/// Run the workspace hooks for `event`, keeping `workspace` as is if they can't run.
+fn apply_workspace_hooks(
+    lua: &LuaRuntime,
+    event: WorkspaceEvent,
+    workspace: Workspace,
+) -> Workspace {
+    let name = match event {
+        WorkspaceEvent::Load => "hook.on_workspace_load",
+        WorkspaceEvent::Save => "hook.on_workspace_save",
+    };
+    match lua.run_workspace_hooks(event, workspace.clone(), move |e| log_lua_error(name, &e)) {
+        Ok(workspace) => workspace,
+        Err(e) => {
+            log_lua_error(name, &e);
+            workspace
+        }
+    }
+}
+
+fn string_to_c_char_ptr(s: String) -> *mut c_char {
+    // If there is an interior NUL (shouldn't happen for JSON), degrade gracefully.
+    match CString::new(s) {
+        Ok(cstr) => cstr.into_raw(),
+        Err(_) => CString::new(json_error("Invalid string (interior NUL)"))
+            .unwrap()
+            .into_raw(),
+    }
+}
+
+/// Execute a single request through the shared send pipeline: Lua `on_request`
+/// hooks, secret and dynamic variable resolution, auth signing, the shared client, then
+/// `on_response` hooks. Transport, secret and signing failures are reported as
+/// `FfiResponse::error`, as is cancellation by `pigeon_shutdown`.
+async fn execute_request(mut parsed: FfiRequest) -> FfiResponse {
+    if let Some(field) = sealed_field(&parsed) {
+        return FfiResponse::error(format!(
+            "the {field} holds an encrypted secret; unlock secrets with the master passphrase first"
+        ));
+    }
+
+    // Auth params can hold credentials, so hooks only ever see the signed result.
+    let mut auth = parsed.auth.take();
+
+    let Some(lua) = get_lua_runtime() else {
+        if let Some(auth) = auth {
+            return FfiResponse::error(format!(
+                "auth provider `{}` needs the Lua runtime to be initialized",
+                auth.kind
+            ));
+        }
+        let unresolved = parsed.clone();
+        if let Err(e) = resolve_placeholders(&mut parsed, None, None) {
+            return FfiResponse::error(e);
+        }
+        let sent = parsed.clone();
+        let response = dispatch_request(parsed, &unresolved.url).await;
+        record_history(&unresolved, &sent, &response);
+        return response;
+    };
+
+    // Hooks run on the Lua thread; this task just waits for them.
+    let hooked = lua
+        .run_request_hooks(parsed.clone(), |e| log_lua_error("hook.on_request", &e))
+        .await;
+    match hooked {
+        Ok(request) => parsed = request,
+        Err(e) => log_lua_error("hook.on_request", &e),
+    }
+
+    // Response hooks get the request as the hooks left it, without secret values.
+    // Dynamic variables run after hooks too, so hooks can add placeholders.
+    let hooked = parsed.clone();
+
+    // Secrets commands block, so keep them off the async workers.
+    let resolved = tokio::task::block_in_place(|| {
+        resolve_placeholders(&mut parsed, auth.as_mut(), Some(&lua))
+    });
+    if let Err(e) = resolved {
+        return FfiResponse::error(e);
+    }
+    let sent = sent_snapshot(&hooked, &parsed);
+
+    // Signed last, so the signature covers whatever the hooks added.
+    if let Some(auth) = &auth {
+        match lua.sign_request(parsed.clone(), auth).await {
+            Ok(request) => parsed = request,
+            Err(e) => {
+                log_lua_error("auth.sign_error", &e);
+                return FfiResponse::error(format!("{e:#}"));
+            }
+        }
+    }
+
+    let response = dispatch_request(parsed, &hooked.url).await;
+    record_history(&hooked, &sent, &response);
+
+    let result = lua
+        .run_response_hooks(&hooked, &response, |e| {
+            log_lua_error("hook.on_response", &e)
+        })
+        .await;
+    if let Err(e) = result {
+        log_lua_error("hook.on_response", &e);
+    }
+
+    response
+}
+
+/// The first part of `request` still holding a secret encrypted by [`Vault`],
+/// which happens while secrets are locked.
+fn sealed_field(request: &FfiRequest) -> Option<&'static str> {
+    if vault::is_sealed(&request.url) {
+        return Some("URL");
+    }
+    if request.headers.iter().any(|h| vault::is_sealed(&h.value)) {
+        return Some("header");
+    }
+    if request
+        .body
+        .as_ref()
+        .is_some_and(|b| vault::is_sealed(&b.content))
+    {
+        return Some("body");
+    }
+    request
+        .auth
+        .as_ref()
+        .is_some_and(|a| a.params.values().any(|v| vault::is_sealed(v)))
+        .then_some("auth")
+}
+
+/// `resolved` as history keeps it: fields whose template in `hooked` uses a
+/// `{{secret:...}}` keep the template, so secret values never reach the database.
+fn sent_snapshot(hooked: &FfiRequest, resolved: &FfiRequest) -> FfiRequest {
+    let has_secret = |template: &str| {
+        let found = std::cell::Cell::new(false);
+        template::render(template, |name| {
+            found.set(found.get() || name.starts_with(SECRET_PREFIX));
+            None
+        });
+        found.get()
+    };
+    let pick = |template: &str, value: &str| {
+        if has_secret(template) {
+            template
+        } else {
+            value
+        }
+        .to_string()
+    };
+
+    let mut sent = resolved.clone();
+    sent.url = pick(&hooked.url, &resolved.url);
+    for (header, template) in sent.headers.iter_mut().zip(&hooked.headers) {
+        header.value = pick(&template.value, &header.value);
+    }
+    if let (Some(body), Some(template)) = (&mut sent.body, &hooked.body) {
+        body.content = pick(&template.content, &body.content);
+    }
+    sent
+}
+
+/// Shown in place of secret values, as front ends mask them.
+const SECRET_MASK: &str = "••••••••";
+
+/// The workspace's secret values that aren't encrypted (see
+/// [`Workspace::secret_values`]), longest first so one containing another is
+/// masked whole.
+fn secret_values() -> Vec<String> {
+    let workspace = get_workspace().lock().unwrap();
+    let mut values: Vec<String> = workspace
+        .secret_values()
+        .into_iter()
+        .filter(|v| !v.is_empty() && !vault::is_sealed(v))
+        .map(str::to_string)
+        .collect();
+    values.sort_by_key(|v| std::cmp::Reverse(v.len()));
+    values
+}
+
+/// `text` with each of `secrets` replaced by [`SECRET_MASK`].
+fn redact(text: &str, secrets: &[String]) -> String {
+    secrets.iter().fold(text.to_string(), |text, secret| {
+        text.replace(secret.as_str(), SECRET_MASK)
+    })
+}
+
+/// `request` as history keeps it: headers flagged `secret` or holding a secret
+/// value are masked whole (and flagged), and secret values elsewhere are masked.
+fn mask_secrets(request: &FfiRequest, secrets: &[String]) -> FfiRequest {
+    let mut masked = request.clone();
+    masked.url = redact(&masked.url, secrets);
+    for header in &mut masked.headers {
+        if header.secret || redact(&header.value, secrets) != header.value {
+            header.value = SECRET_MASK.to_string();
+            header.secret = true;
+        }
+    }
+    if let Some(body) = &mut masked.body {
+        body.content = redact(&body.content, secrets);
+    }
+    if let Some(auth) = &mut masked.auth {
+        for value in auth.params.values_mut() {
+            *value = redact(value, secrets);
+        }
+    }
+    masked
+}
+
+/// Resolve placeholders left after collection variables: `{{secret:NAME}}` through
+/// the Lua secrets command and `{{name}}` through `pigeon.variable`, in the URL,
+/// header values, body and auth params. Unknown names are left untouched.
+fn resolve_placeholders(
+    request: &mut FfiRequest,
+    auth: Option<&mut FfiAuth>,
+    lua: Option<&LuaRuntime>,
+) -> Result<(), String> {
+    let mut fields = vec![&mut request.url];
+    fields.extend(request.headers.iter_mut().map(|h| &mut h.value));
+    if let Some(body) = &mut request.body {
+        fields.push(&mut body.content);
+    }
+    if let Some(auth) = auth {
+        fields.extend(auth.params.values_mut());
+    }
+
+    for field in fields {
+        if !field.contains("{{") {
+            continue;
+        }
+
+        let error = std::cell::RefCell::new(None);
+        let rendered = template::render(field, |name| {
+            let value = match (name.strip_prefix(SECRET_PREFIX), lua) {
+                (Some(secret), Some(lua)) => lua
+                    .resolve_secret(secret.trim())
+                    .map(Some)
+                    .map_err(|e| format!("{e:#}")),
+                (Some(secret), None) => Err(format!(
+                    "cannot resolve secret `{}`: the Lua runtime is not initialized",
+                    secret.trim()
+                )),
+                (None, Some(lua)) => lua.resolve_variable(name).map_err(|e| format!("{e:#}")),
+                (None, None) => Ok(None),
+            };
+            match value {
+                Ok(value) => value,
+                Err(e) => {
+                    error.borrow_mut().get_or_insert(e);
+                    None
+                }
+            }
+        });
+        if let Some(e) = error.into_inner() {
+            return Err(e);
+        }
+        *field = rendered;
+    }
+
+    Ok(())
+}
+
+/// Send a request without running Lua hooks. Used by `pigeon.http`, which is
+/// called while the Lua state is already locked.
+///
+/// Logs and errors show `url` instead of the request's, i.e. the URL before
+/// `{{secret:NAME}}` placeholders were resolved, so secret values never reach them.
+async fn dispatch_request(parsed: FfiRequest, url: &str) -> FfiResponse {
+    // Created before polling so a concurrent shutdown can't be missed.
+    let shutdown = SHUTDOWN.notified();
+    tokio::select! {
+        response = perform_request(parsed, url) => response,
+        _ = shutdown => FfiResponse::failed(
+            FailureKind::Cancelled,
+            "request cancelled: library is shutting down",
+        ),
+    }
+}
+
+async fn perform_request(parsed: FfiRequest, url: &str) -> FfiResponse {
+    let mut in_flight = InFlight::start();
+    let method = parsed
+        .method
+        .parse::<reqwest::Method>()
+        .unwrap_or(reqwest::Method::GET);
+
+    logging::emit(
+        Level::Info,
+        "request.start",
+        serde_json::json!({ "method": method.as_str(), "url": url }),
+    );
+
+    let client = get_http_client();
+    let mut req = client.request(method.clone(), &parsed.url);
+
+    for h in parsed.headers {
+        if h.enabled {
+            req = req.header(&h.key, &h.value);
+        }
+    }
+
+    if let Some(body) = parsed.body {
+        if !body.content_type.trim().is_empty() {
+            req = req.header("Content-Type", body.content_type);
+        }
+        if !body.content.is_empty() {
+            req = req.body(body.content);
+        }
+    }
+
+    let start = std::time::Instant::now();
+    match req.send().await {
+        Ok(mut resp) => {
+            let status = resp.status().as_u16();
+            let status_text = resp.status().to_string();
+            let headers = resp
+                .headers()
+                .iter()
+                .map(|(k, v)| {
+                    (
+                        k.to_string(),
+                        String::from_utf8_lossy(v.as_bytes()).into_owned(),
+                    )
+                })
+                .collect::<Vec<_>>();
+            let content_type = resp
+                .headers()
+                .get(reqwest::header::CONTENT_TYPE)
+                .and_then(|v| v.to_str().ok())
+                .map(str::to_string);
+            // Read in chunks so `pigeon_status` can report progress on large bodies.
+            let mut bytes = Vec::new();
+            loop {
+                match resp.chunk().await {
+                    Ok(Some(chunk)) => {
+                        in_flight.receive(chunk.len());
+                        bytes.extend_from_slice(&chunk);
+                    }
+                    Ok(None) => break,
+                    // E.g. the timeout firing partway through: a cut-off body is no response.
+                    Err(e) => return send_failed(method.as_str(), url, &parsed.url, e),
+                }
+            }
+            let (body, body_base64) = decode_body(content_type.as_deref(), &bytes);
+            let duration_ms = start.elapsed().as_millis() as u64;
+
+            logging::emit(
+                Level::Info,
+                "request.finish",
+                serde_json::json!({
+                    "method": method.as_str(),
+                    "url": url,
+                    "status": status,
+                    "durationMs": duration_ms,
+                }),
+            );
+
+            FfiResponse {
+                status,
+                status_text,
+                headers,
+                body,
+                body_base64,
+                duration_ms,
+                failure: None,
+            }
+        }
+        Err(e) => send_failed(method.as_str(), url, &parsed.url, e),
+    }
+}
+
+/// Log a request to `resolved_url` that failed while sending or reading the body,
+/// and describe it. The resolved URL is left out; `url` is logged instead.
+fn send_failed(method: &str, url: &str, resolved_url: &str, e: reqwest::Error) -> FfiResponse {
+    let e = e.without_url();
+    logging::emit(
+        Level::Error,
+        "request.error",
+        serde_json::json!({
+            "method": method,
+            "url": url,
+            "error": e.to_string(),
+        }),
+    );
+    FfiResponse::from_send_error(&e, resolved_url)
+}
+
+/// Send an HTTP request described by a JSON string and return response JSON.
+///
+/// The request and response are added to the history (see `pigeon_history`), filed
+/// under the space named by the request's optional `space` (id or name), or the
+/// first space.
+///
+/// # Safety
+/// - `req_json` must be either NULL or point to a valid NUL-terminated C string.
+/// - Returned pointer must be freed by calling `pigeon_free_string`.
+#[no_mangle]
+pub unsafe extern "C" fn pigeon_send_request(req_json: *const c_char) -> *mut c_char {
+    let result = std::panic::catch_unwind(AssertUnwindSafe(|| {
+        if req_json.is_null() {
+            return string_to_c_char_ptr(json_error("req_json is null"));
+        }
+
+        let req_str = unsafe { CStr::from_ptr(req_json) };
+        let req_str = match req_str.to_str() {
+            Ok(s) => s,
+            Err(e) => return string_to_c_char_ptr(json_error(format!("invalid utf-8: {e}"))),
+        };
+
+        let parsed: FfiRequest = match serde_json::from_str(req_str) {
+            Ok(v) => v,
+            Err(e) => return string_to_c_char_ptr(json_error(format!("invalid json: {e}"))),
+        };
+
+        let response = get_tokio_runtime().block_on(execute_request(parsed));
+        let response_json = serde_json::to_string(&response)
+            .unwrap_or_else(|e| json_error(format!("serialize response failed: {e}")));
+
+        string_to_c_char_ptr(response_json)
+    }));
+
+    match result {
+        Ok(ptr) => ptr,
+        Err(_) => string_to_c_char_ptr(json_error("panic in pigeon_send_request")),
+    }
+}
+
+/// Send a request saved in the workspace (`pigeon.workspace.add_request` or
+/// `pigeon_workspace_save`) and return response JSON as `pigeon_send_request` does.
+///
+/// # Safety
+/// - `request` must be either NULL or point to a valid NUL-terminated C string
+///   holding the saved request's id or name.
+/// - `space` must be either NULL (the first space) or point to a valid
+///   NUL-terminated C string naming the space (id or name) whose history records it.
+/// - Returns the response, or an error-shaped response (`status: 0`) if there is
+///   no such request.
+/// - Returned pointer must be freed by calling `pigeon_free_string`.
+#[no_mangle]
+pub unsafe extern "C" fn pigeon_send_saved_request(
+    request: *const c_char,
+    space: *const c_char,
+) -> *mut c_char {
+    let result = std::panic::catch_unwind(AssertUnwindSafe(|| {
+        let (key, space) = match unsafe {
+            (
+                required_c_str(request, "request"),
+                optional_c_str(space, "space"),
+            )
+        } {
+            (Ok(k), Ok(s)) => (k, s),
+            (Err(e), _) | (_, Err(e)) => return string_to_c_char_ptr(json_error(e)),
+        };
+
+        let parsed = get_workspace()
+            .lock()
+            .unwrap()
+            .find_request(key)
+            .map(FfiRequest::from);
+        let response = match parsed {
+            Some(mut parsed) => {
+                parsed.space = space.map(str::to_string);
+                get_tokio_runtime().block_on(execute_request(parsed))
+            }
+            None => FfiResponse::error(format!("unknown saved request: {key}")),
+        };
+        let response_json = serde_json::to_string(&response)
+            .unwrap_or_else(|e| json_error(format!("serialize response failed: {e}")));
+
+        string_to_c_char_ptr(response_json)
+    }));
+
+    match result {
+        Ok(ptr) => ptr,
+        Err(_) => string_to_c_char_ptr(json_error("panic in pigeon_send_saved_request")),
+    }
+}
+
+/// Send a GraphQL operation and return the response with `data`/`errors` unpacked.
+///
+/// # Safety
+/// - `req_json` must be either NULL or point to a valid NUL-terminated C string of the form
+///   `{"url": "...", "query": "...", "variables"?: {...}, "operationName"?: "...", "headers"?: [...]}`.
+/// - Returns `{status, statusText, headers, durationMs, data, errors, partial, body}` or
+///   `{"error": "...message..."}` if the input is invalid.
+/// - Returned pointer must be freed by calling `pigeon_free_string`.
+#[no_mangle]
+pub unsafe extern "C" fn pigeon_send_graphql(req_json: *const c_char) -> *mut c_char {
+    let result = std::panic::catch_unwind(AssertUnwindSafe(|| {
+        let req_json = match unsafe { required_c_str(req_json, "req_json") } {
+            Ok(s) => s,
+            Err(e) => return string_to_c_char_ptr(json_result_error(e)),
+        };
+
+        let parsed: GraphqlRequest = match serde_json::from_str(req_json) {
+            Ok(v) => v,
+            Err(e) => return string_to_c_char_ptr(json_result_error(format!("invalid json: {e}"))),
+        };
+
+        let response = get_tokio_runtime().block_on(execute_request(parsed.into_ffi_request()));
+        string_to_c_char_ptr(
+            serde_json::to_string(&GraphqlResponse::from(response))
+                .unwrap_or_else(|e| json_result_error(format!("serialize response failed: {e}"))),
+        )
+    }));
+
+    match result {
+        Ok(ptr) => ptr,
+        Err(_) => string_to_c_char_ptr(json_result_error("panic in pigeon_send_graphql")),
+    }
+}
+
+/// Introspect a GraphQL endpoint, e.g. for completion and a schema explorer in
+/// a query editor.
+///
+/// # Safety
+/// - `req_json` must be either NULL or point to a valid NUL-terminated C string of the form
+///   `{"url": "...", "headers"?: [...], "auth"?: {...}}`.
+/// - Returns `{"schema": {"queryType": "Query", "mutationType": ..., "subscriptionType": ...,
+///   "types": [{"name", "kind", "description", "fields": [{"name", "type": "[User!]!",
+///   "description", "args": [...], "deprecated"}], "inputFields", "enumValues",
+///   "possibleTypes"}]}}` or `{"error": "...message..."}`.
+/// - Returned pointer must be freed by calling `pigeon_free_string`.
+#[no_mangle]
+pub unsafe extern "C" fn pigeon_graphql_schema(req_json: *const c_char) -> *mut c_char {
+    let result = std::panic::catch_unwind(AssertUnwindSafe(|| {
+        let req_json = match unsafe { required_c_str(req_json, "req_json") } {
+            Ok(s) => s,
+            Err(e) => return string_to_c_char_ptr(json_result_error(e)),
+        };
+
+        let parsed: SchemaRequest = match serde_json::from_str(req_json) {
+            Ok(v) => v,
+            Err(e) => return string_to_c_char_ptr(json_result_error(format!("invalid json: {e}"))),
+        };
+
+        let request = parsed.into_graphql_request().into_ffi_request();
+        let response =
+            GraphqlResponse::from(get_tokio_runtime().block_on(execute_request(request)));
+        if response.data.is_null() {
+            let message = response
+                .errors
+                .first()
+                .and_then(|e| e["message"].as_str())
+                .map(str::to_string)
+                .unwrap_or_else(|| format!("HTTP {} {}", response.status, response.status_text));
+            return string_to_c_char_ptr(json_result_error(format!(
+                "introspection failed: {message}"
+            )));
+        }
+        match Schema::from_introspection(&response.data) {
+            Ok(schema) => string_to_c_char_ptr(serde_json::json!({ "schema": schema }).to_string()),
+            Err(e) => string_to_c_char_ptr(json_result_error(format!("{e:#}"))),
+        }
+    }));
+
+    match result {
+        Ok(ptr) => ptr,
+        Err(_) => string_to_c_char_ptr(json_result_error("panic in pigeon_graphql_schema")),
+    }
+}
+
+/// Maximum number of requests from one batch that are in flight at once.
+const BATCH_CONCURRENCY: usize = 8;
+
+/// Send a JSON array of requests concurrently and return an array of responses
+/// in the same order.
+///
+/// Each element is parsed independently, so one malformed entry yields an error
+/// response at its index instead of failing the whole batch.
+///
+/// # Safety
+/// - `requests_json` must be either NULL or point to a valid NUL-terminated C string.
+/// - Returns a JSON array of responses, or `{"error": "...message..."}` if the
+///   input is not a JSON array.
+/// - Returned pointer must be freed by calling `pigeon_free_string`.
+#[no_mangle]
+pub unsafe extern "C" fn pigeon_send_batch(requests_json: *const c_char) -> *mut c_char {
+    let result = std::panic::catch_unwind(AssertUnwindSafe(|| {
+        let requests_json = match unsafe { required_c_str(requests_json, "requests_json") } {
+            Ok(s) => s,
+            Err(e) => return string_to_c_char_ptr(json_result_error(e)),
+        };
+
+        let items: Vec<serde_json::Value> = match serde_json::from_str(requests_json) {
+            Ok(v) => v,
+            Err(e) => return string_to_c_char_ptr(json_result_error(format!("invalid json: {e}"))),
+        };
+
+        let responses = get_tokio_runtime().block_on(async move {
+            let semaphore = Arc::new(tokio::sync::Semaphore::new(BATCH_CONCURRENCY));
+
+            let handles = items
+                .into_iter()
+                .map(|item| {
+                    let semaphore = semaphore.clone();
+                    tokio::spawn(async move {
+                        let parsed: FfiRequest = match serde_json::from_value(item) {
+                            Ok(v) => v,
+                            Err(e) => return FfiResponse::error(format!("invalid request: {e}")),
+                        };
+                        let _permit = semaphore.acquire_owned().await;
+                        execute_request(parsed).await
+                    })
+                })
+                .collect::<Vec<_>>();
+
+            let mut responses = Vec::with_capacity(handles.len());
+            for handle in handles {
+                responses.push(
+                    handle.await.unwrap_or_else(|e| {
+                        FfiResponse::error(format!("request task failed: {e}"))
+                    }),
+                );
+            }
+            responses
+        });
+
+        string_to_c_char_ptr(
+            serde_json::to_string(&responses)
+                .unwrap_or_else(|e| json_result_error(format!("serialize responses failed: {e}"))),
+        )
+    }));
+
+    match result {
+        Ok(ptr) => ptr,
+        Err(_) => string_to_c_char_ptr(json_result_error("panic in pigeon_send_batch")),
+    }
+}
+
+/// Buffer-based variant of `pigeon_send_request` that avoids C-string round trips
+/// for large payloads.
+///
+/// The request is decoded and the response encoded with `encoding`
+/// (0 = JSON, 1 = MessagePack with named fields); the payload shapes match
+/// `pigeon_send_request`. Errors are returned as an error-shaped response, in JSON
+/// if `encoding` itself is unknown.
+///
+/// # Safety
+/// - `req` must point to `req_len` readable bytes (or be NULL).
+/// - Returns a buffer laid out as `[len: u64 little-endian][payload; len]`.
+/// - Returned pointer must be freed by calling `pigeon_free_buffer`.
+#[no_mangle]
+pub unsafe extern "C" fn pigeon_send_request_encoded(
+    req: *const u8,
+    req_len: usize,
+    encoding: u32,
+) -> *mut u8 {
+    let result = std::panic::catch_unwind(AssertUnwindSafe(|| {
+        let encoding = match Encoding::try_from(encoding) {
+            Ok(e) => e,
+            Err(e) => return codec::into_buffer(json_error(e.to_string()).into_bytes()),
+        };
+
+        let encode = |response: &FfiResponse| {
+            encoding.encode(response).unwrap_or_else(|e| {
+                json_error(format!("serialize response failed: {e}")).into_bytes()
+            })
+        };
+
+        if req.is_null() {
+            return codec::into_buffer(encode(&FfiResponse::error("req is null")));
+        }
+
+        let bytes = unsafe { std::slice::from_raw_parts(req, req_len) };
+        let parsed: FfiRequest = match encoding.decode(bytes) {
+            Ok(v) => v,
+            Err(e) => {
+                return codec::into_buffer(encode(&FfiResponse::error(format!(
+                    "invalid request: {e}"
+                ))))
+            }
+        };
+
+        let response = get_tokio_runtime().block_on(execute_request(parsed));
+        codec::into_buffer(encode(&response))
+    }));
+
+    match result {
+        Ok(ptr) => ptr,
+        Err(_) => {
+            codec::into_buffer(json_error("panic in pigeon_send_request_encoded").into_bytes())
+        }
+    }
+}
+
+/// Free a buffer returned by `pigeon_send_request_encoded`.
+///
+/// # Safety
+/// - `ptr` must be either NULL or a pointer previously returned by a buffer-returning
+///   `pigeon_*` function.
+/// - Must not be called twice for the same pointer.
+#[no_mangle]
+pub unsafe extern "C" fn pigeon_free_buffer(ptr: *mut u8) {
+    if ptr.is_null() {
+        return;
+    }
+    unsafe { codec::free_buffer(ptr) };
+}
+
+/// Free a string returned by any `pigeon_*` function.
+///
+/// # Safety
+/// - `ptr` must be either NULL or a pointer previously returned by a `pigeon_*` function.
+/// - Must not be called twice for the same pointer.
+#[no_mangle]
+pub unsafe extern "C" fn pigeon_free_string(ptr: *mut c_char) {
+    if ptr.is_null() {
+        return;
+    }
+    unsafe {
+        drop(CString::from_raw(ptr));
+    }
+}
+
+/// Build a fresh Lua runtime: run `config.lua` (if present), then the enabled plugins.
+///
+/// Plugin failures are logged and recorded per plugin rather than failing the load.
+fn init_lua_runtime(config_dir: &Path, error_event: &str) -> Result<LuaRuntime, String> {
+    let runtime =
+        LuaRuntime::new(config_dir).map_err(|e| format!("Failed to create Lua runtime: {e}"))?;
+
+    let config_file = config_dir.join("config.lua");
+    if config_file.exists() {
+        if let Err(e) = runtime.load_file(&config_file) {
+            log_lua_error(error_event, &e);
+            return Err(format!("Failed to load config file: {e}"));
+        }
+    }
+
+    // Each profile runs after config.lua and those before it, so it can override them.
+    for name in PROFILES.read().unwrap().iter() {
+        let path = profiles::path(config_dir, name)?;
+        if let Err(e) = runtime.load_file(&path) {
+            log_lua_error(error_event, &e);
+            return Err(format!("Failed to load profile `{name}`: {e}"));
+        }
+    }
+
+    match runtime.load_plugins() {
+        Ok(plugins) => {
+            for plugin in plugins {
+                if let Some(error) = plugin.error {
+                    logging::emit(
+                        Level::Error,
+                        "plugin.load_error",
+                        serde_json::json!({ "plugin": plugin.name, "error": error }),
+                    );
+                }
+            }
+        }
+        Err(e) => log_lua_error("plugin.discover_error", &e),
+    }
+
+    Ok(runtime)
+}
+
+/// Evaluate the config in a fresh Lua state and swap it in on success.
+fn reload_lua_runtime(config_dir: &Path) -> Result<(), String> {
+    if !config_dir.join("config.lua").exists() {
+        return Err("config file not found".to_string());
+    }
+
+    let runtime = Arc::new(
+        init_lua_runtime(config_dir, "lua.reload_error")
+            .map_err(|e| format!("Failed to reload config: {e}"))?,
+    );
+    *LUA_RUNTIME.write().unwrap() = Some(runtime.clone());
+    follow_workspace_storage(&runtime);
+    follow_history_retention(Some(&runtime));
+    follow_http_settings(Some(&runtime));
+    CONFIG_GENERATION.fetch_add(1, Ordering::SeqCst);
+    Ok(())
+}
+
+/// Watcher callback: reload unless the runtime was shut down meanwhile.
+fn reload_on_change(path: &Path) {
+    let Some(config_dir) = get_lua_runtime().map(|rt| rt.config_dir().to_path_buf()) else {
+        return;
+    };
+    let path = path.display().to_string();
+    match reload_lua_runtime(&config_dir) {
+        Ok(()) => logging::emit(
+            Level::Info,
+            "config.reloaded",
+            serde_json::json!({ "path": path }),
+        ),
+        Err(e) => logging::emit(
+            Level::Error,
+            "config.reload_error",
+            serde_json::json!({ "path": path, "error": e }),
+        ),
+    }
+}
+
+/// Initialize the Lua runtime and load the configuration file and plugins, then run
+/// the `pigeon.on_workspace_load` hooks on the workspace.
+///
+/// Profiles named in the `PIGEON_PROFILE` environment variable are applied as with
+/// `pigeon_load_config_with_profiles`.
+///
+/// # Safety
+/// - Returns a JSON string: `{"success": true}` on success or
+///   `{"error": "...message..."}` on failure.
+/// - Returned pointer must be freed by calling `pigeon_free_string`.
+#[no_mangle]
+pub unsafe extern "C" fn pigeon_load_config() -> *mut c_char {
+    unsafe { pigeon_load_config_with_profiles(std::ptr::null()) }
+}
+
+/// Like `pigeon_load_config`, with `profiles/<name>.lua` overlays run after
+/// `config.lua` in the order given, so later files override earlier settings.
+///
+/// `config.lua` and the `lua/`, `plugins/` and `profiles/` directories are then
+/// watched, and reloaded as with `pigeon_reload_config` when they change. Each
+/// automatic reload is logged as `config.reloaded` (or `config.reload_error`) and
+/// bumps `pigeon_config_generation`. Reloads keep the selected profiles.
+///
+/// # Safety
+/// - `profiles` must be either NULL (use `PIGEON_PROFILE`, if set) or point to a
+///   valid NUL-terminated C string listing profile names separated by commas,
+///   such as `"work,ci"`. A profile without a file fails the load.
+/// - Returns a JSON string: `{"success": true}` on success or
+///   `{"error": "...message..."}` on failure.
+/// - Returned pointer must be freed by calling `pigeon_free_string`.
+#[no_mangle]
+pub unsafe extern "C" fn pigeon_load_config_with_profiles(profiles: *const c_char) -> *mut c_char {
+    let result = std::panic::catch_unwind(AssertUnwindSafe(|| {
+        let profiles = match unsafe { optional_c_str(profiles, "profiles") } {
+            Ok(list) => profiles::select(list),
+            Err(e) => return string_to_c_char_ptr(json_result_error(e)),
+        };
+        let config_dir = match paths::config_dir() {
+            Ok(dir) => dir,
+            Err(e) => return string_to_c_char_ptr(json_result_error(e)),
+        };
+        // An initialized runtime keeps its profiles; the load is rejected below.
+        if get_lua_runtime().is_none() {
+            *PROFILES.write().unwrap() = profiles;
+        }
+
+        let runtime = match init_lua_runtime(&config_dir, "lua.load_error") {
+            Ok(rt) => rt,
+            Err(e) => return string_to_c_char_ptr(json_result_error(e)),
+        };
+
+        // Store runtime globally. If the slot is taken, the runtime was already initialized
+        // and we should report an error instead of silently succeeding.
+        let mut slot = LUA_RUNTIME.write().unwrap();
+        if slot.is_some() {
+            return string_to_c_char_ptr(json_result_error(
+                "Lua runtime already initialized; use pigeon_reload_config instead",
+            ));
+        }
+        let runtime = Arc::new(runtime);
+        *slot = Some(runtime.clone());
+        drop(slot);
+
+        follow_workspace_storage(&runtime);
+        follow_history_retention(Some(&runtime));
+        follow_http_settings(Some(&runtime));
+        // Not locked while hooks run, since they may call `pigeon.workspace`.
+        let workspace = get_workspace().lock().unwrap().clone();
+        let workspace = apply_workspace_hooks(&runtime, WorkspaceEvent::Load, workspace);
+        *get_workspace().lock().unwrap() = workspace;
+        workspace_changed();
+        CONFIG_GENERATION.fetch_add(1, Ordering::SeqCst);
+
+        if let Err(e) = watcher::start(&config_dir, reload_on_change) {
+            logging::emit(
+                Level::Error,
+                "config.watch_error",
+                serde_json::json!({ "error": format!("{e:#}") }),
+            );
+        }
+
+        // Return success JSON object (not "null" string)
+        string_to_c_char_ptr(json_success())
+    }));
+
+    match result {
+        Ok(ptr) => ptr,
+        Err(_) => string_to_c_char_ptr(json_result_error(
+            "panic in pigeon_load_config_with_profiles",
+        )),
+    }
+}
+
+/// Report what the core is busy with, for a host's status bar. Cheap enough to
+/// poll every second.
+///
+/// # Safety
+/// - Returns `{"inFlight": 2, "received": 5120, "jobs": 1, "run": {"name", "done",
+///   "total"} | null, "mock": "http://127.0.0.1:PORT" | null, "profiles": ["work"]}`:
+///   requests being sent and the body bytes they have received so far,
+///   `pigeon.schedule` jobs, the collection run in progress, the mock server's URL
+///   and the active profiles.
+/// - Returned pointer must be freed by calling `pigeon_free_string`.
+#[no_mangle]
+pub unsafe extern "C" fn pigeon_status() -> *mut c_char {
+    let result = std::panic::catch_unwind(AssertUnwindSafe(|| {
+        string_to_c_char_ptr(
+            serde_json::json!({
+                "inFlight": IN_FLIGHT.load(Ordering::Relaxed),
+                "received": RECEIVED.load(Ordering::Relaxed),
+                "jobs": lua::schedule::active_jobs(),
+                "run": *COLLECTION_RUN.lock().unwrap(),
+                "mock": mock::url(),
+                "profiles": *PROFILES.read().unwrap(),
+            })
+            .to_string(),
+        )
+    }));
+
+    match result {
+        Ok(ptr) => ptr,
+        Err(_) => string_to_c_char_ptr(json_result_error("panic in pigeon_status")),
+    }
+}
+
+/// Report the profiles in `profiles/` and the ones applied to the current config.
+///
+/// # Safety
+/// - Returns `{"active": ["work"], "available": ["home", "work"]}`, or
+///   `{"error": "...message..."}` if the config directory can't be resolved.
+/// - Returned pointer must be freed by calling `pigeon_free_string`.
+#[no_mangle]
+pub unsafe extern "C" fn pigeon_profiles() -> *mut c_char {
+    let result = std::panic::catch_unwind(AssertUnwindSafe(|| {
+        let config_dir = match get_lua_runtime() {
+            Some(rt) => rt.config_dir().to_path_buf(),
+            None => match paths::config_dir() {
+                Ok(dir) => dir,
+                Err(e) => return string_to_c_char_ptr(json_result_error(e)),
+            },
+        };
+
+        string_to_c_char_ptr(
+            serde_json::json!({
+                "active": *PROFILES.read().unwrap(),
+                "available": profiles::available(&config_dir),
+            })
+            .to_string(),
+        )
+    }));
+
+    match result {
+        Ok(ptr) => ptr,
+        Err(_) => string_to_c_char_ptr(json_result_error("panic in pigeon_profiles")),
+    }
+}
+
+/// Reload the configuration file and plugins.
+///
+/// The config is evaluated in a brand-new Lua state that replaces the current one
+/// only on success, so hooks and plugins aren't registered twice and a broken
+/// config keeps the previous one active.
+///
+/// # Safety
+/// - Returns a JSON string: `{"success": true}` on success or
+///   `{"error": "...message..."}` on failure.
+/// - Returned pointer must be freed by calling `pigeon_free_string`.
+#[no_mangle]
+pub unsafe extern "C" fn pigeon_reload_config() -> *mut c_char {
+    let result = std::panic::catch_unwind(AssertUnwindSafe(|| {
+        let config_dir = match get_lua_runtime() {
+            Some(rt) => rt.config_dir().to_path_buf(),
+            None => return string_to_c_char_ptr(json_result_error("Lua runtime not initialized")),
+        };
+
+        match reload_lua_runtime(&config_dir) {
+            // Return success JSON object (not "null" string)
+            Ok(()) => string_to_c_char_ptr(json_success()),
+            Err(e) => string_to_c_char_ptr(json_result_error(e)),
+        }
+    }));
+
+    match result {
+        Ok(ptr) => ptr,
+        Err(e) => string_to_c_char_ptr(json_result_error(format!(
+            "panic in pigeon_reload_config: {e:?}"
+        ))),
+    }
+}
+
+/// List plugins discovered under `plugins/` in the config directory.
+///
+/// `version` and `requires` come from the plugin's `plugin.setup{...}` manifest;
+/// a missing or mismatched dependency shows up as the plugin's `error`.
+///
+/// # Safety
+/// - Returns `{"plugins": [{"name", "path", "enabled", "loaded", "error", "version",
+///   "requires"}, ...]}` or `{"error": "...message..."}`.
+/// - Returned pointer must be freed by calling `pigeon_free_string`.
+#[no_mangle]
+pub unsafe extern "C" fn pigeon_plugins_list() -> *mut c_char {
+    let result = std::panic::catch_unwind(AssertUnwindSafe(|| {
+        let runtime = match get_lua_runtime() {
+            Some(rt) => rt,
+            None => return string_to_c_char_ptr(json_result_error("Lua runtime not initialized")),
+        };
+
+        string_to_c_char_ptr(serde_json::json!({ "plugins": runtime.plugins() }).to_string())
+    }));
+
+    match result {
+        Ok(ptr) => ptr,
+        Err(_) => string_to_c_char_ptr(json_result_error("panic in pigeon_plugins_list")),
+    }
+}
+
+/// Enable or disable a plugin by name; the choice is persisted in the config directory.
+///
+/// Enabling loads the plugin immediately. Disabling a loaded plugin takes effect on
+/// the next `pigeon_reload_config`.
+///
+/// # Safety
+/// - `name` must be either NULL or point to a valid NUL-terminated C string.
+/// - Returns `{"plugin": {...}}` with the updated state or `{"error": "...message..."}`.
+/// - Returned pointer must be freed by calling `pigeon_free_string`.
+#[no_mangle]
+pub unsafe extern "C" fn pigeon_plugin_set_enabled(
+    name: *const c_char,
+    enabled: bool,
+) -> *mut c_char {
+    let result = std::panic::catch_unwind(AssertUnwindSafe(|| {
+        let name = match unsafe { required_c_str(name, "name") } {
+            Ok(n) => n,
+            Err(e) => return string_to_c_char_ptr(json_result_error(e)),
+        };
+
+        let runtime = match get_lua_runtime() {
+            Some(rt) => rt,
+            None => return string_to_c_char_ptr(json_result_error("Lua runtime not initialized")),
+        };
+
+        match runtime.set_plugin_enabled(name, enabled) {
+            Ok(plugin) => {
+                if let Some(error) = &plugin.error {
+                    logging::emit(
+                        Level::Error,
+                        "plugin.load_error",
+                        serde_json::json!({ "plugin": plugin.name, "error": error }),
+                    );
+                }
+                string_to_c_char_ptr(serde_json::json!({ "plugin": plugin }).to_string())
+            }
+            Err(e) => string_to_c_char_ptr(json_result_error(format!("{e:#}"))),
+        }
+    }));
+
+    match result {
+        Ok(ptr) => ptr,
+        Err(_) => string_to_c_char_ptr(json_result_error("panic in pigeon_plugin_set_enabled")),
+    }
+}
+
+/// List cookies in the shared cookie jar.
+///
+/// # Safety
+/// - `domain` must be either NULL (all cookies) or point to a valid NUL-terminated C string.
+/// - Returns a JSON array of cookies or `{"error": "...message..."}` on failure.
+/// - Returned pointer must be freed by calling `pigeon_free_string`.
+#[no_mangle]
+pub unsafe extern "C" fn pigeon_cookies_list(domain: *const c_char) -> *mut c_char {
+    let result = std::panic::catch_unwind(AssertUnwindSafe(|| {
+        let domain = match unsafe { optional_c_str(domain, "domain") } {
+            Ok(d) => d,
+            Err(e) => return string_to_c_char_ptr(json_result_error(e)),
+        };
+
+        let cookies = get_cookie_jar().list(domain);
+        string_to_c_char_ptr(
+            serde_json::to_string(&cookies)
+                .unwrap_or_else(|e| json_result_error(format!("serialize cookies failed: {e}"))),
+        )
+    }));
+
+    match result {
+        Ok(ptr) => ptr,
+        Err(_) => string_to_c_char_ptr(json_result_error("panic in pigeon_cookies_list")),
+    }
+}
+
+/// List the cookies the shared cookie jar would attach to a request to `url`.
+///
+/// # Safety
+/// - `url` must be either NULL or point to a valid NUL-terminated C string.
+/// - Returns a JSON array of cookies or `{"error": "...message..."}` on failure.
+/// - Returned pointer must be freed by calling `pigeon_free_string`.
+#[no_mangle]
+pub unsafe extern "C" fn pigeon_cookies_for_url(url: *const c_char) -> *mut c_char {
+    let result = std::panic::catch_unwind(AssertUnwindSafe(|| {
+        let url = match unsafe { required_c_str(url, "url") } {
+            Ok(s) => s,
+            Err(e) => return string_to_c_char_ptr(json_result_error(e)),
+        };
+
+        match get_cookie_jar().for_url(url) {
+            Ok(cookies) => {
+                string_to_c_char_ptr(serde_json::to_string(&cookies).unwrap_or_else(|e| {
+                    json_result_error(format!("serialize cookies failed: {e}"))
+                }))
+            }
+            Err(e) => string_to_c_char_ptr(json_result_error(format!("invalid url: {e}"))),
+        }
+    }));
+
+    match result {
+        Ok(ptr) => ptr,
+        Err(_) => string_to_c_char_ptr(json_result_error("panic in pigeon_cookies_for_url")),
+    }
+}
+
+/// Insert or replace a cookie in the shared cookie jar.
+///
+/// # Safety
+/// - `cookie_json` must be either NULL or point to a valid NUL-terminated C string
+///   of the form `{"url": "...", "name": "...", "value": "...", "domain"?: "...", "path"?: "...",
+///   "secure"?: bool, "httpOnly"?: bool, "expires"?: "RFC 3339"}`.
+/// - Returns `{"success": true}` or `{"error": "...message..."}`.
+/// - Returned pointer must be freed by calling `pigeon_free_string`.
+#[no_mangle]
+pub unsafe extern "C" fn pigeon_cookies_set(cookie_json: *const c_char) -> *mut c_char {
+    let result = std::panic::catch_unwind(AssertUnwindSafe(|| {
+        let cookie_json = match unsafe { required_c_str(cookie_json, "cookie_json") } {
+            Ok(s) => s,
+            Err(e) => return string_to_c_char_ptr(json_result_error(e)),
+        };
+
+        let cookie: NewCookie = match serde_json::from_str(cookie_json) {
+            Ok(c) => c,
+            Err(e) => return string_to_c_char_ptr(json_result_error(format!("invalid json: {e}"))),
+        };
+
+        match get_cookie_jar().set(cookie) {
+            Ok(()) => string_to_c_char_ptr(json_success()),
+            Err(e) => string_to_c_char_ptr(json_result_error(e.to_string())),
+        }
+    }));
+
+    match result {
+        Ok(ptr) => ptr,
+        Err(_) => string_to_c_char_ptr(json_result_error("panic in pigeon_cookies_set")),
+    }
+}
+
+/// Remove one cookie from the shared cookie jar.
+///
+/// # Safety
+/// - `key_json` must be either NULL or point to a valid NUL-terminated C string
+///   of the form `{"domain": "...", "path": "...", "name": "..."}`, as listed by
+///   `pigeon_cookies_list`.
+/// - Returns `{"removed": true|false}` or `{"error": "...message..."}`.
+/// - Returned pointer must be freed by calling `pigeon_free_string`.
+#[no_mangle]
+pub unsafe extern "C" fn pigeon_cookies_remove(key_json: *const c_char) -> *mut c_char {
+    let result = std::panic::catch_unwind(AssertUnwindSafe(|| {
+        let key_json = match unsafe { required_c_str(key_json, "key_json") } {
+            Ok(s) => s,
+            Err(e) => return string_to_c_char_ptr(json_result_error(e)),
+        };
+
+        let key: CookieKey = match serde_json::from_str(key_json) {
+            Ok(k) => k,
+            Err(e) => return string_to_c_char_ptr(json_result_error(format!("invalid json: {e}"))),
+        };
+
+        let removed = get_cookie_jar().remove(&key);
+        string_to_c_char_ptr(serde_json::json!({ "removed": removed }).to_string())
+    }));
+
+    match result {
+        Ok(ptr) => ptr,
+        Err(_) => string_to_c_char_ptr(json_result_error("panic in pigeon_cookies_remove")),
+    }
+}
+
+/// Remove every cookie from the shared cookie jar.
+///
+/// # Safety
+/// - Returns `{"success": true}`.
+/// - Returned pointer must be freed by calling `pigeon_free_string`.
+#[no_mangle]
+pub unsafe extern "C" fn pigeon_cookies_clear() -> *mut c_char {
+    let result = std::panic::catch_unwind(AssertUnwindSafe(|| {
+        get_cookie_jar().clear();
+        string_to_c_char_ptr(json_success())
+    }));
+
+    match result {
+        Ok(ptr) => ptr,
+        Err(_) => string_to_c_char_ptr(json_result_error("panic in pigeon_cookies_clear")),
+    }
+}
+
+/// Evaluate a Lua snippet in the initialized Lua runtime.
+///
+/// # Safety
+/// - `script` must be either NULL or point to a valid NUL-terminated C string.
+/// - Returns `{"result": <value>}` on success or `{"error": "...message..."}` on failure.
+/// - Returned pointer must be freed by calling `pigeon_free_string`.
+#[no_mangle]
+pub unsafe extern "C" fn pigeon_eval_lua(script: *const c_char) -> *mut c_char {
+    let result = std::panic::catch_unwind(AssertUnwindSafe(|| {
+        let script = match unsafe { required_c_str(script, "script") } {
+            Ok(s) => s,
+            Err(e) => return string_to_c_char_ptr(json_result_error(e)),
+        };
+
+        let runtime = match get_lua_runtime() {
+            Some(rt) => rt,
+            None => return string_to_c_char_ptr(json_result_error("Lua runtime not initialized")),
+        };
+
+        match runtime.eval(script) {
+            Ok(value) => string_to_c_char_ptr(serde_json::json!({ "result": value }).to_string()),
+            Err(e) => {
+                log_lua_error("lua.eval_error", &e);
+                string_to_c_char_ptr(json_result_error(format!("{e:#}")))
+            }
+        }
+    }));
+
+    match result {
+        Ok(ptr) => ptr,
+        Err(_) => string_to_c_char_ptr(json_result_error("panic in pigeon_eval_lua")),
+    }
+}
+
+/// Read a value written by the Lua config, addressed by dotted path. A setting
+/// overridden with `pigeon_settings_set` reads as the override.
+///
+/// # Safety
+/// - `path` must be either NULL (the whole config) or point to a valid NUL-terminated
+///   C string such as `"theme.mode"` or `"http.timeout"`.
+/// - Returns `{"value": <value>}` (`null` when unset) or `{"error": "...message..."}`.
+/// - Returned pointer must be freed by calling `pigeon_free_string`.
+#[no_mangle]
+pub unsafe extern "C" fn pigeon_config_get(path: *const c_char) -> *mut c_char {
+    let result = std::panic::catch_unwind(AssertUnwindSafe(|| {
+        let path = match unsafe { optional_c_str(path, "path") } {
+            Ok(p) => p.unwrap_or(""),
+            Err(e) => return string_to_c_char_ptr(json_result_error(e)),
+        };
+
+        if let Some(value) = get_settings().lock().unwrap().get(path) {
+            return string_to_c_char_ptr(serde_json::json!({ "value": value }).to_string());
+        }
+
+        let runtime = match get_lua_runtime() {
+            Some(rt) => rt,
+            None => return string_to_c_char_ptr(json_result_error("Lua runtime not initialized")),
+        };
+
+        match runtime.config_get(path) {
+            Ok(value) => string_to_c_char_ptr(serde_json::json!({ "value": value }).to_string()),
+            Err(e) => string_to_c_char_ptr(json_result_error(format!("{e:#}"))),
+        }
+    }));
+
+    match result {
+        Ok(ptr) => ptr,
+        Err(_) => string_to_c_char_ptr(json_result_error("panic in pigeon_config_get")),
+    }
+}
+
+/// `{"settings": [...]}` for every setting a settings view can change.
+fn settings_json() -> String {
+    let lua = get_lua_runtime();
+    let config = |path: &str| {
+        lua.as_ref()
+            .and_then(|lua| lua.config_get(path).ok())
+            .unwrap_or(serde_json::Value::Null)
+    };
+    let settings = get_settings().lock().unwrap().describe(config);
+    serde_json::json!({ "settings": settings }).to_string()
+}
+
+/// List the settings a host's settings view can change (timeouts, proxy, TLS,
+/// theme, history retention, locale).
+///
+/// # Safety
+/// - Returns `{"settings": [{"path": "http.timeout", "type": "number", "value": 30,
+///   "config": 30, "overridden": false}, ...]}`, where `type` is `"number"`,
+///   `"string"`, `"boolean"` or `"choice"` (with `"choices"`), `config` is the
+///   value from `config.lua` and `value` the one in effect.
+/// - Returned pointer must be freed by calling `pigeon_free_string`.
+#[no_mangle]
+pub unsafe extern "C" fn pigeon_settings() -> *mut c_char {
+    let result =
+        std::panic::catch_unwind(AssertUnwindSafe(|| string_to_c_char_ptr(settings_json())));
+
+    match result {
+        Ok(ptr) => ptr,
+        Err(_) => string_to_c_char_ptr(json_result_error("panic in pigeon_settings")),
+    }
+}
+
+/// Override a setting over `config.lua`, or go back to the config's value.
+///
+/// Overrides are saved in the data directory and apply right away: the HTTP client
+/// is rebuilt, history is pruned to new limits, and `pigeon_config_generation` is
+/// bumped so hosts re-read values such as `theme.mode` (which `pigeon_config_get`
+/// reports from the override).
+///
+/// # Safety
+/// - `change_json` must be either NULL or point to a valid NUL-terminated C string
+///   of the form `{"path": "http.timeout", "value": 30}`; a `null` value removes
+///   the override.
+/// - Returns the settings as `pigeon_settings` does, or `{"error": "...message..."}`.
+/// - Returned pointer must be freed by calling `pigeon_free_string`.
+#[no_mangle]
+pub unsafe extern "C" fn pigeon_settings_set(change_json: *const c_char) -> *mut c_char {
+    #[derive(Deserialize)]
+    struct Change {
+        path: String,
+        #[serde(default)]
+        value: serde_json::Value,
+    }
+
+    let result = std::panic::catch_unwind(AssertUnwindSafe(|| {
+        let change_json = match unsafe { required_c_str(change_json, "change_json") } {
+            Ok(s) => s,
+            Err(e) => return string_to_c_char_ptr(json_result_error(e)),
+        };
+        let change: Change = match serde_json::from_str(change_json) {
+            Ok(c) => c,
+            Err(e) => return string_to_c_char_ptr(json_result_error(format!("invalid json: {e}"))),
+        };
+
+        {
+            let mut overrides = get_settings().lock().unwrap();
+            let mut updated = overrides.clone();
+            if let Err(e) = updated.set(&change.path, change.value) {
+                return string_to_c_char_ptr(json_result_error(format!("{e:#}")));
+            }
+            let saved = paths::data_dir()
+                .map_err(anyhow::Error::msg)
+                .and_then(|dir| updated.save(&dir.join(SETTINGS_FILE)));
+            if let Err(e) = saved {
+                return string_to_c_char_ptr(json_result_error(format!("{e:#}")));
+            }
+            *overrides = updated;
+        }
+
+        let lua = get_lua_runtime();
+        follow_http_settings(lua.as_deref());
+        follow_history_retention(lua.as_deref());
+        CONFIG_GENERATION.fetch_add(1, Ordering::SeqCst);
+        string_to_c_char_ptr(settings_json())
+    }));
+
+    match result {
+        Ok(ptr) => ptr,
+        Err(_) => string_to_c_char_ptr(json_result_error("panic in pigeon_settings_set")),
+    }
+}
+
+/// The session a host saved with `pigeon_session_save`, to reopen where it left off.
+///
+/// # Safety
+/// - Returns `{"session": {"window": {"width", "height", "x", "y", "maximized"},
+///   "space": "...", "tabs": [...], "activeTab": 0, "libraryTab": "...", "theme":
+///   "..."}}`, `{"session": null}` when none was saved, or `{"error": "...message..."}`.
+/// - Returned pointer must be freed by calling `pigeon_free_string`.
+#[no_mangle]
+pub unsafe extern "C" fn pigeon_session() -> *mut c_char {
+    let result = std::panic::catch_unwind(AssertUnwindSafe(|| {
+        let session = paths::data_dir()
+            .map_err(anyhow::Error::msg)
+            .and_then(|dir| Session::load(&dir.join(SESSION_FILE)));
+        match session {
+            Ok(session) => {
+                string_to_c_char_ptr(serde_json::json!({ "session": session }).to_string())
+            }
+            Err(e) => string_to_c_char_ptr(json_result_error(format!("{e:#}"))),
+        }
+    }));
+
+    match result {
+        Ok(ptr) => ptr,
+        Err(_) => string_to_c_char_ptr(json_result_error("panic in pigeon_session")),
+    }
+}
+
+/// Save where a host is (window, current space, open tabs, theme) for the next
+/// launch, replacing the saved session. Hosts call it as they close.
+///
+/// # Safety
+/// - `session_json` must be either NULL or point to a valid NUL-terminated C string
+///   holding a session as `pigeon_session` returns it; every field is optional.
+/// - Returns `{"success": true}` or `{"error": "...message..."}`.
+/// - Returned pointer must be freed by calling `pigeon_free_string`.
+#[no_mangle]
+pub unsafe extern "C" fn pigeon_session_save(session_json: *const c_char) -> *mut c_char {
+    let result = std::panic::catch_unwind(AssertUnwindSafe(|| {
+        let session_json = match unsafe { required_c_str(session_json, "session_json") } {
+            Ok(s) => s,
+            Err(e) => return string_to_c_char_ptr(json_result_error(e)),
+        };
+        let session: Session = match serde_json::from_str(session_json) {
+            Ok(s) => s,
+            Err(e) => return string_to_c_char_ptr(json_result_error(format!("invalid json: {e}"))),
+        };
+
+        let saved = paths::data_dir()
+            .map_err(anyhow::Error::msg)
+            .and_then(|dir| session.save(&dir.join(SESSION_FILE)));
+        match saved {
+            Ok(()) => string_to_c_char_ptr(json_success()),
+            Err(e) => string_to_c_char_ptr(json_result_error(format!("{e:#}"))),
+        }
+    }));
+
+    match result {
+        Ok(ptr) => ptr,
+        Err(_) => string_to_c_char_ptr(json_result_error("panic in pigeon_session_save")),
+    }
+}
+
+/// List the auth providers registered from Lua, for use as a request's `auth.type`.
+///
+/// # Safety
+/// - Returns `{"providers": ["name", ...]}` or `{"error": "...message..."}`.
+/// - Returned pointer must be freed by calling `pigeon_free_string`.
+#[no_mangle]
+pub unsafe extern "C" fn pigeon_auth_providers() -> *mut c_char {
+    let result = std::panic::catch_unwind(AssertUnwindSafe(|| {
+        let runtime = match get_lua_runtime() {
+            Some(rt) => rt,
+            None => return string_to_c_char_ptr(json_result_error("Lua runtime not initialized")),
+        };
+
+        match runtime.auth_providers() {
+            Ok(names) => {
+                string_to_c_char_ptr(serde_json::json!({ "providers": names }).to_string())
+            }
+            Err(e) => string_to_c_char_ptr(json_result_error(format!("{e:#}"))),
+        }
+    }));
+
+    match result {
+        Ok(ptr) => ptr,
+        Err(_) => string_to_c_char_ptr(json_result_error("panic in pigeon_auth_providers")),
+    }
+}
+
+/// List the commands registered from Lua with `pigeon.command`, for a command palette.
+///
+/// # Safety
+/// - Returns `{"commands": ["name", ...]}` or `{"error": "...message..."}`.
+/// - Returned pointer must be freed by calling `pigeon_free_string`.
+#[no_mangle]
+pub unsafe extern "C" fn pigeon_commands() -> *mut c_char {
+    let result = std::panic::catch_unwind(AssertUnwindSafe(|| {
+        let runtime = match get_lua_runtime() {
+            Some(rt) => rt,
+            None => return string_to_c_char_ptr(json_result_error("Lua runtime not initialized")),
+        };
+
+        match runtime.commands() {
+            Ok(names) => string_to_c_char_ptr(serde_json::json!({ "commands": names }).to_string()),
+            Err(e) => string_to_c_char_ptr(json_result_error(format!("{e:#}"))),
+        }
+    }));
+
+    match result {
+        Ok(ptr) => ptr,
+        Err(_) => string_to_c_char_ptr(json_result_error("panic in pigeon_commands")),
+    }
+}
+
+/// Run a command registered with `pigeon.command`.
+///
+/// # Safety
+/// - `name` must be either NULL or point to a valid NUL-terminated C string.
+/// - `context_json` must be either NULL (an empty context) or point to a valid
+///   NUL-terminated C string such as `{"request": {...}, "response"?: {...}}`, in the
+///   shapes used by `pigeon_send_request`. It is passed to the command as its argument.
+/// - Returns `{"result": <value>}` with what the command returned, or `{"error": "...message..."}`.
+/// - Returned pointer must be freed by calling `pigeon_free_string`.
+#[no_mangle]
+pub unsafe extern "C" fn pigeon_run_command(
+    name: *const c_char,
+    context_json: *const c_char,
+) -> *mut c_char {
+    let result = std::panic::catch_unwind(AssertUnwindSafe(|| {
+        let name = match unsafe { required_c_str(name, "name") } {
+            Ok(s) => s,
+            Err(e) => return string_to_c_char_ptr(json_result_error(e)),
+        };
+        let context = match unsafe { optional_c_str(context_json, "context_json") } {
+            Ok(Some(s)) => match serde_json::from_str(s) {
+                Ok(v) => v,
+                Err(e) => {
+                    return string_to_c_char_ptr(json_result_error(format!("invalid json: {e}")))
+                }
+            },
+            Ok(None) => serde_json::json!({}),
+            Err(e) => return string_to_c_char_ptr(json_result_error(e)),
+        };
+
+        let runtime = match get_lua_runtime() {
+            Some(rt) => rt,
+            None => return string_to_c_char_ptr(json_result_error("Lua runtime not initialized")),
+        };
+
+        match runtime.run_command(name, context) {
+            Ok(value) => string_to_c_char_ptr(serde_json::json!({ "result": value }).to_string()),
+            Err(e) => {
+                log_lua_error("command.error", &e);
+                string_to_c_char_ptr(json_result_error(format!("{e:#}")))
+            }
+        }
+    }));
+
+    match result {
+        Ok(ptr) => ptr,
+        Err(_) => string_to_c_char_ptr(json_result_error("panic in pigeon_run_command")),
+    }
+}
+
+/// Render a response body with the `pigeon.formatter` registered for its `Content-Type`.
+///
+/// # Safety
+/// - `response_json` must be either NULL or point to a valid NUL-terminated C string
+///   holding a response as returned by `pigeon_send_request`.
+/// - Returns `{"body": "..."}` with the formatted body, `{"body": null}` when no
+///   formatter matches (render it as usual), or `{"error": "...message..."}`.
+/// - Returned pointer must be freed by calling `pigeon_free_string`.
+#[no_mangle]
+pub unsafe extern "C" fn pigeon_format_response(response_json: *const c_char) -> *mut c_char {
+    let result = std::panic::catch_unwind(AssertUnwindSafe(|| {
+        let response_json = match unsafe { required_c_str(response_json, "response_json") } {
+            Ok(s) => s,
+            Err(e) => return string_to_c_char_ptr(json_result_error(e)),
+        };
+        let response: FfiResponse = match serde_json::from_str(response_json) {
+            Ok(v) => v,
+            Err(e) => return string_to_c_char_ptr(json_result_error(format!("invalid json: {e}"))),
+        };
+
+        let runtime = match get_lua_runtime() {
+            Some(rt) => rt,
+            None => return string_to_c_char_ptr(json_result_error("Lua runtime not initialized")),
+        };
+
+        match runtime.format_response(&response) {
+            Ok(body) => string_to_c_char_ptr(serde_json::json!({ "body": body }).to_string()),
+            Err(e) => {
+                log_lua_error("formatter.error", &e);
+                string_to_c_char_ptr(json_result_error(format!("{e:#}")))
+            }
+        }
+    }));
+
+    match result {
+        Ok(ptr) => ptr,
+        Err(_) => string_to_c_char_ptr(json_result_error("panic in pigeon_format_response")),
+    }
+}
+
+/// Return the key bindings configured from Lua with `pigeon.keymap.set`.
+///
+/// Only actions the config overrides are listed; front ends keep their own
+/// defaults for the rest.
+///
+/// # Safety
+/// - Returns `{"keymap": {"<action>": ["ctrl+j", ...]}}` or `{"error": "...message..."}`.
+/// - Returned pointer must be freed by calling `pigeon_free_string`.
+#[no_mangle]
+pub unsafe extern "C" fn pigeon_keymap() -> *mut c_char {
+    let result = std::panic::catch_unwind(AssertUnwindSafe(|| {
+        let runtime = match get_lua_runtime() {
+            Some(rt) => rt,
+            None => return string_to_c_char_ptr(json_result_error("Lua runtime not initialized")),
+        };
+
+        match runtime.keymap() {
+            Ok(keymap) => string_to_c_char_ptr(serde_json::json!({ "keymap": keymap }).to_string()),
+            Err(e) => string_to_c_char_ptr(json_result_error(format!("{e:#}"))),
+        }
+    }));
+
+    match result {
+        Ok(ptr) => ptr,
+        Err(_) => string_to_c_char_ptr(json_result_error("panic in pigeon_keymap")),
+    }
+}
+
+/// Return the themes declared in the Lua config.
+///
+/// # Safety
+/// - Returns `{"themes": {"<name>": {<colors>}}, "active": "<name>" | null}` or
+///   `{"error": "...message..."}`.
+/// - Returned pointer must be freed by calling `pigeon_free_string`.
+#[no_mangle]
+pub unsafe extern "C" fn pigeon_themes() -> *mut c_char {
+    let result = std::panic::catch_unwind(AssertUnwindSafe(|| {
+        let runtime = match get_lua_runtime() {
+            Some(rt) => rt,
+            None => return string_to_c_char_ptr(json_result_error("Lua runtime not initialized")),
+        };
+
+        match runtime.themes() {
+            Ok(themes) => string_to_c_char_ptr(
+                serde_json::to_string(&themes).unwrap_or_else(|e| json_result_error(e.to_string())),
+            ),
+            Err(e) => string_to_c_char_ptr(json_result_error(format!("{e:#}"))),
+        }
+    }));
+
+    match result {
+        Ok(ptr) => ptr,
+        Err(_) => string_to_c_char_ptr(json_result_error("panic in pigeon_themes")),
+    }
+}
+
+/// Return Lua console output (`print`, `pigeon.log.*` and script errors) newer than `after_id`.
+///
+/// The console keeps the most recent 500 entries across config reloads.
+///
+/// # Safety
+/// - Pass `0` for every buffered entry, or the last `id` seen to get only newer ones.
+/// - Returns `{"entries": [{"id", "timestamp", "level", "source", "message"}, ...]}`.
+/// - Returned pointer must be freed by calling `pigeon_free_string`.
+#[no_mangle]
+pub unsafe extern "C" fn pigeon_lua_console(after_id: u64) -> *mut c_char {
+    let result = std::panic::catch_unwind(AssertUnwindSafe(|| {
+        let entries = lua::console::entries(after_id);
+        string_to_c_char_ptr(serde_json::json!({ "entries": entries }).to_string())
+    }));
+
+    match result {
+        Ok(ptr) => ptr,
+        Err(_) => string_to_c_char_ptr(json_result_error("panic in pigeon_lua_console")),
+    }
+}
+
+/// Clear the Lua console buffer.
+///
+/// # Safety
+/// - Returns `{"success": true}`.
+/// - Returned pointer must be freed by calling `pigeon_free_string`.
+#[no_mangle]
+pub unsafe extern "C" fn pigeon_lua_console_clear() -> *mut c_char {
+    lua::console::clear();
+    string_to_c_char_ptr(json_success())
+}
+
+/// Return how many times a config has been loaded, counting automatic reloads.
+///
+/// # Safety
+/// - Returns `{"generation": <number>}`; `0` until `pigeon_load_config` succeeds.
+/// - Returned pointer must be freed by calling `pigeon_free_string`.
+#[no_mangle]
+pub unsafe extern "C" fn pigeon_config_generation() -> *mut c_char {
+    let generation = CONFIG_GENERATION.load(Ordering::SeqCst);
+    string_to_c_char_ptr(serde_json::json!({ "generation": generation }).to_string())
+}
+
+/// Return the workspace: endpoints, headers, bodies, spaces and environments,
+/// including anything the config added through `pigeon.workspace`.
+///
+/// # Safety
+/// - Returns `{"workspace": {"endpoints", "headers", "bodies", "spaces", "environments"}}`.
+/// - Returned pointer must be freed by calling `pigeon_free_string`.
+#[no_mangle]
+pub unsafe extern "C" fn pigeon_workspace() -> *mut c_char {
+    let result = std::panic::catch_unwind(AssertUnwindSafe(|| {
+        let workspace = get_workspace().lock().unwrap();
+        string_to_c_char_ptr(serde_json::json!({ "workspace": *workspace }).to_string())
+    }));
+
+    match result {
+        Ok(ptr) => ptr,
+        Err(_) => string_to_c_char_ptr(json_result_error("panic in pigeon_workspace")),
+    }
+}
+
+/// Replace the workspace with the host's edited copy, after passing it through
+/// the `pigeon.on_workspace_save` hooks, and write it to the data directory.
+///
+/// Changes made by the core (`pigeon.workspace`) are saved
+/// automatically shortly after they happen, and on `pigeon_shutdown`. A save
+/// that changes the workspace can be reverted with `pigeon_workspace_undo`.
+///
+/// # Safety
+/// - `workspace_json` must be either NULL or point to a valid NUL-terminated C string
+///   holding a workspace in the shape returned by `pigeon_workspace`.
+/// - Returns `{"workspace": {...}}` with the workspace as stored (hooks may have changed
+///   it), or `{"error": "...message..."}`, also when writing the file failed.
+/// - Returned pointer must be freed by calling `pigeon_free_string`.
+#[no_mangle]
+pub unsafe extern "C" fn pigeon_workspace_save(workspace_json: *const c_char) -> *mut c_char {
+    let result = std::panic::catch_unwind(AssertUnwindSafe(|| {
+        let workspace_json = match unsafe { required_c_str(workspace_json, "workspace_json") } {
+            Ok(s) => s,
+            Err(e) => return string_to_c_char_ptr(json_result_error(e)),
+        };
+
+        let mut workspace: Workspace = match serde_json::from_str(workspace_json) {
+            Ok(w) => w,
+            Err(e) => return string_to_c_char_ptr(json_result_error(format!("invalid json: {e}"))),
+        };
+        if let Some(lua) = get_lua_runtime() {
+            workspace = apply_workspace_hooks(&lua, WorkspaceEvent::Save, workspace);
+        }
+
+        let json = serde_json::json!({ "workspace": workspace }).to_string();
+        // Written right away; the hooks already ran, so a pending autosave is redundant.
+        if let Some(autosave) = WORKSPACE_AUTOSAVE.get() {
+            autosave.take_pending();
+        }
+        let written = write_workspace(&workspace);
+        {
+            let mut current = get_workspace().lock().unwrap();
+            // Hosts save after every change, so only record the ones that change something.
+            if serde_json::to_value(&*current).ok() != serde_json::to_value(&workspace).ok() {
+                let before = std::mem::replace(&mut *current, workspace);
+                WORKSPACE_UNDO
+                    .lock()
+                    .unwrap()
+                    .push("Edit workspace".to_string(), before);
+            }
+        }
+        match written {
+            Ok(()) => string_to_c_char_ptr(json),
+            Err(e) => string_to_c_char_ptr(json_result_error(format!("{e:#}"))),
+        }
+    }));
+
+    match result {
+        Ok(ptr) => ptr,
+        Err(_) => string_to_c_char_ptr(json_result_error("panic in pigeon_workspace_save")),
+    }
+}
+
+/// Search the library's endpoints, headers and bodies.
+///
+/// # Safety
+/// - `query` must be either NULL (match everything) or point to a valid NUL-terminated
+///   C string such as `users #auth`: every word must match, `#tag` by tag and other
+///   words as case-insensitive substrings of names, URLs, methods, keys and tags.
+/// - Returns `{"endpoints": [...], "headers": [...], "bodies": [...]}` with items in the
+///   shape returned by `pigeon_workspace`.
+/// - Returned pointer must be freed by calling `pigeon_free_string`.
+#[no_mangle]
+pub unsafe extern "C" fn pigeon_workspace_search(query: *const c_char) -> *mut c_char {
+    let result = std::panic::catch_unwind(AssertUnwindSafe(|| {
+        let query = match unsafe { optional_c_str(query, "query") } {
+            Ok(q) => q.unwrap_or_default(),
+            Err(e) => return string_to_c_char_ptr(json_result_error(e)),
+        };
+
+        let results = get_workspace().lock().unwrap().search(query);
+        let json = serde_json::to_string(&results)
+            .unwrap_or_else(|e| json_result_error(format!("serialize results failed: {e}")));
+        string_to_c_char_ptr(json)
+    }));
+
+    match result {
+        Ok(ptr) => ptr,
+        Err(_) => string_to_c_char_ptr(json_result_error("panic in pigeon_workspace_search")),
+    }
+}
+
+/// Add a space, e.g. for a "new space" action. It's saved like other core
+/// changes and can be reverted with `pigeon_workspace_undo`.
+///
+/// # Safety
+/// - `fields_json` must be either NULL or point to a valid NUL-terminated C string
+///   holding fields to set, e.g. `{"name": "Smoke test", "selected_endpoint_id": "..."}`.
+///   Without a name the space is called `Space 1`, `Space 2`, ...
+/// - Returns `{"id": "...", "workspace": {...}}`, or `{"error": "...message..."}` when
+///   another space has the name.
+/// - Returned pointer must be freed by calling `pigeon_free_string`.
+#[no_mangle]
+pub unsafe extern "C" fn pigeon_space_create(fields_json: *const c_char) -> *mut c_char {
+    let result = std::panic::catch_unwind(AssertUnwindSafe(|| {
+        let fields = match unsafe { optional_c_str(fields_json, "fields_json") } {
+            Ok(Some(json)) => match serde_json::from_str(json) {
+                Ok(fields) => fields,
+                Err(e) => {
+                    return string_to_c_char_ptr(json_result_error(format!("invalid json: {e}")))
+                }
+            },
+            Ok(None) => serde_json::json!({}),
+            Err(e) => return string_to_c_char_ptr(json_result_error(e)),
+        };
+
+        let created = edit_workspace("Add space".to_string(), |workspace| {
+            let id = workspace.add_space(fields)?;
+            Ok(serde_json::json!({ "id": id, "workspace": *workspace }).to_string())
+        });
+        match created {
+            Ok(json) => string_to_c_char_ptr(json),
+            Err(e) => string_to_c_char_ptr(json_result_error(format!("{e:#}"))),
+        }
+    }));
+
+    match result {
+        Ok(ptr) => ptr,
+        Err(_) => string_to_c_char_ptr(json_result_error("panic in pigeon_space_create")),
+    }
+}
+
+/// Resolve what a space sends: its selected endpoint, headers and body with the
+/// space's overrides applied and its own one-off headers merged in. Headers holding
+/// a secret value are flagged `secret`, for front ends to mask.
+///
+/// # Safety
+/// - `space` must point to a valid NUL-terminated C string holding a space id or name.
+/// - Returns `{"request": {...}}` in the shape taken by `pigeon_send_request` (with
+///   `space` set, so history records it), or `{"error": "...message..."}`.
+/// - Returned pointer must be freed by calling `pigeon_free_string`.
+#[no_mangle]
+pub unsafe extern "C" fn pigeon_space_request(space: *const c_char) -> *mut c_char {
+    let result = std::panic::catch_unwind(AssertUnwindSafe(|| {
+        let space = match unsafe { required_c_str(space, "space") } {
+            Ok(s) => s,
+            Err(e) => return string_to_c_char_ptr(json_result_error(e)),
+        };
+
+        let resolved = get_workspace().lock().unwrap().space_request(space);
+        match resolved {
+            Ok(request) => {
+                let mut request = FfiRequest::from(&request);
+                request.space = Some(space.to_string());
+                let secrets = secret_values();
+                for header in &mut request.headers {
+                    header.secret = vault::is_sealed(&header.value)
+                        || redact(&header.value, &secrets) != header.value;
+                }
+                string_to_c_char_ptr(serde_json::json!({ "request": request }).to_string())
+            }
+            Err(e) => string_to_c_char_ptr(json_result_error(format!("{e:#}"))),
+        }
+    }));
+
+    match result {
+        Ok(ptr) => ptr,
+        Err(_) => string_to_c_char_ptr(json_result_error("panic in pigeon_space_request")),
+    }
+}
+
+/// Show what sending a request would put on the wire, without sending it.
+///
+/// Placeholders are resolved, the URL is normalized with its query encoded,
+/// disabled headers are dropped and the body's Content-Type is added as a header.
+/// `on_request` hooks and auth signing don't run, as they may have side effects;
+/// `auth` keeps only its type. `{{secret:NAME}}` placeholders are left as written so
+/// previews never reveal secrets.
+///
+/// # Safety
+/// - `req_json` must point to a valid NUL-terminated C string holding a request as
+///   taken by `pigeon_send_request`.
+/// - Returns `{"request": {...}, "resolved": {"host": "api.internal"}, "unresolved":
+///   ["token"]}`: the request as it would be sent, the value each placeholder resolved
+///   to and the placeholders nothing defines, or `{"error": "...message..."}` for
+///   invalid JSON or a failing `pigeon.variable`.
+/// - Returned pointer must be freed by calling `pigeon_free_string`.
+#[no_mangle]
+pub unsafe extern "C" fn pigeon_request_preview(req_json: *const c_char) -> *mut c_char {
+    let result = std::panic::catch_unwind(AssertUnwindSafe(|| {
+        let req_json = match unsafe { required_c_str(req_json, "req_json") } {
+            Ok(s) => s,
+            Err(e) => return string_to_c_char_ptr(json_result_error(e)),
+        };
+        let request: FfiRequest = match serde_json::from_str(req_json) {
+            Ok(r) => r,
+            Err(e) => return string_to_c_char_ptr(json_result_error(format!("invalid json: {e}"))),
+        };
+
+        match preview_request(request, get_lua_runtime().as_deref()) {
+            Ok(preview) => string_to_c_char_ptr(serde_json::json!(preview).to_string()),
+            Err(e) => string_to_c_char_ptr(json_result_error(e)),
+        }
+    }));
+
+    match result {
+        Ok(ptr) => ptr,
+        Err(_) => string_to_c_char_ptr(json_result_error("panic in pigeon_request_preview")),
+    }
+}
+
+#[derive(Debug, Serialize)]
+struct RequestPreview {
+    request: FfiRequest,
+    /// Values by placeholder name; secrets are never resolved.
+    resolved: BTreeMap<String, String>,
+    unresolved: Vec<String>,
+}
+
+/// `request` as `perform_request` would send it, for `pigeon_request_preview`.
+fn preview_request(
+    mut request: FfiRequest,
+    lua: Option<&LuaRuntime>,
+) -> Result<RequestPreview, String> {
+    let resolved = std::cell::RefCell::new(BTreeMap::new());
+    let unresolved = std::cell::RefCell::new(Vec::<String>::new());
+    let mut fields = vec![&mut request.url];
+    fields.extend(request.headers.iter_mut().map(|h| &mut h.value));
+    if let Some(body) = &mut request.body {
+        fields.push(&mut body.content);
+    }
+
+    for field in fields {
+        if !field.contains("{{") {
+            continue;
+        }
+
+        let error = std::cell::RefCell::new(None);
+        let rendered = template::render(field, |name| {
+            if name.starts_with(SECRET_PREFIX) {
+                return None;
+            }
+            let value = match lua {
+                Some(lua) => lua.resolve_variable(name).map_err(|e| format!("{e:#}")),
+                None => Ok(None),
+            };
+            match value {
+                Ok(Some(value)) => {
+                    resolved
+                        .borrow_mut()
+                        .insert(name.to_string(), value.clone());
+                    Some(value)
+                }
+                Ok(None) => {
+                    let mut unresolved = unresolved.borrow_mut();
+                    if !unresolved.iter().any(|n| n == name) {
+                        unresolved.push(name.to_string());
+                    }
+                    None
+                }
+                Err(e) => {
+                    error.borrow_mut().get_or_insert(e);
+                    None
+                }
+            }
+        });
+        if let Some(e) = error.into_inner() {
+            return Err(e);
+        }
+        *field = rendered;
+    }
+
+    if let Ok(url) = reqwest::Url::parse(&request.url) {
+        request.url = url.to_string();
+    }
+    request.headers.retain(|h| h.enabled);
+    if let Some(body) = &request.body {
+        if !body.content_type.trim().is_empty() {
+            request.headers.push(FfiHeader {
+                key: "Content-Type".to_string(),
+                value: body.content_type.clone(),
+                enabled: true,
+                secret: false,
+            });
+        }
+    }
+    if let Some(auth) = &mut request.auth {
+        auth.params.clear();
+    }
+
+    Ok(RequestPreview {
+        request,
+        resolved: resolved.into_inner(),
+        unresolved: unresolved.into_inner(),
+    })
+}
+
+/// Edit a library endpoint, header or body, or a space, in place.
+///
+/// Spaces refer to items by id, so they keep their selections across renames.
+/// The change is saved like other core changes, shortly afterwards, and can be
+/// reverted with `pigeon_workspace_undo`.
+///
+/// # Safety
+/// - `kind` must point to a valid NUL-terminated C string: `endpoint`, `header`, `body`
+///   or `space`.
+/// - `key` must point to a valid NUL-terminated C string holding the item's id or name.
+/// - `changes_json` must point to a valid NUL-terminated C string holding the fields to
+///   change, e.g. `{"name": "Users", "url": "https://users.internal"}` or
+///   `{"notes": "Markdown"}`; others are kept.
+/// - Returns `{"id": "...", "workspace": {...}}`, or `{"error": "...message..."}` for an
+///   unknown item or a name another item already has.
+/// - Returned pointer must be freed by calling `pigeon_free_string`.
+#[no_mangle]
+pub unsafe extern "C" fn pigeon_workspace_update(
+    kind: *const c_char,
+    key: *const c_char,
+    changes_json: *const c_char,
+) -> *mut c_char {
+    let result = std::panic::catch_unwind(AssertUnwindSafe(|| {
+        let (kind, key, changes_json) = match unsafe {
+            (
+                required_c_str(kind, "kind"),
+                required_c_str(key, "key"),
+                required_c_str(changes_json, "changes_json"),
+            )
+        } {
+            (Ok(k), Ok(i), Ok(c)) => (k, i, c),
+            (Err(e), _, _) | (_, Err(e), _) | (_, _, Err(e)) => {
+                return string_to_c_char_ptr(json_result_error(e))
+            }
+        };
+
+        let changes: serde_json::Value = match serde_json::from_str(changes_json) {
+            Ok(c) => c,
+            Err(e) => return string_to_c_char_ptr(json_result_error(format!("invalid json: {e}"))),
+        };
+        let edited = edit_workspace(format!("Edit {kind} `{key}`"), |workspace| {
+            let id = workspace.apply_changes(kind, key, changes)?;
+            Ok(serde_json::json!({ "id": id, "workspace": *workspace }).to_string())
+        });
+        match edited {
+            Ok(json) => string_to_c_char_ptr(json),
+            Err(e) => string_to_c_char_ptr(json_result_error(format!("{e:#}"))),
+        }
+    }));
+
+    match result {
+        Ok(ptr) => ptr,
+        Err(_) => string_to_c_char_ptr(json_result_error("panic in pigeon_workspace_update")),
+    }
+}
+
+/// Remove or edit several library items or spaces as one change, e.g. for bulk
+/// actions on a multi-selection. Either every step applies or none do, and one
+/// `pigeon_workspace_undo` reverts them all.
+///
+/// # Safety
+/// - `ops_json` must point to a valid NUL-terminated C string holding a list of steps:
+///   `{"op": "remove", "kind": "endpoint", "key": "..."}` or `{"op": "update", "kind":
+///   "header", "key": "...", "changes": {"tags": ["auth"]}}`, with kinds and keys as
+///   taken by `pigeon_workspace_remove` and `pigeon_workspace_update`.
+/// - Returns `{"workspace": {...}}`, or `{"error": "...message..."}` naming the first
+///   step that failed.
+/// - Returned pointer must be freed by calling `pigeon_free_string`.
+#[no_mangle]
+pub unsafe extern "C" fn pigeon_workspace_bulk(ops_json: *const c_char) -> *mut c_char {
+    let result = std::panic::catch_unwind(AssertUnwindSafe(|| {
+        let ops_json = match unsafe { required_c_str(ops_json, "ops_json") } {
+            Ok(s) => s,
+            Err(e) => return string_to_c_char_ptr(json_result_error(e)),
+        };
+        let ops: Vec<model::BulkOp> = match serde_json::from_str(ops_json) {
+            Ok(ops) => ops,
+            Err(e) => return string_to_c_char_ptr(json_result_error(format!("invalid json: {e}"))),
+        };
+
+        let label = format!("Edit {} items", ops.len());
+        let edited = edit_workspace(label, |workspace| {
+            workspace.apply_bulk(ops)?;
+            Ok(serde_json::json!({ "workspace": *workspace }).to_string())
+        });
+        match edited {
+            Ok(json) => string_to_c_char_ptr(json),
+            Err(e) => string_to_c_char_ptr(json_result_error(format!("{e:#}"))),
+        }
+    }));
+
+    match result {
+        Ok(ptr) => ptr,
+        Err(_) => string_to_c_char_ptr(json_result_error("panic in pigeon_workspace_bulk")),
+    }
+}
+
+/// Whether this is the first launch: no workspace had been saved yet, so the one
+/// loaded is the sample data. Hosts can offer `pigeon_workspace_setup` instead.
+///
+/// # Safety
+/// - Returns `{"firstRun": true|false}`.
+/// - Returned pointer must be freed by calling `pigeon_free_string`.
+#[no_mangle]
+pub unsafe extern "C" fn pigeon_workspace_first_run() -> *mut c_char {
+    let result = std::panic::catch_unwind(AssertUnwindSafe(|| {
+        get_workspace();
+        let first_run = FIRST_RUN.load(Ordering::Relaxed);
+        string_to_c_char_ptr(serde_json::json!({ "firstRun": first_run }).to_string())
+    }));
+
+    match result {
+        Ok(ptr) => ptr,
+        Err(_) => string_to_c_char_ptr(json_result_error("panic in pigeon_workspace_first_run")),
+    }
+}
+
+/// Choices made in a first-run setup; see `pigeon_workspace_setup`.
+#[derive(Debug, Deserialize)]
+struct WorkspaceSetup {
+    #[serde(default)]
+    sample: bool,
+    import: Option<SetupImport>,
+    space: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct SetupImport {
+    kind: String,
+    data: String,
+}
+
+/// Replace the workspace with a fresh one set up as chosen on first launch: the
+/// sample endpoints or nothing, optionally a collection imported into the library,
+/// then a first space using the first imported (or sample) endpoint.
+///
+/// # Safety
+/// - `setup_json` must point to a valid NUL-terminated C string:
+///   `{"sample": false, "import": {"kind": "curl", "data": "curl ..."}, "space": "Smoke
+///   test"}`. `import` (kinds as for `pigeon_import_collection`) and `space` are optional;
+///   an unnamed space is called `Space 1`.
+/// - Returns `{"workspace": {...}, "imported": 3}`, or `{"error": "...message..."}` with
+///   the workspace left as it was.
+/// - Returned pointer must be freed by calling `pigeon_free_string`.
+#[no_mangle]
+pub unsafe extern "C" fn pigeon_workspace_setup(setup_json: *const c_char) -> *mut c_char {
+    let result = std::panic::catch_unwind(AssertUnwindSafe(|| {
+        let setup_json = match unsafe { required_c_str(setup_json, "setup_json") } {
+            Ok(s) => s,
+            Err(e) => return string_to_c_char_ptr(json_result_error(e)),
+        };
+        let setup: WorkspaceSetup = match serde_json::from_str(setup_json) {
+            Ok(setup) => setup,
+            Err(e) => return string_to_c_char_ptr(json_result_error(format!("invalid json: {e}"))),
+        };
+        let imported = match &setup.import {
+            Some(import) => match import
+                .kind
+                .parse::<CollectionKind>()
+                .and_then(|kind| collection::import(kind, &import.data))
+            {
+                Ok(c) => Some(c),
+                Err(e) => return string_to_c_char_ptr(json_result_error(format!("import: {e:#}"))),
+            },
+            None => None,
+        };
+
+        let edited = edit_workspace("Set up workspace".to_string(), |workspace| {
+            let mut fresh = if setup.sample {
+                Workspace {
+                    spaces: Vec::new(),
+                    ..Workspace::default()
+                }
+            } else {
+                Workspace::empty()
+            };
+            let ids = imported
+                .as_ref()
+                .map(|c| fresh.add_collection(c))
+                .unwrap_or_default();
+            let mut space = serde_json::Map::new();
+            if let Some(name) = setup
+                .space
+                .as_deref()
+                .map(str::trim)
+                .filter(|n| !n.is_empty())
+            {
+                space.insert("name".into(), name.into());
+            }
+            if let Some(id) = ids.first().or(fresh.endpoints.first().map(|e| &e.id)) {
+                space.insert("selected_endpoint_id".into(), id.to_string().into());
+            }
+            fresh.add_space(space.into())?;
+            *workspace = fresh;
+            Ok(serde_json::json!({ "workspace": *workspace, "imported": ids.len() }).to_string())
+        });
+        match edited {
+            Ok(json) => {
+                FIRST_RUN.store(false, Ordering::Relaxed);
+                string_to_c_char_ptr(json)
+            }
+            Err(e) => string_to_c_char_ptr(json_result_error(format!("{e:#}"))),
+        }
+    }));
+
+    match result {
+        Ok(ptr) => ptr,
+        Err(_) => string_to_c_char_ptr(json_result_error("panic in pigeon_workspace_setup")),
+    }
+}
+
+/// Move a library item or space to another position in its list, e.g. for
+/// move-up/move-down actions or drag and drop. The order is saved with the
+/// workspace and can be reverted with `pigeon_workspace_undo`.
+///
+/// # Safety
+/// - `kind` must point to a valid NUL-terminated C string: `endpoint`, `header`,
+///   `body`, `space` or `request`.
+/// - `key` must point to a valid NUL-terminated C string holding the item's id or name.
+/// - `index` is the item's new zero-based position; past the end moves it last.
+/// - Returns `{"id": "...", "workspace": {...}}`, or `{"error": "...message..."}`.
+/// - Returned pointer must be freed by calling `pigeon_free_string`.
+#[no_mangle]
+pub unsafe extern "C" fn pigeon_workspace_move(
+    kind: *const c_char,
+    key: *const c_char,
+    index: u32,
+) -> *mut c_char {
+    let result = std::panic::catch_unwind(AssertUnwindSafe(|| {
+        let (kind, key) =
+            match unsafe { (required_c_str(kind, "kind"), required_c_str(key, "key")) } {
+                (Ok(k), Ok(i)) => (k, i),
+                (Err(e), _) | (_, Err(e)) => return string_to_c_char_ptr(json_result_error(e)),
+            };
+
+        let moved = edit_workspace(format!("Move {kind} `{key}`"), |workspace| {
+            let id = workspace.move_item(kind, key, index as usize)?;
+            Ok(serde_json::json!({ "id": id, "workspace": *workspace }).to_string())
+        });
+        match moved {
+            Ok(json) => string_to_c_char_ptr(json),
+            Err(e) => string_to_c_char_ptr(json_result_error(format!("{e:#}"))),
+        }
+    }));
+
+    match result {
+        Ok(ptr) => ptr,
+        Err(_) => string_to_c_char_ptr(json_result_error("panic in pigeon_workspace_move")),
+    }
+}
+
+/// Copy a library item, space or saved request. The copy is listed right after
+/// the original as `<name> copy`, and can be reverted with `pigeon_workspace_undo`.
+///
+/// # Safety
+/// - `kind` must point to a valid NUL-terminated C string: `endpoint`, `header`,
+///   `body`, `space` or `request`.
+/// - `key` must point to a valid NUL-terminated C string holding the item's id or name.
+/// - Returns `{"id": "...", "workspace": {...}}` with the copy's id, or
+///   `{"error": "...message..."}`.
+/// - Returned pointer must be freed by calling `pigeon_free_string`.
+#[no_mangle]
+pub unsafe extern "C" fn pigeon_workspace_duplicate(
+    kind: *const c_char,
+    key: *const c_char,
+) -> *mut c_char {
+    let result = std::panic::catch_unwind(AssertUnwindSafe(|| {
+        let (kind, key) =
+            match unsafe { (required_c_str(kind, "kind"), required_c_str(key, "key")) } {
+                (Ok(k), Ok(i)) => (k, i),
+                (Err(e), _) | (_, Err(e)) => return string_to_c_char_ptr(json_result_error(e)),
+            };
+
+        let duplicated = edit_workspace(format!("Duplicate {kind} `{key}`"), |workspace| {
+            let id = workspace.duplicate_item(kind, key)?;
+            Ok(serde_json::json!({ "id": id, "workspace": *workspace }).to_string())
+        });
+        match duplicated {
+            Ok(json) => string_to_c_char_ptr(json),
+            Err(e) => string_to_c_char_ptr(json_result_error(format!("{e:#}"))),
+        }
+    }));
+
+    match result {
+        Ok(ptr) => ptr,
+        Err(_) => string_to_c_char_ptr(json_result_error("panic in pigeon_workspace_duplicate")),
+    }
+}
+
+/// Remove a library item, space or saved request. Spaces stop selecting a
+/// removed endpoint, header or body. Can be reverted with `pigeon_workspace_undo`.
+///
+/// # Safety
+/// - `kind` must point to a valid NUL-terminated C string: `endpoint`, `header`,
+///   `body`, `space` or `request`.
+/// - `key` must point to a valid NUL-terminated C string holding the item's id or name.
+/// - Returns `{"id": "...", "workspace": {...}}`, or `{"error": "...message..."}`.
+/// - Returned pointer must be freed by calling `pigeon_free_string`.
+#[no_mangle]
+pub unsafe extern "C" fn pigeon_workspace_remove(
+    kind: *const c_char,
+    key: *const c_char,
+) -> *mut c_char {
+    let result = std::panic::catch_unwind(AssertUnwindSafe(|| {
+        let (kind, key) =
+            match unsafe { (required_c_str(kind, "kind"), required_c_str(key, "key")) } {
+                (Ok(k), Ok(i)) => (k, i),
+                (Err(e), _) | (_, Err(e)) => return string_to_c_char_ptr(json_result_error(e)),
+            };
+
+        let removed = edit_workspace(format!("Remove {kind} `{key}`"), |workspace| {
+            let id = workspace.remove_item(kind, key)?;
+            Ok(serde_json::json!({ "id": id, "workspace": *workspace }).to_string())
+        });
+        match removed {
+            Ok(json) => string_to_c_char_ptr(json),
+            Err(e) => string_to_c_char_ptr(json_result_error(format!("{e:#}"))),
+        }
+    }));
+
+    match result {
+        Ok(ptr) => ptr,
+        Err(_) => string_to_c_char_ptr(json_result_error("panic in pigeon_workspace_remove")),
+    }
+}
+
+/// Revert the last host edit to the workspace (`pigeon_workspace_save` or
+/// `pigeon_workspace_update`). Changes the config makes through `pigeon.workspace`
+/// aren't recorded; it makes them again on reload.
+///
+/// # Safety
+/// - Returns `{"label": "Edit endpoint `users`", "workspace": {...}}` with the workspace
+///   as restored, or `{"error": "nothing to undo"}`.
+/// - Returned pointer must be freed by calling `pigeon_free_string`.
+#[no_mangle]
+pub unsafe extern "C" fn pigeon_workspace_undo() -> *mut c_char {
+    let result = std::panic::catch_unwind(AssertUnwindSafe(|| {
+        string_to_c_char_ptr(step_workspace(UndoStack::undo, "nothing to undo"))
+    }));
+
+    match result {
+        Ok(ptr) => ptr,
+        Err(_) => string_to_c_char_ptr(json_result_error("panic in pigeon_workspace_undo")),
+    }
+}
+
+/// Reapply the last edit reverted by `pigeon_workspace_undo`. Any other edit in
+/// between discards what could be redone.
+///
+/// # Safety
+/// - Returns `{"label": "...", "workspace": {...}}`, or `{"error": "nothing to redo"}`.
+/// - Returned pointer must be freed by calling `pigeon_free_string`.
+#[no_mangle]
+pub unsafe extern "C" fn pigeon_workspace_redo() -> *mut c_char {
+    let result = std::panic::catch_unwind(AssertUnwindSafe(|| {
+        string_to_c_char_ptr(step_workspace(UndoStack::redo, "nothing to redo"))
+    }));
+
+    match result {
+        Ok(ptr) => ptr,
+        Err(_) => string_to_c_char_ptr(json_result_error("panic in pigeon_workspace_redo")),
+    }
+}
+
+fn step_workspace(
+    step: fn(&mut UndoStack, &mut Workspace) -> Option<String>,
+    empty: &str,
+) -> String {
+    let json = {
+        let mut workspace = get_workspace().lock().unwrap();
+        match step(&mut WORKSPACE_UNDO.lock().unwrap(), &mut workspace) {
+            Some(label) => {
+                serde_json::json!({ "label": label, "workspace": *workspace }).to_string()
+            }
+            None => return json_result_error(empty),
+        }
+    };
+    workspace_changed();
+    json
+}
+
+/// Query the request history, newest first.
+///
+/// # Safety
+/// - `query_json` must be either NULL (the latest 100 entries) or point to a valid
+///   NUL-terminated C string such as `{"space"?: "id or name", "since"?: "2024-01-01T00:00:00Z",
+///   "until"?, "status"?: 404 | "4xx" | "error", "method"?: "POST", "url"?: "substring",
+///   "text"?: "found in the URL, request or response body", "pinned"?: true, "limit"?: 100}`.
+/// - Returns `{"entries": [{"id", "spaceId", "space", "timestamp", "request", "sent",
+///   "response", "pinned"}]}`, with the requests and `response` in the shapes used by
+///   `pigeon_send_request`. `sent` is the request as it went out, with variables filled
+///   in but secrets left as placeholders, to send again as-is (null for old entries), or
+///   `{"error": "...message..."}`.
+/// - Returned pointer must be freed by calling `pigeon_free_string`.
+#[no_mangle]
+pub unsafe extern "C" fn pigeon_history(query_json: *const c_char) -> *mut c_char {
+    let result = std::panic::catch_unwind(AssertUnwindSafe(|| {
+        let query: HistoryQuery = match unsafe { optional_c_str(query_json, "query_json") } {
+            Ok(Some(s)) => match serde_json::from_str(s) {
+                Ok(q) => q,
+                Err(e) => {
+                    return string_to_c_char_ptr(json_result_error(format!("invalid json: {e}")))
+                }
+            },
+            Ok(None) => HistoryQuery::default(),
+            Err(e) => return string_to_c_char_ptr(json_result_error(e)),
+        };
+
+        let Some(history) = get_history() else {
+            return string_to_c_char_ptr(json_result_error("history is unavailable"));
+        };
+        match history.query(&query) {
+            Ok(entries) => {
+                string_to_c_char_ptr(serde_json::json!({ "entries": entries }).to_string())
+            }
+            Err(e) => string_to_c_char_ptr(json_result_error(format!("{e:#}"))),
+        }
+    }));
+
+    match result {
+        Ok(ptr) => ptr,
+        Err(_) => string_to_c_char_ptr(json_result_error("panic in pigeon_history")),
+    }
+}
+
+/// Pin a history entry as a saved response, e.g. a known-good baseline, or unpin it.
+/// Pinned entries are never pruned by `history.max_entries` or `history.max_age_days`.
+///
+/// # Safety
+/// - `id` is an entry id from `pigeon_history`.
+/// - Returns `{"success": true}` or `{"error": "...message..."}` for an unknown entry.
+/// - Returned pointer must be freed by calling `pigeon_free_string`.
+#[no_mangle]
+pub unsafe extern "C" fn pigeon_history_pin(id: i64, pinned: bool) -> *mut c_char {
+    let result = std::panic::catch_unwind(AssertUnwindSafe(|| {
+        let Some(history) = get_history() else {
+            return string_to_c_char_ptr(json_result_error("history is unavailable"));
+        };
+        match history.set_pinned(id, pinned) {
+            Ok(()) => string_to_c_char_ptr(json_success()),
+            Err(e) => string_to_c_char_ptr(json_result_error(format!("{e:#}"))),
+        }
+    }));
+
+    match result {
+        Ok(ptr) => ptr,
+        Err(_) => string_to_c_char_ptr(json_result_error("panic in pigeon_history_pin")),
+    }
+}
+
+/// Delete a history entry, pinned or not.
+///
+/// # Safety
+/// - `id` is an entry id from `pigeon_history`.
+/// - Returns `{"success": true}` or `{"error": "...message..."}` for an unknown entry.
+/// - Returned pointer must be freed by calling `pigeon_free_string`.
+#[no_mangle]
+pub unsafe extern "C" fn pigeon_history_delete(id: i64) -> *mut c_char {
+    let result = std::panic::catch_unwind(AssertUnwindSafe(|| {
+        let Some(history) = get_history() else {
+            return string_to_c_char_ptr(json_result_error("history is unavailable"));
+        };
+        match history.delete(id) {
+            Ok(()) => string_to_c_char_ptr(json_success()),
+            Err(e) => string_to_c_char_ptr(json_result_error(format!("{e:#}"))),
+        }
+    }));
+
+    match result {
+        Ok(ptr) => ptr,
+        Err(_) => string_to_c_char_ptr(json_result_error("panic in pigeon_history_delete")),
+    }
+}
+
+/// Delete a space's history, or all of it. Pinned entries (saved responses) are kept.
+///
+/// # Safety
+/// - `space` must be either NULL (every space) or point to a valid NUL-terminated C
+///   string holding a space id or name.
+/// - Returns `{"deleted": 12}` or `{"error": "...message..."}`.
+/// - Returned pointer must be freed by calling `pigeon_free_string`.
+#[no_mangle]
+pub unsafe extern "C" fn pigeon_history_clear(space: *const c_char) -> *mut c_char {
+    let result = std::panic::catch_unwind(AssertUnwindSafe(|| {
+        let space = match unsafe { optional_c_str(space, "space") } {
+            Ok(space) => space,
+            Err(e) => return string_to_c_char_ptr(json_result_error(e)),
+        };
+        let Some(history) = get_history() else {
+            return string_to_c_char_ptr(json_result_error("history is unavailable"));
+        };
+        match history.clear(space) {
+            Ok(deleted) => {
+                string_to_c_char_ptr(serde_json::json!({ "deleted": deleted }).to_string())
+            }
+            Err(e) => string_to_c_char_ptr(json_result_error(format!("{e:#}"))),
+        }
+    }));
 
-static TOKIO_RUNTIME: OnceLock<tokio::runtime::Runtime> = OnceLock::new();
-static LUA_RUNTIME: OnceLock<LuaRuntime> = OnceLock::new();
+    match result {
+        Ok(ptr) => ptr,
+        Err(_) => string_to_c_char_ptr(json_result_error("panic in pigeon_history_clear")),
+    }
+}
 
-fn get_tokio_runtime() -> &'static tokio::runtime::Runtime {
-    TOKIO_RUNTIME.get_or_init(|| {
-        tokio::runtime::Builder::new_multi_thread()
-            .enable_all()
-            .build()
-            .expect("Failed to create Tokio runtime")
-    })
+/// Return the library version as a plain semver string (e.g. `0.1.0`).
+///
+/// # Safety
+/// - Returned pointer must be freed by calling `pigeon_free_string`.
+#[no_mangle]
+pub unsafe extern "C" fn pigeon_version() -> *mut c_char {
+    string_to_c_char_ptr(env!("CARGO_PKG_VERSION").to_string())
 }
 
-#[derive(Debug, Deserialize)]
-#[serde(rename_all = "camelCase")]
-struct FfiRequest {
-    method: String,
-    url: String,
-    #[serde(default)]
-    headers: Vec<FfiHeader>,
-    body: Option<FfiBody>,
+/// Describe the features this build supports so hosts can feature-detect.
+///
+/// # Safety
+/// - Returns `{"version": "...", "features": ["cookies", ...]}`.
+/// - Returned pointer must be freed by calling `pigeon_free_string`.
+#[no_mangle]
+pub unsafe extern "C" fn pigeon_capabilities() -> *mut c_char {
+    string_to_c_char_ptr(
+        serde_json::json!({
+            "version": env!("CARGO_PKG_VERSION"),
+            "features": CAPABILITIES,
+        })
+        .to_string(),
+    )
 }
 
-#[derive(Debug, Deserialize)]
-#[serde(rename_all = "camelCase")]
-struct FfiHeader {
-    key: String,
-    value: String,
-    #[serde(default = "default_true")]
-    enabled: bool,
+/// Register a callback receiving structured JSON log lines (request start/finish,
+/// Lua errors, panics). Pass NULL to unregister.
+///
+/// # Safety
+/// - `callback` must stay valid until it is replaced or unregistered.
+/// - It may be invoked from any thread; the line pointer is only valid during the call.
+#[no_mangle]
+pub unsafe extern "C" fn pigeon_set_log_callback(callback: Option<LogCallback>) {
+    logging::set_callback(callback);
 }
 
-fn default_true() -> bool {
-    true
+/// Import a collection from cURL, Postman (v2.1) or OpenAPI (JSON) data.
+///
+/// # Safety
+/// - `kind` and `data` must be either NULL or point to valid NUL-terminated C strings.
+///   `kind` is one of `"curl"`, `"postman"` or `"openapi"`.
+/// - Returns `{"collection": {"name": "...", "requests": [...]}}` or `{"error": "...message..."}`.
+/// - Returned pointer must be freed by calling `pigeon_free_string`.
+#[no_mangle]
+pub unsafe extern "C" fn pigeon_import_collection(
+    kind: *const c_char,
+    data: *const c_char,
+) -> *mut c_char {
+    let result = std::panic::catch_unwind(AssertUnwindSafe(|| {
+        let (kind, data) =
+            match unsafe { (required_c_str(kind, "kind"), required_c_str(data, "data")) } {
+                (Ok(k), Ok(d)) => (k, d),
+                (Err(e), _) | (_, Err(e)) => return string_to_c_char_ptr(json_result_error(e)),
+            };
+
+        let imported = kind
+            .parse::<CollectionKind>()
+            .and_then(|kind| collection::import(kind, data));
+
+        match imported {
+            Ok(c) => string_to_c_char_ptr(serde_json::json!({ "collection": c }).to_string()),
+            Err(e) => string_to_c_char_ptr(json_result_error(format!("{e:#}"))),
+        }
+    }));
+
+    match result {
+        Ok(ptr) => ptr,
+        Err(_) => string_to_c_char_ptr(json_result_error("panic in pigeon_import_collection")),
+    }
 }
 
-#[derive(Debug, Deserialize)]
-#[serde(rename_all = "camelCase")]
-struct FfiBody {
-    #[serde(default)]
-    content_type: String,
-    #[serde(default)]
-    content: String,
+/// Export a collection as cURL commands, a Postman (v2.1) collection or an OpenAPI document.
+///
+/// # Safety
+/// - `kind` and `collection_json` must be either NULL or point to valid NUL-terminated
+///   C strings. `collection_json` has the shape returned by `pigeon_import_collection`.
+/// - Returns `{"data": "..."}` or `{"error": "...message..."}`.
+/// - Returned pointer must be freed by calling `pigeon_free_string`.
+#[no_mangle]
+pub unsafe extern "C" fn pigeon_export_collection(
+    kind: *const c_char,
+    collection_json: *const c_char,
+) -> *mut c_char {
+    let result = std::panic::catch_unwind(AssertUnwindSafe(|| {
+        let (kind, collection_json) = match unsafe {
+            (
+                required_c_str(kind, "kind"),
+                required_c_str(collection_json, "collection_json"),
+            )
+        } {
+            (Ok(k), Ok(c)) => (k, c),
+            (Err(e), _) | (_, Err(e)) => return string_to_c_char_ptr(json_result_error(e)),
+        };
+
+        let exported = kind.parse::<CollectionKind>().and_then(|kind| {
+            let collection: Collection = serde_json::from_str(collection_json)?;
+            collection::export(kind, &collection)
+        });
+
+        match exported {
+            Ok(data) => string_to_c_char_ptr(serde_json::json!({ "data": data }).to_string()),
+            Err(e) => string_to_c_char_ptr(json_result_error(format!("{e:#}"))),
+        }
+    }));
+
+    match result {
+        Ok(ptr) => ptr,
+        Err(_) => string_to_c_char_ptr(json_result_error("panic in pigeon_export_collection")),
+    }
 }
 
-#[derive(Debug, Serialize)]
-#[serde(rename_all = "camelCase")]
-struct FfiResponse {
-    status: u16,
-    status_text: String,
-    headers: Vec<(String, String)>,
-    body: String,
-    duration_ms: u64,
+/// Export the whole workspace (endpoints, headers, bodies, spaces, environments,
+/// folders and saved requests) as one versioned JSON document, after passing it
+/// through the `pigeon.on_workspace_save` hooks so they can strip secrets. Values
+/// of secret headers and variables are left empty.
+///
+/// # Safety
+/// - Returns `{"data": "..."}` for `pigeon_workspace_import`, or `{"error": "...message..."}`.
+/// - Returned pointer must be freed by calling `pigeon_free_string`.
+#[no_mangle]
+pub unsafe extern "C" fn pigeon_workspace_export() -> *mut c_char {
+    let result = std::panic::catch_unwind(AssertUnwindSafe(|| export_workspace(false)));
+
+    match result {
+        Ok(ptr) => ptr,
+        Err(_) => string_to_c_char_ptr(json_result_error("panic in pigeon_workspace_export")),
+    }
 }
 
-fn json_error(message: impl Into<String>) -> String {
-    serde_json::to_string(&FfiResponse {
-        status: 0,
-        status_text: "Error".to_string(),
-        headers: vec![],
-        body: message.into(),
-        duration_ms: 0,
-    })
-    .unwrap_or_else(|_| "{\"status\":0,\"statusText\":\"Error\",\"headers\":[],\"body\":\"serialization error\",\"durationMs\":0}".to_string())
+/// Like `pigeon_workspace_export`, but keeping the values of secret headers and
+/// variables, encrypted with the master passphrase.
+///
+/// # Safety
+/// - Returns `{"data": "..."}`, or `{"error": "...message..."}` (also while secrets are locked).
+/// - Returned pointer must be freed by calling `pigeon_free_string`.
+#[no_mangle]
+pub unsafe extern "C" fn pigeon_workspace_export_with_secrets() -> *mut c_char {
+    let result = std::panic::catch_unwind(AssertUnwindSafe(|| export_workspace(true)));
+
+    match result {
+        Ok(ptr) => ptr,
+        Err(_) => string_to_c_char_ptr(json_result_error(
+            "panic in pigeon_workspace_export_with_secrets",
+        )),
+    }
 }
 
-fn string_to_c_char_ptr(s: String) -> *mut c_char {
-    // If there is an interior NUL (shouldn't happen for JSON), degrade gracefully.
-    match CString::new(s) {
-        Ok(cstr) => cstr.into_raw(),
-        Err(_) => CString::new(json_error("Invalid string (interior NUL)"))
-            .unwrap()
-            .into_raw(),
+fn export_workspace(include_secrets: bool) -> *mut c_char {
+    let mut workspace = get_workspace().lock().unwrap().clone();
+    if let Some(lua) = get_lua_runtime() {
+        workspace = apply_workspace_hooks(&lua, WorkspaceEvent::Save, workspace);
+    }
+    let exported = if include_secrets {
+        seal_secrets(&mut workspace).and_then(|()| workspace.export())
+    } else {
+        workspace
+            .secret_values_mut()
+            .into_iter()
+            .for_each(String::clear);
+        workspace.export()
+    };
+    match exported {
+        Ok(data) => string_to_c_char_ptr(serde_json::json!({ "data": data }).to_string()),
+        Err(e) => string_to_c_char_ptr(json_result_error(format!("{e:#}"))),
     }
 }
 
-/// Send an HTTP request described by a JSON string and return response JSON.
+/// Replace the workspace with one exported by `pigeon_workspace_export`, after
+/// passing it through the `pigeon.on_workspace_load` hooks. The import can be
+/// reverted with `pigeon_workspace_undo`.
 ///
 /// # Safety
-/// - `req_json` must be either NULL or point to a valid NUL-terminated C string.
+/// - `data` must point to a valid NUL-terminated C string holding the exported document.
+/// - Returns `{"workspace": {...}}` as imported, or `{"error": "...message..."}` for data
+///   that isn't an export or comes from a newer, unsupported version.
 /// - Returned pointer must be freed by calling `pigeon_free_string`.
 #[no_mangle]
-pub unsafe extern "C" fn pigeon_send_request(req_json: *const c_char) -> *mut c_char {
+pub unsafe extern "C" fn pigeon_workspace_import(data: *const c_char) -> *mut c_char {
     let result = std::panic::catch_unwind(AssertUnwindSafe(|| {
-        if req_json.is_null() {
-            return string_to_c_char_ptr(json_error("req_json is null"));
-        }
-
-        let req_str = unsafe { CStr::from_ptr(req_json) };
-        let req_str = match req_str.to_str() {
-            Ok(s) => s,
-            Err(e) => return string_to_c_char_ptr(json_error(format!("invalid utf-8: {e}"))),
+        let data = match unsafe { required_c_str(data, "data") } {
+            Ok(d) => d,
+            Err(e) => return string_to_c_char_ptr(json_result_error(e)),
         };
 
-        let parsed: FfiRequest = match serde_json::from_str(req_str) {
-            Ok(v) => v,
-            Err(e) => return string_to_c_char_ptr(json_error(format!("invalid json: {e}"))),
+        let mut imported = match Workspace::import(data) {
+            Ok(w) => w,
+            Err(e) => return string_to_c_char_ptr(json_result_error(format!("{e:#}"))),
         };
+        open_secrets(&mut imported);
+        if let Some(lua) = get_lua_runtime() {
+            imported = apply_workspace_hooks(&lua, WorkspaceEvent::Load, imported);
+        }
+        let json = serde_json::json!({ "workspace": imported }).to_string();
+        let replaced = edit_workspace("Import workspace".to_string(), |workspace| {
+            *workspace = imported;
+            Ok(())
+        });
+        match replaced {
+            Ok(()) => string_to_c_char_ptr(json),
+            Err(e) => string_to_c_char_ptr(json_result_error(format!("{e:#}"))),
+        }
+    }));
 
-        let rt = get_tokio_runtime();
-        let response_json: String = rt.block_on(async move {
-            let method = parsed
-                .method
-                .parse::<reqwest::Method>()
-                .unwrap_or(reqwest::Method::GET);
-
-            let client = reqwest::Client::new();
-            let mut req = client.request(method, &parsed.url);
+    match result {
+        Ok(ptr) => ptr,
+        Err(_) => string_to_c_char_ptr(json_result_error("panic in pigeon_workspace_import")),
+    }
+}
 
-            for h in parsed.headers {
-                if h.enabled {
-                    req = req.header(&h.key, &h.value);
-                }
-            }
+/// Unlock secret headers and environment variables with the master passphrase,
+/// decrypting them in the workspace. Without encrypted values yet, this sets the
+/// passphrase they will be saved with. `PIGEON_PASSPHRASE` does the same when set.
+///
+/// # Safety
+/// - `passphrase` must point to a valid NUL-terminated C string.
+/// - Returns `{"success": true}`, or `{"error": "...message..."}` for a wrong passphrase.
+/// - Returned pointer must be freed by calling `pigeon_free_string`.
+#[no_mangle]
+pub unsafe extern "C" fn pigeon_secrets_unlock(passphrase: *const c_char) -> *mut c_char {
+    let result = std::panic::catch_unwind(AssertUnwindSafe(|| {
+        let passphrase = match unsafe { required_c_str(passphrase, "passphrase") } {
+            Ok(p) => p,
+            Err(e) => return string_to_c_char_ptr(json_result_error(e)),
+        };
 
-            if let Some(body) = parsed.body {
-                if !body.content_type.trim().is_empty() {
-                    req = req.header("Content-Type", body.content_type);
-                }
-                if !body.content.is_empty() {
-                    req = req.body(body.content);
-                }
+        let mut workspace = get_workspace().lock().unwrap();
+        let values = workspace.secret_values_mut();
+        let mut vault = match Vault::unlock(passphrase, values.iter().map(|v| v.as_str())) {
+            Ok(vault) => vault,
+            Err(e) => return string_to_c_char_ptr(json_result_error(format!("{e:#}"))),
+        };
+        for value in values.into_iter().filter(|v| vault::is_sealed(v)) {
+            match vault.open(value) {
+                Ok(plain) => *value = plain,
+                Err(e) => logging::emit(
+                    Level::Error,
+                    "secrets.open_error",
+                    serde_json::json!({ "error": format!("{e:#}") }),
+                ),
             }
+        }
+        *VAULT.lock().unwrap() = Some(vault);
+        string_to_c_char_ptr(json_success())
+    }));
 
-            let start = std::time::Instant::now();
-            match req.send().await {
-                Ok(resp) => {
-                    let status = resp.status().as_u16();
-                    let status_text = resp.status().to_string();
-                    let headers = resp
-                        .headers()
-                        .iter()
-                        .map(|(k, v)| (k.to_string(), v.to_str().unwrap_or("").to_string()))
-                        .collect::<Vec<_>>();
-                    let body = resp.text().await.unwrap_or_default();
-                    let duration_ms = start.elapsed().as_millis() as u64;
-
-                    serde_json::to_string(&FfiResponse {
-                        status,
-                        status_text,
-                        headers,
-                        body,
-                        duration_ms,
-                    })
-                    .unwrap_or_else(|e| json_error(format!("serialize response failed: {e}")))
-                }
-                Err(e) => json_error(format!("request failed: {e}")),
-            }
-        });
+    match result {
+        Ok(ptr) => ptr,
+        Err(_) => string_to_c_char_ptr(json_result_error("panic in pigeon_secrets_unlock")),
+    }
+}
 
-        string_to_c_char_ptr(response_json)
+/// Report whether secrets are unlocked and how many values are still encrypted,
+/// so hosts know when to ask for the passphrase.
+///
+/// # Safety
+/// - Returns `{"locked": true, "sealed": 2}`.
+/// - Returned pointer must be freed by calling `pigeon_free_string`.
+#[no_mangle]
+pub unsafe extern "C" fn pigeon_secrets_status() -> *mut c_char {
+    let result = std::panic::catch_unwind(AssertUnwindSafe(|| {
+        let mut workspace = get_workspace().lock().unwrap();
+        let sealed = workspace
+            .secret_values_mut()
+            .into_iter()
+            .filter(|v| vault::is_sealed(v))
+            .count();
+        let locked = VAULT.lock().unwrap().is_none();
+        string_to_c_char_ptr(serde_json::json!({ "locked": locked, "sealed": sealed }).to_string())
     }));
 
     match result {
         Ok(ptr) => ptr,
-        Err(_) => string_to_c_char_ptr(json_error("panic in pigeon_send_request")),
+        Err(_) => string_to_c_char_ptr(json_result_error("panic in pigeon_secrets_status")),
     }
 }
 
-/// Free a string returned by `pigeon_send_request`.
+/// Save a credential in the platform keychain (macOS Keychain, Secret Service or
+/// Windows Credential Manager) configured with `pigeon.secrets.keychain()`. Requests
+/// refer to it as `{{secret:NAME}}`, which is resolved when they are sent. On macOS
+/// the value can't contain line breaks.
 ///
 /// # Safety
-/// - `ptr` must be either NULL or a pointer previously returned by `pigeon_send_request`.
-/// - Must not be called twice for the same pointer.
+/// - `name` must point to a valid NUL-terminated C string of letters, digits and `_-./:@`.
+/// - `value` must be NULL (delete the entry) or point to a valid NUL-terminated C string.
+/// - Returns `{"success": true}` or `{"error": "...message..."}`.
+/// - Returned pointer must be freed by calling `pigeon_free_string`.
 #[no_mangle]
-pub unsafe extern "C" fn pigeon_free_string(ptr: *mut c_char) {
-    if ptr.is_null() {
-        return;
-    }
-    unsafe {
-        drop(CString::from_raw(ptr));
+pub unsafe extern "C" fn pigeon_secrets_store(
+    name: *const c_char,
+    value: *const c_char,
+) -> *mut c_char {
+    let result = std::panic::catch_unwind(AssertUnwindSafe(|| {
+        let name = match unsafe { required_c_str(name, "name") } {
+            Ok(n) => n,
+            Err(e) => return string_to_c_char_ptr(json_result_error(e)),
+        };
+        let value = match unsafe { optional_c_str(value, "value") } {
+            Ok(v) => v,
+            Err(e) => return string_to_c_char_ptr(json_result_error(e)),
+        };
+        let Some(runtime) = get_lua_runtime() else {
+            return string_to_c_char_ptr(json_result_error("Lua runtime not initialized"));
+        };
+
+        match runtime.store_secret(name, value) {
+            Ok(()) => string_to_c_char_ptr(json_success()),
+            Err(e) => string_to_c_char_ptr(json_result_error(format!("{e:#}"))),
+        }
+    }));
+
+    match result {
+        Ok(ptr) => ptr,
+        Err(_) => string_to_c_char_ptr(json_result_error("panic in pigeon_secrets_store")),
     }
 }
 
-/// Initialize the Lua runtime and load the configuration file.
+/// Cancel outstanding requests, persist state (the cookie jar and any pending
+/// workspace changes) and drop the tokio and Lua runtimes. The library may be
+/// used again afterwards; runtimes are recreated on demand and `pigeon_load_config` must be called again.
 ///
 /// # Safety
-/// - Returns a JSON string: `{"success": true}` on success or
-///   `{"error": "...message..."}` on failure.
+/// - Must not be called from a thread that is itself running a pigeon call.
+/// - Returns `{"success": true}` or `{"error": "...message..."}` if persisting failed;
+///   the runtimes are released either way.
 /// - Returned pointer must be freed by calling `pigeon_free_string`.
 #[no_mangle]
-pub unsafe extern "C" fn pigeon_load_config() -> *mut c_char {
+pub unsafe extern "C" fn pigeon_shutdown() -> *mut c_char {
     let result = std::panic::catch_unwind(AssertUnwindSafe(|| {
-        // Get config directory
-        // Prefer XDG (~/.config/pigeon), fallback to platform config dir
-        let config_dir = if let Some(home) = dirs::home_dir() {
-            let xdg_config = home.join(".config").join("pigeon");
-            if xdg_config.exists() || home.join(".config").exists() {
-                match std::fs::create_dir_all(&xdg_config) {
-                    Ok(_) => Ok(xdg_config),
-                    Err(e) => Err(format!("Failed to create config directory: {}", e)),
-                }
-            } else {
-                dirs::config_dir()
-                    .ok_or_else(|| "Failed to get config directory".to_string())
-                    .and_then(|mut dir| {
-                        dir.push("pigeon");
-                        std::fs::create_dir_all(&dir)
-                            .map_err(|e| format!("Failed to create config directory: {}", e))?;
-                        Ok(dir)
-                    })
-            }
-        } else {
-            dirs::config_dir()
-                .ok_or_else(|| "Failed to get config directory".to_string())
-                .and_then(|mut dir| {
-                    dir.push("pigeon");
-                    std::fs::create_dir_all(&dir)
-                        .map_err(|e| format!("Failed to create config directory: {}", e))?;
-                    Ok(dir)
-                })
-        };
+        SHUTDOWN.notify_waiters();
+        mock::stop();
+        watcher::stop();
 
-        let config_dir = match config_dir {
-            Ok(dir) => dir,
-            Err(e) => return string_to_c_char_ptr(format!(r#"{{"error": "{}"}}"#, e)),
+        let flushed = match COOKIE_JAR.get() {
+            Some(jar) => paths::data_dir()
+                .map_err(anyhow::Error::msg)
+                .and_then(|dir| jar.save(&dir.join(COOKIES_FILE))),
+            None => Ok(()),
         };
-
-        // Create Lua runtime
-        let runtime = match LuaRuntime::new(&config_dir) {
-            Ok(rt) => rt,
-            Err(e) => {
-                return string_to_c_char_ptr(format!(
-                    r#"{{"error": "Failed to create Lua runtime: {}"}}"#,
-                    e
-                ));
-            }
+        // Saved before the Lua runtime goes away, so the save hooks still run.
+        let saved = match WORKSPACE_AUTOSAVE.get() {
+            Some(autosave) if autosave.take_pending() => save_workspace(),
+            _ => Ok(()),
         };
 
-        // Load config file
-        let mut config_file = config_dir.clone();
-        config_file.push("config.lua");
+        LUA_RUNTIME.write().unwrap().take();
+        HTTP_CLIENT.write().unwrap().take();
 
-        if config_file.exists() {
-            if let Err(e) = runtime.load_file(&config_file) {
-                return string_to_c_char_ptr(format!(
-                    r#"{{"error": "Failed to load config file: {}"}}"#,
-                    e
-                ));
+        // Callers still inside `block_on` hold their own handle; whoever is last drops it.
+        if let Some(rt) = TOKIO_RUNTIME.write().unwrap().take() {
+            if let Ok(rt) = Arc::try_unwrap(rt) {
+                rt.shutdown_timeout(std::time::Duration::from_secs(1));
             }
         }
 
-        // Store runtime globally. If this fails, the runtime was already initialized
-        // and we should report an error instead of silently succeeding.
-        if LUA_RUNTIME.set(runtime).is_err() {
-            return string_to_c_char_ptr(
-                r#"{"error": "Lua runtime already initialized; use pigeon_reload_config instead"}"#
-                    .to_string(),
-            );
+        match flushed.and(saved) {
+            Ok(()) => string_to_c_char_ptr(json_success()),
+            Err(e) => string_to_c_char_ptr(json_result_error(format!("{e:#}"))),
         }
-
-        // Return success JSON object (not "null" string)
-        string_to_c_char_ptr(r#"{"success": true}"#.to_string())
     }));
 
     match result {
         Ok(ptr) => ptr,
-        Err(_) => string_to_c_char_ptr(r#"{"error": "panic in pigeon_load_config"}"#.to_string()),
+        Err(_) => string_to_c_char_ptr(json_result_error("panic in pigeon_shutdown")),
     }
 }
 
-/// Reload the configuration file.
+/// Start the mock server with the given routes.
 ///
 /// # Safety
-/// - Returns a JSON string: `{"success": true}` on success or
-///   `{"error": "...message..."}` on failure.
+/// - `config_json` must be either NULL or point to a valid NUL-terminated C string of the form
+///   `{"host"?: "127.0.0.1", "port"?: 0, "routes": [{"method"?, "path", "status"?, "headers"?, "body"?, "delayMs"?}]}`.
+/// - Returns `{"url": "http://127.0.0.1:PORT"}` or `{"error": "...message..."}`.
 /// - Returned pointer must be freed by calling `pigeon_free_string`.
 #[no_mangle]
-pub unsafe extern "C" fn pigeon_reload_config() -> *mut c_char {
+pub unsafe extern "C" fn pigeon_mock_start(config_json: *const c_char) -> *mut c_char {
     let result = std::panic::catch_unwind(AssertUnwindSafe(|| {
-        let runtime = match LUA_RUNTIME.get() {
-            Some(rt) => rt,
-            None => {
-                return string_to_c_char_ptr(
-                    r#"{"error": "Lua runtime not initialized"}"#.to_string(),
-                );
-            }
+        let config_json = match unsafe { required_c_str(config_json, "config_json") } {
+            Ok(s) => s,
+            Err(e) => return string_to_c_char_ptr(json_result_error(e)),
         };
 
-        let config_dir = runtime.config_dir();
-        let mut config_file = config_dir.to_path_buf();
-        config_file.push("config.lua");
+        let config: MockConfig = match serde_json::from_str(config_json) {
+            Ok(c) => c,
+            Err(e) => return string_to_c_char_ptr(json_result_error(format!("invalid json: {e}"))),
+        };
 
-        if !config_file.exists() {
-            return string_to_c_char_ptr(r#"{"error": "config file not found"}"#.to_string());
+        match get_tokio_runtime().block_on(mock::start(config)) {
+            Ok(url) => string_to_c_char_ptr(serde_json::json!({ "url": url }).to_string()),
+            Err(e) => string_to_c_char_ptr(json_result_error(format!("{e:#}"))),
         }
+    }));
 
-        if let Err(e) = runtime.load_file(&config_file) {
-            return string_to_c_char_ptr(format!(
-                r#"{{"error": "Failed to reload config: {}"}}"#,
-                e
-            ));
+    match result {
+        Ok(ptr) => ptr,
+        Err(_) => string_to_c_char_ptr(json_result_error("panic in pigeon_mock_start")),
+    }
+}
+
+/// Stop the mock server. Recorded requests stay available until the next start.
+///
+/// # Safety
+/// - Returns `{"success": true}` or `{"error": "mock server not running"}`.
+/// - Returned pointer must be freed by calling `pigeon_free_string`.
+#[no_mangle]
+pub unsafe extern "C" fn pigeon_mock_stop() -> *mut c_char {
+    let result = std::panic::catch_unwind(AssertUnwindSafe(|| {
+        if mock::stop() {
+            string_to_c_char_ptr(json_success())
+        } else {
+            string_to_c_char_ptr(json_result_error("mock server not running"))
         }
+    }));
 
-        // Return success JSON object (not "null" string)
-        string_to_c_char_ptr(r#"{"success": true}"#.to_string())
+    match result {
+        Ok(ptr) => ptr,
+        Err(_) => string_to_c_char_ptr(json_result_error("panic in pigeon_mock_stop")),
+    }
+}
+
+/// List requests received by the mock server since it was last started.
+///
+/// # Safety
+/// - Returns a JSON array of `{timestamp, method, path, query, headers, body, matchedRoute}`.
+/// - Returned pointer must be freed by calling `pigeon_free_string`.
+#[no_mangle]
+pub unsafe extern "C" fn pigeon_mock_requests() -> *mut c_char {
+    let result = std::panic::catch_unwind(AssertUnwindSafe(|| {
+        string_to_c_char_ptr(
+            serde_json::to_string(&mock::hits())
+                .unwrap_or_else(|e| json_result_error(format!("serialize requests failed: {e}"))),
+        )
     }));
 
     match result {
         Ok(ptr) => ptr,
-        Err(e) => {
-            let error_msg = format!(r#"{{"error": "panic in pigeon_reload_config: {:?}"}}"#, e);
-            string_to_c_char_ptr(error_msg)
-        }
+        Err(_) => string_to_c_char_ptr(json_result_error("panic in pigeon_mock_requests")),
+    }
+}
+
+/// Callback receiving one JSON step result while `pigeon_run_collection` runs.
+///
+/// The pointer is only valid for the duration of the call, which happens on the
+/// thread that called `pigeon_run_collection`.
+pub type RunCallback = extern "C" fn(result_json: *const c_char);
+
+/// Run a request set in order, substituting `{{variables}}`, applying each step's
+/// `extract` rules and Lua `script`, and streaming per-step results to `callback`.
+///
+/// # Safety
+/// - `plan_json` must be either NULL or point to a valid NUL-terminated C string of the form
+///   `{"name"?, "variables"?: {...}, "stopOnError"?: bool, "requests": [{...request, "name"?, "extract"?, "script"?}]}`.
+/// - `callback` may be NULL; otherwise it receives
+///   `{index, name, request, response, passed, error, tests}` for every executed step,
+///   where `tests` lists the step script's `pigeon.test` results as `{name, passed, error, durationMs}`.
+/// - Returns `{name, total, passed, failed, skipped, testsPassed, testsFailed, durationMs, variables}` or
+///   `{"error": "...message..."}` if the plan is invalid.
+/// - Returned pointer must be freed by calling `pigeon_free_string`.
+#[no_mangle]
+pub unsafe extern "C" fn pigeon_run_collection(
+    plan_json: *const c_char,
+    callback: Option<RunCallback>,
+) -> *mut c_char {
+    let result = std::panic::catch_unwind(AssertUnwindSafe(|| {
+        let plan_json = match unsafe { required_c_str(plan_json, "plan_json") } {
+            Ok(s) => s,
+            Err(e) => return string_to_c_char_ptr(json_result_error(e)),
+        };
+
+        let plan: RunPlan = match serde_json::from_str(plan_json) {
+            Ok(p) => p,
+            Err(e) => return string_to_c_char_ptr(json_result_error(format!("invalid json: {e}"))),
+        };
+
+        *COLLECTION_RUN.lock().unwrap() = Some(RunProgress {
+            name: plan.name.clone(),
+            done: 0,
+            total: plan.requests.len(),
+        });
+        let summary = get_tokio_runtime().block_on(runner::run(plan, get_lua_runtime(), |step| {
+            if let Some(progress) = COLLECTION_RUN.lock().unwrap().as_mut() {
+                progress.done = step.index + 1;
+            }
+            let Some(callback) = callback else {
+                return;
+            };
+            if let Ok(Ok(line)) = serde_json::to_string(step).map(CString::new) {
+                callback(line.as_ptr());
+            }
+        }));
+        COLLECTION_RUN.lock().unwrap().take();
+
+        string_to_c_char_ptr(
+            serde_json::to_string(&summary)
+                .unwrap_or_else(|e| json_result_error(format!("serialize summary failed: {e}"))),
+        )
+    }));
+
+    match result {
+        Ok(ptr) => ptr,
+        Err(_) => string_to_c_char_ptr(json_result_error("panic in pigeon_run_collection")),
     }
 }
@@ -0,0 +1,64 @@
+use serde::Serialize;
+use std::ffi::{c_char, CString};
+use std::sync::{Once, RwLock};
+
+/// Host callback receiving one JSON log line per call.
+///
+/// The pointer is only valid for the duration of the call. The callback may be
+/// invoked from any thread (including tokio worker threads).
+pub type LogCallback = extern "C" fn(line: *const c_char);
+
+static CALLBACK: RwLock<Option<LogCallback>> = RwLock::new(None);
+static PANIC_HOOK: Once = Once::new();
+
+#[derive(Debug, Clone, Copy, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Level {
+    Info,
+    Warn,
+    Error,
+}
+
+/// Install (or with `None`, remove) the host log callback.
+pub fn set_callback(callback: Option<LogCallback>) {
+    *CALLBACK.write().unwrap() = callback;
+
+    // Route panics through the callback too; the previous hook still runs.
+    PANIC_HOOK.call_once(|| {
+        let previous = std::panic::take_hook();
+        std::panic::set_hook(Box::new(move |info| {
+            emit(
+                Level::Error,
+                "panic",
+                serde_json::json!({ "message": info.to_string() }),
+            );
+            previous(info);
+        }));
+    });
+}
+
+/// Deliver a structured event to the host, if a callback is registered.
+///
+/// `fields` should be a JSON object; its keys are merged into the log line next
+/// to `timestamp`, `level` and `event`.
+pub fn emit(level: Level, event: &str, fields: serde_json::Value) {
+    // Copy the pointer out so a callback that re-registers itself can't deadlock.
+    let Some(callback) = *CALLBACK.read().unwrap_or_else(|e| e.into_inner()) else {
+        return;
+    };
+
+    let mut line = serde_json::Map::new();
+    line.insert(
+        "timestamp".to_string(),
+        serde_json::Value::String(chrono::Utc::now().to_rfc3339()),
+    );
+    line.insert("level".to_string(), serde_json::json!(level));
+    line.insert("event".to_string(), serde_json::json!(event));
+    if let serde_json::Value::Object(fields) = fields {
+        line.extend(fields);
+    }
+
+    if let Ok(line) = CString::new(serde_json::Value::Object(line).to_string()) {
+        callback(line.as_ptr());
+    }
+}
@@ -1,5 +1,25 @@
+pub mod auth;
+pub mod commands;
 pub mod config;
+pub mod console;
+pub mod crypto;
+pub mod executor;
+pub mod expect;
+pub mod formatter;
+pub mod fs;
+pub mod history;
+pub mod hooks;
+pub mod http;
+pub mod json;
+pub mod keymap;
+pub mod limits;
+pub mod package;
 pub mod plugin;
 pub mod runtime;
+pub mod schedule;
+pub mod secrets;
+pub mod theme;
+pub mod variables;
+pub mod workspace;
 
 pub use runtime::LuaRuntime;
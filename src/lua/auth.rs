@@ -0,0 +1,71 @@
+use anyhow::{anyhow, Context, Result};
+use mlua::{Function, Lua, LuaSerdeExt, SerializeOptions, Table, Value};
+
+use crate::{FfiAuth, FfiRequest};
+
+/// Registry key holding registered auth providers by name.
+const PROVIDERS: &str = "pigeon.auth.providers";
+
+/// Register `pigeon.register_auth{ name = "...", sign = function(request, params) end }`.
+///
+/// A provider becomes selectable as a request's `auth.type`. `sign` receives the
+/// request table and the endpoint's `auth.params`, and may edit the request in
+/// place or return a replacement (e.g. with an added `Authorization` header).
+pub fn setup(lua: &Lua, table: &Table) -> Result<()> {
+    let register = lua.create_function(|lua, provider: Table| {
+        let name: String = provider
+            .get("name")
+            .map_err(|_| mlua::Error::runtime("auth provider needs a string `name`"))?;
+        provider.get::<_, Function>("sign").map_err(|_| {
+            mlua::Error::runtime(format!("auth provider `{name}` needs a `sign` function"))
+        })?;
+
+        provider_table(lua)?.set(name, provider)
+    })?;
+    table.set("register_auth", register)?;
+
+    Ok(())
+}
+
+/// Names of all registered auth providers, sorted.
+pub fn providers(lua: &Lua) -> Result<Vec<String>> {
+    let mut names = provider_table(lua)?
+        .pairs::<String, Value>()
+        .map(|pair| pair.map(|(name, _)| name))
+        .collect::<mlua::Result<Vec<_>>>()?;
+    names.sort();
+    Ok(names)
+}
+
+/// Sign `request` with the provider named by `auth.kind`.
+pub fn sign(lua: &Lua, request: FfiRequest, auth: &FfiAuth) -> Result<FfiRequest> {
+    let provider: Option<Table> = provider_table(lua)?.get(auth.kind.as_str())?;
+    let provider = provider.ok_or_else(|| anyhow!("unknown auth provider: {}", auth.kind))?;
+    let sign: Function = provider.get("sign")?;
+
+    let options = SerializeOptions::new()
+        .serialize_none_to_null(false)
+        .serialize_unit_to_null(false);
+    let value = lua.to_value_with(&request, options)?;
+    let params = lua.to_value_with(&auth.params, options)?;
+
+    let returned: Value = sign
+        .call((value.clone(), params))
+        .with_context(|| format!("auth provider `{}` failed", auth.kind))?;
+    let signed = match returned {
+        Value::Table(_) => lua.from_value(returned),
+        _ => lua.from_value(value),
+    }
+    .with_context(|| format!("auth provider `{}` returned an invalid request", auth.kind))?;
+
+    Ok(signed)
+}
+
+fn provider_table(lua: &Lua) -> mlua::Result<Table<'_>> {
+    if let Some(providers) = lua.named_registry_value::<Option<Table>>(PROVIDERS)? {
+        return Ok(providers);
+    }
+    let providers = lua.create_table()?;
+    lua.set_named_registry_value(PROVIDERS, providers.clone())?;
+    Ok(providers)
+}
@@ -0,0 +1,78 @@
+use anyhow::{anyhow, Context, Result};
+use mlua::{Function, Lua, LuaSerdeExt, SerializeOptions, Table};
+
+/// Registry key holding the `{ name, fn }` entries in registration order.
+const COMMANDS_KEY: &str = "pigeon.commands";
+
+/// Register `pigeon.command(name, fn)`.
+///
+/// ```lua
+/// pigeon.command("Copy response as JSON", function(ctx)
+///   return pigeon.json.encode(ctx.response)
+/// end)
+/// ```
+///
+/// Commands are listed in the front end's command palette in registration order.
+/// Running one calls `fn(ctx)` with the current `request` and `response` (when
+/// there is one); a string the command returns is shown to the user. Registering
+/// a name again replaces the earlier command.
+pub fn setup(lua: &Lua, table: &Table) -> Result<()> {
+    let command = lua.create_function(|lua, (name, f): (String, Function)| {
+        let name = name.trim().to_string();
+        if name.is_empty() {
+            return Err(mlua::Error::runtime("command name must not be empty"));
+        }
+
+        let commands = commands_table(lua)?;
+        let entry = lua.create_table()?;
+        entry.set("name", name.as_str())?;
+        entry.set("fn", f)?;
+
+        for (index, existing) in commands.clone().sequence_values::<Table>().enumerate() {
+            if existing?.get::<_, String>("name")? == name {
+                return commands.raw_set(index + 1, entry);
+            }
+        }
+        commands.raw_push(entry)
+    })?;
+    table.set("command", command)?;
+
+    Ok(())
+}
+
+/// Names of the registered commands, in registration order.
+pub fn list(lua: &Lua) -> Result<Vec<String>> {
+    commands_table(lua)?
+        .sequence_values::<Table>()
+        .map(|entry| Ok(entry?.get("name")?))
+        .collect()
+}
+
+/// Run command `name` with `context` as its argument and return its result as JSON.
+pub fn run(lua: &Lua, name: &str, context: &serde_json::Value) -> Result<serde_json::Value> {
+    let f = commands_table(lua)?
+        .sequence_values::<Table>()
+        .find_map(|entry| {
+            let entry = entry.ok()?;
+            (entry.get::<_, String>("name").ok()? == name).then(|| entry.get::<_, Function>("fn"))
+        })
+        .ok_or_else(|| anyhow!("unknown command: {name}"))??;
+
+    let options = SerializeOptions::new()
+        .serialize_none_to_null(false)
+        .serialize_unit_to_null(false);
+    let result: mlua::Value = f
+        .call(lua.to_value_with(context, options)?)
+        .with_context(|| format!("command `{name}` failed"))?;
+
+    super::runtime::to_json(lua, result)
+}
+
+fn commands_table(lua: &Lua) -> mlua::Result<Table<'_>> {
+    if let Some(commands) = lua.named_registry_value::<Option<Table>>(COMMANDS_KEY)? {
+        return Ok(commands);
+    }
+    let commands = lua.create_table()?;
+    lua.set_named_registry_value(COMMANDS_KEY, commands.clone())?;
+    Ok(commands)
+}
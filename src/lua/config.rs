@@ -1,6 +1,249 @@
-use anyhow::Result;
-use mlua::{Lua, Table};
+use anyhow::{bail, Result};
+use mlua::{Lua, Table, Value};
+use std::path::Path;
+
+/// Key of the user settings table inside the `pigeon` global (`pigeon.config`).
+pub const CONFIG_KEY: &str = "config";
+
+/// Type a setting in [`SETTINGS`] must have.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Kind {
+    Number,
+    String,
+    Boolean,
+    /// A string out of a fixed set.
+    OneOf(&'static [&'static str]),
+}
+
+impl Kind {
+    fn name(self) -> String {
+        match self {
+            Kind::Number => "a number".to_string(),
+            Kind::String => "a string".to_string(),
+            Kind::Boolean => "a boolean".to_string(),
+            Kind::OneOf(choices) => format!("one of {}", choices.join(", ")),
+        }
+    }
+
+    fn matches(self, value: &Value) -> bool {
+        match (self, value) {
+            (Kind::Number, Value::Integer(_) | Value::Number(_))
+            | (Kind::String, Value::String(_))
+            | (Kind::Boolean, Value::Boolean(_)) => true,
+            (Kind::OneOf(choices), Value::String(s)) => {
+                s.to_str().is_ok_and(|s| choices.contains(&s))
+            }
+            _ => false,
+        }
+    }
+
+    fn matches_json(self, value: &serde_json::Value) -> bool {
+        match (self, value) {
+            (Kind::Number, serde_json::Value::Number(_))
+            | (Kind::String, serde_json::Value::String(_))
+            | (Kind::Boolean, serde_json::Value::Bool(_)) => true,
+            (Kind::OneOf(choices), serde_json::Value::String(s)) => choices.contains(&s.as_str()),
+            _ => false,
+        }
+    }
+}
+
+/// Settings the core and front ends understand, by dotted path.
+///
+/// Sections named here (`history`, `http`, `theme`, `tls`, `ui`, `workspace`) only accept
+/// these keys; other top-level sections are left to plugins and not checked.
+const SETTINGS: &[(&str, Kind)] = &[
+    ("history.max_age_days", Kind::Number),
+    ("history.max_entries", Kind::Number),
+    ("http.proxy", Kind::String),
+    ("http.timeout", Kind::Number),
+    ("theme.mode", Kind::String),
+    ("tls.ca_file", Kind::String),
+    ("tls.insecure", Kind::Boolean),
+    ("ui.locale", Kind::String),
+    ("workspace.storage", Kind::OneOf(&["file", "directory"])),
+    ("workspace.path", Kind::String),
+];
+
+/// Register `pigeon.config` and `pigeon.setup(opts)`.
+///
+/// `config.lua` may either assign into `pigeon.config` directly or call
+/// `pigeon.setup{ http = { timeout = 30 } }`, which deep-merges into it.
+/// `pigeon.setup` rejects unknown or mistyped [`SETTINGS`] with the file and
+/// line of the call, e.g. "`http.timeout` must be a number, got string at config.lua:12".
+pub fn setup(lua: &Lua, table: &Table) -> Result<()> {
+    table.set(CONFIG_KEY, lua.create_table()?)?;
+
+    let setup = lua.create_function(|lua, opts: Table| {
+        if let Err(e) = check_table(&opts, "") {
+            let message = match caller_location(lua) {
+                Some(location) => format!("{e} at {location}"),
+                None => e.to_string(),
+            };
+            return Err(mlua::Error::runtime(message));
+        }
+        let config = config_table(lua)?;
+        merge(&config, opts)
+    })?;
+    table.set("setup", setup)?;
+
+    Ok(())
+}
+
+/// Look up a dotted path (e.g. `"theme.mode"`) in `pigeon.config`.
+///
+/// An empty path returns the whole table; missing keys resolve to `nil`.
+pub fn get<'lua>(lua: &'lua Lua, path: &str) -> mlua::Result<Value<'lua>> {
+    let mut current = Value::Table(config_table(lua)?);
+
+    for segment in path.split('.').filter(|s| !s.is_empty()) {
+        current = match current {
+            Value::Table(t) => t.get(segment)?,
+            _ => return Ok(Value::Nil),
+        };
+    }
+
+    Ok(current)
+}
+
+/// Check everything in `pigeon.config` against [`SETTINGS`].
+///
+/// `pigeon.setup` reports bad values where it is called; this catches values
+/// assigned to `pigeon.config` directly, once the script has run.
+pub fn validate(lua: &Lua) -> Result<()> {
+    let pigeon: Table = lua.globals().get("pigeon")?;
+    match pigeon.get::<_, Value>(CONFIG_KEY)? {
+        Value::Table(config) => check_table(&config, ""),
+        other => bail!(
+            "`pigeon.{CONFIG_KEY}` must be a table, got {}",
+            type_name(&other)
+        ),
+    }
+}
+
+/// The type a settings view edits `path` with: `"number"`, `"string"`, `"boolean"`
+/// or `"choice"` (with its choices). `None` for paths not in [`SETTINGS`].
+pub fn setting_type(path: &str) -> Option<(&'static str, &'static [&'static str])> {
+    let (_, kind) = SETTINGS.iter().find(|(name, _)| *name == path)?;
+    Some(match kind {
+        Kind::Number => ("number", &[]),
+        Kind::String => ("string", &[]),
+        Kind::Boolean => ("boolean", &[]),
+        Kind::OneOf(choices) => ("choice", choices),
+    })
+}
+
+/// Check a value set outside Lua, such as a settings override, against [`SETTINGS`].
+pub fn check_json(path: &str, value: &serde_json::Value) -> Result<()> {
+    let Some((_, kind)) = SETTINGS.iter().find(|(name, _)| *name == path) else {
+        bail!("unknown setting `{path}`");
+    };
+    if !kind.matches_json(value) {
+        bail!("`{path}` must be {}", kind.name());
+    }
+    Ok(())
+}
+
+/// Check the settings in `table`, whose own path is `prefix` (empty for the root).
+fn check_table(table: &Table, prefix: &str) -> Result<()> {
+    for pair in table.clone().pairs::<Value, Value>() {
+        let (key, value) = pair?;
+        let path = match &key {
+            Value::String(key) => match prefix {
+                "" => key.to_str()?.to_string(),
+                prefix => format!("{prefix}.{}", key.to_str()?),
+            },
+            // Array entries only occur in plugin sections, which aren't checked.
+            _ if prefix.is_empty() => bail!("config keys must be strings, got {}", type_name(&key)),
+            _ => continue,
+        };
+        check(&path, &value)?;
+    }
+    Ok(())
+}
+
+fn check(path: &str, value: &Value) -> Result<()> {
+    if let Some((_, kind)) = SETTINGS.iter().find(|(name, _)| *name == path) {
+        if !kind.matches(value) {
+            let got = match (kind, value) {
+                (Kind::OneOf(_), Value::String(s)) => format!("`{}`", s.to_string_lossy()),
+                _ => type_name(value).to_string(),
+            };
+            bail!("`{path}` must be {}, got {got}", kind.name());
+        }
+        return Ok(());
+    }
+
+    if is_section(path) {
+        return match value {
+            Value::Table(table) => check_table(table, path),
+            other => bail!("`{path}` must be a table, got {}", type_name(other)),
+        };
+    }
+
+    match path.rsplit_once('.') {
+        // A key inside a section pigeon owns, most likely a typo.
+        Some((parent, _)) if is_section(parent) => {
+            let known: Vec<&str> = SETTINGS
+                .iter()
+                .map(|(name, _)| *name)
+                .filter(|name| name.starts_with(&format!("{parent}.")))
+                .collect();
+            bail!(
+                "unknown setting `{path}` (expected one of: {})",
+                known.join(", ")
+            )
+        }
+        _ => Ok(()),
+    }
+}
+
+fn is_section(path: &str) -> bool {
+    let section = format!("{path}.");
+    SETTINGS.iter().any(|(name, _)| name.starts_with(&section))
+}
+
+/// Lua's name for `value`'s type, with integers reported as numbers.
+fn type_name(value: &Value) -> &'static str {
+    match value {
+        Value::Integer(_) => "number",
+        other => other.type_name(),
+    }
+}
+
+/// `config.lua:12` for the Lua code calling the current Rust function.
+fn caller_location(lua: &Lua) -> Option<String> {
+    let debug = lua.inspect_stack(1)?;
+    let line = debug.curr_line();
+    let source = debug.source().source?.into_owned();
+    let file = source.strip_prefix('@').unwrap_or(&source);
+    let file = Path::new(file)
+        .file_name()
+        .map_or(file.into(), |name| name.to_string_lossy());
+    (line > 0).then(|| format!("{file}:{line}"))
+}
+
+fn config_table(lua: &Lua) -> mlua::Result<Table<'_>> {
+    let pigeon: Table = lua.globals().get("pigeon")?;
+    pigeon.get(CONFIG_KEY)
+}
+
+/// Recursively merge `src` into `dst`. Nested maps are merged, everything else
+/// (including array-like tables) replaces the existing value.
+fn merge<'lua>(dst: &Table<'lua>, src: Table<'lua>) -> mlua::Result<()> {
+    for pair in src.pairs::<Value, Value>() {
+        let (key, value) = pair?;
+        let existing: Value = dst.get(key.clone())?;
+
+        match (existing, value) {
+            (Value::Table(existing), Value::Table(value))
+                if existing.raw_len() == 0 && value.raw_len() == 0 =>
+            {
+                merge(&existing, value)?;
+            }
+            (_, value) => dst.set(key, value)?,
+        }
+    }
 
-pub fn setup(_lua: &Lua, _table: &Table) -> Result<()> {
     Ok(())
 }
@@ -0,0 +1,120 @@
+use anyhow::Result;
+use chrono::{DateTime, Utc};
+use mlua::{Function, Lua, Table, Value, Variadic};
+use serde::Serialize;
+use std::collections::VecDeque;
+use std::sync::Mutex;
+
+use crate::logging::{self, Level};
+
+/// Entries kept for the console; older ones are dropped first.
+const CAPACITY: usize = 500;
+
+/// One line of script output.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ConsoleEntry {
+    /// Increasing id, so hosts can ask for entries after the last one they saw.
+    pub id: u64,
+    pub timestamp: DateTime<Utc>,
+    pub level: Level,
+    /// `print`, `log`, or the event that reported a script error (e.g. `hook.on_request`).
+    pub source: String,
+    pub message: String,
+}
+
+struct Console {
+    next_id: u64,
+    entries: VecDeque<ConsoleEntry>,
+}
+
+// Shared by every Lua state so output from before a reload stays visible.
+static CONSOLE: Mutex<Console> = Mutex::new(Console {
+    next_id: 1,
+    entries: VecDeque::new(),
+});
+
+/// Replace `print` and register `pigeon.log.{info,warn,error}`.
+///
+/// Arguments are converted with `tostring` and joined by tabs, as `print` does.
+/// Output goes to the console buffer (see [`entries`]) and to the host log
+/// callback as `lua.log` events.
+pub fn setup(lua: &Lua, table: &Table) -> Result<()> {
+    let print = lua.create_function(|lua, args: Variadic<Value>| {
+        push(Level::Info, "print", format_args(lua, args)?);
+        Ok(())
+    })?;
+    lua.globals().set("print", print)?;
+
+    let log = lua.create_table()?;
+    for (name, level) in [
+        ("info", Level::Info),
+        ("warn", Level::Warn),
+        ("error", Level::Error),
+    ] {
+        let f = lua.create_function(move |lua, args: Variadic<Value>| {
+            push(level, "log", format_args(lua, args)?);
+            Ok(())
+        })?;
+        log.set(name, f)?;
+    }
+    table.set("log", log)?;
+
+    Ok(())
+}
+
+/// Append a line to the console and forward it to the host log callback.
+pub fn push(level: Level, source: &str, message: String) {
+    logging::emit(
+        level,
+        "lua.log",
+        serde_json::json!({ "source": source, "message": message }),
+    );
+    record(level, source, message);
+}
+
+/// Append a line to the console only, for errors already reported under their own event.
+pub fn record(level: Level, source: &str, message: String) {
+    let mut console = CONSOLE.lock().unwrap_or_else(|e| e.into_inner());
+    let id = console.next_id;
+    console.next_id += 1;
+    if console.entries.len() == CAPACITY {
+        console.entries.pop_front();
+    }
+    console.entries.push_back(ConsoleEntry {
+        id,
+        timestamp: Utc::now(),
+        level,
+        source: source.to_string(),
+        message,
+    });
+}
+
+/// Buffered entries with an id greater than `after`, oldest first.
+pub fn entries(after: u64) -> Vec<ConsoleEntry> {
+    let console = CONSOLE.lock().unwrap_or_else(|e| e.into_inner());
+    console
+        .entries
+        .iter()
+        .filter(|entry| entry.id > after)
+        .cloned()
+        .collect()
+}
+
+/// Drop every buffered entry. Ids keep increasing.
+pub fn clear() {
+    CONSOLE
+        .lock()
+        .unwrap_or_else(|e| e.into_inner())
+        .entries
+        .clear();
+}
+
+fn format_args(lua: &Lua, args: Variadic<Value>) -> mlua::Result<String> {
+    let tostring: Function = lua.globals().get("tostring")?;
+    let parts = args
+        .into_iter()
+        .map(|value| tostring.call::<_, String>(value))
+        .collect::<mlua::Result<Vec<_>>>()?;
+    Ok(parts.join("\t"))
+}
@@ -0,0 +1,109 @@
+use anyhow::Result;
+use base64::engine::general_purpose::{STANDARD, URL_SAFE_NO_PAD};
+use base64::Engine;
+use hmac::{Hmac, Mac};
+use md5::Md5;
+use mlua::{Lua, Table};
+use percent_encoding::{percent_decode, utf8_percent_encode, AsciiSet, NON_ALPHANUMERIC};
+use sha2::{Digest, Sha256};
+
+/// Everything except RFC 3986 unreserved characters, as most signing schemes expect.
+const URL_ENCODE_SET: &AsciiSet = &NON_ALPHANUMERIC
+    .remove(b'-')
+    .remove(b'_')
+    .remove(b'.')
+    .remove(b'~');
+
+/// Register `pigeon.crypto` (digests) and `pigeon.encode` (byte/string encodings).
+///
+/// Digests take an optional output format: `"hex"` (default), `"base64"` or `"raw"`,
+/// e.g. `pigeon.crypto.hmac_sha256(secret, payload, "base64")`.
+pub fn setup(lua: &Lua, table: &Table) -> Result<()> {
+    let crypto = lua.create_table()?;
+
+    crypto.set(
+        "sha256",
+        lua.create_function(|lua, (data, format): (mlua::String, Option<String>)| {
+            output(lua, &Sha256::digest(data.as_bytes()), format)
+        })?,
+    )?;
+    crypto.set(
+        "md5",
+        lua.create_function(|lua, (data, format): (mlua::String, Option<String>)| {
+            output(lua, &Md5::digest(data.as_bytes()), format)
+        })?,
+    )?;
+    crypto.set(
+        "hmac_sha256",
+        lua.create_function(
+            |lua, (key, data, format): (mlua::String, mlua::String, Option<String>)| {
+                let mut mac = Hmac::<Sha256>::new_from_slice(key.as_bytes())
+                    .map_err(mlua::Error::external)?;
+                mac.update(data.as_bytes());
+                output(lua, &mac.finalize().into_bytes(), format)
+            },
+        )?,
+    )?;
+    table.set("crypto", crypto)?;
+
+    let encode = lua.create_table()?;
+    encode.set(
+        "base64",
+        lua.create_function(|_, data: mlua::String| Ok(STANDARD.encode(data.as_bytes())))?,
+    )?;
+    encode.set(
+        "base64_decode",
+        lua.create_function(|lua, data: String| {
+            let bytes = STANDARD
+                .decode(data.trim())
+                .map_err(mlua::Error::external)?;
+            lua.create_string(bytes)
+        })?,
+    )?;
+    encode.set(
+        "base64url",
+        lua.create_function(|_, data: mlua::String| Ok(URL_SAFE_NO_PAD.encode(data.as_bytes())))?,
+    )?;
+    encode.set(
+        "hex",
+        lua.create_function(|_, data: mlua::String| Ok(hex::encode(data.as_bytes())))?,
+    )?;
+    encode.set(
+        "hex_decode",
+        lua.create_function(|lua, data: String| {
+            let bytes = hex::decode(data.trim()).map_err(mlua::Error::external)?;
+            lua.create_string(bytes)
+        })?,
+    )?;
+    encode.set(
+        "url",
+        lua.create_function(|_, data: String| {
+            Ok(utf8_percent_encode(&data, URL_ENCODE_SET).to_string())
+        })?,
+    )?;
+    encode.set(
+        "url_decode",
+        lua.create_function(|lua, data: String| {
+            let bytes: Vec<u8> = percent_decode(data.replace('+', " ").as_bytes()).collect();
+            lua.create_string(bytes)
+        })?,
+    )?;
+    table.set("encode", encode)?;
+
+    Ok(())
+}
+
+fn output<'lua>(
+    lua: &'lua Lua,
+    digest: &[u8],
+    format: Option<String>,
+) -> mlua::Result<mlua::String<'lua>> {
+    match format.as_deref().unwrap_or("hex") {
+        "hex" => lua.create_string(hex::encode(digest)),
+        "base64" => lua.create_string(STANDARD.encode(digest)),
+        "raw" => lua.create_string(digest),
+        other => Err(mlua::Error::runtime(format!(
+            "unknown digest format `{other}` (expected hex, base64 or raw)"
+        ))),
+    }
+}
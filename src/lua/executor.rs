@@ -0,0 +1,137 @@
+use anyhow::{anyhow, Context, Result};
+use mlua::Lua;
+use std::sync::mpsc;
+use std::sync::{Arc, Weak};
+use std::thread::ThreadId;
+
+use super::limits;
+
+/// Work queued for the Lua thread.
+type Job = Box<dyn FnOnce(&Lua) + Send>;
+
+/// A dedicated thread that owns a Lua state and runs queued jobs one at a time.
+///
+/// Callers never hold the state themselves: sync callers wait for the reply,
+/// async callers await it without blocking their executor. The thread exits,
+/// dropping the state, once the last handle is gone.
+pub struct LuaThread {
+    jobs: Arc<mpsc::Sender<Job>>,
+    thread: ThreadId,
+}
+
+/// Handle that doesn't keep the thread alive, for work the state schedules on itself.
+#[derive(Clone)]
+pub struct WeakLuaThread {
+    jobs: Weak<mpsc::Sender<Job>>,
+    thread: ThreadId,
+}
+
+impl LuaThread {
+    /// Start the thread and create the state on it with `create`.
+    pub fn spawn(create: impl FnOnce() -> Result<Lua> + Send + 'static) -> Result<Self> {
+        let (jobs, queue) = mpsc::channel::<Job>();
+        let (ready, created) = mpsc::sync_channel::<Result<()>>(1);
+
+        let handle = std::thread::Builder::new()
+            .name("pigeon-lua".to_string())
+            .spawn(move || {
+                let lua = match create() {
+                    Ok(lua) => {
+                        let _ = ready.send(Ok(()));
+                        lua
+                    }
+                    Err(e) => {
+                        let _ = ready.send(Err(e));
+                        return;
+                    }
+                };
+                while let Ok(job) = queue.recv() {
+                    // Every job gets the full instruction budget.
+                    limits::reset(&lua);
+                    job(&lua);
+                }
+            })
+            .context("Failed to start Lua thread")?;
+
+        created
+            .recv()
+            .map_err(|_| anyhow!("Lua thread exited during startup"))??;
+
+        Ok(Self {
+            jobs: Arc::new(jobs),
+            thread: handle.thread().id(),
+        })
+    }
+
+    pub fn downgrade(&self) -> WeakLuaThread {
+        WeakLuaThread {
+            jobs: Arc::downgrade(&self.jobs),
+            thread: self.thread,
+        }
+    }
+
+    /// Run `f` on the Lua thread and wait for its result.
+    pub fn call<R: Send + 'static>(
+        &self,
+        f: impl FnOnce(&Lua) -> Result<R> + Send + 'static,
+    ) -> Result<R> {
+        check_reentrant(self.thread)?;
+        let (reply, result) = mpsc::sync_channel(1);
+        self.jobs
+            .send(Box::new(move |lua| {
+                let _ = reply.send(f(lua));
+            }))
+            .map_err(|_| stopped())?;
+        result.recv().map_err(|_| stopped())?
+    }
+
+    /// Queue `f` without waiting for it. Does nothing if the thread has stopped.
+    pub fn send(&self, f: impl FnOnce(&Lua) + Send + 'static) {
+        let _ = self.jobs.send(Box::new(f));
+    }
+
+    /// Run `f` on the Lua thread; the caller's task yields until it finishes.
+    pub async fn call_async<R: Send + 'static>(
+        &self,
+        f: impl FnOnce(&Lua) -> Result<R> + Send + 'static,
+    ) -> Result<R> {
+        send_async(&self.jobs, self.thread, f).await
+    }
+}
+
+impl WeakLuaThread {
+    /// Like [`LuaThread::call_async`]; `None` once the thread has been dropped.
+    pub async fn call_async<R: Send + 'static>(
+        &self,
+        f: impl FnOnce(&Lua) -> Result<R> + Send + 'static,
+    ) -> Option<Result<R>> {
+        let jobs = self.jobs.upgrade()?;
+        Some(send_async(&jobs, self.thread, f).await)
+    }
+}
+
+async fn send_async<R: Send + 'static>(
+    jobs: &mpsc::Sender<Job>,
+    thread: ThreadId,
+    f: impl FnOnce(&Lua) -> Result<R> + Send + 'static,
+) -> Result<R> {
+    check_reentrant(thread)?;
+    let (reply, result) = tokio::sync::oneshot::channel();
+    jobs.send(Box::new(move |lua| {
+        let _ = reply.send(f(lua));
+    }))
+    .map_err(|_| stopped())?;
+    result.await.map_err(|_| stopped())?
+}
+
+/// A job waiting on its own thread would never run.
+fn check_reentrant(thread: ThreadId) -> Result<()> {
+    if std::thread::current().id() == thread {
+        return Err(anyhow!("Lua runtime called re-entrantly from a script"));
+    }
+    Ok(())
+}
+
+fn stopped() -> anyhow::Error {
+    anyhow!("Lua thread stopped")
+}
@@ -0,0 +1,195 @@
+use anyhow::Result;
+use mlua::{Function, Lua, LuaSerdeExt, SerializeOptions, Table, Value};
+use serde::{Deserialize, Serialize};
+use std::time::Instant;
+
+use crate::runner::lookup_path;
+
+/// Registry key holding the shared expectation methods.
+const METHODS_KEY: &str = "pigeon.expect.methods";
+/// Registry key holding the results list while a script's tests are collected.
+const RESULTS_KEY: &str = "pigeon.tests.results";
+
+/// Outcome of one `pigeon.test` block.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TestResult {
+    pub name: String,
+    pub passed: bool,
+    /// The first failed assertion or error raised by the test.
+    pub error: Option<String>,
+    pub duration_ms: u64,
+}
+
+/// Register `pigeon.expect(response)` and `pigeon.test(name, fn)`.
+///
+/// ```lua
+/// pigeon.test("creates the user", function()
+///   pigeon.expect(response)
+///     :status(201)
+///     :header("content-type", "json")
+///     :json("$.user.id")
+///     :json("$.user.roles[0]", "admin")
+///     :time_under(500)
+/// end)
+/// ```
+///
+/// Each assertion raises an error when it fails, so a test stops at its first
+/// failure. `header` matches names case-insensitively and checks that the value
+/// contains the given text; `json` checks that the path exists, or equals the
+/// expected value when one is given. Both response shapes work: the one step
+/// scripts and hooks receive, and the one `pigeon.http.request` returns.
+///
+/// `pigeon.test` returns `passed, error`. In collection step scripts its results
+/// are also reported with the step.
+pub fn setup(lua: &Lua, table: &Table) -> Result<()> {
+    let methods = lua.create_table()?;
+
+    let status = lua.create_function(|_, (this, expected): (Table, u16)| {
+        let actual: u16 = response(&this)?.get("status")?;
+        check(actual == expected, || {
+            format!("expected status {expected}, got {actual}")
+        })?;
+        Ok(this)
+    })?;
+    methods.set("status", status)?;
+
+    let header = lua.create_function(
+        |_, (this, name, expected): (Table, String, Option<String>)| {
+            let value = header_value(&response(&this)?, &name)?;
+            match (value, expected) {
+                (None, _) => check(false, || format!("expected header `{name}`"))?,
+                (Some(value), Some(expected)) => check(value.contains(&expected), || {
+                    format!("expected header `{name}` to contain {expected:?}, got {value:?}")
+                })?,
+                (Some(_), None) => {}
+            }
+            Ok(this)
+        },
+    )?;
+    methods.set("header", header)?;
+
+    let json = lua.create_function(|lua, (this, path, expected): (Table, String, Value)| {
+        let body: String = response(&this)?.get("body")?;
+        let body: serde_json::Value = serde_json::from_str(&body)
+            .map_err(|e| mlua::Error::runtime(format!("response body is not JSON: {e}")))?;
+        let actual = lookup_path(&body, &dotted_path(&path));
+        match (actual, expected) {
+            (None, _) => check(false, || format!("expected a value at `{path}`"))?,
+            (Some(_), Value::Nil) => {}
+            (Some(actual), expected) => {
+                let expected: serde_json::Value = lua.from_value(expected)?;
+                check(*actual == expected, || {
+                    format!("expected `{path}` to be {expected}, got {actual}")
+                })?;
+            }
+        }
+        Ok(this)
+    })?;
+    methods.set("json", json)?;
+
+    let time_under = lua.create_function(|_, (this, limit): (Table, u64)| {
+        let response = response(&this)?;
+        let duration: u64 = match response.get::<_, Option<u64>>("durationMs")? {
+            Some(duration) => duration,
+            None => response.get("duration_ms")?,
+        };
+        check(duration < limit, || {
+            format!("expected response in under {limit}ms, took {duration}ms")
+        })?;
+        Ok(this)
+    })?;
+    methods.set("time_under", time_under)?;
+
+    lua.set_named_registry_value(METHODS_KEY, methods)?;
+
+    let expect = lua.create_function(|lua, response: Table| {
+        let this = lua.create_table()?;
+        this.set("response", response)?;
+        let meta = lua.create_table()?;
+        meta.set("__index", lua.named_registry_value::<Table>(METHODS_KEY)?)?;
+        this.set_metatable(Some(meta));
+        Ok(this)
+    })?;
+    table.set("expect", expect)?;
+
+    let test = lua.create_function(|lua, (name, f): (String, Function)| {
+        let start = Instant::now();
+        let error = f.call::<_, ()>(()).err().map(|e| message(&e));
+        let result = TestResult {
+            name,
+            passed: error.is_none(),
+            error: error.clone(),
+            duration_ms: start.elapsed().as_millis() as u64,
+        };
+        if let Some(results) = lua.named_registry_value::<Option<Table>>(RESULTS_KEY)? {
+            let options = SerializeOptions::new().serialize_none_to_null(false);
+            results.raw_push(lua.to_value_with(&result, options)?)?;
+        }
+        Ok((result.passed, error))
+    })?;
+    table.set("test", test)?;
+
+    Ok(())
+}
+
+/// Run `f`, collecting the results of every `pigeon.test` it triggers.
+pub fn collect<T>(lua: &Lua, f: impl FnOnce() -> T) -> Result<(T, Vec<TestResult>)> {
+    let results = lua.create_table()?;
+    lua.set_named_registry_value(RESULTS_KEY, results.clone())?;
+    let value = f();
+    lua.unset_named_registry_value(RESULTS_KEY)?;
+
+    let results = results
+        .sequence_values::<Value>()
+        .map(|result| lua.from_value(result?))
+        .collect::<mlua::Result<_>>()?;
+    Ok((value, results))
+}
+
+fn response<'lua>(this: &Table<'lua>) -> mlua::Result<Table<'lua>> {
+    this.get("response")
+}
+
+fn check(ok: bool, message: impl FnOnce() -> String) -> mlua::Result<()> {
+    if ok {
+        Ok(())
+    } else {
+        Err(mlua::Error::runtime(message()))
+    }
+}
+
+/// Find a header by case-insensitive name, in either `[[name, value], ...]` or
+/// `{ name = value }` form. Repeated headers are joined with `, `.
+fn header_value(response: &Table, name: &str) -> mlua::Result<Option<String>> {
+    let headers: Table = response.get("headers")?;
+    let mut values = Vec::new();
+    for pair in headers.pairs::<Value, Value>() {
+        let (key, value): (String, String) = match pair? {
+            (Value::String(key), Value::String(value)) => {
+                (key.to_str()?.to_string(), value.to_str()?.to_string())
+            }
+            (Value::Integer(_), Value::Table(pair)) => (pair.get(1)?, pair.get(2)?),
+            _ => continue,
+        };
+        if key.eq_ignore_ascii_case(name) {
+            values.push(value);
+        }
+    }
+    Ok((!values.is_empty()).then(|| values.join(", ")))
+}
+
+/// Turn a simple JSONPath (`$.items[0].id`) into the dotted form `lookup_path` takes.
+fn dotted_path(path: &str) -> String {
+    let path = path.strip_prefix('$').unwrap_or(path);
+    path.replace('[', ".").replace(']', "")
+}
+
+/// The innermost message of a Lua error, without the callback traceback.
+fn message(error: &mlua::Error) -> String {
+    match error {
+        mlua::Error::CallbackError { cause, .. } => message(cause),
+        mlua::Error::RuntimeError(message) => message.clone(),
+        other => other.to_string(),
+    }
+}
@@ -0,0 +1,82 @@
+use anyhow::{bail, Context, Result};
+use mlua::{Function, Lua, LuaSerdeExt, SerializeOptions, Table, Value};
+
+use crate::FfiResponse;
+
+/// Registry key holding formatters by lowercased media type.
+const FORMATTERS_KEY: &str = "pigeon.formatters";
+
+/// Register `pigeon.formatter(content_type, fn)`.
+///
+/// ```lua
+/// pigeon.formatter("application/vnd.foo+json", function(body, response)
+///   return pigeon.json.encode(pigeon.json.decode(body).payload)
+/// end)
+/// ```
+///
+/// When a response's `Content-Type` matches (ignoring parameters such as
+/// `charset` and case), front ends show the string `fn(body, response)` returns
+/// instead of their default rendering. Registering a type again replaces its formatter.
+pub fn setup(lua: &Lua, table: &Table) -> Result<()> {
+    let formatter = lua.create_function(|lua, (content_type, f): (String, Function)| {
+        let key = media_type(&content_type);
+        if key.is_empty() {
+            return Err(mlua::Error::runtime("content type must not be empty"));
+        }
+        formatters_table(lua)?.set(key, f)
+    })?;
+    table.set("formatter", formatter)?;
+
+    Ok(())
+}
+
+/// Format `response` with the formatter for its content type, if one is registered.
+pub fn format(lua: &Lua, response: &FfiResponse) -> Result<Option<String>> {
+    let Some((_, content_type)) = response
+        .headers
+        .iter()
+        .find(|(name, _)| name.eq_ignore_ascii_case("content-type"))
+    else {
+        return Ok(None);
+    };
+    let key = media_type(content_type);
+    let Some(f) = formatters_table(lua)?.get::<_, Option<Function>>(key.as_str())? else {
+        return Ok(None);
+    };
+
+    let options = SerializeOptions::new()
+        .serialize_none_to_null(false)
+        .serialize_unit_to_null(false);
+    let formatted: Value = f
+        .call((
+            response.body.as_str(),
+            lua.to_value_with(response, options)?,
+        ))
+        .with_context(|| format!("formatter for `{key}` failed"))?;
+    match formatted {
+        Value::String(s) => Ok(Some(s.to_str()?.to_string())),
+        other => bail!(
+            "formatter for `{key}` must return a string, got {}",
+            other.type_name()
+        ),
+    }
+}
+
+/// `application/json` for `Application/JSON; charset=utf-8`.
+fn media_type(content_type: &str) -> String {
+    content_type
+        .split(';')
+        .next()
+        .unwrap_or_default()
+        .trim()
+        .to_ascii_lowercase()
+}
+
+fn formatters_table(lua: &Lua) -> mlua::Result<Table<'_>> {
+    if let Some(formatters) = lua.named_registry_value::<Option<Table>>(FORMATTERS_KEY)? {
+        return Ok(formatters);
+    }
+    let formatters = lua.create_table()?;
+    lua.set_named_registry_value(FORMATTERS_KEY, formatters.clone())?;
+    Ok(formatters)
+}
@@ -0,0 +1,182 @@
+use anyhow::{bail, Context, Result};
+use mlua::{Lua, Table};
+use std::path::{Component, Path, PathBuf};
+use std::sync::Arc;
+
+use crate::paths;
+
+/// Directories `pigeon.fs` may touch. Relative paths resolve against the first.
+struct Roots(Vec<PathBuf>);
+
+/// Register `pigeon.fs`, file access confined to the pigeon data and config dirs.
+///
+/// Relative paths resolve under the data dir; absolute paths must point inside
+/// one of the two (`pigeon.fs.data_dir` / `pigeon.fs.config_dir`). `..` segments
+/// are rejected and symlinks are resolved before the check. Failures raise a Lua error.
+pub fn setup(lua: &Lua, table: &Table, config_dir: &Path) -> Result<()> {
+    let fs = lua.create_table()?;
+
+    let mut roots = Vec::new();
+    if let Ok(data_dir) = paths::data_dir() {
+        fs.set("data_dir", data_dir.display().to_string())?;
+        roots.push(data_dir);
+    }
+    fs.set("config_dir", config_dir.display().to_string())?;
+    roots.push(config_dir.to_path_buf());
+    let roots = Arc::new(Roots(roots));
+
+    let r = roots.clone();
+    fs.set(
+        "read",
+        lua.create_function(move |lua, path: String| {
+            let path = r.resolve(&path).map_err(mlua::Error::external)?;
+            let bytes = std::fs::read(&path)
+                .with_context(|| format!("Failed to read {}", path.display()))
+                .map_err(mlua::Error::external)?;
+            lua.create_string(bytes)
+        })?,
+    )?;
+
+    let r = roots.clone();
+    fs.set(
+        "write",
+        lua.create_function(move |_, (path, content): (String, mlua::String)| {
+            let path = r.resolve(&path).map_err(mlua::Error::external)?;
+            write(&path, content.as_bytes(), false).map_err(mlua::Error::external)
+        })?,
+    )?;
+
+    let r = roots.clone();
+    fs.set(
+        "append",
+        lua.create_function(move |_, (path, content): (String, mlua::String)| {
+            let path = r.resolve(&path).map_err(mlua::Error::external)?;
+            write(&path, content.as_bytes(), true).map_err(mlua::Error::external)
+        })?,
+    )?;
+
+    let r = roots.clone();
+    fs.set(
+        "exists",
+        lua.create_function(move |_, path: String| {
+            let path = r.resolve(&path).map_err(mlua::Error::external)?;
+            Ok(path.exists())
+        })?,
+    )?;
+
+    let r = roots.clone();
+    fs.set(
+        "list",
+        lua.create_function(move |_, path: Option<String>| {
+            let path = r
+                .resolve(path.as_deref().unwrap_or(""))
+                .map_err(mlua::Error::external)?;
+            list(&path).map_err(mlua::Error::external)
+        })?,
+    )?;
+
+    let r = roots.clone();
+    fs.set(
+        "mkdir",
+        lua.create_function(move |_, path: String| {
+            let path = r.resolve(&path).map_err(mlua::Error::external)?;
+            std::fs::create_dir_all(&path)
+                .with_context(|| format!("Failed to create {}", path.display()))
+                .map_err(mlua::Error::external)
+        })?,
+    )?;
+
+    let r = roots;
+    fs.set(
+        "remove",
+        lua.create_function(move |_, path: String| {
+            let path = r.resolve(&path).map_err(mlua::Error::external)?;
+            std::fs::remove_file(&path)
+                .with_context(|| format!("Failed to remove {}", path.display()))
+                .map_err(mlua::Error::external)
+        })?,
+    )?;
+
+    table.set("fs", fs)?;
+
+    Ok(())
+}
+
+impl Roots {
+    fn resolve(&self, path: &str) -> Result<PathBuf> {
+        let requested = Path::new(path);
+        if requested
+            .components()
+            .any(|c| matches!(c, Component::ParentDir))
+        {
+            bail!("pigeon.fs: `..` is not allowed in paths: {path}");
+        }
+
+        let Some(default_root) = self.0.first() else {
+            bail!("pigeon.fs: no accessible directories");
+        };
+        let full = if requested.is_absolute() {
+            requested.to_path_buf()
+        } else {
+            default_root.join(requested)
+        };
+
+        // Canonicalize the deepest existing ancestor so symlinks can't escape a root.
+        let real = canonicalize_existing(&full)?;
+        let allowed = self.0.iter().any(|root| {
+            root.canonicalize()
+                .map(|root| real.starts_with(root))
+                .unwrap_or(false)
+        });
+        if !allowed {
+            bail!("pigeon.fs: access outside the pigeon config/data directories: {path}");
+        }
+
+        Ok(full)
+    }
+}
+
+fn canonicalize_existing(path: &Path) -> Result<PathBuf> {
+    let mut existing = path;
+    let mut rest = Vec::new();
+    while std::fs::symlink_metadata(existing).is_err() {
+        let (Some(parent), Some(name)) = (existing.parent(), existing.file_name()) else {
+            break;
+        };
+        rest.push(name.to_os_string());
+        existing = parent;
+    }
+
+    let mut real = existing
+        .canonicalize()
+        .with_context(|| format!("Failed to resolve {}", path.display()))?;
+    real.extend(rest.into_iter().rev());
+    Ok(real)
+}
+
+fn write(path: &Path, content: &[u8], append: bool) -> Result<()> {
+    use std::io::Write;
+
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)
+            .with_context(|| format!("Failed to create {}", parent.display()))?;
+    }
+    let mut file = std::fs::OpenOptions::new()
+        .create(true)
+        .write(true)
+        .append(append)
+        .truncate(!append)
+        .open(path)
+        .with_context(|| format!("Failed to open {}", path.display()))?;
+    file.write_all(content)
+        .with_context(|| format!("Failed to write {}", path.display()))
+}
+
+fn list(path: &Path) -> Result<Vec<String>> {
+    let mut names = std::fs::read_dir(path)
+        .with_context(|| format!("Failed to list {}", path.display()))?
+        .map(|entry| entry.map(|e| e.file_name().to_string_lossy().into_owned()))
+        .collect::<std::io::Result<Vec<_>>>()?;
+    names.sort();
+    Ok(names)
+}
@@ -0,0 +1,137 @@
+use anyhow::Result;
+use mlua::{Lua, LuaSerdeExt, SerializeOptions, Table, Value};
+use std::collections::BTreeMap;
+
+use crate::history::{HistoryEntry, HistoryQuery};
+
+/// Register `pigeon.history.list(space, opts)`, `pigeon.history.latest()`,
+/// `pigeon.history.pin(id, pinned)`, `pigeon.history.delete(id)` and
+/// `pigeon.history.clear(space)`.
+///
+/// ```lua
+/// local total = 0
+/// local entries = pigeon.history.list("Test Space", { status = "2xx", limit = 50 })
+/// for _, entry in ipairs(entries) do
+///   total = total + entry.duration_ms
+/// end
+/// print("average latency", total / #entries)
+/// ```
+///
+/// Entries look like `pigeon.http.request` results (`status`, `status_text`,
+/// `headers`, `body`, `duration_ms`) plus `id`, `timestamp` (RFC 3339), `space`,
+/// `space_id`, `method`, `url`, the `request` as hooks left it and, in `sent`,
+/// as it went out with variables filled in and secrets left as placeholders
+/// (nil for old entries), and `pinned`. `list` takes a space id or name (nil for all spaces)
+/// and returns its responses newest first; `opts` filters them with `since`, `until`, `status` (`404`, `"4xx"`
+/// or `"error"`), `method`, `url` (a substring), `text` (found in the URL, request or response body,
+/// ignoring case), `pinned` and `limit` (100 by default). `latest` returns the most
+/// recent response in any space, or nil if nothing has been sent yet.
+///
+/// `pin(id)` keeps an entry as a saved response, exempt from the
+/// `history.max_entries` and `history.max_age_days` limits; `pin(id, false)`
+/// releases it. `delete(id)` removes one entry; `clear(space)` removes a space's
+/// entries (every space's for nil) except pinned ones and returns how many went.
+pub fn setup(lua: &Lua, table: &Table) -> Result<()> {
+    let history = lua.create_table()?;
+
+    let list = lua.create_function(|lua, (space, opts): (Option<String>, Value)| {
+        let mut query: HistoryQuery = match opts {
+            Value::Nil => HistoryQuery::default(),
+            opts => lua.from_value(opts)?,
+        };
+        query.space = space;
+
+        let result = lua.create_table()?;
+        for entry in query_history(&query)? {
+            result.raw_push(entry_table(lua, entry)?)?;
+        }
+        Ok(result)
+    })?;
+    history.set("list", list)?;
+
+    let latest = lua.create_function(|lua, ()| {
+        let query = HistoryQuery {
+            limit: Some(1),
+            ..HistoryQuery::default()
+        };
+        query_history(&query)?
+            .into_iter()
+            .next()
+            .map(|entry| entry_table(lua, entry))
+            .transpose()
+    })?;
+    history.set("latest", latest)?;
+
+    let pin = lua.create_function(|_, (id, pinned): (i64, Option<bool>)| {
+        let history =
+            crate::get_history().ok_or_else(|| mlua::Error::runtime("history is unavailable"))?;
+        history
+            .set_pinned(id, pinned.unwrap_or(true))
+            .map_err(|e| mlua::Error::runtime(format!("{e:#}")))
+    })?;
+    history.set("pin", pin)?;
+
+    let delete = lua.create_function(|_, id: i64| {
+        let history =
+            crate::get_history().ok_or_else(|| mlua::Error::runtime("history is unavailable"))?;
+        history
+            .delete(id)
+            .map_err(|e| mlua::Error::runtime(format!("{e:#}")))
+    })?;
+    history.set("delete", delete)?;
+
+    let clear = lua.create_function(|_, space: Option<String>| {
+        let history =
+            crate::get_history().ok_or_else(|| mlua::Error::runtime("history is unavailable"))?;
+        history
+            .clear(space.as_deref())
+            .map_err(|e| mlua::Error::runtime(format!("{e:#}")))
+    })?;
+    history.set("clear", clear)?;
+
+    table.set("history", history)?;
+
+    Ok(())
+}
+
+fn query_history(query: &HistoryQuery) -> mlua::Result<Vec<HistoryEntry>> {
+    let history =
+        crate::get_history().ok_or_else(|| mlua::Error::runtime("history is unavailable"))?;
+    history
+        .query(query)
+        .map_err(|e| mlua::Error::runtime(format!("{e:#}")))
+}
+
+fn entry_table(lua: &Lua, entry: HistoryEntry) -> mlua::Result<Table<'_>> {
+    let response = entry.response;
+    let mut headers: BTreeMap<String, String> = BTreeMap::new();
+    for (key, value) in response.headers {
+        headers
+            .entry(key)
+            .and_modify(|v| {
+                v.push_str(", ");
+                v.push_str(&value);
+            })
+            .or_insert(value);
+    }
+
+    let options = SerializeOptions::new()
+        .serialize_none_to_null(false)
+        .serialize_unit_to_null(false);
+    let result = lua.create_table()?;
+    result.set("id", entry.id)?;
+    result.set("space", entry.space)?;
+    result.set("space_id", entry.space_id.map(|id| id.to_string()))?;
+    result.set("timestamp", entry.timestamp.to_rfc3339())?;
+    result.set("method", entry.request.method.to_ascii_uppercase())?;
+    result.set("url", entry.request.url.as_str())?;
+    result.set("request", lua.to_value_with(&entry.request, options)?)?;
+    result.set("sent", lua.to_value_with(&entry.sent, options)?)?;
+    result.set("status", response.status)?;
+    result.set("status_text", response.status_text)?;
+    result.set("headers", headers)?;
+    result.set("body", response.body)?;
+    result.set("duration_ms", response.duration_ms)?;
+    result.set("pinned", entry.pinned)?;
+    Ok(result)
+}
@@ -0,0 +1,169 @@
+use anyhow::Result;
+use mlua::{Function, Lua, LuaSerdeExt, SerializeOptions, Table, Value};
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+
+use crate::model::Workspace;
+use crate::{FfiRequest, FfiResponse};
+
+/// Registry key holding the list of `pigeon.on_request` hooks.
+const REQUEST_HOOKS: &str = "pigeon.hooks.on_request";
+/// Registry key holding the list of `pigeon.on_response` hooks.
+const RESPONSE_HOOKS: &str = "pigeon.hooks.on_response";
+/// Registry key holding the list of `pigeon.on_workspace_load` hooks.
+const WORKSPACE_LOAD_HOOKS: &str = "pigeon.hooks.on_workspace_load";
+/// Registry key holding the list of `pigeon.on_workspace_save` hooks.
+const WORKSPACE_SAVE_HOOKS: &str = "pigeon.hooks.on_workspace_save";
+
+/// When workspace hooks run.
+#[derive(Debug, Clone, Copy)]
+pub enum WorkspaceEvent {
+    /// The workspace was loaded at startup.
+    Load,
+    /// The host is about to store the workspace.
+    Save,
+}
+
+/// Register `pigeon.on_request(fn)`, `pigeon.on_response(fn)`,
+/// `pigeon.on_workspace_load(fn)` and `pigeon.on_workspace_save(fn)`.
+///
+/// Request hooks run in registration order for every request sent through the
+/// core and receive the request as a table (`method`, `url`, `headers`, `body`).
+/// A hook may edit the table in place or return a replacement.
+///
+/// Response hooks are called as `fn(response, request)` once the request has
+/// finished, with `status`, `statusText`, `headers`, `body` and `durationMs`.
+/// Transport failures are reported too, with `status` 0. Return values are ignored.
+///
+/// Workspace hooks receive the whole workspace (`endpoints`, `headers`, `bodies`,
+/// `spaces`, `environments`, `folders`) when it is loaded or saved, and like
+/// request hooks may edit it in place or return a replacement. The result replaces
+/// the stored workspace, so edit the table rather than calling `pigeon.workspace`
+/// from them.
+pub fn setup(lua: &Lua, table: &Table) -> Result<()> {
+    let on_request = lua.create_function(|lua, hook: Function| {
+        let hooks = hook_list(lua, REQUEST_HOOKS)?;
+        hooks.raw_push(hook)
+    })?;
+    table.set("on_request", on_request)?;
+
+    let on_response = lua.create_function(|lua, hook: Function| {
+        let hooks = hook_list(lua, RESPONSE_HOOKS)?;
+        hooks.raw_push(hook)
+    })?;
+    table.set("on_response", on_response)?;
+
+    let on_workspace_load = lua.create_function(|lua, hook: Function| {
+        let hooks = hook_list(lua, WORKSPACE_LOAD_HOOKS)?;
+        hooks.raw_push(hook)
+    })?;
+    table.set("on_workspace_load", on_workspace_load)?;
+
+    let on_workspace_save = lua.create_function(|lua, hook: Function| {
+        let hooks = hook_list(lua, WORKSPACE_SAVE_HOOKS)?;
+        hooks.raw_push(hook)
+    })?;
+    table.set("on_workspace_save", on_workspace_save)?;
+
+    Ok(())
+}
+
+/// Pass `request` through every registered `on_request` hook.
+///
+/// A hook that errors or produces an invalid request is reported to `on_error`
+/// and skipped, so the request continues with the previous hook's result.
+pub fn run_request_hooks(
+    lua: &Lua,
+    request: FfiRequest,
+    on_error: impl FnMut(anyhow::Error),
+) -> Result<FfiRequest> {
+    run_transform_hooks(lua, REQUEST_HOOKS, "on_request", request, on_error)
+}
+
+/// Pass `workspace` through every hook registered for `event`.
+///
+/// As with request hooks, a failing hook is reported to `on_error` and skipped.
+pub fn run_workspace_hooks(
+    lua: &Lua,
+    event: WorkspaceEvent,
+    workspace: Workspace,
+    on_error: impl FnMut(anyhow::Error),
+) -> Result<Workspace> {
+    let (key, name) = match event {
+        WorkspaceEvent::Load => (WORKSPACE_LOAD_HOOKS, "on_workspace_load"),
+        WorkspaceEvent::Save => (WORKSPACE_SAVE_HOOKS, "on_workspace_save"),
+    };
+    run_transform_hooks(lua, key, name, workspace, on_error)
+}
+
+/// Call every registered `on_response` hook with `response` and `request`.
+///
+/// A hook that errors is reported to `on_error`; the remaining hooks still run.
+pub fn run_response_hooks(
+    lua: &Lua,
+    request: &FfiRequest,
+    response: &FfiResponse,
+    mut on_error: impl FnMut(anyhow::Error),
+) -> Result<()> {
+    let hooks = hook_list(lua, RESPONSE_HOOKS)?;
+    if hooks.raw_len() == 0 {
+        return Ok(());
+    }
+
+    let options = SerializeOptions::new()
+        .serialize_none_to_null(false)
+        .serialize_unit_to_null(false);
+    let request = lua.to_value_with(request, options)?;
+    let response = lua.to_value_with(response, options)?;
+
+    for hook in hooks.sequence_values::<Function>() {
+        let hook = hook?;
+        if let Err(e) = hook.call::<_, ()>((response.clone(), request.clone())) {
+            on_error(anyhow::Error::new(e).context("on_response hook failed"));
+        }
+    }
+
+    Ok(())
+}
+
+/// Run the hooks under `key` in order, each getting the previous one's result.
+fn run_transform_hooks<T: Serialize + DeserializeOwned>(
+    lua: &Lua,
+    key: &str,
+    name: &str,
+    mut value: T,
+    mut on_error: impl FnMut(anyhow::Error),
+) -> Result<T> {
+    let hooks = hook_list(lua, key)?;
+    let options = SerializeOptions::new()
+        .serialize_none_to_null(false)
+        .serialize_unit_to_null(false);
+
+    for hook in hooks.sequence_values::<Function>() {
+        let hook = hook?;
+        let result = (|| -> mlua::Result<T> {
+            let table = lua.to_value_with(&value, options)?;
+            let returned: Value = hook.call(table.clone())?;
+            match returned {
+                Value::Table(_) => lua.from_value(returned),
+                _ => lua.from_value(table),
+            }
+        })();
+
+        match result {
+            Ok(updated) => value = updated,
+            Err(e) => on_error(anyhow::Error::new(e).context(format!("{name} hook failed"))),
+        }
+    }
+
+    Ok(value)
+}
+
+fn hook_list<'lua>(lua: &'lua Lua, key: &str) -> mlua::Result<Table<'lua>> {
+    if let Some(hooks) = lua.named_registry_value::<Option<Table>>(key)? {
+        return Ok(hooks);
+    }
+    let hooks = lua.create_table()?;
+    lua.set_named_registry_value(key, hooks.clone())?;
+    Ok(hooks)
+}
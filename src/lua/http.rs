@@ -0,0 +1,109 @@
+use anyhow::{bail, Result};
+use mlua::{Lua, LuaSerdeExt, Table, Value};
+use serde::Deserialize;
+use std::collections::BTreeMap;
+use std::time::Duration;
+
+use crate::{FfiBody, FfiHeader, FfiRequest, FfiResponse};
+
+/// Upper bound for a single `pigeon.http.request` call so scripts can't hang forever.
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(30);
+
+#[derive(Debug, Deserialize)]
+struct LuaRequest {
+    #[serde(default = "default_method")]
+    method: String,
+    url: String,
+    #[serde(default)]
+    headers: BTreeMap<String, String>,
+    #[serde(default)]
+    body: Option<String>,
+}
+
+fn default_method() -> String {
+    "GET".to_string()
+}
+
+/// Register `pigeon.http.request{ method, url, headers, body }`.
+///
+/// Returns `{ status, status_text, headers, body, duration_ms }`, with repeated
+/// headers joined by `", "`. Transport failures raise a Lua error.
+pub fn setup(lua: &Lua, table: &Table) -> Result<()> {
+    let http = lua.create_table()?;
+
+    let request = lua.create_function(|lua, opts: Value| {
+        let req: LuaRequest = lua.from_value(opts)?;
+        let response = send(req).map_err(mlua::Error::external)?;
+
+        let mut headers: BTreeMap<String, String> = BTreeMap::new();
+        for (key, value) in response.headers {
+            headers
+                .entry(key)
+                .and_modify(|v| {
+                    v.push_str(", ");
+                    v.push_str(&value);
+                })
+                .or_insert(value);
+        }
+
+        let result = lua.create_table()?;
+        result.set("status", response.status)?;
+        result.set("status_text", response.status_text)?;
+        result.set("headers", headers)?;
+        result.set("body", response.body)?;
+        result.set("duration_ms", response.duration_ms)?;
+        Ok(result)
+    })?;
+    http.set("request", request)?;
+
+    table.set("http", http)?;
+
+    Ok(())
+}
+
+/// Only http(s) URLs are allowed, and `on_request` hooks are not applied. The
+/// request runs on the shared tokio runtime while the Lua thread waits for it.
+fn send(req: LuaRequest) -> Result<FfiResponse> {
+    let url = reqwest::Url::parse(&req.url)?;
+    if !matches!(url.scheme(), "http" | "https") {
+        bail!(
+            "pigeon.http only supports http(s) URLs, got {}",
+            url.scheme()
+        );
+    }
+
+    let request = FfiRequest {
+        method: req.method,
+        url: req.url,
+        headers: req
+            .headers
+            .into_iter()
+            .map(|(key, value)| FfiHeader {
+                key,
+                value,
+                enabled: true,
+                secret: false,
+            })
+            .collect(),
+        body: req.body.map(|content| FfiBody {
+            content_type: String::new(),
+            content,
+        }),
+        auth: None,
+        space: None,
+    };
+
+    let (tx, rx) = std::sync::mpsc::sync_channel(1);
+    crate::get_tokio_runtime().spawn(async move {
+        let url = request.url.clone();
+        let dispatch = crate::dispatch_request(request, &url);
+        let result = tokio::time::timeout(REQUEST_TIMEOUT, dispatch).await;
+        let _ = tx.send(result);
+    });
+
+    match rx.recv()? {
+        Ok(response) if response.status == 0 => bail!("{}", response.body),
+        Ok(response) => Ok(response),
+        Err(_) => bail!("request timed out after {}s", REQUEST_TIMEOUT.as_secs()),
+    }
+}
@@ -0,0 +1,40 @@
+use anyhow::Result;
+use mlua::{Lua, LuaSerdeExt, Table, Value};
+
+/// Register `pigeon.json.encode(value, { pretty = bool })`, `pigeon.json.decode(text)`
+/// and `pigeon.json.null`.
+///
+/// JSON `null` decodes to `pigeon.json.null` so it survives inside arrays, and
+/// decoded arrays keep their array-ness when encoded again (even when empty).
+pub fn setup(lua: &Lua, table: &Table) -> Result<()> {
+    let json = lua.create_table()?;
+
+    let encode = lua.create_function(|lua, (value, opts): (Value, Option<Table>)| {
+        let pretty = opts
+            .map(|o| o.get::<_, Option<bool>>("pretty"))
+            .transpose()?
+            .flatten()
+            .unwrap_or(false);
+        let value: serde_json::Value = lua.from_value(value)?;
+        let text = if pretty {
+            serde_json::to_string_pretty(&value)
+        } else {
+            serde_json::to_string(&value)
+        };
+        text.map_err(mlua::Error::external)
+    })?;
+    json.set("encode", encode)?;
+
+    let decode = lua.create_function(|lua, text: mlua::String| {
+        let value: serde_json::Value =
+            serde_json::from_slice(text.as_bytes()).map_err(mlua::Error::external)?;
+        lua.to_value(&value)
+    })?;
+    json.set("decode", decode)?;
+
+    json.set("null", lua.null())?;
+
+    table.set("json", json)?;
+
+    Ok(())
+}
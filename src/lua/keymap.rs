@@ -0,0 +1,127 @@
+use anyhow::{bail, Result};
+use mlua::{Lua, Table, Value};
+use std::collections::BTreeMap;
+
+/// Registry key holding the `action -> chords` table.
+const KEYMAP_KEY: &str = "pigeon.keymap";
+/// Modifiers in the order they appear in a normalized chord.
+const MODIFIERS: &[&str] = &["ctrl", "alt", "shift", "meta"];
+/// Non-character key names accepted in chords.
+const NAMED_KEYS: &[&str] = &[
+    "enter",
+    "escape",
+    "tab",
+    "space",
+    "backspace",
+    "delete",
+    "up",
+    "down",
+    "left",
+    "right",
+    "pageup",
+    "pagedown",
+    "home",
+    "end",
+];
+
+/// Register `pigeon.keymap.set(action, chord_or_chords)`.
+///
+/// ```lua
+/// pigeon.keymap.set("send_request", { "ctrl+s", "alt+enter" })
+/// pigeon.keymap.set("quit", "ctrl+q")
+/// ```
+///
+/// Chords are `modifier+...+key` with modifiers `ctrl`, `alt`, `shift`, `meta` and
+/// a single character or a named key (`enter`, `tab`, `up`, ...). Setting an action
+/// replaces the front end's default bindings for it; an empty list unbinds it.
+pub fn setup(lua: &Lua, table: &Table) -> Result<()> {
+    let keymap = lua.create_table()?;
+
+    let set = lua.create_function(|lua, (action, chords): (String, Value)| {
+        if action.trim().is_empty() {
+            return Err(mlua::Error::runtime("keymap action must not be empty"));
+        }
+        let chords: Vec<String> = match chords {
+            Value::String(s) => vec![s.to_str()?.to_string()],
+            Value::Table(t) => t.sequence_values::<String>().collect::<mlua::Result<_>>()?,
+            _ => {
+                return Err(mlua::Error::runtime(
+                    "keymap chords must be a string or a list of strings",
+                ))
+            }
+        };
+        let chords = chords
+            .iter()
+            .map(|c| normalize_chord(c))
+            .collect::<Result<Vec<_>>>()
+            .map_err(|e| mlua::Error::runtime(format!("keymap `{action}`: {e}")))?;
+
+        keymap_table(lua)?.set(action, chords)
+    })?;
+    keymap.set("set", set)?;
+
+    table.set("keymap", keymap)?;
+
+    Ok(())
+}
+
+/// Bindings set from Lua, as `action -> normalized chords`.
+pub fn get(lua: &Lua) -> Result<BTreeMap<String, Vec<String>>> {
+    Ok(keymap_table(lua)?
+        .pairs::<String, Vec<String>>()
+        .collect::<mlua::Result<_>>()?)
+}
+
+/// Lowercase a chord and put its modifiers in a fixed order (`Shift+Ctrl+K` -> `ctrl+shift+k`).
+fn normalize_chord(chord: &str) -> Result<String> {
+    let chord = chord.trim().to_lowercase();
+    // A trailing `+` means the plus key itself, e.g. `ctrl++`.
+    let (mods, key) = match chord.strip_suffix("++") {
+        Some(mods) => (mods, "+"),
+        None => match chord.rsplit_once('+') {
+            Some((mods, key)) => (mods, key),
+            None => ("", chord.as_str()),
+        },
+    };
+    let key = match key {
+        "return" => "enter",
+        "esc" => "escape",
+        "del" => "delete",
+        other => other,
+    };
+
+    if key.chars().count() != 1 && !NAMED_KEYS.contains(&key) {
+        bail!("unknown key `{key}` in chord `{chord}`");
+    }
+
+    let mut present = Vec::new();
+    for modifier in mods.split('+').filter(|m| !m.is_empty()) {
+        let modifier = match modifier {
+            "control" => "ctrl",
+            "option" => "alt",
+            "cmd" | "super" => "meta",
+            other => other,
+        };
+        if !MODIFIERS.contains(&modifier) {
+            bail!("unknown modifier `{modifier}` in chord `{chord}`");
+        }
+        present.push(modifier);
+    }
+
+    let mut parts: Vec<&str> = MODIFIERS
+        .iter()
+        .copied()
+        .filter(|m| present.contains(m))
+        .collect();
+    parts.push(key);
+    Ok(parts.join("+"))
+}
+
+fn keymap_table(lua: &Lua) -> mlua::Result<Table<'_>> {
+    if let Some(keymap) = lua.named_registry_value::<Option<Table>>(KEYMAP_KEY)? {
+        return Ok(keymap);
+    }
+    let keymap = lua.create_table()?;
+    lua.set_named_registry_value(KEYMAP_KEY, keymap.clone())?;
+    Ok(keymap)
+}
@@ -0,0 +1,116 @@
+use anyhow::Result;
+use mlua::{Debug, HookTriggers, Lua, MultiValue, Table, Thread, Value};
+use std::sync::atomic::{AtomicU64, Ordering};
+
+/// Instructions a single entry into Lua (loading the config, one hook run, one
+/// scheduled job, ...) may execute before it is aborted.
+pub const INSTRUCTION_LIMIT: u64 = 200_000_000;
+/// Upper bound for memory allocated by the Lua state.
+pub const MEMORY_LIMIT: usize = 256 * 1024 * 1024;
+/// How often the instruction hook runs; the limit is enforced at this granularity.
+const CHECK_EVERY: u32 = 10_000;
+
+/// Instructions used since the last [`reset`], kept in the state's app data.
+struct Budget(AtomicU64);
+
+/// Install the instruction-count hook and memory limit on `lua`.
+///
+/// A script that runs past the instruction budget fails with `script exceeded
+/// budget of N instructions`, and allocations past the memory limit fail with
+/// Lua's `not enough memory`, instead of hanging or exhausting the host process.
+pub fn setup(lua: &Lua) -> Result<()> {
+    lua.set_memory_limit(MEMORY_LIMIT)?;
+
+    lua.set_app_data(Budget(AtomicU64::new(0)));
+    lua.set_hook(triggers(lua), check_budget);
+
+    // mlua hooks one thread at a time, so move the hook into each coroutine while
+    // it runs and back to the caller afterwards.
+    let coroutine: Table = lua.globals().get("coroutine")?;
+    let resume = lua.create_function(|lua, (co, args): (Thread, MultiValue)| {
+        let caller = lua.current_thread();
+        co.set_hook(triggers(lua), check_budget);
+        let result = co.resume::<_, MultiValue>(args);
+        caller.set_hook(triggers(lua), check_budget);
+
+        let (ok, mut values) = match result {
+            Ok(values) => (true, values),
+            Err(e) => (
+                false,
+                MultiValue::from_vec(vec![Value::String(lua.create_string(message(&e))?)]),
+            ),
+        };
+        values.push_front(Value::Boolean(ok));
+        Ok(values)
+    })?;
+    coroutine.set("resume", resume)?;
+
+    // Rebuild `wrap` on top of the hooked `resume`.
+    let wrap: mlua::Function = lua
+        .load(
+            r#"
+            local create, resume = coroutine.create, coroutine.resume
+            local function unpack_result(ok, ...)
+                if not ok then error((...), 0) end
+                return ...
+            end
+            return function(f)
+                local co = create(f)
+                return function(...) return unpack_result(resume(co, ...)) end
+            end
+            "#,
+        )
+        .set_name("coroutine.wrap")
+        .eval()?;
+    coroutine.set("wrap", wrap)?;
+
+    Ok(())
+}
+
+/// Start a fresh instruction budget; called whenever the host enters Lua.
+pub fn reset(lua: &Lua) {
+    let Some(budget) = lua.app_data_ref::<Budget>() else {
+        return;
+    };
+    let exhausted = budget.0.swap(0, Ordering::Relaxed) > INSTRUCTION_LIMIT;
+    drop(budget);
+    if exhausted {
+        lua.set_hook(triggers(lua), check_budget);
+    }
+}
+
+/// Check every `CHECK_EVERY` instructions, or every instruction once the budget is
+/// spent so `pcall` can't keep a runaway script alive.
+fn triggers(lua: &Lua) -> HookTriggers {
+    let every = match lua.app_data_ref::<Budget>() {
+        Some(budget) if budget.0.load(Ordering::Relaxed) > INSTRUCTION_LIMIT => 1,
+        _ => CHECK_EVERY,
+    };
+    HookTriggers::new().every_nth_instruction(every)
+}
+
+fn check_budget(lua: &Lua, _: Debug) -> mlua::Result<()> {
+    let Some(budget) = lua.app_data_ref::<Budget>() else {
+        return Ok(());
+    };
+    let before = budget.0.fetch_add(CHECK_EVERY.into(), Ordering::Relaxed);
+    drop(budget);
+    if before + u64::from(CHECK_EVERY) <= INSTRUCTION_LIMIT {
+        return Ok(());
+    }
+    if before <= INSTRUCTION_LIMIT {
+        lua.current_thread().set_hook(triggers(lua), check_budget);
+    }
+    Err(mlua::Error::runtime(format!(
+        "script exceeded budget of {INSTRUCTION_LIMIT} instructions"
+    )))
+}
+
+/// The message of a Lua error, without mlua's `runtime error:` prefix.
+fn message(error: &mlua::Error) -> String {
+    match error {
+        mlua::Error::CallbackError { cause, .. } => message(cause),
+        mlua::Error::RuntimeError(message) => message.clone(),
+        other => other.to_string(),
+    }
+}
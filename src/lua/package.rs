@@ -0,0 +1,30 @@
+use anyhow::Result;
+use mlua::{Lua, Table};
+use std::path::Path;
+
+use super::plugin::PLUGINS_DIR;
+
+/// Directory under the config dir that `require` searches first.
+pub const MODULES_DIR: &str = "lua";
+
+/// Point `require` at the config directory: `require("mylib")` loads
+/// `lua/mylib.lua` (or `lua/mylib/init.lua`), then the same under `plugins/`.
+///
+/// Native modules stay unavailable, as the state is created in safe mode.
+pub fn setup(lua: &Lua, config_dir: &Path) -> Result<()> {
+    let package: Table = lua.globals().get("package")?;
+
+    let path = [MODULES_DIR, PLUGINS_DIR]
+        .iter()
+        .flat_map(|dir| {
+            let dir = config_dir.join(dir);
+            [dir.join("?.lua"), dir.join("?").join("init.lua")]
+        })
+        .map(|pattern| pattern.display().to_string())
+        .collect::<Vec<_>>()
+        .join(";");
+
+    package.set("path", path)?;
+
+    Ok(())
+}
@@ -1,6 +1,326 @@
-use anyhow::Result;
-use mlua::{Lua, Table};
+use anyhow::{anyhow, bail, Context, Result};
+use mlua::{Lua, LuaSerdeExt, Table, Value};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet};
+use std::path::{Path, PathBuf};
+
+/// Directory under the config dir that is scanned for `*.lua` plugins.
+pub const PLUGINS_DIR: &str = "plugins";
+/// Enable/disable state, kept next to `config.lua`.
+const STATE_FILE: &str = "plugins.json";
+/// Key of the table holding each plugin's return value (`pigeon.plugins.<name>`).
+const PLUGINS_KEY: &str = "plugins";
+
+/// A discovered plugin and the outcome of loading it.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PluginInfo {
+    /// File stem, e.g. `aws-sigv4` for `plugins/aws-sigv4.lua`.
+    pub name: String,
+    pub path: PathBuf,
+    pub enabled: bool,
+    pub loaded: bool,
+    /// Load failure for this plugin; other plugins are unaffected.
+    pub error: Option<String>,
+    /// Declared with `plugin.setup{ version = ... }`.
+    pub version: Option<String>,
+    /// Plugins this one needs, as `name -> version requirement` (`*` for any).
+    pub requires: BTreeMap<String, String>,
+}
+
+/// What a plugin passes to `plugin.setup{ name, version, requires, conflicts }`.
+#[derive(Debug, Deserialize)]
+struct Manifest {
+    name: Option<String>,
+    version: Option<String>,
+    #[serde(default)]
+    requires: Requires,
+    #[serde(default)]
+    conflicts: Vec<String>,
+}
+
+/// `requires = { "base" }` or `requires = { base = ">=1.2" }`.
+#[derive(Debug, Deserialize)]
+#[serde(untagged)]
+enum Requires {
+    Names(Vec<String>),
+    Versions(BTreeMap<String, String>),
+}
+
+impl Default for Requires {
+    fn default() -> Self {
+        Self::Names(Vec::new())
+    }
+}
+
+/// Plugins known to this Lua state, kept in its app data while and after loading.
+struct Plugins {
+    plugins: Vec<PluginInfo>,
+    /// Names currently being loaded, innermost last, for cycle detection.
+    loading: Vec<String>,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct PluginState {
+    #[serde(default)]
+    disabled: BTreeSet<String>,
+}
+
+/// Register `pigeon.plugins`, where each loaded plugin's return value is kept.
+pub fn setup(lua: &Lua, table: &Table) -> Result<()> {
+    table.set(PLUGINS_KEY, lua.create_table()?)?;
+    Ok(())
+}
+
+/// Find `plugins/*.lua` under `config_dir`, sorted by name. Missing dir means no plugins.
+pub fn discover(config_dir: &Path) -> Result<Vec<PluginInfo>> {
+    let dir = config_dir.join(PLUGINS_DIR);
+    if !dir.is_dir() {
+        return Ok(Vec::new());
+    }
+
+    let disabled = read_state(config_dir)?.disabled;
+    let mut plugins = Vec::new();
+    for entry in std::fs::read_dir(&dir)
+        .with_context(|| format!("Failed to read plugin dir: {}", dir.display()))?
+    {
+        let path = entry?.path();
+        if path.extension().and_then(|e| e.to_str()) != Some("lua") || !path.is_file() {
+            continue;
+        }
+        let Some(name) = path.file_stem().and_then(|s| s.to_str()) else {
+            continue;
+        };
+        plugins.push(PluginInfo {
+            name: name.to_string(),
+            enabled: !disabled.contains(name),
+            path: path.clone(),
+            loaded: false,
+            error: None,
+            version: None,
+            requires: BTreeMap::new(),
+        });
+    }
+
+    plugins.sort_by(|a, b| a.name.cmp(&b.name));
+    Ok(plugins)
+}
+
+/// Load every enabled plugin in `plugins`, dependencies first, and return the outcome.
+///
+/// A plugin can declare itself with `plugin.setup{...}` at the top of its file:
+///
+/// ```lua
+/// plugin.setup{
+///   version = "1.2.0",
+///   requires = { ["aws-auth"] = ">=2.0" }, -- or { "aws-auth" } for any version
+///   conflicts = { "legacy-auth" },
+/// }
+/// ```
+///
+/// Required plugins are loaded on the spot, so file order doesn't matter. A
+/// missing, disabled, failing or too old dependency, an enabled conflicting
+/// plugin, a dependency cycle or a `name` other than the file's fails that
+/// plugin's load with an error saying which.
+pub fn load_all(lua: &Lua, plugins: Vec<PluginInfo>) -> Vec<PluginInfo> {
+    let names: Vec<String> = plugins
+        .iter()
+        .filter(|p| p.enabled)
+        .map(|p| p.name.clone())
+        .collect();
+    lua.set_app_data(Plugins {
+        plugins,
+        loading: Vec::new(),
+    });
+
+    for name in names {
+        // Failures are recorded on the plugin.
+        let _ = load(lua, &name);
+    }
+    snapshot(lua)
+}
+
+/// Record that `name` was enabled or disabled, loading it if it was enabled.
+///
+/// A loaded plugin can't be unloaded, so disabling only takes effect on reload.
+pub fn set_enabled_in_state(lua: &Lua, name: &str, enabled: bool) -> Result<PluginInfo> {
+    with_plugin(lua, name, |plugin| plugin.enabled = enabled)?;
+    if enabled {
+        with_plugin(lua, name, |plugin| plugin.error = None)?;
+        let _ = load(lua, name);
+    }
+    with_plugin(lua, name, |plugin| plugin.clone())
+}
+
+/// Plugins known to this Lua state and the outcome of loading them.
+fn snapshot(lua: &Lua) -> Vec<PluginInfo> {
+    lua.app_data_ref::<Plugins>()
+        .map(|state| state.plugins.clone())
+        .unwrap_or_default()
+}
+
+fn with_plugin<R>(lua: &Lua, name: &str, f: impl FnOnce(&mut PluginInfo) -> R) -> Result<R> {
+    let mut state = lua
+        .app_data_mut::<Plugins>()
+        .ok_or_else(|| anyhow!("plugins are not loaded"))?;
+    let plugin = state
+        .plugins
+        .iter_mut()
+        .find(|p| p.name == name)
+        .ok_or_else(|| anyhow!("unknown plugin: {name}"))?;
+    Ok(f(plugin))
+}
+
+/// Load plugin `name` unless it is already loaded or has failed.
+fn load(lua: &Lua, name: &str) -> Result<()> {
+    let plugin = with_plugin(lua, name, |plugin| plugin.clone())?;
+    if plugin.loaded {
+        return Ok(());
+    }
+    if let Some(error) = plugin.error {
+        bail!("{error}");
+    }
+    {
+        let mut state = lua.app_data_mut::<Plugins>().unwrap();
+        if let Some(start) = state.loading.iter().position(|n| n == name) {
+            let mut cycle = state.loading[start..].to_vec();
+            cycle.push(name.to_string());
+            bail!("dependency cycle: {}", cycle.join(" -> "));
+        }
+        state.loading.push(name.to_string());
+    }
+
+    let result = run(lua, &plugin);
+
+    lua.app_data_mut::<Plugins>().unwrap().loading.pop();
+    let error = result.as_ref().err().map(|e| format!("{e:#}"));
+    with_plugin(lua, name, |plugin| {
+        plugin.loaded = error.is_none();
+        plugin.error = error;
+    })?;
+    result
+}
+
+/// Run a plugin file in its own environment so its globals don't leak into
+/// `config.lua` or other plugins. Its return value is stored as `pigeon.plugins.<name>`.
+fn run(lua: &Lua, plugin: &PluginInfo) -> Result<()> {
+    let script = std::fs::read_to_string(&plugin.path)
+        .with_context(|| format!("Failed to read plugin: {}", plugin.path.display()))?;
+
+    let env = lua.create_table()?;
+    let meta = lua.create_table()?;
+    meta.set("__index", lua.globals())?;
+    env.set_metatable(Some(meta));
+    env.set("plugin", plugin_table(lua, &plugin.name)?)?;
+
+    let exports: Value = lua
+        .load(&script)
+        .set_name(plugin.path.display().to_string())
+        .set_environment(env)
+        .eval()
+        .with_context(|| format!("Failed to load plugin: {}", plugin.name))?;
+
+    let pigeon: Table = lua.globals().get("pigeon")?;
+    let plugins: Table = pigeon.get(PLUGINS_KEY)?;
+    let exports = match exports {
+        Value::Nil => Value::Boolean(true),
+        other => other,
+    };
+    plugins.set(plugin.name.as_str(), exports)?;
 
-pub fn setup(_lua: &Lua, _table: &Table) -> Result<()> {
     Ok(())
 }
+
+/// The `plugin` table a plugin file sees, with `setup` bound to that plugin.
+fn plugin_table<'lua>(lua: &'lua Lua, name: &str) -> Result<Table<'lua>> {
+    let table = lua.create_table()?;
+    table.set("name", name)?;
+
+    let name = name.to_string();
+    let setup = lua.create_function(move |lua, manifest: Value| {
+        let manifest: Manifest = lua.from_value(manifest)?;
+        declare(lua, &name, manifest).map_err(|e| mlua::Error::runtime(format!("{e:#}")))
+    })?;
+    table.set("setup", setup)?;
+
+    Ok(table)
+}
+
+/// Check a manifest and load its dependencies.
+fn declare(lua: &Lua, name: &str, manifest: Manifest) -> Result<()> {
+    if let Some(declared) = &manifest.name {
+        if declared != name {
+            bail!("plugin declares name `{declared}` but its file is `{name}.lua`");
+        }
+    }
+    if let Some(version) = &manifest.version {
+        semver::Version::parse(version).with_context(|| format!("invalid version `{version}`"))?;
+    }
+
+    let requires = match manifest.requires {
+        Requires::Names(names) => names.into_iter().map(|n| (n, "*".to_string())).collect(),
+        Requires::Versions(versions) => versions,
+    };
+    with_plugin(lua, name, |plugin| {
+        plugin.version = manifest.version.clone();
+        plugin.requires = requires.clone();
+    })?;
+
+    for other in &manifest.conflicts {
+        if let Ok(true) = with_plugin(lua, other, |p| p.enabled) {
+            bail!("conflicts with `{other}`, which is enabled");
+        }
+    }
+
+    for (dependency, requirement) in &requires {
+        let req = semver::VersionReq::parse(requirement).with_context(|| {
+            format!("invalid version requirement `{requirement}` for `{dependency}`")
+        })?;
+        let enabled = with_plugin(lua, dependency, |p| p.enabled)
+            .map_err(|_| anyhow!("requires `{dependency}`, which is not installed"))?;
+        if !enabled {
+            bail!("requires `{dependency}`, which is disabled");
+        }
+        load(lua, dependency)
+            .map_err(|e| anyhow!("requires `{dependency}`, which failed to load: {e:#}"))?;
+
+        let version = with_plugin(lua, dependency, |p| p.version.clone())?;
+        if req != semver::VersionReq::STAR {
+            let found = version
+                .as_deref()
+                .and_then(|v| semver::Version::parse(v).ok())
+                .ok_or_else(|| {
+                    anyhow!("requires `{dependency}` {requirement}, which declares no version")
+                })?;
+            if !req.matches(&found) {
+                bail!("requires `{dependency}` {requirement}, found {found}");
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// Persist whether `name` is enabled.
+pub fn set_enabled(config_dir: &Path, name: &str, enabled: bool) -> Result<()> {
+    let mut state = read_state(config_dir)?;
+    if enabled {
+        state.disabled.remove(name);
+    } else {
+        state.disabled.insert(name.to_string());
+    }
+
+    let path = config_dir.join(STATE_FILE);
+    std::fs::write(&path, serde_json::to_string_pretty(&state)?)
+        .with_context(|| format!("Failed to write plugin state: {}", path.display()))
+}
+
+fn read_state(config_dir: &Path) -> Result<PluginState> {
+    let path = config_dir.join(STATE_FILE);
+    if !path.exists() {
+        return Ok(PluginState::default());
+    }
+    let data = std::fs::read_to_string(&path)
+        .with_context(|| format!("Failed to read plugin state: {}", path.display()))?;
+    serde_json::from_str(&data).with_context(|| format!("Invalid plugin state: {}", path.display()))
+}
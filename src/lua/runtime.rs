@@ -1,14 +1,34 @@
+use anyhow::anyhow;
 use anyhow::{Context, Ok, Result};
-use mlua::{Lua, LuaOptions, StdLib};
+use mlua::{
+    DeserializeOptions, Lua, LuaOptions, LuaSerdeExt, MultiValue, SerializeOptions, StdLib, Value,
+};
+use std::collections::BTreeMap;
 use std::path::{Path, PathBuf};
-use std::sync::{Arc, Mutex};
+use std::sync::Mutex;
 
-use super::{config, plugin};
+use super::executor::{LuaThread, WeakLuaThread};
+use super::expect::{self, TestResult};
+use super::hooks::WorkspaceEvent;
+use super::plugin::{self, PluginInfo};
+use super::theme::{self, ThemeSet};
+use super::{
+    auth, commands, config, console, crypto, formatter, fs, history, hooks, http, json, keymap,
+    limits, package, schedule, secrets, variables, workspace,
+};
+use crate::model::Workspace;
+use crate::{FfiAuth, FfiRequest, FfiResponse};
 
 /// Lua runtime wrapper that manages the shared Lua state and provides safe execution.
+///
+/// The state lives on its own thread (see [`LuaThread`]) and every method queues a
+/// job there. Methods on the request path are async, so a slow hook or script
+/// never blocks the caller's thread.
 pub struct LuaRuntime {
-    lua: Arc<Mutex<Lua>>,
+    lua: LuaThread,
     config_path: PathBuf,
+    plugins: Mutex<Vec<PluginInfo>>,
+    secrets: secrets::Cache,
 }
 
 impl LuaRuntime {
@@ -22,30 +42,245 @@ impl LuaRuntime {
             | StdLib::UTF8
             | StdLib::MATH
             | StdLib::PACKAGE;
-        let lua = Lua::new_with(stdlib, LuaOptions::default())?;
+        let lua = LuaThread::spawn(move || Ok(Lua::new_with(stdlib, LuaOptions::default())?))?;
 
-        let runtime = Self {
-            lua: Arc::new(Mutex::new(lua)),
-            config_path: config_dir.to_path_buf(),
-        };
+        let config_path = config_dir.to_path_buf();
+        let secrets = secrets::Cache::default();
+        let (dir, handle, cache) = (config_path.clone(), lua.downgrade(), secrets.clone());
+        lua.call(move |lua| setup(lua, &dir, handle, cache))?;
 
-        runtime.setup()?;
-
-        Ok(runtime)
+        Ok(Self {
+            lua,
+            config_path,
+            plugins: Mutex::new(Vec::new()),
+            secrets,
+        })
     }
 
-    /// Execute a Lua script from a file
+    /// Execute a Lua script from a file, then check what it put in `pigeon.config`.
     pub fn load_file(&self, path: &Path) -> Result<()> {
-        let lua = self.lua.lock().unwrap();
-        let script = std::fs::read_to_string(path)
+        let path = path.to_path_buf();
+        let script = std::fs::read_to_string(&path)
             .with_context(|| format!("Failed to read lua script: {}", path.display()))?;
 
-        lua.load(&script)
-            .set_name(path.display().to_string())
-            .exec()
-            .with_context(|| format!("Failed to execute Lua script: {}", path.display()))?;
+        self.lua.call(move |lua| {
+            lua.load(&script)
+                .set_name(path.display().to_string())
+                .exec()
+                .with_context(|| format!("Failed to execute Lua script: {}", path.display()))?;
+            config::validate(lua).with_context(|| format!("Invalid config in {}", path.display()))
+        })
+    }
+
+    /// Evaluate a Lua snippet and return its first result as JSON.
+    ///
+    /// Values without a JSON representation (functions, userdata) become `null`.
+    pub fn eval(&self, script: &str) -> Result<serde_json::Value> {
+        let script = script.to_string();
+        self.lua.call(move |lua| {
+            let values: MultiValue = lua
+                .load(&script)
+                .set_name("eval")
+                .eval()
+                .context("Failed to evaluate Lua script")?;
+
+            let value = values.into_iter().next().unwrap_or(Value::Nil);
+            to_json(lua, value)
+        })
+    }
+
+    /// Read a value from `pigeon.config` by dotted path (e.g. `"http.timeout"`).
+    pub fn config_get(&self, path: &str) -> Result<serde_json::Value> {
+        let path = path.to_string();
+        self.lua.call(move |lua| {
+            let value = config::get(lua, &path)
+                .with_context(|| format!("Failed to read config value: {path}"))?;
+            to_json(lua, value)
+        })
+    }
+
+    /// Run a collection-step script with `request`, `response` and `vars` in scope.
+    ///
+    /// The script gets its own environment (falling back to globals), so these names
+    /// don't leak between steps. Changes to `vars` are written back to `variables`.
+    /// Returns the results of the `pigeon.test` blocks the script ran.
+    pub async fn run_step_script(
+        &self,
+        script: &str,
+        request: &serde_json::Value,
+        response: &serde_json::Value,
+        variables: &mut BTreeMap<String, String>,
+    ) -> Result<Vec<TestResult>> {
+        let (script, request, response, vars) = (
+            script.to_string(),
+            request.clone(),
+            response.clone(),
+            variables.clone(),
+        );
+        let (vars, tests) = self
+            .lua
+            .call_async(move |lua| step_script(lua, &script, &request, &response, vars))
+            .await?;
+
+        *variables = vars;
+        Ok(tests)
+    }
+
+    /// Apply the `pigeon.on_request` hooks to an outgoing request.
+    ///
+    /// Failing hooks are reported to `on_error` and skipped.
+    pub async fn run_request_hooks(
+        &self,
+        request: FfiRequest,
+        on_error: impl FnMut(anyhow::Error) + Send + 'static,
+    ) -> Result<FfiRequest> {
+        self.lua
+            .call_async(move |lua| hooks::run_request_hooks(lua, request, on_error))
+            .await
+    }
+
+    /// Notify the `pigeon.on_response` hooks about a finished request.
+    ///
+    /// Failing hooks are reported to `on_error` and skipped.
+    pub async fn run_response_hooks(
+        &self,
+        request: &FfiRequest,
+        response: &FfiResponse,
+        on_error: impl FnMut(anyhow::Error) + Send + 'static,
+    ) -> Result<()> {
+        let (request, response) = (request.clone(), response.clone());
+        self.lua
+            .call_async(move |lua| hooks::run_response_hooks(lua, &request, &response, on_error))
+            .await
+    }
+
+    /// Apply the `pigeon.on_workspace_load` or `pigeon.on_workspace_save` hooks.
+    ///
+    /// Failing hooks are reported to `on_error` and skipped.
+    pub fn run_workspace_hooks(
+        &self,
+        event: WorkspaceEvent,
+        workspace: Workspace,
+        on_error: impl FnMut(anyhow::Error) + Send + 'static,
+    ) -> Result<Workspace> {
+        self.lua
+            .call(move |lua| hooks::run_workspace_hooks(lua, event, workspace, on_error))
+    }
+
+    /// Sign a request with the registered auth provider named by `auth`.
+    pub async fn sign_request(&self, request: FfiRequest, auth: &FfiAuth) -> Result<FfiRequest> {
+        let auth = auth.clone();
+        self.lua
+            .call_async(move |lua| auth::sign(lua, request, &auth))
+            .await
+    }
+
+    /// Names of the registered auth providers.
+    pub fn auth_providers(&self) -> Result<Vec<String>> {
+        self.lua.call(auth::providers)
+    }
+
+    /// Discover `plugins/*.lua` and load the enabled ones.
+    ///
+    /// A failing plugin doesn't stop the others; its error is kept in the returned
+    /// list (also available later from [`Self::plugins`]).
+    pub fn load_plugins(&self) -> Result<Vec<PluginInfo>> {
+        let plugins = plugin::discover(&self.config_path)?;
+        let plugins = self
+            .lua
+            .call(move |lua| Ok(plugin::load_all(lua, plugins)))?;
+
+        *self.plugins.lock().unwrap() = plugins.clone();
+        Ok(plugins)
+    }
+
+    /// Plugins found by the last [`Self::load_plugins`].
+    pub fn plugins(&self) -> Vec<PluginInfo> {
+        self.plugins.lock().unwrap().clone()
+    }
+
+    /// Enable or disable a plugin and persist the choice.
+    ///
+    /// Enabling loads the plugin right away if it isn't loaded yet. A loaded
+    /// plugin can't be unloaded, so disabling takes effect on the next reload.
+    pub fn set_plugin_enabled(&self, name: &str, enabled: bool) -> Result<PluginInfo> {
+        let mut plugins = self.plugins.lock().unwrap();
+        let index = plugins
+            .iter()
+            .position(|p| p.name == name)
+            .ok_or_else(|| anyhow!("unknown plugin: {name}"))?;
+
+        plugin::set_enabled(&self.config_path, name, enabled)?;
+        let name = name.to_string();
+        let info = self
+            .lua
+            .call(move |lua| plugin::set_enabled_in_state(lua, &name, enabled))?;
+
+        plugins[index] = info.clone();
+        Ok(info)
+    }
+
+    /// Names of the commands registered with `pigeon.command`.
+    pub fn commands(&self) -> Result<Vec<String>> {
+        self.lua.call(commands::list)
+    }
+
+    /// Run a `pigeon.command` with `context` (`request`, `response`) and return its result.
+    pub fn run_command(&self, name: &str, context: serde_json::Value) -> Result<serde_json::Value> {
+        let name = name.to_string();
+        self.lua
+            .call(move |lua| commands::run(lua, &name, &context))
+    }
+
+    /// Body of `response` as rendered by its `pigeon.formatter`, or `None` without one.
+    pub fn format_response(&self, response: &FfiResponse) -> Result<Option<String>> {
+        let response = response.clone();
+        self.lua.call(move |lua| formatter::format(lua, &response))
+    }
+
+    /// Key bindings set with `pigeon.keymap.set`, as `action -> chords`.
+    pub fn keymap(&self) -> Result<BTreeMap<String, Vec<String>>> {
+        self.lua.call(keymap::get)
+    }
+
+    /// Themes registered with `pigeon.theme.register` and the one picked with `pigeon.theme.use`.
+    pub fn themes(&self) -> Result<ThemeSet> {
+        self.lua.call(theme::get)
+    }
+
+    /// Compute a `pigeon.variable` value; `None` if no such variable is registered.
+    pub fn resolve_variable(&self, name: &str) -> Result<Option<String>> {
+        let name = name.to_string();
+        self.lua.call(move |lua| variables::resolve(lua, &name))
+    }
+
+    /// Resolve a `{{secret:NAME}}` value through the configured secrets command.
+    ///
+    /// The Lua thread is only used to read the command, not while it runs.
+    pub fn resolve_secret(&self, name: &str) -> Result<String> {
+        if let Some(value) = self.secrets.lock().unwrap().get(name) {
+            return Ok(value.clone());
+        }
 
-        Ok(())
+        let command = self.lua.call(secrets::command)?.ok_or_else(|| {
+            anyhow!("cannot resolve secret `{name}`: no pigeon.secrets.command configured")
+        })?;
+        let value = secrets::run(&command, name)?;
+        if command.cache {
+            self.secrets
+                .lock()
+                .unwrap()
+                .insert(name.to_string(), value.clone());
+        }
+
+        Ok(value)
+    }
+
+    /// Save secret `name` (or with `None`, delete it) in the keychain set up with
+    /// `pigeon.secrets.keychain`, where `{{secret:NAME}}` then finds it.
+    pub fn store_secret(&self, name: &str, value: Option<&str>) -> Result<()> {
+        let service = self.lua.call(secrets::keychain_service)?;
+        secrets::store(&self.secrets, &service, name, value)
     }
 
     /// Get the config directory path
@@ -54,19 +289,101 @@ impl LuaRuntime {
     }
 }
 
-impl LuaRuntime {
-    /// Setup the Lua runtime
-    fn setup(&self) -> Result<()> {
-        let lua = self.lua.lock().unwrap();
-        let globals = lua.globals();
+impl Drop for LuaRuntime {
+    /// Stop `pigeon.schedule` jobs along with the runtime, e.g. on config reload.
+    fn drop(&mut self) {
+        // Queued behind whatever is running instead of waiting for it.
+        self.lua.send(schedule::cancel_all);
+    }
+}
 
-        let config_table = lua.create_table()?;
+/// Register the `pigeon` API on a fresh Lua state.
+fn setup(
+    lua: &Lua,
+    config_dir: &Path,
+    handle: WeakLuaThread,
+    secrets_cache: secrets::Cache,
+) -> Result<()> {
+    let globals = lua.globals();
 
-        config::setup(&lua, &config_table)?;
-        plugin::setup(&lua, &config_table)?;
+    limits::setup(lua)?;
 
-        globals.set("pigeon", config_table)?;
+    let config_table = lua.create_table()?;
 
-        Ok(())
-    }
+    commands::setup(lua, &config_table)?;
+    config::setup(lua, &config_table)?;
+    console::setup(lua, &config_table)?;
+    auth::setup(lua, &config_table)?;
+    crypto::setup(lua, &config_table)?;
+    expect::setup(lua, &config_table)?;
+    formatter::setup(lua, &config_table)?;
+    fs::setup(lua, &config_table, config_dir)?;
+    history::setup(lua, &config_table)?;
+    hooks::setup(lua, &config_table)?;
+    http::setup(lua, &config_table)?;
+    json::setup(lua, &config_table)?;
+    keymap::setup(lua, &config_table)?;
+    package::setup(lua, config_dir)?;
+    plugin::setup(lua, &config_table)?;
+    schedule::setup(lua, &config_table, handle)?;
+    secrets::setup(lua, &config_table, secrets_cache)?;
+    theme::setup(lua, &config_table)?;
+    variables::setup(lua, &config_table)?;
+    workspace::setup(lua, &config_table)?;
+
+    globals.set("pigeon", config_table)?;
+
+    Ok(())
+}
+
+/// Body of [`LuaRuntime::run_step_script`]; returns the updated `vars` and the test results.
+fn step_script(
+    lua: &Lua,
+    script: &str,
+    request: &serde_json::Value,
+    response: &serde_json::Value,
+    variables: BTreeMap<String, String>,
+) -> Result<(BTreeMap<String, String>, Vec<TestResult>)> {
+    let options = SerializeOptions::new()
+        .serialize_none_to_null(false)
+        .serialize_unit_to_null(false);
+
+    let env = lua.create_table()?;
+    let meta = lua.create_table()?;
+    meta.set("__index", lua.globals())?;
+    env.set_metatable(Some(meta));
+    env.set("request", lua.to_value_with(request, options)?)?;
+    env.set("response", lua.to_value_with(response, options)?)?;
+    env.set("vars", lua.to_value_with(&variables, options)?)?;
+
+    let (result, tests) = expect::collect(lua, || {
+        lua.load(script)
+            .set_name("step script")
+            .set_environment(env.clone())
+            .exec()
+    })?;
+    result.context("Step script failed")?;
+
+    let vars: BTreeMap<String, serde_json::Value> = lua.from_value(env.get("vars")?)?;
+    let vars = vars
+        .into_iter()
+        .map(|(k, v)| match v {
+            serde_json::Value::String(s) => (k, s),
+            other => (k, other.to_string()),
+        })
+        .collect();
+
+    Ok((vars, tests))
+}
+
+/// Convert a Lua value to JSON, mapping unsupported types (functions, userdata) to `null`.
+pub(super) fn to_json(lua: &Lua, value: Value) -> Result<serde_json::Value> {
+    let json = lua
+        .from_value_with(
+            value,
+            DeserializeOptions::new().deny_unsupported_types(false),
+        )
+        .context("Failed to convert Lua value to JSON")?;
+
+    Ok(json)
 }
@@ -0,0 +1,214 @@
+use anyhow::{anyhow, bail, Context, Result};
+use mlua::{Function, Lua, Table, Value};
+use std::collections::HashMap;
+use std::str::FromStr;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::time::Duration;
+use tokio::task::AbortHandle;
+
+use super::console;
+use super::executor::WeakLuaThread;
+use crate::logging::{self, Level};
+
+/// Registry key holding scheduled functions by job id.
+const JOBS_KEY: &str = "pigeon.schedule.jobs";
+/// Jobs scheduled across Lua states, for status reporting.
+static ACTIVE_JOBS: AtomicUsize = AtomicUsize::new(0);
+/// Shortest interval accepted, so a job can't monopolize the Lua state.
+const MIN_INTERVAL: Duration = Duration::from_secs(1);
+
+/// When a job runs: a fixed interval or a cron expression (in local time).
+#[derive(Debug)]
+enum Spec {
+    Every(Duration),
+    Cron(Box<cron::Schedule>),
+}
+
+/// Tasks spawned for this Lua state, kept in its app data.
+struct Jobs {
+    lua: WeakLuaThread,
+    next_id: u64,
+    tasks: HashMap<u64, AbortHandle>,
+}
+
+/// Register `pigeon.schedule(spec, fn)` and `pigeon.unschedule(id)`.
+///
+/// ```lua
+/// pigeon.schedule("15m", refresh_token)
+/// pigeon.schedule("0 */5 * * * *", function() pigeon.http.request{ url = health_url } end)
+/// ```
+///
+/// `spec` is a number of seconds, an interval such as `"30s"`, `"5m"` or `"1h"`,
+/// or a cron expression (5 fields, or 6-7 with seconds first). The first run
+/// happens after one interval. Jobs run one at a time on the core's tokio runtime
+/// and stop when the runtime is dropped, e.g. on config reload. Errors are logged
+/// as `schedule.job_error` and don't cancel the job.
+pub fn setup(lua: &Lua, table: &Table, handle: WeakLuaThread) -> Result<()> {
+    lua.set_app_data(Jobs {
+        lua: handle,
+        next_id: 1,
+        tasks: HashMap::new(),
+    });
+
+    let schedule = lua.create_function(|lua, (spec, f): (Value, Function)| {
+        let (label, spec) = parse(&spec).map_err(|e| mlua::Error::runtime(format!("{e:#}")))?;
+
+        let mut jobs = lua
+            .app_data_mut::<Jobs>()
+            .ok_or_else(|| mlua::Error::runtime("scheduler is not available"))?;
+        let id = jobs.next_id;
+        jobs.next_id += 1;
+        jobs_table(lua)?.set(id, f)?;
+
+        let task = crate::get_tokio_runtime().spawn(run(jobs.lua.clone(), id, label, spec));
+        jobs.tasks.insert(id, task.abort_handle());
+        ACTIVE_JOBS.fetch_add(1, Ordering::Relaxed);
+        Ok(id)
+    })?;
+    table.set("schedule", schedule)?;
+
+    let unschedule = lua.create_function(|lua, id: u64| {
+        if let Some(mut jobs) = lua.app_data_mut::<Jobs>() {
+            if let Some(task) = jobs.tasks.remove(&id) {
+                task.abort();
+                ACTIVE_JOBS.fetch_sub(1, Ordering::Relaxed);
+            }
+        }
+        jobs_table(lua)?.set(id, Value::Nil)
+    })?;
+    table.set("unschedule", unschedule)?;
+
+    Ok(())
+}
+
+/// Jobs scheduled and not yet unscheduled or cancelled, across Lua states.
+pub fn active_jobs() -> usize {
+    ACTIVE_JOBS.load(Ordering::Relaxed)
+}
+
+/// Abort every job scheduled on this Lua state.
+pub fn cancel_all(lua: &Lua) {
+    if let Some(mut jobs) = lua.app_data_mut::<Jobs>() {
+        for (_, task) in jobs.tasks.drain() {
+            task.abort();
+            ACTIVE_JOBS.fetch_sub(1, Ordering::Relaxed);
+        }
+    }
+}
+
+async fn run(lua: WeakLuaThread, id: u64, label: String, spec: Spec) {
+    while let Some(delay) = next_delay(&spec) {
+        tokio::time::sleep(delay).await;
+
+        let label = label.clone();
+        let keep_going = lua
+            .call_async(move |lua| Ok(run_once(lua, id, &label)))
+            .await
+            .is_some_and(|result| result.unwrap_or(false));
+        if !keep_going {
+            break;
+        }
+    }
+}
+
+/// Call job `id`; `false` once the job is gone.
+fn run_once(lua: &Lua, id: u64, label: &str) -> bool {
+    let job = match jobs_table(lua).and_then(|jobs| jobs.get::<_, Option<Function>>(id)) {
+        Ok(Some(job)) => job,
+        _ => return false,
+    };
+
+    if let Err(e) = job.call::<_, ()>(()) {
+        logging::emit(
+            Level::Error,
+            "schedule.job_error",
+            serde_json::json!({ "job": id, "schedule": label, "error": e.to_string() }),
+        );
+        console::record(Level::Error, "schedule.job_error", e.to_string());
+    }
+    true
+}
+
+fn next_delay(spec: &Spec) -> Option<Duration> {
+    match spec {
+        Spec::Every(interval) => Some(*interval),
+        Spec::Cron(schedule) => {
+            let next = schedule.upcoming(chrono::Local).next()?;
+            (next - chrono::Local::now()).to_std().ok()
+        }
+    }
+}
+
+fn parse(spec: &Value) -> Result<(String, Spec)> {
+    let interval = match spec {
+        Value::Integer(secs) => Duration::from_secs(u64::try_from(*secs).unwrap_or(0)),
+        Value::Number(secs) if *secs > 0.0 => Duration::try_from_secs_f64(*secs)
+            .map_err(|_| anyhow!("schedule interval of {secs} seconds is out of range"))?,
+        Value::Number(_) => Duration::ZERO,
+        Value::String(s) => {
+            let spec = s.to_str()?.trim();
+            return match parse_interval(spec)? {
+                Some(interval) => Ok((spec.to_string(), every(interval)?)),
+                None => Ok((spec.to_string(), parse_cron(spec)?)),
+            };
+        }
+        other => bail!(
+            "schedule must be a number of seconds, an interval or a cron expression, got {}",
+            other.type_name()
+        ),
+    };
+    Ok((format!("{}s", interval.as_secs_f64()), every(interval)?))
+}
+
+fn every(interval: Duration) -> Result<Spec> {
+    if interval < MIN_INTERVAL {
+        bail!(
+            "schedule interval must be at least {}s",
+            MIN_INTERVAL.as_secs()
+        );
+    }
+    Ok(Spec::Every(interval))
+}
+
+/// `"90s"`, `"5m"`, `"2h"`, `"1d"`; `None` if `spec` isn't in that form.
+fn parse_interval(spec: &str) -> Result<Option<Duration>> {
+    let Some(split) = spec.find(|c: char| !c.is_ascii_digit()) else {
+        return Ok(None);
+    };
+    let (value, unit) = spec.split_at(split);
+    let secs: u64 = match unit {
+        "s" => 1,
+        "m" => 60,
+        "h" => 60 * 60,
+        "d" => 24 * 60 * 60,
+        _ => return Ok(None),
+    };
+    if value.is_empty() {
+        return Ok(None);
+    }
+    match value.parse::<u64>().ok().and_then(|v| v.checked_mul(secs)) {
+        Some(secs) => Ok(Some(Duration::from_secs(secs))),
+        None => bail!("schedule interval `{spec}` is out of range"),
+    }
+}
+
+fn parse_cron(spec: &str) -> Result<Spec> {
+    // The cron crate wants a seconds field; accept the usual 5-field form too.
+    let expression = if spec.split_whitespace().count() == 5 {
+        format!("0 {spec}")
+    } else {
+        spec.to_string()
+    };
+    let schedule = cron::Schedule::from_str(&expression)
+        .with_context(|| format!("invalid schedule `{spec}`"))?;
+    Ok(Spec::Cron(Box::new(schedule)))
+}
+
+fn jobs_table(lua: &Lua) -> mlua::Result<Table<'_>> {
+    if let Some(jobs) = lua.named_registry_value::<Option<Table>>(JOBS_KEY)? {
+        return Ok(jobs);
+    }
+    let jobs = lua.create_table()?;
+    lua.set_named_registry_value(JOBS_KEY, jobs.clone())?;
+    Ok(jobs)
+}
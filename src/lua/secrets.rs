@@ -0,0 +1,358 @@
+use anyhow::{bail, Context, Result};
+use mlua::{Lua, LuaSerdeExt, Table, Value};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::io::{Read, Write};
+use std::process::{Command, Stdio};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+/// Registry key holding the configured secrets command.
+const COMMAND_KEY: &str = "pigeon.secrets.command";
+/// Keychain service (macOS) or `service` attribute (Secret Service, Windows) used by default.
+const DEFAULT_SERVICE: &str = "pigeon";
+/// How long a secrets command may run before it is killed.
+const COMMAND_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// External command used to resolve `{{secret:NAME}}` placeholders.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SecretCommand {
+    /// Program and arguments; `{name}` in any argument is replaced by the secret
+    /// name, otherwise the name is appended as the last argument.
+    pub argv: Vec<String>,
+    /// Keep only the first line of output (as `pass show` prints metadata after it).
+    #[serde(default)]
+    pub first_line: bool,
+    /// Remember resolved values until the config is reloaded.
+    #[serde(default = "default_cache")]
+    pub cache: bool,
+    /// Service the platform keychain files secrets under, for commands set up by
+    /// `pigeon.secrets.keychain`; these can also store secrets.
+    #[serde(default)]
+    pub keychain: Option<String>,
+}
+
+fn default_cache() -> bool {
+    true
+}
+
+/// Secret values resolved so far, for commands registered with `cache = true`.
+/// Shared with `pigeon.secrets.store` so a stored value replaces the cached one.
+pub type Cache = Arc<Mutex<HashMap<String, String>>>;
+
+/// Register `pigeon.secrets.command(argv, { first_line = bool, cache = bool })`,
+/// `pigeon.secrets.keychain({ service = "pigeon", cache = bool })`,
+/// `pigeon.secrets.store(name, value)` and `pigeon.secrets.delete(name)`.
+///
+/// ```lua
+/// pigeon.secrets.command({ "pass", "show", "api/{name}" }, { first_line = true })
+/// pigeon.secrets.command({ "op", "read", "op://Private/{name}/credential" })
+///
+/// -- macOS Keychain, Secret Service (`secret-tool`) or Windows Credential Manager
+/// pigeon.secrets.keychain()
+/// pigeon.secrets.store("GITHUB_TOKEN", "ghp_...")
+/// ```
+///
+/// The command is spawned directly (no shell) when a request containing
+/// `{{secret:NAME}}` is sent; its trimmed stdout becomes the value.
+pub fn setup(lua: &Lua, table: &Table, cache: Cache) -> Result<()> {
+    let secrets = lua.create_table()?;
+
+    let command = lua.create_function(|lua, (argv, opts): (Vec<String>, Option<Table>)| {
+        if argv.is_empty() {
+            return Err(mlua::Error::runtime(
+                "pigeon.secrets.command needs a program to run",
+            ));
+        }
+        let opt = |key: &str| -> mlua::Result<Option<bool>> {
+            opts.as_ref().map_or(Ok(None), |o| o.get(key))
+        };
+        let command = SecretCommand {
+            argv,
+            first_line: opt("first_line")?.unwrap_or(false),
+            cache: opt("cache")?.unwrap_or(true),
+            keychain: None,
+        };
+        lua.set_named_registry_value(COMMAND_KEY, lua.to_value(&command)?)
+    })?;
+    secrets.set("command", command)?;
+
+    let keychain = lua.create_function(|lua, opts: Option<Table>| {
+        let service = match &opts {
+            Some(o) => o.get::<_, Option<String>>("service")?,
+            None => None,
+        };
+        let cache = match &opts {
+            Some(o) => o.get::<_, Option<bool>>("cache")?,
+            None => None,
+        };
+        let command = keychain_command(service.as_deref().unwrap_or(DEFAULT_SERVICE))
+            .map_err(|e| mlua::Error::runtime(format!("{e:#}")))?;
+        let command = SecretCommand {
+            cache: cache.unwrap_or(true),
+            ..command
+        };
+        lua.set_named_registry_value(COMMAND_KEY, lua.to_value(&command)?)
+    })?;
+    secrets.set("keychain", keychain)?;
+
+    let c = cache.clone();
+    secrets.set(
+        "store",
+        lua.create_function(move |lua, (name, value): (String, String)| {
+            keychain_service(lua)
+                .and_then(|service| store(&c, &service, &name, Some(&value)))
+                .map_err(|e| mlua::Error::runtime(format!("{e:#}")))
+        })?,
+    )?;
+
+    secrets.set(
+        "delete",
+        lua.create_function(move |lua, name: String| {
+            keychain_service(lua)
+                .and_then(|service| store(&cache, &service, &name, None))
+                .map_err(|e| mlua::Error::runtime(format!("{e:#}")))
+        })?,
+    )?;
+
+    table.set("secrets", secrets)?;
+
+    Ok(())
+}
+
+/// The command registered by `config.lua`, if any.
+pub fn command(lua: &Lua) -> Result<Option<SecretCommand>> {
+    let value: Value = lua.named_registry_value(COMMAND_KEY)?;
+    if value.is_nil() {
+        return Ok(None);
+    }
+    Ok(Some(lua.from_value(value)?))
+}
+
+/// Service of the keychain set up with `pigeon.secrets.keychain`.
+pub fn keychain_service(lua: &Lua) -> Result<String> {
+    command(lua)?.and_then(|c| c.keychain).ok_or_else(|| {
+        anyhow::anyhow!("secrets can only be stored after pigeon.secrets.keychain()")
+    })
+}
+
+/// [`keychain_store`], then update `cache` to match.
+pub fn store(cache: &Cache, service: &str, name: &str, value: Option<&str>) -> Result<()> {
+    keychain_store(service, name, value)?;
+
+    let mut cache = cache.lock().unwrap();
+    match value {
+        Some(value) => cache.insert(name.to_string(), value.to_string()),
+        None => cache.remove(name),
+    };
+    Ok(())
+}
+
+/// Run `command` for `name` and return its output.
+pub fn run(command: &SecretCommand, name: &str) -> Result<String> {
+    check_name(name)?;
+
+    let mut argv: Vec<String> = command
+        .argv
+        .iter()
+        .map(|arg| arg.replace("{name}", name))
+        .collect();
+    if !command.argv.iter().any(|arg| arg.contains("{name}")) {
+        argv.push(name.to_string());
+    }
+
+    let stdout = exec(&argv, None)
+        .map_err(|e| anyhow::anyhow!("secrets command failed for `{name}`: {e:#}"))?;
+    let value = if command.first_line {
+        stdout.lines().next().unwrap_or("")
+    } else {
+        stdout.trim_end_matches(['\r', '\n'])
+    };
+    Ok(value.to_string())
+}
+
+/// Names go into command lines, so keep them to characters no tool treats specially.
+fn check_name(name: &str) -> Result<()> {
+    let valid = !name.is_empty()
+        && !name.starts_with('-')
+        && name
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || "_-./:@".contains(c));
+    if !valid {
+        bail!("invalid secret name: {name:?}");
+    }
+    Ok(())
+}
+
+/// The command reading secrets from this platform's keychain, filed under `service`.
+fn keychain_command(service: &str) -> Result<SecretCommand> {
+    check_name(service).context("invalid keychain service")?;
+    let script = WINDOWS_READ.replace("{service}", service);
+    let argv: Vec<&str> = if cfg!(target_os = "macos") {
+        vec![
+            "security",
+            "find-generic-password",
+            "-s",
+            service,
+            "-a",
+            "{name}",
+            "-w",
+        ]
+    } else if cfg!(windows) {
+        vec![
+            "powershell",
+            "-NoProfile",
+            "-NonInteractive",
+            "-Command",
+            &script,
+        ]
+    } else {
+        vec![
+            "secret-tool",
+            "lookup",
+            "service",
+            service,
+            "account",
+            "{name}",
+        ]
+    };
+    Ok(SecretCommand {
+        argv: argv.into_iter().map(str::to_string).collect(),
+        first_line: false,
+        cache: true,
+        keychain: Some(service.to_string()),
+    })
+}
+
+/// Reads a Windows Credential Manager entry through the WinRT password vault.
+const WINDOWS_READ: &str = "[void][Windows.Security.Credentials.PasswordVault,Windows.Security.Credentials,ContentType=WindowsRuntime]; \
+    $c = (New-Object Windows.Security.Credentials.PasswordVault).Retrieve('{service}', '{name}'); \
+    $c.RetrievePassword(); [Console]::Out.Write($c.Password)";
+/// Writes one, taking the password from stdin.
+const WINDOWS_WRITE: &str = "[void][Windows.Security.Credentials.PasswordVault,Windows.Security.Credentials,ContentType=WindowsRuntime]; \
+    $v = New-Object Windows.Security.Credentials.PasswordVault; \
+    try { $v.Remove($v.Retrieve('{service}', '{name}')) } catch {}; \
+    $p = [Console]::In.ReadToEnd(); \
+    if ($p) { $v.Add((New-Object Windows.Security.Credentials.PasswordCredential('{service}', '{name}', $p))) }";
+
+/// Save `value` for `name` in the platform keychain under `service`, or remove the
+/// entry when `value` is `None`.
+pub fn keychain_store(service: &str, name: &str, value: Option<&str>) -> Result<()> {
+    check_name(service).context("invalid keychain service")?;
+    check_name(name)?;
+
+    let command;
+    let (argv, input): (Vec<String>, Option<&str>) = if cfg!(target_os = "macos") {
+        match value {
+            // `security` only takes the password as an argument, so the command goes
+            // on stdin (`-i`) to keep it out of the process list.
+            Some(value) => {
+                if value.contains(['\n', '\r']) {
+                    bail!("keychain entry `{name}` can't hold a line break on macOS");
+                }
+                let value = value.replace('\\', "\\\\").replace('"', "\\\"");
+                command =
+                    format!("add-generic-password -U -s {service} -a {name} -w \"{value}\"\n");
+                (
+                    vec!["security".to_string(), "-i".to_string()],
+                    Some(command.as_str()),
+                )
+            }
+            None => {
+                let argv = [
+                    "security",
+                    "delete-generic-password",
+                    "-s",
+                    service,
+                    "-a",
+                    name,
+                ];
+                (argv.into_iter().map(str::to_string).collect(), None)
+            }
+        }
+    } else if cfg!(windows) {
+        let script = WINDOWS_WRITE
+            .replace("{service}", service)
+            .replace("{name}", name);
+        let argv = [
+            "powershell",
+            "-NoProfile",
+            "-NonInteractive",
+            "-Command",
+            &script,
+        ];
+        (
+            argv.into_iter().map(str::to_string).collect(),
+            Some(value.unwrap_or("")),
+        )
+    } else {
+        let label = format!("--label=pigeon: {name}");
+        let argv = match value {
+            Some(_) => vec!["secret-tool", "store", &label],
+            None => vec!["secret-tool", "clear"],
+        };
+        let argv = argv
+            .into_iter()
+            .chain(["service", service, "account", name])
+            .map(str::to_string)
+            .collect();
+        (argv, value)
+    };
+
+    exec(&argv, input)
+        .map(drop)
+        .map_err(|e| anyhow::anyhow!("cannot update keychain entry `{name}`: {e:#}"))
+}
+
+/// Run `argv` with `input` on stdin, returning its stdout. Fails if it exits
+/// unsuccessfully (with its stderr) or runs longer than [`COMMAND_TIMEOUT`].
+fn exec(argv: &[String], input: Option<&str>) -> Result<String> {
+    let mut child = Command::new(&argv[0])
+        .args(&argv[1..])
+        .stdin(if input.is_some() {
+            Stdio::piped()
+        } else {
+            Stdio::null()
+        })
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .with_context(|| format!("Failed to run `{}`", argv[0]))?;
+
+    if let (Some(mut stdin), Some(input)) = (child.stdin.take(), input) {
+        // Dropped afterwards, closing the pipe so the command sees the end of input.
+        let _ = stdin.write_all(input.as_bytes());
+    }
+
+    // Drain the pipes on their own threads so a chatty command can't block on a full pipe.
+    let stdout = child.stdout.take().map(read_to_end);
+    let stderr = child.stderr.take().map(read_to_end);
+
+    let start = Instant::now();
+    let status = loop {
+        if let Some(status) = child.try_wait()? {
+            break status;
+        }
+        if start.elapsed() > COMMAND_TIMEOUT {
+            let _ = child.kill();
+            let _ = child.wait();
+            bail!("timed out after {}s", COMMAND_TIMEOUT.as_secs());
+        }
+        std::thread::sleep(Duration::from_millis(20));
+    };
+
+    let stdout = stdout.and_then(|t| t.join().ok()).unwrap_or_default();
+    if !status.success() {
+        let stderr = stderr.and_then(|t| t.join().ok()).unwrap_or_default();
+        bail!("{status}: {}", stderr.trim());
+    }
+    Ok(stdout)
+}
+
+fn read_to_end(mut pipe: impl Read + Send + 'static) -> std::thread::JoinHandle<String> {
+    std::thread::spawn(move || {
+        let mut out = String::new();
+        let _ = pipe.read_to_string(&mut out);
+        out
+    })
+}
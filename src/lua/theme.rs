@@ -0,0 +1,105 @@
+use anyhow::{bail, Result};
+use mlua::{Lua, LuaSerdeExt, Table, Value};
+use serde::Serialize;
+use std::collections::BTreeMap;
+
+/// Registry key holding registered themes by name.
+const THEMES_KEY: &str = "pigeon.themes";
+/// Registry key holding the name passed to `pigeon.theme.use`.
+const ACTIVE_KEY: &str = "pigeon.theme.active";
+
+/// Themes declared by the config, plus the one it asked to start with.
+#[derive(Debug, Serialize)]
+pub struct ThemeSet {
+    pub themes: BTreeMap<String, serde_json::Value>,
+    pub active: Option<String>,
+}
+
+/// Register `pigeon.theme.register(name, colors)` and `pigeon.theme.use(name)`.
+///
+/// ```lua
+/// pigeon.theme.register("dusk", {
+///   c1 = "#D97D55", c2 = "#F4E9D7", c3 = "#B8C4A9", c4 = "#6FA4AF",
+///   focus_border = "#D97D55",
+///   json = { key = "#6FA4AF", string = "#B8C4A9", number = "#D97D55" },
+///   status = { success = "green", redirect = "blue", client_error = "#FF8700" },
+/// })
+/// pigeon.theme.use("dusk")
+/// ```
+///
+/// Colors are `#rgb`/`#rrggbb` or a named terminal color; nested tables group
+/// related colors. Which keys are used is up to the front end, so missing keys
+/// fall back to its defaults. `use` may name a theme the front end ships with;
+/// the TUI has `light`, `dark`, `solarized`, `high-contrast` and the `autumn`,
+/// `sage` and `sand` palettes.
+pub fn setup(lua: &Lua, table: &Table) -> Result<()> {
+    let theme = lua.create_table()?;
+
+    let register = lua.create_function(|lua, (name, colors): (String, Table)| {
+        if name.trim().is_empty() {
+            return Err(mlua::Error::runtime("theme name must not be empty"));
+        }
+        let value: serde_json::Value = lua.from_value(Value::Table(colors))?;
+        validate(&value, "").map_err(|e| mlua::Error::runtime(format!("theme `{name}`: {e}")))?;
+
+        // Store the validated copy so later edits to `colors` can't bypass the check.
+        themes_table(lua)?.set(name, lua.to_value(&value)?)
+    })?;
+    theme.set("register", register)?;
+
+    let use_theme =
+        lua.create_function(|lua, name: String| lua.set_named_registry_value(ACTIVE_KEY, name))?;
+    theme.set("use", use_theme)?;
+
+    table.set("theme", theme)?;
+
+    Ok(())
+}
+
+/// Registered themes, as plain JSON color maps.
+pub fn get(lua: &Lua) -> Result<ThemeSet> {
+    let themes = themes_table(lua)?
+        .pairs::<String, Table>()
+        .map(|pair| {
+            let (name, colors) = pair?;
+            Ok((name, lua.from_value(Value::Table(colors))?))
+        })
+        .collect::<mlua::Result<_>>()?;
+    let active = lua.named_registry_value::<Option<String>>(ACTIVE_KEY)?;
+
+    Ok(ThemeSet { themes, active })
+}
+
+fn validate(value: &serde_json::Value, path: &str) -> Result<()> {
+    match value {
+        serde_json::Value::Object(map) => {
+            for (key, value) in map {
+                let path = if path.is_empty() {
+                    key.clone()
+                } else {
+                    format!("{path}.{key}")
+                };
+                validate(value, &path)?;
+            }
+            Ok(())
+        }
+        serde_json::Value::String(color) if is_color(color) => Ok(()),
+        other => bail!("`{path}` is not a color: {other}"),
+    }
+}
+
+fn is_color(color: &str) -> bool {
+    match color.strip_prefix('#') {
+        Some(hex) => matches!(hex.len(), 3 | 6) && hex.chars().all(|c| c.is_ascii_hexdigit()),
+        None => !color.is_empty() && color.chars().all(|c| c.is_ascii_alphabetic()),
+    }
+}
+
+fn themes_table(lua: &Lua) -> mlua::Result<Table<'_>> {
+    if let Some(themes) = lua.named_registry_value::<Option<Table>>(THEMES_KEY)? {
+        return Ok(themes);
+    }
+    let themes = lua.create_table()?;
+    lua.set_named_registry_value(THEMES_KEY, themes.clone())?;
+    Ok(themes)
+}
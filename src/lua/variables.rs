@@ -0,0 +1,65 @@
+use anyhow::{Context, Result};
+use mlua::{Function, Lua, Table, Value};
+
+/// Registry key holding dynamic variables by name.
+const VARIABLES_KEY: &str = "pigeon.variables";
+
+/// Register `pigeon.variable(name, fn)`, plus `pigeon.now()` (Unix time in seconds,
+/// with millisecond precision) since the `os` library isn't available.
+///
+/// ```lua
+/// pigeon.variable("nowPlus1h", function() return math.floor(pigeon.now()) + 3600 end)
+/// ```
+///
+/// `{{name}}` in a request's URL, header values, body or auth params is then
+/// replaced with `fn()` at send time. Strings, numbers and booleans are used as-is;
+/// returning `nil` leaves the placeholder untouched.
+pub fn setup(lua: &Lua, table: &Table) -> Result<()> {
+    let variable = lua.create_function(|lua, (name, f): (String, Function)| {
+        let name = name.trim();
+        if name.is_empty() || name.contains(['{', '}']) {
+            return Err(mlua::Error::runtime(format!(
+                "invalid variable name: {name:?}"
+            )));
+        }
+        variables_table(lua)?.set(name, f)
+    })?;
+    table.set("variable", variable)?;
+
+    let now =
+        lua.create_function(|_, ()| Ok(chrono::Utc::now().timestamp_millis() as f64 / 1000.0))?;
+    table.set("now", now)?;
+
+    Ok(())
+}
+
+/// Compute the dynamic variable `name`; `None` if it isn't registered or returned `nil`.
+pub fn resolve(lua: &Lua, name: &str) -> Result<Option<String>> {
+    let Some(f) = variables_table(lua)?.get::<_, Option<Function>>(name)? else {
+        return Ok(None);
+    };
+
+    let value: Value = f
+        .call(())
+        .with_context(|| format!("variable `{name}` failed"))?;
+    Ok(match value {
+        Value::Nil => None,
+        Value::Boolean(b) => Some(b.to_string()),
+        Value::Integer(i) => Some(i.to_string()),
+        Value::Number(n) => Some(n.to_string()),
+        Value::String(s) => Some(s.to_str()?.to_string()),
+        other => anyhow::bail!(
+            "variable `{name}` must return a string, number or boolean, got {}",
+            other.type_name()
+        ),
+    })
+}
+
+fn variables_table(lua: &Lua) -> mlua::Result<Table<'_>> {
+    if let Some(variables) = lua.named_registry_value::<Option<Table>>(VARIABLES_KEY)? {
+        return Ok(variables);
+    }
+    let variables = lua.create_table()?;
+    lua.set_named_registry_value(VARIABLES_KEY, variables.clone())?;
+    Ok(variables)
+}
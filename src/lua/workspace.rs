@@ -0,0 +1,551 @@
+use anyhow::Result;
+use mlua::{Lua, LuaSerdeExt, SerializeOptions, Table, Value};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet};
+use uuid::Uuid;
+
+use crate::model::{
+    Endpoint, Environment, Header, KeyValue, Request, RequestAuth, RequestBody, SpaceOverrides,
+    Workspace,
+};
+
+#[derive(Debug, Deserialize)]
+struct LuaEndpoint {
+    name: Option<String>,
+    url: String,
+    #[serde(default = "default_method")]
+    method: String,
+    /// Folder id, or a `/`-separated path created as needed.
+    folder: Option<String>,
+    #[serde(default)]
+    tags: Vec<String>,
+    /// Left as starred in the UI when unset.
+    favorite: Option<bool>,
+    /// Markdown; notes written in the UI are kept when unset.
+    notes: Option<String>,
+}
+
+fn default_method() -> String {
+    "GET".to_string()
+}
+
+#[derive(Debug, Deserialize)]
+struct LuaHeader {
+    name: Option<String>,
+    key: String,
+    #[serde(default)]
+    value: String,
+    #[serde(default = "default_true")]
+    enabled: bool,
+    #[serde(default)]
+    tags: Vec<String>,
+    /// Persist the value encrypted.
+    #[serde(default)]
+    secret: bool,
+}
+
+fn default_true() -> bool {
+    true
+}
+
+#[derive(Debug, Deserialize)]
+struct LuaRequest {
+    name: String,
+    /// Library endpoint (id or name) supplying the default method and url.
+    endpoint: Option<String>,
+    method: Option<String>,
+    url: Option<String>,
+    #[serde(default)]
+    params: BTreeMap<String, String>,
+    #[serde(default)]
+    headers: BTreeMap<String, String>,
+    body: Option<LuaBody>,
+    auth: Option<RequestAuth>,
+    folder: Option<String>,
+}
+
+/// `body = "text"` or `body = { content_type = "application/json", content = "{}" }`.
+#[derive(Debug, Deserialize)]
+#[serde(untagged)]
+enum LuaBody {
+    Text(String),
+    Full {
+        #[serde(default)]
+        content_type: String,
+        content: String,
+    },
+}
+
+#[derive(Debug, Deserialize)]
+struct LuaFolder {
+    name: String,
+    /// Parent folder id or path; top level when unset.
+    parent: Option<String>,
+    #[serde(default)]
+    collapsed: bool,
+}
+
+#[derive(Debug, Deserialize)]
+struct LuaEnvironment {
+    name: String,
+    #[serde(default)]
+    variables: BTreeMap<String, String>,
+    /// Names of variables to persist encrypted.
+    #[serde(default)]
+    secrets: BTreeSet<String>,
+}
+
+/// What `list_folders` reports per folder.
+#[derive(Debug, Serialize)]
+struct FolderSummary {
+    id: Uuid,
+    name: String,
+    /// Full path, e.g. `users/admin`.
+    path: String,
+    parent_id: Option<Uuid>,
+    collapsed: bool,
+}
+
+/// What `list_spaces` reports per space.
+#[derive(Debug, Default, Deserialize)]
+#[serde(default)]
+struct LuaOverrides {
+    method: Option<String>,
+    url: Option<String>,
+    host: Option<String>,
+    /// Values by library header name or id.
+    headers: BTreeMap<String, String>,
+    body: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+struct SpaceSummary {
+    id: Uuid,
+    name: String,
+    selected_endpoint_id: Option<Uuid>,
+    selected_header_ids: Vec<Uuid>,
+    selected_body_id: Option<Uuid>,
+    overrides: SpaceOverrides,
+    headers: Vec<KeyValue>,
+    archived: bool,
+}
+
+/// Register `pigeon.workspace.{add_endpoint, add_header, add_environment, add_request,
+/// update_endpoint, update_header, update_body, add_folder, remove_folder, list_folders,
+/// list_spaces, set_overrides, search, move, remove, duplicate}`.
+///
+/// ```lua
+/// for _, service in ipairs({ "users", "orders", "billing" }) do
+///   pigeon.workspace.add_endpoint{
+///     name = service .. " health",
+///     url = "https://" .. service .. ".internal/health",
+///     folder = "services/" .. service,
+///     tags = { service, "health" },
+///   }
+/// end
+/// pigeon.workspace.add_header{ key = "X-Team", value = "api" }
+/// pigeon.workspace.add_header{ key = "Authorization", value = "Bearer ...", secret = true }
+/// pigeon.workspace.add_environment{
+///   name = "staging",
+///   variables = { host = "staging.internal", token = "..." },
+///   secrets = { "token" },
+/// }
+/// pigeon.workspace.add_request{
+///   name = "Create user (bad payload)",
+///   method = "POST",
+///   url = "https://users.internal/users",
+///   params = { dry_run = "true" },
+///   headers = { ["Content-Type"] = "application/json" },
+///   body = '{"name": 42}',
+/// }
+/// ```
+///
+/// Each `add_*` returns the item's id. An item with the same name as an existing
+/// one replaces it, so re-running the config on reload doesn't add duplicates.
+/// Endpoint names default to `METHOD url`, header names to the key. Endpoints
+/// and spaces take markdown `notes`, shown in the UI's details pane.
+///
+/// `update_endpoint(key, changes)` (and `update_header`, `update_body`,
+/// `update_space`) edits the item whose id or name is `key` in place, e.g.
+/// `pigeon.workspace.update_endpoint("users health", { name = "users ping" })`
+/// or `pigeon.workspace.update_space("Test Space", { notes = "# Smoke test" })`.
+/// Fields left out are kept and spaces keep their selections; renaming onto
+/// another item's name is an error. Endpoints also take a `folder` id or path.
+///
+/// Saved requests bundle everything needed to send them (`method`, `url`,
+/// `params`, `headers`, `body`, `auth = { type, params }`). Given an `endpoint`
+/// (id or name), the method and url default to that endpoint's.
+///
+/// `set_overrides(space, { method, url, host, headers, body })` changes what a
+/// space sends without touching the shared items it selects: `host` swaps the
+/// endpoint URL's host and port, `headers` maps header names (or ids) to new
+/// values and `body` replaces the body content. Each call replaces the space's
+/// previous overrides; `nil` clears them.
+///
+/// A space's own one-off headers go in its `headers` list, e.g.
+/// `update_space("Test Space", { headers = { { key = "X-Debug", value = "1" } } })`.
+/// They are sent after the selected library headers; enabled ones (the default;
+/// `enabled = false` keeps one around unsent) replace a library header with the
+/// same key.
+///
+/// `search(query)` returns the `endpoints`, `headers` and `bodies` matching
+/// every word of `query`, by name, URL, method, notes or key, or by tag with `#tag`.
+///
+/// `move(kind, key, position)` reorders an `endpoint`, `header`, `body`, `space`
+/// or `request`, e.g. `pigeon.workspace.move("endpoint", "users health", 1)` to
+/// list it first. Positions count from 1, as in Lua lists. `remove(kind, key)`
+/// deletes one; spaces stop selecting a removed endpoint, header or body.
+/// `duplicate(kind, key)` copies one as `<name> copy` and returns the copy's id.
+/// Spaces also take `archived = true` to hide them from the sidebar.
+///
+/// Folders nest endpoints in the sidebar. `folder` (on endpoints) and `parent`
+/// (on `add_folder{ name, parent, collapsed }`) take a folder id or a path such
+/// as `"services/users"`, whose missing levels are created. `remove_folder(id)`
+/// moves the folder's contents up a level.
+pub fn setup(lua: &Lua, table: &Table) -> Result<()> {
+    let workspace = lua.create_table()?;
+
+    let add_endpoint = lua.create_function(|lua, opts: Value| {
+        let endpoint: LuaEndpoint = lua.from_value(opts)?;
+        if endpoint.url.trim().is_empty() {
+            return Err(mlua::Error::runtime("endpoint url must not be empty"));
+        }
+        let method = endpoint.method.to_ascii_uppercase();
+        let name = endpoint
+            .name
+            .unwrap_or_else(|| format!("{method} {}", endpoint.url));
+        let id = {
+            let mut workspace = crate::get_workspace().lock().unwrap();
+            let folder_id = match &endpoint.folder {
+                Some(folder) => resolve_folder(&mut workspace, folder),
+                None => None,
+            };
+            let existing = workspace.endpoints.iter().find(|e| e.name == name);
+            let favorite = endpoint
+                .favorite
+                .unwrap_or_else(|| existing.is_some_and(|e| e.favorite));
+            let notes = endpoint
+                .notes
+                .unwrap_or_else(|| existing.map(|e| e.notes.clone()).unwrap_or_default());
+            workspace.upsert_endpoint(Endpoint {
+                name,
+                url: endpoint.url,
+                method,
+                folder_id,
+                tags: endpoint.tags,
+                favorite,
+                notes,
+                ..Endpoint::default()
+            })
+        };
+        crate::workspace_changed();
+        Ok(id.to_string())
+    })?;
+    workspace.set("add_endpoint", add_endpoint)?;
+
+    let add_header = lua.create_function(|lua, opts: Value| {
+        let header: LuaHeader = lua.from_value(opts)?;
+        if header.key.trim().is_empty() {
+            return Err(mlua::Error::runtime("header key must not be empty"));
+        }
+        let id = crate::get_workspace()
+            .lock()
+            .unwrap()
+            .upsert_header(Header {
+                name: header.name.unwrap_or_else(|| header.key.clone()),
+                key: header.key,
+                value: header.value,
+                enabled: header.enabled,
+                tags: header.tags,
+                secret: header.secret,
+                ..Header::default()
+            });
+        crate::workspace_changed();
+        Ok(id.to_string())
+    })?;
+    workspace.set("add_header", add_header)?;
+
+    let add_environment = lua.create_function(|lua, opts: Value| {
+        let environment: LuaEnvironment = lua.from_value(opts)?;
+        if environment.name.trim().is_empty() {
+            return Err(mlua::Error::runtime("environment name must not be empty"));
+        }
+        let id = crate::get_workspace()
+            .lock()
+            .unwrap()
+            .upsert_environment(Environment {
+                name: environment.name,
+                variables: environment.variables,
+                secrets: environment.secrets,
+                ..Environment::default()
+            });
+        crate::workspace_changed();
+        Ok(id.to_string())
+    })?;
+    workspace.set("add_environment", add_environment)?;
+
+    let add_request = lua.create_function(|lua, opts: Value| {
+        let request: LuaRequest = lua.from_value(opts)?;
+        if request.name.trim().is_empty() {
+            return Err(mlua::Error::runtime("request name must not be empty"));
+        }
+        let id = {
+            let mut workspace = crate::get_workspace().lock().unwrap();
+            let endpoint = match &request.endpoint {
+                Some(key) => Some(
+                    workspace
+                        .endpoints
+                        .iter()
+                        .find(|e| e.name == *key || e.id.to_string() == *key)
+                        .cloned()
+                        .ok_or_else(|| mlua::Error::runtime(format!("unknown endpoint: {key}")))?,
+                ),
+                None => None,
+            };
+            let url = request
+                .url
+                .or_else(|| endpoint.as_ref().map(|e| e.url.clone()))
+                .filter(|url| !url.trim().is_empty())
+                .ok_or_else(|| mlua::Error::runtime("request needs a url or an endpoint"))?;
+            let method = request
+                .method
+                .or_else(|| endpoint.as_ref().map(|e| e.method.clone()))
+                .unwrap_or_else(default_method)
+                .to_ascii_uppercase();
+            let folder_id = match &request.folder {
+                Some(folder) => resolve_folder(&mut workspace, folder),
+                None => None,
+            };
+            let pairs = |map: BTreeMap<String, String>| {
+                map.into_iter()
+                    .map(|(key, value)| KeyValue {
+                        key,
+                        value,
+                        enabled: true,
+                    })
+                    .collect()
+            };
+            workspace.upsert_request(Request {
+                name: request.name,
+                endpoint_id: endpoint.map(|e| e.id),
+                method,
+                url,
+                params: pairs(request.params),
+                headers: pairs(request.headers),
+                body: request.body.map(|body| match body {
+                    LuaBody::Text(content) => RequestBody {
+                        content_type: String::new(),
+                        content,
+                    },
+                    LuaBody::Full {
+                        content_type,
+                        content,
+                    } => RequestBody {
+                        content_type,
+                        content,
+                    },
+                }),
+                auth: request.auth,
+                folder_id,
+                ..Request::default()
+            })
+        };
+        crate::workspace_changed();
+        Ok(id.to_string())
+    })?;
+    workspace.set("add_request", add_request)?;
+
+    for kind in ["endpoint", "header", "body", "space"] {
+        let update = lua.create_function(move |lua, (key, changes): (String, Value)| {
+            let mut changes: serde_json::Value = lua.from_value(changes)?;
+            let id = {
+                let mut workspace = crate::get_workspace().lock().unwrap();
+                if let Some(fields) = changes.as_object_mut() {
+                    if let Some(folder) = fields.remove("folder") {
+                        let folder_id = folder
+                            .as_str()
+                            .and_then(|folder| resolve_folder(&mut workspace, folder));
+                        fields.insert("folder_id".to_string(), serde_json::json!(folder_id));
+                    }
+                }
+                workspace
+                    .apply_changes(kind, &key, changes)
+                    .map_err(|e| mlua::Error::runtime(format!("{e:#}")))?
+            };
+            crate::workspace_changed();
+            Ok(id.to_string())
+        })?;
+        workspace.set(format!("update_{kind}"), update)?;
+    }
+
+    let add_folder = lua.create_function(|lua, opts: Value| {
+        let folder: LuaFolder = lua.from_value(opts)?;
+        let name = folder.name.trim();
+        if name.is_empty() || name.contains('/') {
+            return Err(mlua::Error::runtime(
+                "folder name must not be empty or contain `/`",
+            ));
+        }
+        let id = {
+            let mut workspace = crate::get_workspace().lock().unwrap();
+            let parent = match &folder.parent {
+                Some(parent) => resolve_folder(&mut workspace, parent),
+                None => None,
+            };
+            let id = workspace.ensure_folder(name, parent);
+            if let Some(entry) = workspace.folders.iter_mut().find(|f| f.id == id) {
+                entry.collapsed = folder.collapsed;
+            }
+            id
+        };
+        crate::workspace_changed();
+        Ok(id.to_string())
+    })?;
+    workspace.set("add_folder", add_folder)?;
+
+    let remove_folder = lua.create_function(|_, id: String| {
+        let id: Uuid = id
+            .parse()
+            .map_err(|_| mlua::Error::runtime(format!("invalid folder id: {id}")))?;
+        let removed = crate::get_workspace().lock().unwrap().remove_folder(id);
+        if removed {
+            crate::workspace_changed();
+        }
+        Ok(removed)
+    })?;
+    workspace.set("remove_folder", remove_folder)?;
+
+    let list_folders = lua.create_function(|lua, ()| {
+        let folders: Vec<FolderSummary> = {
+            let workspace = crate::get_workspace().lock().unwrap();
+            workspace
+                .folders
+                .iter()
+                .map(|folder| FolderSummary {
+                    id: folder.id,
+                    name: folder.name.clone(),
+                    path: workspace.folder_path(folder.id).unwrap_or_default(),
+                    parent_id: folder.parent_id,
+                    collapsed: folder.collapsed,
+                })
+                .collect()
+        };
+        lua.to_value_with(
+            &folders,
+            SerializeOptions::new().serialize_none_to_null(false),
+        )
+    })?;
+    workspace.set("list_folders", list_folders)?;
+
+    let list_spaces = lua.create_function(|lua, ()| {
+        let spaces: Vec<SpaceSummary> = crate::get_workspace()
+            .lock()
+            .unwrap()
+            .spaces
+            .iter()
+            .map(|space| SpaceSummary {
+                id: space.id,
+                name: space.name.clone(),
+                selected_endpoint_id: space.selected_endpoint_id,
+                selected_header_ids: space.selected_header_ids.clone(),
+                selected_body_id: space.selected_body_id,
+                overrides: space.overrides.clone(),
+                headers: space.headers.clone(),
+                archived: space.archived,
+            })
+            .collect();
+        lua.to_value_with(
+            &spaces,
+            SerializeOptions::new().serialize_none_to_null(false),
+        )
+    })?;
+    workspace.set("list_spaces", list_spaces)?;
+
+    let set_overrides = lua.create_function(|lua, (key, opts): (String, Value)| {
+        let opts: LuaOverrides = match opts {
+            Value::Nil => LuaOverrides::default(),
+            opts => lua.from_value(opts)?,
+        };
+        {
+            let mut workspace = crate::get_workspace().lock().unwrap();
+            let mut headers = BTreeMap::new();
+            for (name, value) in opts.headers {
+                let header = workspace
+                    .headers
+                    .iter()
+                    .find(|h| h.name == name || h.id.to_string() == name)
+                    .ok_or_else(|| mlua::Error::runtime(format!("unknown header: {name}")))?;
+                headers.insert(header.id, value);
+            }
+            let space = workspace
+                .spaces
+                .iter_mut()
+                .find(|s| s.name == key || s.id.to_string() == key)
+                .ok_or_else(|| mlua::Error::runtime(format!("unknown space: {key}")))?;
+            space.overrides = SpaceOverrides {
+                method: opts.method.map(|m| m.to_ascii_uppercase()),
+                url: opts.url,
+                host: opts.host,
+                headers,
+                body: opts.body,
+            };
+        }
+        crate::workspace_changed();
+        Ok(())
+    })?;
+    workspace.set("set_overrides", set_overrides)?;
+
+    let search = lua.create_function(|lua, query: String| {
+        let results = crate::get_workspace().lock().unwrap().search(&query);
+        lua.to_value_with(
+            &results,
+            SerializeOptions::new().serialize_none_to_null(false),
+        )
+    })?;
+    workspace.set("search", search)?;
+
+    let move_item = lua.create_function(|_, (kind, key, position): (String, String, usize)| {
+        let id = crate::get_workspace()
+            .lock()
+            .unwrap()
+            .move_item(&kind, &key, position.saturating_sub(1))
+            .map_err(|e| mlua::Error::runtime(format!("{e:#}")))?;
+        crate::workspace_changed();
+        Ok(id.to_string())
+    })?;
+    workspace.set("move", move_item)?;
+
+    let remove_item = lua.create_function(|_, (kind, key): (String, String)| {
+        let id = crate::get_workspace()
+            .lock()
+            .unwrap()
+            .remove_item(&kind, &key)
+            .map_err(|e| mlua::Error::runtime(format!("{e:#}")))?;
+        crate::workspace_changed();
+        Ok(id.to_string())
+    })?;
+    workspace.set("remove", remove_item)?;
+
+    let duplicate_item = lua.create_function(|_, (kind, key): (String, String)| {
+        let id = crate::get_workspace()
+            .lock()
+            .unwrap()
+            .duplicate_item(&kind, &key)
+            .map_err(|e| mlua::Error::runtime(format!("{e:#}")))?;
+        crate::workspace_changed();
+        Ok(id.to_string())
+    })?;
+    workspace.set("duplicate", duplicate_item)?;
+
+    table.set("workspace", workspace)?;
+
+    Ok(())
+}
+
+/// The folder with id `key`, or the folder at path `key`, created as needed.
+fn resolve_folder(workspace: &mut Workspace, key: &str) -> Option<Uuid> {
+    match key.parse::<Uuid>() {
+        Ok(id) if workspace.folders.iter().any(|f| f.id == id) => Some(id),
+        _ => workspace.ensure_folder_path(key),
+    }
+}
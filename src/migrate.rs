@@ -0,0 +1,64 @@
+use anyhow::{bail, Context, Result};
+use serde_json::{Map, Value};
+
+/// Version of the workspace layout this build writes, saved as `schema_version`.
+/// Bump it together with a new entry in [`WORKSPACE_MIGRATIONS`].
+pub const WORKSPACE_VERSION: u32 = 1;
+
+type Migration = fn(&mut Map<String, Value>) -> Result<()>;
+
+/// `WORKSPACE_MIGRATIONS[n]` upgrades a workspace of version `n` to `n + 1`.
+/// Steps see raw JSON, so they can rename or reshape fields the model no
+/// longer knows about.
+const WORKSPACE_MIGRATIONS: &[Migration] = &[unversioned];
+
+/// Bring a workspace as stored by any earlier build up to [`WORKSPACE_VERSION`],
+/// ready to deserialize. One written by a newer build is an error rather than
+/// being read with fields missing and then saved over.
+pub fn workspace(mut value: Value) -> Result<Value> {
+    let Value::Object(fields) = &mut value else {
+        bail!("workspace is not an object");
+    };
+    let version = match fields.get("schema_version") {
+        None => 0,
+        Some(v) => v
+            .as_u64()
+            .and_then(|v| u32::try_from(v).ok())
+            .with_context(|| format!("invalid schema_version {v}"))?,
+    };
+    if version > WORKSPACE_VERSION {
+        bail!(
+            "workspace schema version {version} is newer than this build supports \
+             ({WORKSPACE_VERSION}); update pigeon to open it"
+        );
+    }
+
+    for (from, migrate) in WORKSPACE_MIGRATIONS
+        .iter()
+        .enumerate()
+        .skip(version as usize)
+    {
+        migrate(fields).with_context(|| {
+            format!(
+                "Failed to migrate workspace from schema version {from} to {}",
+                from + 1
+            )
+        })?;
+    }
+    fields.insert("schema_version".to_string(), WORKSPACE_VERSION.into());
+    Ok(value)
+}
+
+/// 0 → 1: workspaces saved before versioning. Lists that were required then may
+/// be missing from hand-written or trimmed files; start them empty.
+fn unversioned(fields: &mut Map<String, Value>) -> Result<()> {
+    for name in ["endpoints", "headers", "bodies", "spaces"] {
+        let list = fields
+            .entry(name)
+            .or_insert_with(|| Value::Array(Vec::new()));
+        if list.is_null() {
+            *list = Value::Array(Vec::new());
+        }
+    }
+    Ok(())
+}
@@ -0,0 +1,298 @@
+use anyhow::{bail, Context, Result};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::sync::{Arc, Mutex};
+use tokio::io::{AsyncBufReadExt, AsyncReadExt, AsyncWriteExt, BufReader};
+use tokio::net::{TcpListener, TcpStream};
+use tokio::sync::oneshot;
+
+/// Upper bound on the request line plus headers of a single mock request.
+const MAX_HEAD_BYTES: usize = 64 * 1024;
+/// Upper bound on a mock request's body; larger ones get `413 Payload Too Large`.
+const MAX_BODY_BYTES: usize = 16 * 1024 * 1024;
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MockConfig {
+    #[serde(default = "default_host")]
+    pub host: String,
+    /// `0` picks a free port; the chosen address is returned by [`start`].
+    #[serde(default)]
+    pub port: u16,
+    #[serde(default)]
+    pub routes: Vec<MockRoute>,
+}
+
+fn default_host() -> String {
+    "127.0.0.1".to_string()
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MockRoute {
+    /// Matches any method when absent.
+    pub method: Option<String>,
+    /// Exact path, or a prefix when it ends in `*` (e.g. `/api/*`).
+    pub path: String,
+    #[serde(default = "default_status")]
+    pub status: u16,
+    #[serde(default)]
+    pub headers: Vec<(String, String)>,
+    #[serde(default)]
+    pub body: String,
+    #[serde(default)]
+    pub delay_ms: u64,
+}
+
+fn default_status() -> u16 {
+    200
+}
+
+/// A request received by the mock server.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MockHit {
+    pub timestamp: DateTime<Utc>,
+    pub method: String,
+    pub path: String,
+    pub query: Option<String>,
+    pub headers: Vec<(String, String)>,
+    pub body: String,
+    /// Index into the configured routes, `None` if the request got a 404.
+    pub matched_route: Option<usize>,
+}
+
+struct RunningServer {
+    url: String,
+    shutdown: oneshot::Sender<()>,
+}
+
+static SERVER: Mutex<Option<RunningServer>> = Mutex::new(None);
+static HITS: Mutex<Vec<MockHit>> = Mutex::new(Vec::new());
+
+impl MockRoute {
+    fn matches(&self, method: &str, path: &str) -> bool {
+        let method_ok = self
+            .method
+            .as_deref()
+            .is_none_or(|m| m.eq_ignore_ascii_case(method));
+        let path_ok = match self.path.strip_suffix('*') {
+            Some(prefix) => path.starts_with(prefix),
+            None => self.path == path,
+        };
+        method_ok && path_ok
+    }
+}
+
+/// Bind and start serving `config` on the current tokio runtime. Returns the base URL.
+///
+/// Previously recorded hits are cleared.
+pub async fn start(config: MockConfig) -> Result<String> {
+    if let Some(server) = SERVER.lock().unwrap().as_ref() {
+        bail!("mock server already running at {}", server.url);
+    }
+
+    let listener = TcpListener::bind((config.host.as_str(), config.port))
+        .await
+        .with_context(|| {
+            format!(
+                "Failed to bind mock server to {}:{}",
+                config.host, config.port
+            )
+        })?;
+    let url = format!("http://{}", listener.local_addr()?);
+
+    HITS.lock().unwrap().clear();
+
+    let routes = Arc::new(config.routes);
+    let (shutdown, mut stopped) = oneshot::channel();
+
+    tokio::spawn(async move {
+        loop {
+            tokio::select! {
+                _ = &mut stopped => break,
+                accepted = listener.accept() => {
+                    if let Ok((stream, _)) = accepted {
+                        tokio::spawn(handle_connection(stream, routes.clone()));
+                    }
+                }
+            }
+        }
+    });
+
+    let mut slot = SERVER.lock().unwrap();
+    if slot.is_some() {
+        // Lost a race with a concurrent start; shut ours down again.
+        let _ = shutdown.send(());
+        bail!("mock server already running");
+    }
+    *slot = Some(RunningServer {
+        url: url.clone(),
+        shutdown,
+    });
+
+    Ok(url)
+}
+
+/// Stop the running server. Returns `false` if none was running.
+pub fn stop() -> bool {
+    match SERVER.lock().unwrap().take() {
+        Some(server) => {
+            let _ = server.shutdown.send(());
+            true
+        }
+        None => false,
+    }
+}
+
+/// The running server's base URL, if any.
+pub fn url() -> Option<String> {
+    SERVER
+        .lock()
+        .unwrap()
+        .as_ref()
+        .map(|server| server.url.clone())
+}
+
+/// Requests recorded since the last [`start`].
+pub fn hits() -> Vec<MockHit> {
+    HITS.lock().unwrap().clone()
+}
+
+async fn handle_connection(stream: TcpStream, routes: Arc<Vec<MockRoute>>) {
+    let (reader, mut writer) = stream.into_split();
+    let mut reader = BufReader::new(reader);
+
+    let hit = match read_request(&mut reader).await {
+        Ok(hit) => hit,
+        Err(rejection) => {
+            let _ = writer.write_all(rejection.reply()).await;
+            return;
+        }
+    };
+
+    let matched = routes
+        .iter()
+        .position(|r| r.matches(&hit.method, &hit.path));
+    let route = matched.and_then(|i| routes.get(i)).cloned();
+    HITS.lock().unwrap().push(MockHit {
+        matched_route: matched,
+        ..hit
+    });
+
+    let (status, headers, body) = match route {
+        Some(route) => {
+            if route.delay_ms > 0 {
+                tokio::time::sleep(std::time::Duration::from_millis(route.delay_ms)).await;
+            }
+            (route.status, route.headers, route.body)
+        }
+        None => (404, vec![], "no mock route matched".to_string()),
+    };
+
+    let reason = reqwest::StatusCode::from_u16(status)
+        .ok()
+        .and_then(|s| s.canonical_reason())
+        .unwrap_or("");
+    let mut response = format!("HTTP/1.1 {status} {reason}\r\n");
+    for (key, value) in &headers {
+        response.push_str(&format!("{key}: {value}\r\n"));
+    }
+    response.push_str(&format!(
+        "content-length: {}\r\nconnection: close\r\n\r\n",
+        body.len()
+    ));
+
+    let _ = writer.write_all(response.as_bytes()).await;
+    let _ = writer.write_all(body.as_bytes()).await;
+    let _ = writer.shutdown().await;
+}
+
+/// Why a request was turned away without being recorded.
+enum Rejection {
+    Malformed,
+    TooLarge,
+}
+
+impl Rejection {
+    fn reply(&self) -> &'static [u8] {
+        match self {
+            Rejection::Malformed => {
+                b"HTTP/1.1 400 Bad Request\r\ncontent-length: 0\r\nconnection: close\r\n\r\n"
+            }
+            Rejection::TooLarge => {
+                b"HTTP/1.1 413 Payload Too Large\r\ncontent-length: 0\r\nconnection: close\r\n\r\n"
+            }
+        }
+    }
+}
+
+impl From<std::io::Error> for Rejection {
+    fn from(_: std::io::Error) -> Self {
+        Rejection::Malformed
+    }
+}
+
+/// Parse one HTTP/1.1 request.
+async fn read_request<R>(reader: &mut BufReader<R>) -> std::result::Result<MockHit, Rejection>
+where
+    R: tokio::io::AsyncRead + Unpin,
+{
+    let mut head_bytes = 0;
+    let mut line = String::new();
+    head_bytes += reader.read_line(&mut line).await?;
+
+    let mut parts = line.split_whitespace();
+    let (Some(method), Some(target)) = (parts.next(), parts.next()) else {
+        return Err(Rejection::Malformed);
+    };
+    let method = method.to_string();
+    let (path, query) = match target.split_once('?') {
+        Some((p, q)) => (p.to_string(), Some(q.to_string())),
+        None => (target.to_string(), None),
+    };
+
+    let mut headers = Vec::new();
+    let mut content_length = 0usize;
+    loop {
+        line.clear();
+        let read = reader.read_line(&mut line).await?;
+        head_bytes += read;
+        if read == 0 || head_bytes > MAX_HEAD_BYTES {
+            return Err(Rejection::Malformed);
+        }
+        let trimmed = line.trim_end_matches(['\r', '\n']);
+        if trimmed.is_empty() {
+            break;
+        }
+        if let Some((key, value)) = trimmed.split_once(':') {
+            let (key, value) = (key.trim().to_string(), value.trim().to_string());
+            if key.eq_ignore_ascii_case("content-length") {
+                content_length = value.parse().unwrap_or(0);
+            }
+            headers.push((key, value));
+        }
+    }
+
+    if content_length > MAX_BODY_BYTES {
+        return Err(Rejection::TooLarge);
+    }
+    let mut body = Vec::new();
+    reader
+        .take(content_length as u64)
+        .read_to_end(&mut body)
+        .await?;
+    if body.len() < content_length {
+        return Err(Rejection::Malformed);
+    }
+
+    Ok(MockHit {
+        timestamp: Utc::now(),
+        method,
+        path,
+        query,
+        headers,
+        body: String::from_utf8_lossy(&body).into_owned(),
+        matched_route: None,
+    })
+}
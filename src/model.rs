@@ -1,13 +1,30 @@
-use chrono::{DateTime, Utc};
+use anyhow::{anyhow, bail, Context, Result};
 use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet};
+use std::path::Path;
 use uuid::Uuid;
 
+use crate::collection::Collection;
+use crate::migrate;
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Endpoint {
     pub id: Uuid,
     pub name: String,
     pub url: String,
     pub method: String, // GET, POST, PUT, DELETE, etc.
+    /// Containing folder; top level when `None`.
+    #[serde(default)]
+    pub folder_id: Option<Uuid>,
+    /// Labels for [`Workspace::search`], e.g. `auth` or `v2`.
+    #[serde(default)]
+    pub tags: Vec<String>,
+    /// Starred: listed first and in the sidebar's Favorites group.
+    #[serde(default)]
+    pub favorite: bool,
+    /// Markdown notes, e.g. on quirks of the API behind it.
+    #[serde(default)]
+    pub notes: String,
 }
 
 impl Default for Endpoint {
@@ -17,10 +34,99 @@ impl Default for Endpoint {
             name: "New Endpoint".to_string(),
             url: "https://httpbin.org/get".to_string(),
             method: "GET".to_string(),
+            folder_id: None,
+            tags: Vec::new(),
+            favorite: false,
+            notes: String::new(),
         }
     }
 }
 
+/// Self-contained request saved under a name, e.g. "Create user (bad payload)".
+///
+/// Unlike a space's selections it doesn't share headers or a body with other
+/// requests, so variants of the same endpoint can coexist.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Request {
+    pub id: Uuid,
+    pub name: String,
+    /// Library endpoint this request was made from, if any.
+    #[serde(default)]
+    pub endpoint_id: Option<Uuid>,
+    pub method: String,
+    pub url: String,
+    /// Query parameters appended to `url` when sending.
+    #[serde(default)]
+    pub params: Vec<KeyValue>,
+    #[serde(default)]
+    pub headers: Vec<KeyValue>,
+    #[serde(default)]
+    pub body: Option<RequestBody>,
+    #[serde(default)]
+    pub auth: Option<RequestAuth>,
+    /// Containing folder; top level when `None`.
+    #[serde(default)]
+    pub folder_id: Option<Uuid>,
+}
+
+impl Default for Request {
+    fn default() -> Self {
+        Self {
+            id: Uuid::new_v4(),
+            name: "New Request".to_string(),
+            endpoint_id: None,
+            method: "GET".to_string(),
+            url: "https://httpbin.org/get".to_string(),
+            params: Vec::new(),
+            headers: Vec::new(),
+            body: None,
+            auth: None,
+            folder_id: None,
+        }
+    }
+}
+
+/// A header or query parameter of a [`Request`].
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct KeyValue {
+    pub key: String,
+    pub value: String,
+    #[serde(default = "default_true")]
+    pub enabled: bool,
+}
+
+fn default_true() -> bool {
+    true
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RequestBody {
+    pub content_type: String,
+    pub content: String,
+}
+
+/// Auth resolved against the providers registered with `pigeon.register_auth`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RequestAuth {
+    #[serde(rename = "type")]
+    pub kind: String,
+    #[serde(default)]
+    pub params: BTreeMap<String, String>,
+}
+
+/// Group of endpoints, e.g. per service, nested under `parent_id`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Folder {
+    pub id: Uuid,
+    pub name: String,
+    /// Top level when `None`.
+    #[serde(default)]
+    pub parent_id: Option<Uuid>,
+    /// Shown folded in the sidebar.
+    #[serde(default)]
+    pub collapsed: bool,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Header {
     pub id: Uuid,
@@ -28,6 +134,11 @@ pub struct Header {
     pub key: String,
     pub value: String,
     pub enabled: bool,
+    #[serde(default)]
+    pub tags: Vec<String>,
+    /// Value persisted encrypted, masked by front ends and left out of exports.
+    #[serde(default)]
+    pub secret: bool,
 }
 
 impl Default for Header {
@@ -38,6 +149,8 @@ impl Default for Header {
             key: "".to_string(),
             value: "".to_string(),
             enabled: true,
+            tags: Vec::new(),
+            secret: false,
         }
     }
 }
@@ -48,6 +161,8 @@ pub struct Body {
     pub name: String,
     pub content_type: String, // "application/json", "text/plain"
     pub content: String,
+    #[serde(default)]
+    pub tags: Vec<String>,
 }
 
 impl Default for Body {
@@ -57,26 +172,62 @@ impl Default for Body {
             name: "New Body".to_string(),
             content_type: "application/json".to_string(),
             content: "{}".to_string(),
+            tags: Vec::new(),
         }
     }
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct ResponseData {
-    pub status: u16,
-    pub status_text: String,
-    pub headers: Vec<(String, String)>,
-    pub body: String,
-    pub timestamp: DateTime<Utc>,
-    pub duration_ms: u64,
+/// `format` of files written by [`Workspace::export`].
+const EXPORT_FORMAT: &str = "pigeon-workspace";
+/// Newest export `version` this build writes and reads.
+const EXPORT_VERSION: u32 = 1;
+
+/// A workspace in a file of its own, to move it between machines or share it.
+#[derive(Serialize)]
+struct WorkspaceExport {
+    format: String,
+    version: u32,
+    workspace: Workspace,
+}
+
+/// One step of [`Workspace::apply_bulk`].
+#[derive(Debug, Clone, Deserialize)]
+#[serde(tag = "op", rename_all = "snake_case")]
+pub enum BulkOp {
+    Remove {
+        kind: String,
+        key: String,
+    },
+    Update {
+        kind: String,
+        key: String,
+        changes: serde_json::Value,
+    },
+}
+
+/// Library items matching a [`Workspace::search`] query.
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct SearchResults {
+    pub endpoints: Vec<Endpoint>,
+    pub headers: Vec<Header>,
+    pub bodies: Vec<Body>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Workspace {
+    /// Layout version, for [`crate::migrate`]; always the current one once loaded.
+    #[serde(default)]
+    pub schema_version: u32,
     pub endpoints: Vec<Endpoint>,
     pub headers: Vec<Header>,
     pub bodies: Vec<Body>,
     pub spaces: Vec<Space>,
+    #[serde(default)]
+    pub environments: Vec<Environment>,
+    #[serde(default)]
+    pub folders: Vec<Folder>,
+    #[serde(default)]
+    pub requests: Vec<Request>,
 }
 
 impl Default for Workspace {
@@ -104,10 +255,824 @@ impl Default for Workspace {
         };
 
         Self {
+            schema_version: migrate::WORKSPACE_VERSION,
             endpoints: vec![ep1],
             headers: vec![h1],
             bodies: vec![b1],
             spaces: vec![s1],
+            environments: Vec::new(),
+            folders: Vec::new(),
+            requests: Vec::new(),
+        }
+    }
+}
+
+impl Workspace {
+    /// A workspace with nothing in it, for starting from scratch instead of the
+    /// sample data [`Workspace::default`] holds.
+    pub fn empty() -> Self {
+        Self {
+            schema_version: migrate::WORKSPACE_VERSION,
+            endpoints: Vec::new(),
+            headers: Vec::new(),
+            bodies: Vec::new(),
+            spaces: Vec::new(),
+            environments: Vec::new(),
+            folders: Vec::new(),
+            requests: Vec::new(),
+        }
+    }
+
+    /// Load a workspace saved by [`Workspace::save`]; a missing file yields the default one.
+    pub fn load(path: &Path) -> Result<Self> {
+        if !path.exists() {
+            return Ok(Self::default());
+        }
+        let data = std::fs::read_to_string(path)
+            .with_context(|| format!("Failed to read workspace: {}", path.display()))?;
+        serde_json::from_str(&data)
+            .map_err(anyhow::Error::from)
+            .and_then(Self::from_value)
+            .with_context(|| format!("Invalid workspace: {}", path.display()))
+    }
+
+    /// Deserialize a stored workspace, first migrating it from older schema versions.
+    pub fn from_value(value: serde_json::Value) -> Result<Self> {
+        Ok(serde_json::from_value(migrate::workspace(value)?)?)
+    }
+
+    /// Write the workspace to `path`, via a temporary file so a crash mid-write
+    /// leaves the previous version intact.
+    pub fn save(&self, path: &Path) -> Result<()> {
+        let tmp = path.with_extension("json.tmp");
+        std::fs::write(&tmp, serde_json::to_string_pretty(self)?)
+            .with_context(|| format!("Failed to write workspace: {}", tmp.display()))?;
+        std::fs::rename(&tmp, path)
+            .with_context(|| format!("Failed to write workspace: {}", path.display()))
+    }
+
+    /// The workspace as a versioned, pretty-printed JSON document for [`Workspace::import`].
+    pub fn export(&self) -> Result<String> {
+        Ok(serde_json::to_string_pretty(&WorkspaceExport {
+            format: EXPORT_FORMAT.to_string(),
+            version: EXPORT_VERSION,
+            workspace: self.clone(),
+        })?)
+    }
+
+    /// Read a document written by [`Workspace::export`], possibly by another build.
+    pub fn import(data: &str) -> Result<Self> {
+        let mut value: serde_json::Value =
+            serde_json::from_str(data).context("Invalid workspace export")?;
+        match value.get("format").and_then(|f| f.as_str()) {
+            Some(EXPORT_FORMAT) => {}
+            Some(other) => bail!("not a workspace export: format is `{other}`"),
+            None => bail!("not a workspace export: missing `format`"),
+        }
+        let version = value.get("version").and_then(|v| v.as_u64()).unwrap_or(0);
+        if version == 0 || version > u64::from(EXPORT_VERSION) {
+            bail!(
+                "unsupported workspace export version {version} (this build reads up to {EXPORT_VERSION})"
+            );
+        }
+        let workspace = value
+            .get_mut("workspace")
+            .map(serde_json::Value::take)
+            .ok_or_else(|| anyhow!("not a workspace export: missing `workspace`"))?;
+        Self::from_value(workspace).context("Invalid workspace export")
+    }
+
+    /// Add `endpoint`, or update the one with the same name in place (keeping its id).
+    pub fn upsert_endpoint(&mut self, endpoint: Endpoint) -> Uuid {
+        upsert(&mut self.endpoints, endpoint, |e| &e.name, |e| &mut e.id)
+    }
+
+    /// Add `header`, or update the one with the same name in place (keeping its id).
+    pub fn upsert_header(&mut self, header: Header) -> Uuid {
+        upsert(&mut self.headers, header, |h| &h.name, |h| &mut h.id)
+    }
+
+    /// Add `request`, or update the one with the same name in place (keeping its id).
+    pub fn upsert_request(&mut self, request: Request) -> Uuid {
+        upsert(&mut self.requests, request, |r| &r.name, |r| &mut r.id)
+    }
+
+    /// Add an imported collection's requests to the library as endpoints (updating
+    /// same-named ones). Endpoints only hold a method and URL, so a request with
+    /// headers or a body is also kept as a saved request made from its endpoint.
+    /// Returns the endpoints' ids, in the collection's order.
+    pub fn add_collection(&mut self, collection: &Collection) -> Vec<Uuid> {
+        let mut ids = Vec::new();
+        for item in &collection.requests {
+            let name = if item.name.trim().is_empty() {
+                format!("{} {}", item.method, item.url)
+            } else {
+                item.name.clone()
+            };
+            let endpoint_id = self.upsert_endpoint(Endpoint {
+                name: name.clone(),
+                url: item.url.clone(),
+                method: item.method.clone(),
+                ..Endpoint::default()
+            });
+            if !item.headers.is_empty() || item.body.is_some() {
+                self.upsert_request(Request {
+                    name,
+                    endpoint_id: Some(endpoint_id),
+                    method: item.method.clone(),
+                    url: item.url.clone(),
+                    headers: item
+                        .headers
+                        .iter()
+                        .map(|h| KeyValue {
+                            key: h.key.clone(),
+                            value: h.value.clone(),
+                            enabled: h.enabled,
+                        })
+                        .collect(),
+                    body: item.body.as_ref().map(|b| RequestBody {
+                        content_type: b.content_type.clone(),
+                        content: b.content.clone(),
+                    }),
+                    ..Request::default()
+                });
+            }
+            ids.push(endpoint_id);
+        }
+        ids
+    }
+
+    /// Replace the endpoint with `endpoint`'s id. Spaces refer to it by id, so they
+    /// follow a rename; saved requests made from it that still use its old method
+    /// and url pick up the new ones.
+    pub fn update_endpoint(&mut self, endpoint: Endpoint) -> Result<()> {
+        let (id, method, url) = (endpoint.id, endpoint.method.clone(), endpoint.url.clone());
+        let old = replace(
+            &mut self.endpoints,
+            endpoint,
+            "endpoint",
+            |e| &e.name,
+            |e| e.id,
+        )?;
+        for request in self
+            .requests
+            .iter_mut()
+            .filter(|r| r.endpoint_id == Some(id) && r.method == old.method && r.url == old.url)
+        {
+            request.method = method.clone();
+            request.url = url.clone();
+        }
+        Ok(())
+    }
+
+    /// Replace the header with `header`'s id; spaces selecting it keep it selected.
+    pub fn update_header(&mut self, header: Header) -> Result<()> {
+        replace(&mut self.headers, header, "header", |h| &h.name, |h| h.id).map(drop)
+    }
+
+    /// Replace the body with `body`'s id; spaces selecting it keep it selected.
+    pub fn update_body(&mut self, body: Body) -> Result<()> {
+        replace(&mut self.bodies, body, "body", |b| &b.name, |b| b.id).map(drop)
+    }
+
+    /// Replace the space with `space`'s id.
+    pub fn update_space(&mut self, space: Space) -> Result<()> {
+        replace(&mut self.spaces, space, "space", |s| &s.name, |s| s.id).map(drop)
+    }
+
+    /// Apply `changes`, a JSON object of fields such as `{"name": "Users", "url": "..."}`,
+    /// to the `kind` (`endpoint`, `header`, `body` or `space`) whose id or name is `key`.
+    /// Fields left out keep their values; the id can't be changed.
+    pub fn apply_changes(
+        &mut self,
+        kind: &str,
+        key: &str,
+        changes: serde_json::Value,
+    ) -> Result<Uuid> {
+        let serde_json::Value::Object(changes) = changes else {
+            bail!("changes must be an object of fields");
+        };
+        let find = |id: Uuid, name: &str| id.to_string() == key || name == key;
+        let current = match kind {
+            "endpoint" => self
+                .endpoints
+                .iter()
+                .find(|e| find(e.id, &e.name))
+                .map(serde_json::to_value),
+            "header" => self
+                .headers
+                .iter()
+                .find(|h| find(h.id, &h.name))
+                .map(serde_json::to_value),
+            "body" => self
+                .bodies
+                .iter()
+                .find(|b| find(b.id, &b.name))
+                .map(serde_json::to_value),
+            "space" => self
+                .spaces
+                .iter()
+                .find(|s| find(s.id, &s.name))
+                .map(serde_json::to_value),
+            other => bail!("unknown kind `{other}` (expected endpoint, header, body or space)"),
+        };
+        let mut value = current.ok_or_else(|| anyhow!("unknown {kind}: {key}"))??;
+        let serde_json::Value::Object(fields) = &mut value else {
+            bail!("{kind} did not serialize to an object");
+        };
+        for (field, change) in changes {
+            if field != "id" {
+                fields.insert(field, change);
+            }
+        }
+
+        let invalid = || format!("invalid {kind} changes");
+        match kind {
+            "endpoint" => {
+                let endpoint: Endpoint = serde_json::from_value(value).with_context(invalid)?;
+                let id = endpoint.id;
+                self.update_endpoint(endpoint).map(|()| id)
+            }
+            "header" => {
+                let header: Header = serde_json::from_value(value).with_context(invalid)?;
+                let id = header.id;
+                self.update_header(header).map(|()| id)
+            }
+            "body" => {
+                let body: Body = serde_json::from_value(value).with_context(invalid)?;
+                let id = body.id;
+                self.update_body(body).map(|()| id)
+            }
+            _ => {
+                let space: Space = serde_json::from_value(value).with_context(invalid)?;
+                let id = space.id;
+                self.update_space(space).map(|()| id)
+            }
+        }
+    }
+
+    /// Move the `kind` (`endpoint`, `header`, `body`, `space` or `request`) whose id
+    /// or name is `key` to position `index` of its list, or the end if `index` is
+    /// past it. Front ends list items in this order; returns the item's id.
+    pub fn move_item(&mut self, kind: &str, key: &str, index: usize) -> Result<Uuid> {
+        match kind {
+            "endpoint" => move_to(&mut self.endpoints, kind, key, index, |e| (e.id, &e.name)),
+            "header" => move_to(&mut self.headers, kind, key, index, |h| (h.id, &h.name)),
+            "body" => move_to(&mut self.bodies, kind, key, index, |b| (b.id, &b.name)),
+            "space" => move_to(&mut self.spaces, kind, key, index, |s| (s.id, &s.name)),
+            "request" => move_to(&mut self.requests, kind, key, index, |r| (r.id, &r.name)),
+            other => {
+                bail!("unknown kind `{other}` (expected endpoint, header, body, space or request)")
+            }
+        }
+    }
+
+    /// Remove the `kind` (`endpoint`, `header`, `body`, `space` or `request`) whose
+    /// id or name is `key`, returning its id. Spaces stop selecting a removed
+    /// endpoint, header or body, and saved requests forget a removed endpoint.
+    pub fn remove_item(&mut self, kind: &str, key: &str) -> Result<Uuid> {
+        match kind {
+            "endpoint" => {
+                let id = remove_from(&mut self.endpoints, kind, key, |e| (e.id, &e.name))?;
+                for space in self
+                    .spaces
+                    .iter_mut()
+                    .filter(|s| s.selected_endpoint_id == Some(id))
+                {
+                    space.selected_endpoint_id = None;
+                }
+                for request in self
+                    .requests
+                    .iter_mut()
+                    .filter(|r| r.endpoint_id == Some(id))
+                {
+                    request.endpoint_id = None;
+                }
+                Ok(id)
+            }
+            "header" => {
+                let id = remove_from(&mut self.headers, kind, key, |h| (h.id, &h.name))?;
+                for space in &mut self.spaces {
+                    space.selected_header_ids.retain(|h| *h != id);
+                    space.overrides.headers.remove(&id);
+                }
+                Ok(id)
+            }
+            "body" => {
+                let id = remove_from(&mut self.bodies, kind, key, |b| (b.id, &b.name))?;
+                for space in self
+                    .spaces
+                    .iter_mut()
+                    .filter(|s| s.selected_body_id == Some(id))
+                {
+                    space.selected_body_id = None;
+                }
+                Ok(id)
+            }
+            "space" => remove_from(&mut self.spaces, kind, key, |s| (s.id, &s.name)),
+            "request" => remove_from(&mut self.requests, kind, key, |r| (r.id, &r.name)),
+            other => {
+                bail!("unknown kind `{other}` (expected endpoint, header, body, space or request)")
+            }
+        }
+    }
+
+    /// Copy the `kind` (`endpoint`, `header`, `body`, `space` or `request`) whose id
+    /// or name is `key`, listing the copy right after it as `<name> copy` (or
+    /// `<name> copy 2`, ...). Returns the copy's id.
+    pub fn duplicate_item(&mut self, kind: &str, key: &str) -> Result<Uuid> {
+        match kind {
+            "endpoint" => {
+                duplicate_in(&mut self.endpoints, kind, key, |e| (&mut e.id, &mut e.name))
+            }
+            "header" => duplicate_in(&mut self.headers, kind, key, |h| (&mut h.id, &mut h.name)),
+            "body" => duplicate_in(&mut self.bodies, kind, key, |b| (&mut b.id, &mut b.name)),
+            "space" => duplicate_in(&mut self.spaces, kind, key, |s| (&mut s.id, &mut s.name)),
+            "request" => duplicate_in(&mut self.requests, kind, key, |r| (&mut r.id, &mut r.name)),
+            other => {
+                bail!("unknown kind `{other}` (expected endpoint, header, body, space or request)")
+            }
+        }
+    }
+
+    /// Apply removals and edits in order, as [`Workspace::remove_item`] and
+    /// [`Workspace::apply_changes`] do, e.g. for bulk actions on several selected
+    /// items. Either every step applies or, on the first failure, none do.
+    pub fn apply_bulk(&mut self, ops: Vec<BulkOp>) -> Result<()> {
+        let mut next = self.clone();
+        for (i, op) in ops.into_iter().enumerate() {
+            let step = i + 1;
+            match op {
+                BulkOp::Remove { kind, key } => next
+                    .remove_item(&kind, &key)
+                    .with_context(|| format!("step {step}: remove {kind} `{key}`"))?,
+                BulkOp::Update { kind, key, changes } => {
+                    next.apply_changes(&kind, &key, changes)
+                        .with_context(|| format!("step {step}: update {kind} `{key}`"))?
+                }
+            };
+        }
+        *self = next;
+        Ok(())
+    }
+
+    /// Endpoints, headers and bodies matching every word of `query`. A `#tag` word
+    /// must be one of an item's tags; other words match case-insensitively anywhere
+    /// in its name, tags, and URL, method and notes (endpoints), key (headers) or
+    /// content type (bodies). An empty query matches everything. Favorite endpoints come first.
+    pub fn search(&self, query: &str) -> SearchResults {
+        let words: Vec<String> = query.split_whitespace().map(str::to_lowercase).collect();
+        let mut endpoints: Vec<Endpoint> = self
+            .endpoints
+            .iter()
+            .filter(|e| matches_words(&words, &[&e.name, &e.url, &e.method, &e.notes], &e.tags))
+            .cloned()
+            .collect();
+        endpoints.sort_by_key(|e| !e.favorite);
+        SearchResults {
+            endpoints,
+            headers: self
+                .headers
+                .iter()
+                .filter(|h| matches_words(&words, &[&h.name, &h.key], &h.tags))
+                .cloned()
+                .collect(),
+            bodies: self
+                .bodies
+                .iter()
+                .filter(|b| matches_words(&words, &[&b.name, &b.content_type], &b.tags))
+                .cloned()
+                .collect(),
+        }
+    }
+
+    /// The saved request whose id or name is `key`.
+    pub fn find_request(&self, key: &str) -> Option<&Request> {
+        self.requests
+            .iter()
+            .find(|r| r.name == key || r.id.to_string() == key)
+    }
+
+    /// Add `environment`, or update the one with the same name in place (keeping its id).
+    pub fn upsert_environment(&mut self, environment: Environment) -> Uuid {
+        upsert(
+            &mut self.environments,
+            environment,
+            |e| &e.name,
+            |e| &mut e.id,
+        )
+    }
+
+    /// The folder called `name` directly under `parent`, created if there is none.
+    pub fn ensure_folder(&mut self, name: &str, parent: Option<Uuid>) -> Uuid {
+        if let Some(folder) = self
+            .folders
+            .iter()
+            .find(|f| f.name == name && f.parent_id == parent)
+        {
+            return folder.id;
+        }
+        let id = Uuid::new_v4();
+        self.folders.push(Folder {
+            id,
+            name: name.to_string(),
+            parent_id: parent,
+            collapsed: false,
+        });
+        id
+    }
+
+    /// The folder at a `/`-separated path such as `users/admin`, creating missing
+    /// levels. `None` for an empty path, i.e. the top level.
+    pub fn ensure_folder_path(&mut self, path: &str) -> Option<Uuid> {
+        path.split('/')
+            .map(str::trim)
+            .filter(|name| !name.is_empty())
+            .fold(None, |parent, name| Some(self.ensure_folder(name, parent)))
+    }
+
+    /// Remove folder `id`, moving its endpoints, requests and subfolders up to its parent.
+    pub fn remove_folder(&mut self, id: Uuid) -> bool {
+        let Some(index) = self.folders.iter().position(|f| f.id == id) else {
+            return false;
+        };
+        let parent = self.folders.remove(index).parent_id;
+        for folder in self.folders.iter_mut().filter(|f| f.parent_id == Some(id)) {
+            folder.parent_id = parent;
+        }
+        for endpoint in self
+            .endpoints
+            .iter_mut()
+            .filter(|e| e.folder_id == Some(id))
+        {
+            endpoint.folder_id = parent;
+        }
+        for request in self.requests.iter_mut().filter(|r| r.folder_id == Some(id)) {
+            request.folder_id = parent;
+        }
+        true
+    }
+
+    /// `users/admin` for folder `id`, or `None` if there is no such folder.
+    pub fn folder_path(&self, id: Uuid) -> Option<String> {
+        let mut names = Vec::new();
+        let mut current = Some(id);
+        while let Some(id) = current {
+            let folder = self.folders.iter().find(|f| f.id == id)?;
+            // Hosts edit `parent_id` directly, so don't trust it to be acyclic.
+            if names.len() > self.folders.len() {
+                return None;
+            }
+            names.push(folder.name.as_str());
+            current = folder.parent_id;
+        }
+        names.reverse();
+        Some(names.join("/"))
+    }
+
+    /// Values of secret headers (including space overrides of them) and secret
+    /// environment variables, to keep them out of history and logs.
+    pub fn secret_values(&self) -> Vec<&str> {
+        let secret_headers: BTreeSet<Uuid> = self
+            .headers
+            .iter()
+            .filter(|h| h.secret)
+            .map(|h| h.id)
+            .collect();
+        let mut values: Vec<&str> = self
+            .headers
+            .iter()
+            .filter(|h| h.secret)
+            .map(|h| h.value.as_str())
+            .collect();
+        for space in &self.spaces {
+            values.extend(
+                space
+                    .overrides
+                    .headers
+                    .iter()
+                    .filter(|(id, _)| secret_headers.contains(id))
+                    .map(|(_, value)| value.as_str()),
+            );
+        }
+        for environment in &self.environments {
+            values.extend(
+                environment
+                    .variables
+                    .iter()
+                    .filter(|(name, _)| environment.secrets.contains(*name))
+                    .map(|(_, value)| value.as_str()),
+            );
+        }
+        values
+    }
+
+    /// Values of secret headers (including space overrides of them) and secret
+    /// environment variables, to seal, open or strip them in place.
+    pub fn secret_values_mut(&mut self) -> Vec<&mut String> {
+        let secret_headers: BTreeSet<Uuid> = self
+            .headers
+            .iter()
+            .filter(|h| h.secret)
+            .map(|h| h.id)
+            .collect();
+        let mut values: Vec<&mut String> = self
+            .headers
+            .iter_mut()
+            .filter(|h| h.secret)
+            .map(|h| &mut h.value)
+            .collect();
+        for space in &mut self.spaces {
+            values.extend(
+                space
+                    .overrides
+                    .headers
+                    .iter_mut()
+                    .filter(|(id, _)| secret_headers.contains(id))
+                    .map(|(_, value)| value),
+            );
+        }
+        for environment in &mut self.environments {
+            let secrets = &environment.secrets;
+            values.extend(
+                environment
+                    .variables
+                    .iter_mut()
+                    .filter(|(name, _)| secrets.contains(*name))
+                    .map(|(_, value)| value),
+            );
+        }
+        values
+    }
+
+    /// Add a space with `fields` (a JSON object such as `{"name": "Smoke test",
+    /// "selected_endpoint_id": "..."}`) set; others take their defaults. Unnamed
+    /// spaces are called `Space 1`, `Space 2`, ... Returns the new space's id.
+    pub fn add_space(&mut self, fields: serde_json::Value) -> Result<Uuid> {
+        let serde_json::Value::Object(fields) = fields else {
+            bail!("space fields must be an object");
+        };
+        let name = (1..)
+            .map(|n| format!("Space {n}"))
+            .find(|name| self.spaces.iter().all(|s| &s.name != name))
+            .unwrap_or_default();
+        let mut value = serde_json::to_value(Space {
+            name,
+            ..Space::default()
+        })?;
+        if let serde_json::Value::Object(current) = &mut value {
+            for (field, change) in fields {
+                if field != "id" {
+                    current.insert(field, change);
+                }
+            }
+        }
+        let space: Space = serde_json::from_value(value).context("invalid space fields")?;
+        if self.spaces.iter().any(|s| s.name == space.name) {
+            bail!("another space is already named `{}`", space.name);
+        }
+        let id = space.id;
+        self.spaces.push(space);
+        Ok(id)
+    }
+
+    /// The space whose id or name is `key`.
+    pub fn find_space(&self, key: &str) -> Option<&Space> {
+        self.spaces
+            .iter()
+            .find(|s| s.name == key || s.id.to_string() == key)
+    }
+
+    /// The request space `key` sends: its selected endpoint, headers and body,
+    /// with the space's overrides applied and its own headers merged in.
+    pub fn space_request(&self, key: &str) -> Result<Request> {
+        let space = self
+            .find_space(key)
+            .ok_or_else(|| anyhow!("unknown space: {key}"))?;
+        let endpoint = space
+            .selected_endpoint_id
+            .and_then(|id| self.endpoints.iter().find(|e| e.id == id))
+            .ok_or_else(|| anyhow!("space `{}` has no endpoint selected", space.name))?;
+        let overrides = &space.overrides;
+
+        let mut url = overrides
+            .url
+            .clone()
+            .unwrap_or_else(|| endpoint.url.clone());
+        if let Some(host) = &overrides.host {
+            url = swap_host(&url, host);
+        }
+        let mut headers: Vec<KeyValue> = space
+            .selected_header_ids
+            .iter()
+            .filter_map(|id| self.headers.iter().find(|h| h.id == *id))
+            .map(|header| KeyValue {
+                key: header.key.clone(),
+                value: overrides
+                    .headers
+                    .get(&header.id)
+                    .unwrap_or(&header.value)
+                    .clone(),
+                enabled: header.enabled,
+            })
+            .collect();
+        for header in &space.headers {
+            let replaced = headers
+                .iter_mut()
+                .find(|h| header.enabled && h.key.eq_ignore_ascii_case(&header.key));
+            match replaced {
+                Some(existing) => *existing = header.clone(),
+                None => headers.push(header.clone()),
+            }
+        }
+        let selected_body = space
+            .selected_body_id
+            .and_then(|id| self.bodies.iter().find(|b| b.id == id));
+        let body = match (selected_body, &overrides.body) {
+            (Some(body), content) => Some(RequestBody {
+                content_type: body.content_type.clone(),
+                content: content.as_ref().unwrap_or(&body.content).clone(),
+            }),
+            (None, Some(content)) => Some(RequestBody {
+                content_type: String::new(),
+                content: content.clone(),
+            }),
+            (None, None) => None,
+        };
+
+        Ok(Request {
+            name: space.name.clone(),
+            endpoint_id: Some(endpoint.id),
+            method: overrides
+                .method
+                .clone()
+                .unwrap_or_else(|| endpoint.method.clone()),
+            url,
+            headers,
+            body,
+            ..Request::default()
+        })
+    }
+}
+
+fn upsert<T>(
+    items: &mut Vec<T>,
+    mut item: T,
+    name: impl Fn(&T) -> &String,
+    id: impl Fn(&mut T) -> &mut Uuid,
+) -> Uuid {
+    match items
+        .iter_mut()
+        .find(|existing| name(existing) == name(&item))
+    {
+        Some(existing) => {
+            *id(&mut item) = *id(existing);
+            *existing = item;
+            *id(existing)
+        }
+        None => {
+            let new_id = *id(&mut item);
+            items.push(item);
+            new_id
+        }
+    }
+}
+
+/// Move the item whose id or name is `key` to `index`, see [`Workspace::move_item`].
+fn move_to<T>(
+    items: &mut Vec<T>,
+    kind: &str,
+    key: &str,
+    index: usize,
+    identify: impl Fn(&T) -> (Uuid, &String),
+) -> Result<Uuid> {
+    let from = items
+        .iter()
+        .position(|item| {
+            let (id, name) = identify(item);
+            id.to_string() == key || name == key
+        })
+        .ok_or_else(|| anyhow!("unknown {kind}: {key}"))?;
+    let item = items.remove(from);
+    let id = identify(&item).0;
+    items.insert(index.min(items.len()), item);
+    Ok(id)
+}
+
+fn duplicate_in<T: Clone>(
+    items: &mut Vec<T>,
+    kind: &str,
+    key: &str,
+    fields: fn(&mut T) -> (&mut Uuid, &mut String),
+) -> Result<Uuid> {
+    let index = items
+        .iter_mut()
+        .position(|item| {
+            let (id, name) = fields(item);
+            id.to_string() == key || name == key
+        })
+        .ok_or_else(|| anyhow!("unknown {kind}: {key}"))?;
+    let taken: Vec<String> = items
+        .iter_mut()
+        .map(|item| fields(item).1.clone())
+        .collect();
+    let mut copy = items[index].clone();
+    let (id, name) = fields(&mut copy);
+    let base = format!("{name} copy");
+    let mut candidate = base.clone();
+    for n in 2.. {
+        if !taken.contains(&candidate) {
+            break;
+        }
+        candidate = format!("{base} {n}");
+    }
+    *name = candidate;
+    *id = Uuid::new_v4();
+    let id = *id;
+    items.insert(index + 1, copy);
+    Ok(id)
+}
+
+fn remove_from<T>(
+    items: &mut Vec<T>,
+    kind: &str,
+    key: &str,
+    identify: impl Fn(&T) -> (Uuid, &String),
+) -> Result<Uuid> {
+    let index = items
+        .iter()
+        .position(|item| {
+            let (id, name) = identify(item);
+            id.to_string() == key || name == key
+        })
+        .ok_or_else(|| anyhow!("unknown {kind}: {key}"))?;
+    Ok(identify(&items.remove(index)).0)
+}
+
+/// Whether every lowercased word matches `fields` or `tags`, per [`Workspace::search`].
+fn matches_words(words: &[String], fields: &[&str], tags: &[String]) -> bool {
+    words.iter().all(|word| match word.strip_prefix('#') {
+        Some(tag) if !tag.is_empty() => tags.iter().any(|t| t.to_lowercase() == tag),
+        _ => fields
+            .iter()
+            .copied()
+            .chain(tags.iter().map(String::as_str))
+            .any(|field| field.to_lowercase().contains(word.as_str())),
+    })
+}
+
+/// `url` with its host and port replaced by `host`, leaving the scheme, path and
+/// query alone. Works on templates such as `{{base}}/users` that aren't valid URLs.
+fn swap_host(url: &str, host: &str) -> String {
+    let (scheme, rest) = match url.find("://") {
+        Some(index) => url.split_at(index + 3),
+        None => ("", url),
+    };
+    let end = rest.find(['/', '?', '#']).unwrap_or(rest.len());
+    let userinfo = rest[..end].rfind('@').map_or("", |at| &rest[..=at]);
+    format!("{scheme}{userinfo}{host}{}", &rest[end..])
+}
+
+/// Swap `item` in for the entry with its id and return the old one. Names identify
+/// entries in `upsert`, so renaming onto another entry's name is an error.
+fn replace<T>(
+    items: &mut [T],
+    item: T,
+    kind: &str,
+    name: impl Fn(&T) -> &String,
+    id: impl Fn(&T) -> Uuid,
+) -> Result<T> {
+    if items
+        .iter()
+        .any(|other| id(other) != id(&item) && name(other) == name(&item))
+    {
+        bail!("another {kind} is already named `{}`", name(&item));
+    }
+    let existing = items
+        .iter_mut()
+        .find(|existing| id(existing) == id(&item))
+        .ok_or_else(|| anyhow!("unknown {kind}: {}", id(&item)))?;
+    Ok(std::mem::replace(existing, item))
+}
+
+/// Named set of variables, e.g. `staging` with `{ host = "staging.example.com" }`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Environment {
+    pub id: Uuid,
+    pub name: String,
+    pub variables: BTreeMap<String, String>,
+    /// Variables whose values are persisted encrypted, like secret [`Header`]s.
+    #[serde(default)]
+    pub secrets: BTreeSet<String>,
+}
+
+impl Default for Environment {
+    fn default() -> Self {
+        Self {
+            id: Uuid::new_v4(),
+            name: "New Environment".to_string(),
+            variables: BTreeMap::new(),
+            secrets: BTreeSet::new(),
         }
     }
 }
@@ -119,8 +1084,49 @@ pub struct Space {
     pub selected_endpoint_id: Option<Uuid>,
     pub selected_header_ids: Vec<Uuid>,
     pub selected_body_id: Option<Uuid>,
-    pub history: Vec<ResponseData>,
     pub is_request_pending: bool,
+    #[serde(default)]
+    pub overrides: SpaceOverrides,
+    /// One-off headers of this space only, sent after the selected library ones.
+    /// An enabled one replaces a library header with the same key.
+    #[serde(default)]
+    pub headers: Vec<KeyValue>,
+    /// Markdown notes on what the space is for.
+    #[serde(default)]
+    pub notes: String,
+    /// How its responses are shown, kept between sessions.
+    #[serde(default)]
+    pub response_view: ResponseView,
+    /// Put away: kept with its history, but hidden from the sidebar and not
+    /// used as the default space.
+    #[serde(default)]
+    pub archived: bool,
+}
+
+/// Response body display: as received, formatted, or a rich preview (JSON
+/// tree, image, hex dump) where the content type has one.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ResponseView {
+    Raw,
+    Pretty,
+    #[default]
+    Preview,
+}
+
+/// Changes a space makes to its selected endpoint, headers and body, leaving the
+/// shared library items as they are.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SpaceOverrides {
+    pub method: Option<String>,
+    pub url: Option<String>,
+    /// Replaces the URL's host and port, e.g. `localhost:8080`; applied after `url`.
+    pub host: Option<String>,
+    /// Values for selected headers, by header id.
+    pub headers: BTreeMap<Uuid, String>,
+    /// Content for the selected body.
+    pub body: Option<String>,
 }
 
 impl Default for Space {
@@ -131,8 +1137,12 @@ impl Default for Space {
             selected_endpoint_id: None,
             selected_header_ids: Vec::new(),
             selected_body_id: None,
-            history: Vec::new(),
             is_request_pending: false,
+            overrides: SpaceOverrides::default(),
+            headers: Vec::new(),
+            notes: String::new(),
+            response_view: ResponseView::default(),
+            archived: false,
         }
     }
 }
@@ -0,0 +1,37 @@
+use std::path::PathBuf;
+
+/// Resolve (and create) the pigeon config directory.
+///
+/// Prefers XDG (`~/.config/pigeon`), falling back to the platform config dir.
+pub fn config_dir() -> Result<PathBuf, String> {
+    if let Some(home) = dirs::home_dir() {
+        let xdg_config = home.join(".config").join("pigeon");
+        if xdg_config.exists() || home.join(".config").exists() {
+            return match std::fs::create_dir_all(&xdg_config) {
+                Ok(_) => Ok(xdg_config),
+                Err(e) => Err(format!("Failed to create config directory: {}", e)),
+            };
+        }
+    }
+
+    dirs::config_dir()
+        .ok_or_else(|| "Failed to get config directory".to_string())
+        .and_then(|mut dir| {
+            dir.push("pigeon");
+            std::fs::create_dir_all(&dir)
+                .map_err(|e| format!("Failed to create config directory: {}", e))?;
+            Ok(dir)
+        })
+}
+
+/// Resolve (and create) the pigeon data directory used for persisted state.
+pub fn data_dir() -> Result<PathBuf, String> {
+    dirs::data_dir()
+        .ok_or_else(|| "Failed to get data directory".to_string())
+        .and_then(|mut dir| {
+            dir.push("pigeon");
+            std::fs::create_dir_all(&dir)
+                .map_err(|e| format!("Failed to create data directory: {}", e))?;
+            Ok(dir)
+        })
+}
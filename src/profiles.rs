@@ -0,0 +1,51 @@
+use std::path::{Path, PathBuf};
+
+/// Directory under the config dir holding `<name>.lua` profile overlays.
+pub const PROFILES_DIR: &str = "profiles";
+/// Environment variable naming the profiles to use when the host doesn't pass any.
+pub const PROFILE_ENV: &str = "PIGEON_PROFILE";
+
+/// Split a comma-separated list such as `"work, ci"` into profile names.
+pub fn parse(list: &str) -> Vec<String> {
+    list.split(',')
+        .map(str::trim)
+        .filter(|name| !name.is_empty())
+        .map(str::to_string)
+        .collect()
+}
+
+/// Profiles named by `explicit`, or by `PIGEON_PROFILE` when the host passes none.
+pub fn select(explicit: Option<&str>) -> Vec<String> {
+    match explicit {
+        Some(list) => parse(list),
+        None => std::env::var(PROFILE_ENV)
+            .map(|list| parse(&list))
+            .unwrap_or_default(),
+    }
+}
+
+/// The overlay file for profile `name`, which must exist.
+pub fn path(config_dir: &Path, name: &str) -> Result<PathBuf, String> {
+    if name.contains(['/', '\\']) || name.starts_with('.') {
+        return Err(format!("invalid profile name: {name}"));
+    }
+    let path = config_dir.join(PROFILES_DIR).join(format!("{name}.lua"));
+    if !path.is_file() {
+        return Err(format!("profile `{name}` not found: {}", path.display()));
+    }
+    Ok(path)
+}
+
+/// Names of the profiles in `config_dir`, sorted. A missing dir means none.
+pub fn available(config_dir: &Path) -> Vec<String> {
+    let Ok(entries) = std::fs::read_dir(config_dir.join(PROFILES_DIR)) else {
+        return Vec::new();
+    };
+    let mut names: Vec<String> = entries
+        .filter_map(|entry| entry.ok().map(|e| e.path()))
+        .filter(|path| path.extension().and_then(|e| e.to_str()) == Some("lua"))
+        .filter_map(|path| Some(path.file_stem()?.to_str()?.to_string()))
+        .collect();
+    names.sort();
+    names
+}
@@ -0,0 +1,214 @@
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::BTreeMap;
+use std::sync::Arc;
+
+use crate::lua::expect::TestResult;
+use crate::lua::LuaRuntime;
+use crate::{execute_request, template, FfiRequest, FfiResponse};
+
+/// A request set executed in order, with variables flowing between steps.
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RunPlan {
+    #[serde(default)]
+    pub name: String,
+    #[serde(default)]
+    pub requests: Vec<RunStep>,
+    /// Initial values for `{{name}}` placeholders.
+    #[serde(default)]
+    pub variables: BTreeMap<String, String>,
+    #[serde(default)]
+    pub stop_on_error: bool,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RunStep {
+    #[serde(default)]
+    pub name: String,
+    #[serde(flatten)]
+    pub request: FfiRequest,
+    /// Variables to capture from the JSON response body, as `name -> dotted path`
+    /// (e.g. `"token": "data.auth.token"`, `"firstId": "items.0.id"`).
+    #[serde(default)]
+    pub extract: BTreeMap<String, String>,
+    /// Lua run after the response arrives, with `request`, `response` and a
+    /// mutable `vars` table in scope. Requires a loaded Lua runtime.
+    #[serde(default)]
+    pub script: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StepResult<'a> {
+    pub index: usize,
+    pub name: &'a str,
+    /// The request as sent, after placeholder substitution.
+    pub request: &'a FfiRequest,
+    pub response: &'a FfiResponse,
+    pub passed: bool,
+    /// Extraction or script failures; the step counts as failed when set.
+    pub error: Option<String>,
+    /// `pigeon.test` results from the step script; any failure fails the step.
+    pub tests: &'a [TestResult],
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RunSummary {
+    pub name: String,
+    pub total: usize,
+    pub passed: usize,
+    pub failed: usize,
+    pub skipped: usize,
+    pub tests_passed: usize,
+    pub tests_failed: usize,
+    pub duration_ms: u64,
+    pub variables: BTreeMap<String, String>,
+}
+
+/// Execute `plan` sequentially, reporting every step to `on_result` as it finishes.
+pub async fn run(
+    plan: RunPlan,
+    lua: Option<Arc<LuaRuntime>>,
+    mut on_result: impl FnMut(&StepResult),
+) -> RunSummary {
+    let start = std::time::Instant::now();
+    let total = plan.requests.len();
+    let mut variables = plan.variables;
+    let (mut passed, mut failed) = (0, 0);
+    let (mut tests_passed, mut tests_failed) = (0, 0);
+
+    for (index, step) in plan.requests.into_iter().enumerate() {
+        let request = substitute(step.request, &variables);
+        let response = execute_request(request.clone()).await;
+
+        let mut error = extract(&step.extract, &response, &mut variables).err();
+        let mut tests = Vec::new();
+        if error.is_none() {
+            if let Some(script) = &step.script {
+                match run_script(lua.as_deref(), script, &request, &response, &mut variables).await
+                {
+                    Ok(results) => tests = results,
+                    Err(e) => error = Some(e),
+                }
+            }
+        }
+
+        let tests_ok = tests.iter().filter(|t| t.passed).count();
+        tests_passed += tests_ok;
+        tests_failed += tests.len() - tests_ok;
+
+        let ok = error.is_none()
+            && tests_ok == tests.len()
+            && response.status != 0
+            && response.status < 400;
+        if ok {
+            passed += 1;
+        } else {
+            failed += 1;
+        }
+
+        on_result(&StepResult {
+            index,
+            name: &step.name,
+            request: &request,
+            response: &response,
+            passed: ok,
+            error,
+            tests: &tests,
+        });
+
+        if !ok && plan.stop_on_error {
+            break;
+        }
+    }
+
+    RunSummary {
+        name: plan.name,
+        total,
+        passed,
+        failed,
+        skipped: total - passed - failed,
+        tests_passed,
+        tests_failed,
+        duration_ms: start.elapsed().as_millis() as u64,
+        variables,
+    }
+}
+
+fn substitute(mut request: FfiRequest, variables: &BTreeMap<String, String>) -> FfiRequest {
+    let lookup = |name: &str| variables.get(name).cloned();
+
+    request.url = template::render(&request.url, lookup);
+    for header in &mut request.headers {
+        header.value = template::render(&header.value, lookup);
+    }
+    if let Some(body) = &mut request.body {
+        body.content = template::render(&body.content, lookup);
+    }
+    if let Some(auth) = &mut request.auth {
+        for value in auth.params.values_mut() {
+            *value = template::render(value, lookup);
+        }
+    }
+    request
+}
+
+fn extract(
+    rules: &BTreeMap<String, String>,
+    response: &FfiResponse,
+    variables: &mut BTreeMap<String, String>,
+) -> Result<(), String> {
+    if rules.is_empty() {
+        return Ok(());
+    }
+
+    let json: Value = serde_json::from_str(&response.body)
+        .map_err(|e| format!("cannot extract variables, body is not JSON: {e}"))?;
+
+    for (name, path) in rules {
+        let value = lookup_path(&json, path)
+            .ok_or_else(|| format!("extract `{name}`: no value at `{path}`"))?;
+        let value = match value {
+            Value::String(s) => s.clone(),
+            other => other.to_string(),
+        };
+        variables.insert(name.clone(), value);
+    }
+
+    Ok(())
+}
+
+/// Resolve a dotted path (`a.b.0.c`) in a JSON value; numeric segments index arrays.
+pub fn lookup_path<'a>(value: &'a Value, path: &str) -> Option<&'a Value> {
+    path.split('.')
+        .filter(|s| !s.is_empty())
+        .try_fold(value, |current, segment| match current {
+            Value::Array(items) => segment.parse::<usize>().ok().and_then(|i| items.get(i)),
+            Value::Object(map) => map.get(segment),
+            _ => None,
+        })
+}
+
+async fn run_script(
+    lua: Option<&LuaRuntime>,
+    script: &str,
+    request: &FfiRequest,
+    response: &FfiResponse,
+    variables: &mut BTreeMap<String, String>,
+) -> Result<Vec<TestResult>, String> {
+    let lua = lua.ok_or("step has a script but the Lua runtime is not initialized")?;
+
+    let request = serde_json::to_value(request).map_err(|e| e.to_string())?;
+    let mut response = serde_json::to_value(response).map_err(|e| e.to_string())?;
+    if let Some(Value::String(body)) = response.get("body") {
+        // Offer the parsed body for convenience; `nil` when it isn't JSON.
+        response["json"] = serde_json::from_str(body).unwrap_or(Value::Null);
+    }
+
+    lua.run_step_script(script, &request, &response, variables)
+        .await
+        .map_err(|e| format!("{e:#}"))
+}
@@ -0,0 +1,57 @@
+use anyhow::{anyhow, Result};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::io::{BufReader, BufWriter};
+use std::path::Path;
+
+/// Where a host left off, saved when it closes and restored on the next launch.
+///
+/// Hosts keep what applies to them: a terminal has no window to place, and a
+/// host without library tabs leaves `library_tab` unset.
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct Session {
+    pub window: Option<Window>,
+    /// Id of the space that was current.
+    pub space: Option<String>,
+    /// Open tabs in order, each as the host saved it (e.g. its request draft).
+    pub tabs: Vec<Value>,
+    /// Index into `tabs` of the active one.
+    pub active_tab: usize,
+    pub library_tab: Option<String>,
+    /// Name of the theme in use.
+    pub theme: Option<String>,
+}
+
+/// A host window's size and position, in screen pixels.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Window {
+    pub width: u32,
+    pub height: u32,
+    #[serde(default)]
+    pub x: Option<i32>,
+    #[serde(default)]
+    pub y: Option<i32>,
+    #[serde(default)]
+    pub maximized: bool,
+}
+
+impl Session {
+    /// Load the session saved by [`Session::save`]; `None` when there is none.
+    pub fn load(path: &Path) -> Result<Option<Self>> {
+        if !path.exists() {
+            return Ok(None);
+        }
+        let file = std::fs::File::open(path)?;
+        serde_json::from_reader(BufReader::new(file))
+            .map(Some)
+            .map_err(|e| anyhow!("Failed to load session from {}: {e}", path.display()))
+    }
+
+    pub fn save(&self, path: &Path) -> Result<()> {
+        let writer = BufWriter::new(std::fs::File::create(path)?);
+        serde_json::to_writer_pretty(writer, self)
+            .map_err(|e| anyhow!("Failed to save session to {}: {e}", path.display()))
+    }
+}
@@ -0,0 +1,108 @@
+use anyhow::{anyhow, bail, Result};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::BTreeMap;
+use std::io::{BufReader, BufWriter};
+use std::path::Path;
+use std::time::Duration;
+
+use crate::lua::config;
+
+/// Settings a host's settings view can change, in the order it lists them.
+pub const EDITABLE: &[&str] = &[
+    "http.timeout",
+    "http.proxy",
+    "tls.insecure",
+    "tls.ca_file",
+    "theme.mode",
+    "history.max_entries",
+    "history.max_age_days",
+    "ui.locale",
+];
+
+/// Values changed in a host's settings view, by dotted path (`"http.timeout"`).
+///
+/// They take precedence over `config.lua`, which still supplies the defaults
+/// and every setting not overridden here.
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct Overrides(BTreeMap<String, Value>);
+
+/// One [`EDITABLE`] setting as the settings view shows it.
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Setting {
+    pub path: &'static str,
+    /// `"number"`, `"string"`, `"boolean"` or `"choice"`.
+    #[serde(rename = "type")]
+    pub kind: &'static str,
+    #[serde(skip_serializing_if = "<[_]>::is_empty")]
+    pub choices: &'static [&'static str],
+    /// What the core uses: the override if there is one, else `config`.
+    pub value: Value,
+    /// The value from `config.lua`; `null` when it doesn't set one.
+    pub config: Value,
+    pub overridden: bool,
+}
+
+impl Overrides {
+    /// Load overrides saved by [`Overrides::save`]; a missing file yields none.
+    pub fn load(path: &Path) -> Result<Self> {
+        if !path.exists() {
+            return Ok(Self::default());
+        }
+        let file = std::fs::File::open(path)?;
+        serde_json::from_reader(BufReader::new(file))
+            .map_err(|e| anyhow!("Failed to load settings from {}: {e}", path.display()))
+    }
+
+    pub fn save(&self, path: &Path) -> Result<()> {
+        let writer = BufWriter::new(std::fs::File::create(path)?);
+        serde_json::to_writer_pretty(writer, &self.0)
+            .map_err(|e| anyhow!("Failed to save settings to {}: {e}", path.display()))
+    }
+
+    pub fn get(&self, path: &str) -> Option<&Value> {
+        self.0.get(path)
+    }
+
+    /// Override `path` with `value`, or go back to `config.lua` when it is null.
+    pub fn set(&mut self, path: &str, value: Value) -> Result<()> {
+        if !EDITABLE.contains(&path) {
+            bail!("`{path}` can't be changed from the settings view");
+        }
+        if value.is_null() {
+            self.0.remove(path);
+            return Ok(());
+        }
+        config::check_json(path, &value)?;
+        if path == "http.timeout" {
+            let secs = value.as_f64().unwrap_or_default();
+            if secs <= 0.0 || Duration::try_from_secs_f64(secs).is_err() {
+                bail!("`http.timeout` must be a positive number of seconds, got {value}");
+            }
+        }
+        self.0.insert(path.to_string(), value);
+        Ok(())
+    }
+
+    /// Every [`EDITABLE`] setting, with `config` looking a path up in `config.lua`.
+    pub fn describe(&self, config: impl Fn(&str) -> Value) -> Vec<Setting> {
+        EDITABLE
+            .iter()
+            .filter_map(|&path| {
+                let (kind, choices) = config::setting_type(path)?;
+                let config = config(path);
+                let overridden = self.get(path);
+                Some(Setting {
+                    path,
+                    kind,
+                    choices,
+                    value: overridden.cloned().unwrap_or_else(|| config.clone()),
+                    config,
+                    overridden: overridden.is_some(),
+                })
+            })
+            .collect()
+    }
+}
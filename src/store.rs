@@ -0,0 +1,204 @@
+use crate::model::Workspace;
+use anyhow::{bail, Context, Result};
+use serde_json::{Map, Value};
+use std::collections::BTreeSet;
+use std::path::{Path, PathBuf};
+
+/// Workspace lists written one file per entry, each to a directory of its own name.
+const COLLECTIONS: &[&str] = &[
+    "endpoints",
+    "headers",
+    "bodies",
+    "spaces",
+    "environments",
+    "folders",
+    "requests",
+];
+
+/// Top-level file of a directory layout: entry order and any other workspace fields.
+const INDEX_FILE: &str = "workspace.json";
+
+/// Where the workspace is persisted.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Storage {
+    /// One JSON file, as written by [`Workspace::save`].
+    File(PathBuf),
+    /// A tree with one pretty-printed file per endpoint, space, etc., so the
+    /// workspace can be kept in Git and changes show up as small diffs:
+    ///
+    /// ```text
+    /// workspace.json               order of the entries below
+    /// endpoints/get-users-1a2b3c4d.json
+    /// spaces/test-space-5e6f7a8b.json
+    /// ...
+    /// ```
+    Directory(PathBuf),
+}
+
+impl Storage {
+    pub fn path(&self) -> &Path {
+        match self {
+            Storage::File(path) | Storage::Directory(path) => path,
+        }
+    }
+
+    /// Whether anything has been saved here yet.
+    pub fn exists(&self) -> bool {
+        match self {
+            Storage::File(path) => path.exists(),
+            Storage::Directory(dir) => dir.join(INDEX_FILE).exists(),
+        }
+    }
+
+    /// Load the workspace; nothing saved yet yields the default one.
+    pub fn load(&self) -> Result<Workspace> {
+        match self {
+            Storage::File(path) => Workspace::load(path),
+            Storage::Directory(dir) => load_dir(dir),
+        }
+    }
+
+    pub fn save(&self, workspace: &Workspace) -> Result<()> {
+        match self {
+            Storage::File(path) => workspace.save(path),
+            Storage::Directory(dir) => save_dir(dir, workspace),
+        }
+    }
+}
+
+fn load_dir(dir: &Path) -> Result<Workspace> {
+    let index_path = dir.join(INDEX_FILE);
+    if !index_path.exists() {
+        return Ok(Workspace::default());
+    }
+    let Value::Object(mut index) = read_json(&index_path)? else {
+        bail!(
+            "Invalid workspace: {} is not an object",
+            index_path.display()
+        );
+    };
+    let order = match index.remove("order") {
+        Some(Value::Object(order)) => order,
+        _ => Map::new(),
+    };
+
+    for &name in COLLECTIONS {
+        let listed: Vec<String> = order
+            .get(name)
+            .and_then(Value::as_array)
+            .map(|files| {
+                files
+                    .iter()
+                    .filter_map(|f| f.as_str().map(str::to_string))
+                    .collect()
+            })
+            .unwrap_or_default();
+        let mut present = entry_files(&dir.join(name))?;
+        // Listed files first, in order; files added by hand or a merge after them.
+        let mut files: Vec<String> = listed
+            .into_iter()
+            .filter(|file| present.remove(file))
+            .collect();
+        files.extend(present);
+
+        let entries = files
+            .iter()
+            .map(|file| read_json(&dir.join(name).join(file)))
+            .collect::<Result<Vec<_>>>()?;
+        index.insert(name.to_string(), Value::Array(entries));
+    }
+
+    Workspace::from_value(Value::Object(index))
+        .with_context(|| format!("Invalid workspace: {}", dir.display()))
+}
+
+fn save_dir(dir: &Path, workspace: &Workspace) -> Result<()> {
+    let Value::Object(mut fields) = serde_json::to_value(workspace)? else {
+        bail!("workspace did not serialize to an object");
+    };
+
+    let mut order = Map::new();
+    for &name in COLLECTIONS {
+        let entries = match fields.remove(name) {
+            Some(Value::Array(entries)) => entries,
+            _ => Vec::new(),
+        };
+        let sub = dir.join(name);
+        std::fs::create_dir_all(&sub)
+            .with_context(|| format!("Failed to create {}", sub.display()))?;
+
+        let mut stale = entry_files(&sub)?;
+        let mut files = Vec::with_capacity(entries.len());
+        for entry in entries {
+            let file = entry_file_name(&entry);
+            write_if_changed(&sub.join(&file), &entry)?;
+            stale.remove(&file);
+            files.push(Value::String(file));
+        }
+        for file in stale {
+            let path = sub.join(file);
+            std::fs::remove_file(&path)
+                .with_context(|| format!("Failed to remove {}", path.display()))?;
+        }
+        order.insert(name.to_string(), Value::Array(files));
+    }
+
+    fields.insert("order".to_string(), Value::Object(order));
+    write_if_changed(&dir.join(INDEX_FILE), &Value::Object(fields))
+}
+
+/// `get-users-1a2b3c4d.json`: readable, and unique through the id prefix.
+fn entry_file_name(entry: &Value) -> String {
+    let name = entry.get("name").and_then(Value::as_str).unwrap_or("");
+    let id = entry.get("id").and_then(Value::as_str).unwrap_or("");
+    let mut slug = String::new();
+    for c in name.chars() {
+        if c.is_ascii_alphanumeric() {
+            slug.push(c.to_ascii_lowercase());
+        } else if !slug.is_empty() && !slug.ends_with('-') {
+            slug.push('-');
+        }
+    }
+    let slug = slug.trim_end_matches('-');
+    let id: String = id.chars().filter(|c| *c != '-').take(8).collect();
+    match (slug.is_empty(), id.is_empty()) {
+        (true, _) => format!("{id}.json"),
+        (false, true) => format!("{slug}.json"),
+        (false, false) => format!("{slug}-{id}.json"),
+    }
+}
+
+/// The `*.json` files in `dir`, none if it doesn't exist.
+fn entry_files(dir: &Path) -> Result<BTreeSet<String>> {
+    let entries = match std::fs::read_dir(dir) {
+        Ok(entries) => entries,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(BTreeSet::new()),
+        Err(e) => return Err(e).with_context(|| format!("Failed to read {}", dir.display())),
+    };
+    let mut files = BTreeSet::new();
+    for entry in entries {
+        let name = entry?.file_name().to_string_lossy().into_owned();
+        if name.ends_with(".json") {
+            files.insert(name);
+        }
+    }
+    Ok(files)
+}
+
+fn read_json(path: &Path) -> Result<Value> {
+    let data = std::fs::read_to_string(path)
+        .with_context(|| format!("Failed to read workspace: {}", path.display()))?;
+    serde_json::from_str(&data).with_context(|| format!("Invalid workspace: {}", path.display()))
+}
+
+/// Write `value` pretty-printed, leaving files that already hold it untouched so
+/// unchanged entries keep their timestamps.
+fn write_if_changed(path: &Path, value: &Value) -> Result<()> {
+    let mut data = serde_json::to_string_pretty(value)?;
+    data.push('\n');
+    if std::fs::read_to_string(path).is_ok_and(|existing| existing == data) {
+        return Ok(());
+    }
+    std::fs::write(path, data)
+        .with_context(|| format!("Failed to write workspace: {}", path.display()))
+}
@@ -0,0 +1,22 @@
+/// Replace `{{name}}` placeholders using `lookup`. Whitespace inside the braces is
+/// ignored; unresolved placeholders are left untouched.
+pub fn render(input: &str, lookup: impl Fn(&str) -> Option<String>) -> String {
+    let mut out = String::with_capacity(input.len());
+    let mut rest = input;
+
+    while let Some(start) = rest.find("{{") {
+        let Some(len) = rest[start + 2..].find("}}") else {
+            break;
+        };
+        let name = rest[start + 2..start + 2 + len].trim();
+        out.push_str(&rest[..start]);
+        match lookup(name) {
+            Some(value) => out.push_str(&value),
+            None => out.push_str(&rest[start..start + 2 + len + 2]),
+        }
+        rest = &rest[start + 2 + len + 2..];
+    }
+
+    out.push_str(rest);
+    out
+}
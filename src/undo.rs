@@ -0,0 +1,45 @@
+use crate::model::Workspace;
+
+/// Most edits that can be undone; older ones are dropped.
+const LIMIT: usize = 100;
+
+/// Undo and redo stacks of workspace snapshots, each labelled with the edit that
+/// replaced it, e.g. "Edit endpoint `users`".
+pub struct UndoStack {
+    undo: Vec<(String, Workspace)>,
+    redo: Vec<(String, Workspace)>,
+}
+
+impl UndoStack {
+    pub const fn new() -> Self {
+        Self {
+            undo: Vec::new(),
+            redo: Vec::new(),
+        }
+    }
+
+    /// Record that edit `label` replaced `before`. A new edit can't be redone past.
+    pub fn push(&mut self, label: String, before: Workspace) {
+        if self.undo.len() == LIMIT {
+            self.undo.remove(0);
+        }
+        self.undo.push((label, before));
+        self.redo.clear();
+    }
+
+    /// Put back the workspace from before the last edit and return the edit's label.
+    pub fn undo(&mut self, current: &mut Workspace) -> Option<String> {
+        let (label, before) = self.undo.pop()?;
+        let after = std::mem::replace(current, before);
+        self.redo.push((label.clone(), after));
+        Some(label)
+    }
+
+    /// Reapply the last undone edit and return its label.
+    pub fn redo(&mut self, current: &mut Workspace) -> Option<String> {
+        let (label, after) = self.redo.pop()?;
+        let before = std::mem::replace(current, after);
+        self.undo.push((label.clone(), before));
+        Some(label)
+    }
+}
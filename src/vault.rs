@@ -0,0 +1,142 @@
+use anyhow::{anyhow, bail, Result};
+use base64::engine::general_purpose::STANDARD;
+use base64::Engine;
+use ring::aead::{Aad, LessSafeKey, Nonce, UnboundKey, AES_256_GCM, NONCE_LEN};
+use ring::pbkdf2;
+use ring::rand::{SecureRandom, SystemRandom};
+use std::collections::HashMap;
+use std::num::NonZeroU32;
+
+/// Prefix of values written by [`Vault::seal`], followed by base64 of
+/// `salt || nonce || ciphertext+tag`.
+pub const SEALED_PREFIX: &str = "enc:v1:";
+
+const SALT_LEN: usize = 16;
+const PBKDF2_ITERATIONS: u32 = 200_000;
+
+/// Encrypts secret values with AES-256-GCM under a key derived from a master
+/// passphrase (PBKDF2-HMAC-SHA256), so they can be persisted and shared.
+pub struct Vault {
+    passphrase: String,
+    /// Salt for values sealed in this session; reused from existing values when
+    /// possible so the passphrase is only stretched once.
+    salt: [u8; SALT_LEN],
+    keys: HashMap<[u8; SALT_LEN], LessSafeKey>,
+    /// Sealed form of each plaintext seen, so unchanged values keep their
+    /// ciphertext and saves don't rewrite them.
+    sealed: HashMap<String, String>,
+    rng: SystemRandom,
+}
+
+impl Vault {
+    /// A vault for `passphrase`, checked against `sealed` values (e.g. those of
+    /// the loaded workspace): an error if it opens none of them.
+    pub fn unlock<'a>(passphrase: &str, sealed: impl IntoIterator<Item = &'a str>) -> Result<Self> {
+        if passphrase.is_empty() {
+            bail!("the passphrase must not be empty");
+        }
+        let rng = SystemRandom::new();
+        let mut salt = [0; SALT_LEN];
+        rng.fill(&mut salt)
+            .map_err(|_| anyhow!("failed to generate a salt"))?;
+        let mut vault = Self {
+            passphrase: passphrase.to_string(),
+            salt,
+            keys: HashMap::new(),
+            sealed: HashMap::new(),
+            rng,
+        };
+
+        let mut sealed = sealed.into_iter().filter(|v| is_sealed(v)).peekable();
+        if let Some(first) = sealed.peek() {
+            vault.open(first).map_err(|_| anyhow!("wrong passphrase"))?;
+            vault.salt = decode(first)?.0;
+        }
+        Ok(vault)
+    }
+
+    /// `value` encrypted, as `enc:v1:...`. Sealed values are returned as they are.
+    pub fn seal(&mut self, value: &str) -> Result<String> {
+        if is_sealed(value) {
+            return Ok(value.to_string());
+        }
+        if let Some(sealed) = self.sealed.get(value) {
+            return Ok(sealed.clone());
+        }
+
+        let mut nonce = [0; NONCE_LEN];
+        self.rng
+            .fill(&mut nonce)
+            .map_err(|_| anyhow!("failed to generate a nonce"))?;
+        let salt = self.salt;
+        let mut in_out = value.as_bytes().to_vec();
+        self.key(salt)
+            .seal_in_place_append_tag(
+                Nonce::assume_unique_for_key(nonce),
+                Aad::empty(),
+                &mut in_out,
+            )
+            .map_err(|_| anyhow!("failed to encrypt a secret"))?;
+
+        let mut data = salt.to_vec();
+        data.extend_from_slice(&nonce);
+        data.extend_from_slice(&in_out);
+        let sealed = format!("{SEALED_PREFIX}{}", STANDARD.encode(data));
+        self.sealed.insert(value.to_string(), sealed.clone());
+        Ok(sealed)
+    }
+
+    /// The plaintext of a value written by [`Vault::seal`].
+    pub fn open(&mut self, sealed: &str) -> Result<String> {
+        let (salt, nonce, mut in_out) = decode(sealed)?;
+        let plain = self
+            .key(salt)
+            .open_in_place(
+                Nonce::assume_unique_for_key(nonce),
+                Aad::empty(),
+                &mut in_out,
+            )
+            .map_err(|_| anyhow!("cannot decrypt secret: wrong passphrase or damaged value"))?;
+        let plain = String::from_utf8(plain.to_vec())?;
+        self.sealed.insert(plain.clone(), sealed.to_string());
+        Ok(plain)
+    }
+
+    fn key(&mut self, salt: [u8; SALT_LEN]) -> &LessSafeKey {
+        let passphrase = &self.passphrase;
+        self.keys.entry(salt).or_insert_with(|| {
+            let mut key = [0; 32];
+            pbkdf2::derive(
+                pbkdf2::PBKDF2_HMAC_SHA256,
+                NonZeroU32::new(PBKDF2_ITERATIONS).unwrap(),
+                &salt,
+                passphrase.as_bytes(),
+                &mut key,
+            );
+            LessSafeKey::new(UnboundKey::new(&AES_256_GCM, &key).unwrap())
+        })
+    }
+}
+
+pub fn is_sealed(value: &str) -> bool {
+    value.starts_with(SEALED_PREFIX)
+}
+
+fn decode(sealed: &str) -> Result<([u8; SALT_LEN], [u8; NONCE_LEN], Vec<u8>)> {
+    let encoded = sealed
+        .strip_prefix(SEALED_PREFIX)
+        .ok_or_else(|| anyhow!("not an encrypted value"))?;
+    let data = STANDARD
+        .decode(encoded)
+        .map_err(|_| anyhow!("damaged encrypted value"))?;
+    if data.len() < SALT_LEN + NONCE_LEN {
+        bail!("damaged encrypted value");
+    }
+    let (salt, rest) = data.split_at(SALT_LEN);
+    let (nonce, ciphertext) = rest.split_at(NONCE_LEN);
+    Ok((
+        salt.try_into().unwrap(),
+        nonce.try_into().unwrap(),
+        ciphertext.to_vec(),
+    ))
+}
@@ -0,0 +1,79 @@
+use anyhow::{Context, Result};
+use notify::{Event, EventKind, RecommendedWatcher, RecursiveMode, Watcher};
+use std::path::{Path, PathBuf};
+use std::sync::mpsc::{self, RecvTimeoutError};
+use std::sync::Mutex;
+use std::time::Duration;
+
+use crate::lua::package::MODULES_DIR;
+use crate::lua::plugin::PLUGINS_DIR;
+use crate::profiles::PROFILES_DIR;
+
+/// Quiet period after the last change before reloading, so an editor's
+/// write/rename/chmod sequence triggers a single reload.
+const DEBOUNCE: Duration = Duration::from_millis(300);
+
+static WATCHER: Mutex<Option<RecommendedWatcher>> = Mutex::new(None);
+
+/// Watch `config.lua` and everything under `lua/`, `plugins/` and `profiles/` in
+/// `config_dir`, calling `on_change` with the last changed path once edits settle.
+///
+/// Replaces the previous watcher, if any.
+pub fn start(config_dir: &Path, on_change: impl Fn(&Path) + Send + 'static) -> Result<()> {
+    // Some platforms report canonical paths (e.g. `/private/var` on macOS).
+    let root = config_dir
+        .canonicalize()
+        .unwrap_or_else(|_| config_dir.to_path_buf());
+    let (tx, rx) = mpsc::channel::<PathBuf>();
+
+    let watched_root = root.clone();
+    let mut watcher = notify::recommended_watcher(move |event: notify::Result<Event>| {
+        let Ok(event) = event else { return };
+        if matches!(event.kind, EventKind::Access(_)) {
+            return;
+        }
+        for path in event.paths {
+            if is_watched(&watched_root, &path) {
+                let _ = tx.send(path);
+            }
+        }
+    })
+    .context("Failed to create config watcher")?;
+    watcher
+        .watch(&root, RecursiveMode::Recursive)
+        .with_context(|| format!("Failed to watch {}", root.display()))?;
+
+    // Ends once the watcher, and with it the sender, is dropped.
+    std::thread::spawn(move || {
+        while let Ok(mut path) = rx.recv() {
+            loop {
+                match rx.recv_timeout(DEBOUNCE) {
+                    Ok(next) => path = next,
+                    Err(RecvTimeoutError::Timeout) => break,
+                    Err(RecvTimeoutError::Disconnected) => return,
+                }
+            }
+            on_change(&path);
+        }
+    });
+
+    *WATCHER.lock().unwrap() = Some(watcher);
+    Ok(())
+}
+
+/// Stop watching. Returns `false` if no watcher was running.
+pub fn stop() -> bool {
+    WATCHER.lock().unwrap().take().is_some()
+}
+
+fn is_watched(root: &Path, path: &Path) -> bool {
+    match path.strip_prefix(root) {
+        Ok(relative) => {
+            relative == Path::new("config.lua")
+                || relative.starts_with(MODULES_DIR)
+                || relative.starts_with(PLUGINS_DIR)
+                || relative.starts_with(PROFILES_DIR)
+        }
+        Err(_) => false,
+    }
+}
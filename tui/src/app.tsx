@@ -1,17 +1,87 @@
-import { useEffect, useMemo, useState } from "react";
+import { useCallback, useEffect, useMemo, useRef, useState } from "react";
+import { readFileSync, writeFileSync } from "node:fs";
 import { Box, Text, useApp, useInput, useStdin } from "ink";
-import { Spinner, StatusMessage, TextInput } from "@inkjs/ui";
+import { StatusMessage, TextInput } from "@inkjs/ui";
 import { useMachine } from "@xstate/react";
 import type { FfiRequest, HttpMethod, RequestHeader } from "@/types";
-import { sendRequestViaRust } from "@/ffi/client";
-import { terminateRustWorker } from "@/ffi/client";
+import { fetchGraphqlSchema, sendRequestViaRust } from "@/ffi/client";
+import { clearConsole } from "@/ffi/console";
+import {
+  createSpace,
+  exportWorkspace,
+  displayValue,
+  getWorkspace,
+  importWorkspace,
+  isFirstRun,
+  searchWorkspace,
+  secretsStatus,
+  spaceRequest,
+  stepWorkspace,
+  unlockSecrets,
+  withSecretValues,
+  moveWorkspaceItem,
+  updateWorkspaceItem,
+  type ResponseView,
+} from "@/ffi/workspace";
+import { listCommands, runCommand } from "@/ffi/commands";
+import { formatResponse } from "@/ffi/formatter";
+import { shutdownCore, terminateRustWorker } from "@/ffi/client";
+import {
+  saveSession,
+  type Session,
+  type SessionTab,
+} from "@/ffi/session";
 import { HSplit, VSplit } from "@/ui/SplitPane";
 import { TabBar } from "@/ui/TabBar";
 import { KeyHints } from "@/ui/KeyHints";
+import { EndpointTree } from "@/ui/EndpointTree";
 import { TextArea } from "@/ui/TextArea";
+import { JsonTree } from "@/ui/JsonTree";
+import { FindBar, findMatches } from "@/ui/FindBar";
+import { ImagePreview } from "@/ui/ImagePreview";
+import { HexView } from "@/ui/HexView";
+import { HeadersTable } from "@/ui/HeadersTable";
+import { languageFor } from "@/highlight";
+import { formatXml } from "@/xml";
+import { formatJson, jsonProblem, matchingBracket } from "@/json";
+import {
+  graphqlCompletions,
+  graphqlProblem,
+  type GraphqlSchema,
+} from "@/graphql";
+import { NotesPane, type NotesTarget } from "@/ui/NotesPane";
 import { DebugPanel } from "@/ui/DebugPanel";
+import { LuaConsole } from "@/ui/LuaConsole";
+import { CommandPalette } from "@/ui/CommandPalette";
+import { LibrarySearch } from "@/ui/LibrarySearch";
+import { formatTime, HistoryPanel } from "@/ui/HistoryPanel";
+import { AllResponses } from "@/ui/AllResponses";
+import { filterSpaces, SpacesPanel } from "@/ui/SpacesPanel";
+import { ThemePicker } from "@/ui/ThemePicker";
+import { Onboarding } from "@/ui/Onboarding";
+import { SchemaExplorer } from "@/ui/SchemaExplorer";
+import { CookieManager } from "@/ui/CookieManager";
+import { SettingsView } from "@/ui/SettingsView";
+import { Toasts, useToasts } from "@/ui/Toasts";
+import { SendProgress } from "@/ui/SendProgress";
+import { ResponseSparkline } from "@/ui/Sparkline";
+import { ErrorPanel } from "@/ui/ErrorPanel";
+import { ResponseDiff } from "@/ui/ResponseDiff";
+import { RequestPreview } from "@/ui/RequestPreview";
+import { VariableChips } from "@/ui/VariableChips";
+import { StatusBar, type LastResult } from "@/ui/StatusBar";
+import type { HistoryEntry } from "@/ffi/history";
+import { previewRequest } from "@/ffi/collection";
+import { cookiesForUrl } from "@/ffi/cookies";
+import { PassphrasePrompt } from "@/ui/PassphrasePrompt";
+import { newOpenTab, OpenTabBar, type OpenTab } from "@/ui/OpenTabs";
 import { MethodDropdown, MethodDropdownMenu } from "@/ui/MethodDropdown";
-import { theme } from "@/ui/theme";
+import {
+  currentThemeName,
+  cycleTheme,
+  statusColor,
+  theme,
+} from "@/ui/theme";
 import {
   focusMachine,
   type FocusTarget,
@@ -20,6 +90,18 @@ import {
 } from "@/machines/focusMachine";
 import { requestMachine, type RequestState } from "@/machines/requestMachine";
 import { useXStateDebug } from "@/debug/xstateDebug";
+import {
+  applyConfigLocale,
+  applyConfigThemes,
+  getConfigGeneration,
+  getKeymapOverrides,
+} from "@/ffi/confi";
+import {
+  findAction,
+  formatChord,
+  resolveKeymap,
+  type Keymap,
+} from "@/keymap";
 
 const methodOptions: Array<{ label: string; value: HttpMethod }> = [
   { label: "GET", value: "GET" },
@@ -31,7 +113,22 @@ const methodOptions: Array<{ label: string; value: HttpMethod }> = [
   { label: "OPTIONS", value: "OPTIONS" },
 ];
 
+// Built-in palette entries, listed after the plugins' `pigeon.command`s.
+const EXPORT_WORKSPACE_COMMAND = "Export workspace…";
+const IMPORT_WORKSPACE_COMMAND = "Import workspace…";
+const UNLOCK_SECRETS_COMMAND = "Unlock secrets…";
+const LOAD_SCHEMA_COMMAND = "Load GraphQL schema";
+const SCHEMA_EXPLORER_COMMAND = "GraphQL schema…";
+const COOKIES_COMMAND = "Cookies…";
+const SETTINGS_COMMAND = "Settings…";
+const ALL_RESPONSES_COMMAND = "All responses…";
+// Where those read and write, relative to the working directory.
+const WORKSPACE_EXPORT_FILE = "pigeon-workspace.json";
+// Spaces listed in the sidebar; the Spaces panel shows them all.
+const SIDEBAR_SPACES = 8;
+
 function KeyboardShortcuts(props: {
+  keymap: Keymap;
   onExit: () => void;
   onSend: () => void;
   onTab: () => void;
@@ -40,72 +137,125 @@ function KeyboardShortcuts(props: {
   onFocusUrl?: () => void;
   onToggleDebug?: () => void;
   onClearDebugLogs?: () => void;
+  onToggleConsole?: () => void;
+  onPickTheme?: () => void;
+  onCycleTheme?: () => void;
+  onCommandPalette?: () => void;
+  onToggleFolders?: () => void;
+  onSearchLibrary?: () => void;
+  onOpenHistory?: () => void;
+  onAllResponses?: () => void;
+  onOpenSpaces?: () => void;
+  onNewSpace?: () => void;
+  onFind?: () => void;
+  onCycleResponseView?: () => void;
+  onUndo?: () => void;
+  onRedo?: () => void;
+  onToggleFavorite?: () => void;
+  onMove?: (direction: -1 | 1) => void;
+  onNewTab?: () => void;
+  onCloseTab?: () => void;
+  onCycleTab?: (direction: -1 | 1) => void;
   canHandleGlobalShortcut?: () => boolean;
+  isActive?: boolean;
 }) {
-  useInput((input, key) => {
-    const canHandleGlobal = props.canHandleGlobalShortcut?.() ?? true;
-
-    // Avoid quitting while the user is typing (e.g. URL contains 'q').
-    if (input === "q" && canHandleGlobal) {
-      props.onExit();
-      return;
-    }
-
-    if (key.ctrl && input === "c") {
-      props.onExit();
-      return;
-    }
-
-    // Many terminals encode Ctrl+J as LF (\n) instead of reporting ctrl+j.
-    // We treat LF as the "send-request" binding (Posting default).
-    // Important: Enter usually comes as `\r` (and key.return === true).
-    // Ctrl+J usually comes as `\n` (and key.return === false).
-    if (input === "\n" && !key.return) {
-      props.onSend();
-      return;
-    }
-
-    // Some terminals do report ctrl+j explicitly.
-    if (key.ctrl && input === "j") {
-      props.onSend();
-      return;
-    }
-
-    // Best-effort alt+enter (terminals differ; Ink exposes meta, not alt).
-    if (key.return && key.meta) {
-      props.onSend();
-      return;
-    }
-
-    if (key.tab && key.shift) {
-      props.onTabPrev?.();
-      return;
-    }
-
-    if (key.tab) {
-      props.onTab();
-      return;
-    }
-
-    // Posting-like quick focus
-    if (key.ctrl && input === "t") {
-      props.onFocusMethod?.();
-    }
-
-    if (key.ctrl && input === "l") {
-      props.onFocusUrl?.();
-    }
-
-    // Toggle debug panel (only when not actively typing)
-    if (input === "d" && !key.ctrl && canHandleGlobal) {
-      props.onToggleDebug?.();
-    }
-
-    // Clear debug logs
-    if (key.ctrl && input === "i") {
-      props.onClearDebugLogs?.();
-    }
-  });
+  useInput(
+    (input, key) => {
+      // Avoid quitting while the user is typing (e.g. URL contains 'q').
+      const canHandleGlobal = props.canHandleGlobalShortcut?.() ?? true;
+
+      switch (findAction(props.keymap, input, key, canHandleGlobal)) {
+        case "quit":
+          props.onExit();
+          break;
+        case "send_request":
+          props.onSend();
+          break;
+        case "focus_next":
+          props.onTab();
+          break;
+        case "focus_prev":
+          props.onTabPrev?.();
+          break;
+        case "focus_method":
+          props.onFocusMethod?.();
+          break;
+        case "focus_url":
+          props.onFocusUrl?.();
+          break;
+        case "toggle_debug":
+          props.onToggleDebug?.();
+          break;
+        case "clear_debug_logs":
+          props.onClearDebugLogs?.();
+          break;
+        case "toggle_console":
+          props.onToggleConsole?.();
+          break;
+        case "pick_theme":
+          props.onPickTheme?.();
+          break;
+        case "cycle_theme":
+          props.onCycleTheme?.();
+          break;
+        case "command_palette":
+          props.onCommandPalette?.();
+          break;
+        case "toggle_folders":
+          props.onToggleFolders?.();
+          break;
+        case "search_library":
+          props.onSearchLibrary?.();
+          break;
+        case "open_history":
+          props.onOpenHistory?.();
+          break;
+        case "all_responses":
+          props.onAllResponses?.();
+          break;
+        case "open_spaces":
+          props.onOpenSpaces?.();
+          break;
+        case "new_space":
+          props.onNewSpace?.();
+          break;
+        case "find_in_response":
+          props.onFind?.();
+          break;
+        case "cycle_response_view":
+          props.onCycleResponseView?.();
+          break;
+        case "undo":
+          props.onUndo?.();
+          break;
+        case "redo":
+          props.onRedo?.();
+          break;
+        case "toggle_favorite":
+          props.onToggleFavorite?.();
+          break;
+        case "move_up":
+          props.onMove?.(-1);
+          break;
+        case "move_down":
+          props.onMove?.(1);
+          break;
+        case "new_tab":
+          props.onNewTab?.();
+          break;
+        case "close_tab":
+          props.onCloseTab?.();
+          break;
+        case "next_tab":
+          props.onCycleTab?.(1);
+          break;
+        case "prev_tab":
+          props.onCycleTab?.(-1);
+          break;
+      }
+    },
+    { isActive: props.isActive ?? true }
+  );
 
   return null;
 }
@@ -114,23 +264,43 @@ function formatHeaderPreview(headers: RequestHeader[]): string {
   if (headers.length === 0) return "(none)";
   return headers
     .filter((h) => h.enabled !== false)
-    .map((h) => `${h.key}: ${h.value}`)
+    .map((h) => `${h.key}: ${displayValue(h.value, h.secret)}`)
     .join(" | ");
 }
 
+function asHttpMethod(method: string): HttpMethod {
+  const upper = method.toUpperCase();
+  return methodOptions.find((o) => o.value === upper)?.value ?? "GET";
+}
+
 function normalizeHeaders(headers: RequestHeader[]): RequestHeader[] {
   return headers
     .map((h) => ({
       key: h.key.trim(),
       value: h.value.trim(),
       enabled: h.enabled ?? true,
+      secret: h.secret,
     }))
     .filter((h) => h.key.length > 0);
 }
 
+// Parsing runs while rendering, so larger bodies are shown as they came.
+const MAX_PARSED_BODY = 300_000;
+
+/** The parsed body if it's a JSON object or array (not too large), else undefined. */
+function parseJsonBody(text: string): unknown {
+  if (text.length > MAX_PARSED_BODY) return undefined;
+  const trimmed = text.trim();
+  if (!(trimmed.startsWith("{") || trimmed.startsWith("["))) return undefined;
+  try {
+    return JSON.parse(trimmed);
+  } catch {
+    return undefined;
+  }
+}
+
 function maybePrettifyJson(text: string): string {
-  // Avoid huge parse costs; keep it conservative for now.
-  if (text.length > 300_000) return text;
+  if (text.length > MAX_PARSED_BODY) return text;
   const trimmed = text.trim();
   if (!(trimmed.startsWith("{") || trimmed.startsWith("["))) return text;
   try {
@@ -141,10 +311,86 @@ function maybePrettifyJson(text: string): string {
   }
 }
 
-export function App() {
+const RESPONSE_VIEWS: ResponseView[] = ["raw", "pretty", "preview"];
+const RESPONSE_VIEW_LABELS: Record<ResponseView, string> = {
+  raw: "Raw",
+  pretty: "Pretty",
+  preview: "Preview",
+};
+
+/** A GraphQL query body, edited as GraphQL and sent as a JSON envelope. */
+function isGraphql(contentType: string): boolean {
+  const mime = contentType.split(";")[0]!.trim().toLowerCase();
+  return mime === "application/graphql";
+}
+
+/** XML by Content-Type, or by its declaration when the type is unknown. */
+function isXml(text: string, contentType: string): boolean {
+  const language = languageFor(contentType);
+  if (language) return language === "xml";
+  return text.trimStart().startsWith("<?xml");
+}
+
+function maybePrettifyXml(text: string): string {
+  if (text.length > MAX_PARSED_BODY) return text;
+  return formatXml(text) ?? text;
+}
+
+/**
+ * The tabs open when the last run quit, else one with the request of the
+ * space that was current then, else a blank one.
+ */
+function restoredTabs(session: Session | null): OpenTab[] {
+  const space =
+    session?.space && session.tabs.length === 0
+      ? spaceRequest(session.space)
+      : null;
+  const drafts: SessionTab[] =
+    session && session.tabs.length > 0
+      ? session.tabs
+      : space && "request" in space
+      ? [
+          {
+            ...space.request,
+            contentType: space.request.body?.contentType || "application/json",
+            body: space.request.body?.content ?? "",
+          },
+        ]
+      : [];
+  if (drafts.length === 0) {
+    return [newOpenTab(1, "GET", "https://httpbin.org/get")];
+  }
+  return drafts.map((draft, i) => ({
+    ...newOpenTab(i + 1, asHttpMethod(draft.method), draft.url),
+    // Another host may have saved less.
+    headers: withSecretValues(draft.headers ?? [], session?.space),
+    contentType: draft.contentType ?? "application/json",
+    body: draft.body ?? "",
+  }));
+}
+
+export function App(props: { configError?: string; session: Session | null }) {
   const { exit } = useApp();
+  const [configGeneration, setConfigGeneration] = useState(getConfigGeneration);
+  const keymap = useMemo(
+    () => resolveKeymap(getKeymapOverrides()),
+    [configGeneration]
+  );
+  // Bumped after undo/redo so the workspace is re-read.
+  const [workspaceRevision, setWorkspaceRevision] = useState(0);
+  // Config may add endpoints with `pigeon.workspace`, so re-read it on reload.
+  const workspace = useMemo(
+    () => getWorkspace(),
+    [configGeneration, workspaceRevision]
+  );
+  const secrets = useMemo(
+    () => secretsStatus(),
+    [configGeneration, workspaceRevision]
+  );
   const quit = () => {
+    saveCurrentSession();
     terminateRustWorker("quit");
+    shutdownCore();
     exit();
   };
   const { isRawModeSupported } = useStdin();
@@ -153,6 +399,61 @@ export function App() {
   const { startTransition, logTransition, logError, clearLogs } =
     useXStateDebug();
   const [debugPanelVisible, setDebugPanelVisible] = useState(false);
+  const [consoleVisible, setConsoleVisible] = useState(false);
+  // Remounts the console after clearing so it drops what's on screen.
+  const [consoleNonce, setConsoleNonce] = useState(0);
+  const [paletteCommands, setPaletteCommands] = useState<string[] | null>(
+    null
+  );
+  const { toasts, notify, clear: clearToasts } = useToasts();
+  // Overrides folders' saved `collapsed` state in the sidebar.
+  const [foldersExpanded, setFoldersExpanded] = useState(false);
+  const [librarySearchOpen, setLibrarySearchOpen] = useState(false);
+  const [historyOpen, setHistoryOpen] = useState(false);
+  const [allResponsesOpen, setAllResponsesOpen] = useState(false);
+  const [spacesOpen, setSpacesOpen] = useState(false);
+  // Narrows the Spaces panel and the sidebar's space list by name.
+  const [spaceFilter, setSpaceFilter] = useState("");
+  const [themePickerOpen, setThemePickerOpen] = useState(false);
+  // First launch: the setup wizard replaces the sample workspace.
+  const [onboarding, setOnboarding] = useState(isFirstRun);
+  // Find bar over the response body; null when closed.
+  const [findQuery, setFindQuery] = useState<string | null>(null);
+  const [findIndex, setFindIndex] = useState(0);
+  // The response headers filter box is taking typing.
+  const [headersFiltering, setHeadersFiltering] = useState(false);
+  // Encrypted values awaiting the passphrase while the prompt is open.
+  const [unlockPrompt, setUnlockPrompt] = useState<number | null>(null);
+  // Narrows the sidebar to endpoints matching this search query.
+  const [libraryFilter, setLibraryFilter] = useState("");
+  const sidebarWorkspace = useMemo(
+    () =>
+      libraryFilter
+        ? {
+            ...workspace,
+            endpoints: searchWorkspace(libraryFilter).endpoints,
+            requests: [],
+          }
+        : workspace,
+    [workspace, libraryFilter]
+  );
+  // The latest send in any tab, for the status bar.
+  const [lastResult, setLastResult] = useState<LastResult | null>(null);
+  // Theme tokens are updated in place; keep the name in state to re-render.
+  const [themeName, setThemeName] = useState(currentThemeName);
+
+  // The core reloads config.lua when it changes on disk; pick up the new keymap and themes.
+  useEffect(() => {
+    const timer = setInterval(() => {
+      const generation = getConfigGeneration();
+      if (generation === configGeneration) return;
+      applyConfigThemes();
+      applyConfigLocale();
+      setThemeName(currentThemeName());
+      setConfigGeneration(generation);
+    }, 1000);
+    return () => clearInterval(timer);
+  }, [configGeneration]);
 
   // XState machines
   const [focusState, focusSend] = useMachine(focusMachine);
@@ -196,7 +497,25 @@ export function App() {
   const focus = focusState.value as FocusTarget;
   const { currentField, requestTab, responseTab } = focusState.context;
 
-  const { response, error, isLoading } = requestState.context;
+  // Open requests, each with its own response and pending state. The active
+  // one's draft lives in the editor states below and is stored back on switch.
+  const [openTabs, setOpenTabs] = useState<OpenTab[]>(() =>
+    restoredTabs(props.session)
+  );
+  const [activeTabId, setActiveTabId] = useState(() =>
+    Math.min((props.session?.activeTab ?? 0) + 1, openTabs.length)
+  );
+  const nextTabId = useRef(openTabs.length + 1);
+  const activeTabIdRef = useRef(activeTabId);
+  activeTabIdRef.current = activeTabId;
+  const activeTab =
+    openTabs.find((t) => t.id === activeTabId) ?? openTabs[0]!;
+  const { response, error, isLoading } = activeTab;
+
+  const updateTab = (id: number, changes: Partial<OpenTab>) =>
+    setOpenTabs((tabs) =>
+      tabs.map((t) => (t.id === id ? { ...t, ...changes } : t))
+    );
 
   // Helper to determine if current field is a topbar field
   const isTopbarField = (field: string): field is TopbarField =>
@@ -214,35 +533,208 @@ export function App() {
     ? currentField
     : "headerKey";
 
+  // It stays with the body; moving elsewhere hides it until the next find.
+  const findOpen =
+    findQuery !== null && focus === "responseTabs" && responseTab === "body";
+
   const canHandleGlobalShortcut = () => {
     // If you're currently editing/typing in a field, `q` should be treated as input.
+    if (findOpen) return false;
+    if (focus === "responseTabs" && headersFiltering) return false;
     if (focus === "topbar") return false;
     if (
       focus === "requestPane" &&
-      (requestTab === "headers" || requestTab === "body")
+      (requestTab === "headers" ||
+        requestTab === "body" ||
+        requestTab === "info")
     ) {
       return false;
     }
     return true;
   };
 
-  const [method, setMethod] = useState<HttpMethod>("GET");
-  const [url, setUrl] = useState<string>("https://httpbin.org/get");
+  const [method, setMethod] = useState<HttpMethod>(activeTab.method);
+  const [url, setUrl] = useState<string>(activeTab.url);
   const [methodDropdownOpen, setMethodDropdownOpen] = useState(false);
   const [methodHighlightedIndex, setMethodHighlightedIndex] = useState(0);
 
   const [headerKey, setHeaderKey] = useState<string>("");
   const [headerValue, setHeaderValue] = useState<string>("");
-  const [headers, setHeaders] = useState<RequestHeader[]>([]);
+  const [headers, setHeaders] = useState<RequestHeader[]>(activeTab.headers);
   const [headerInputNonce, setHeaderInputNonce] = useState(0);
 
-  const [contentType, setContentType] = useState<string>("application/json");
-  const [body, setBody] = useState<string>("");
+  const [contentType, setContentType] = useState<string>(
+    activeTab.contentType
+  );
+  const [body, setBody] = useState<string>(activeTab.body);
+  // JSON bodies get validation, formatting and bracket matching.
+  const bodyLanguage = languageFor(contentType);
+  // A GraphQL body is the query itself, sent wrapped in a JSON envelope.
+  const bodyIsGraphql = isGraphql(contentType);
+  const [graphqlSchema, setGraphqlSchema] = useState<GraphqlSchema | null>(
+    null
+  );
+  const [schemaOpen, setSchemaOpen] = useState(false);
+  const [cookiesOpen, setCookiesOpen] = useState(false);
+  const [settingsOpen, setSettingsOpen] = useState(false);
+  // Bumped when the cookie manager edits the jar.
+  const [cookieRevision, setCookieRevision] = useState(0);
+  const checkGraphql = useCallback(
+    (query: string) => graphqlProblem(query, graphqlSchema),
+    [graphqlSchema]
+  );
+  const completeGraphql = useCallback(
+    (query: string, index: number) =>
+      graphqlSchema ? graphqlCompletions(query, index, graphqlSchema) : null,
+    [graphqlSchema]
+  );
 
+  // Plugin formatters (`pigeon.formatter`) take precedence over the default.
+  const formattedResponse = useMemo(
+    () => (response ? formatResponse(response) : null),
+    [response, configGeneration]
+  );
+  const responseContentType = useMemo(
+    () =>
+      response?.headers.find(
+        ([name]) => name.toLowerCase() === "content-type"
+      )?.[1] ?? "",
+    [response]
+  );
   const responseBodyForView = useMemo(() => {
     if (!response) return "";
-    return maybePrettifyJson(response.body);
-  }, [response]);
+    if (formattedResponse !== null) return formattedResponse;
+    return isXml(response.body, responseContentType)
+      ? maybePrettifyXml(response.body)
+      : maybePrettifyJson(response.body);
+  }, [response, formattedResponse, responseContentType]);
+  // Highlighting follows the Content-Type; formatter output is shown plain.
+  const responseLanguage = useMemo(
+    () =>
+      response && formattedResponse === null
+        ? languageFor(responseContentType) ??
+          (isXml(response.body, responseContentType) ? "xml" : null)
+        : null,
+    [response, formattedResponse, responseContentType]
+  );
+  // Binary bodies are previewed (images) or dumped (anything else) instead
+  // of shown as text.
+  const responseImage =
+    response?.bodyBase64 !== undefined &&
+    responseContentType.toLowerCase().startsWith("image/");
+  const responseBinary = response?.bodyBase64 !== undefined && !responseImage;
+  // The body as received, text bodies as UTF-8; only built when shown.
+  const responseBytes = useMemo(() => {
+    if (!response) return null;
+    if (response.bodyBase64 !== undefined) {
+      return new Uint8Array(Buffer.from(response.bodyBase64, "base64"));
+    }
+    return responseTab === "hex"
+      ? new Uint8Array(Buffer.from(response.body, "utf8"))
+      : null;
+  }, [response, responseTab]);
+  // JSON bodies get the tree view unless a formatter took over.
+  const responseJson = useMemo(
+    () =>
+      response && formattedResponse === null
+        ? parseJsonBody(response.body)
+        : undefined,
+    [response, formattedResponse]
+  );
+
+  // Archived spaces stay in the Spaces panel only.
+  const activeSpaces = useMemo(
+    () => workspace.spaces.filter((s) => !s.archived),
+    [workspace]
+  );
+  const sidebarSpaces = useMemo(
+    () => filterSpaces(activeSpaces, spaceFilter),
+    [activeSpaces, spaceFilter]
+  );
+  // The space whose settings apply: the first one selecting the request
+  // bar's endpoint, else the first space (where history files responses).
+  const currentSpace = useMemo(() => {
+    const endpoint = workspace.endpoints.find(
+      (e) => e.method === method && e.url === url
+    );
+    return (
+      (endpoint &&
+        activeSpaces.find((s) => s.selected_endpoint_id === endpoint.id)) ??
+      activeSpaces[0]
+    );
+  }, [workspace, activeSpaces, method, url]);
+  // Used while there are no spaces to remember the choice in.
+  const [localResponseView, setLocalResponseView] =
+    useState<ResponseView>("preview");
+  const responseView = currentSpace?.response_view ?? localResponseView;
+
+  // New spaces start out selecting the request bar's endpoint.
+  const addSpace = () => {
+    const endpoint = workspace.endpoints.find(
+      (e) => e.method === method && e.url === url
+    );
+    const created = createSpace(
+      endpoint ? { selected_endpoint_id: endpoint.id } : {}
+    );
+    if ("error" in created) {
+      notify({ variant: "error", text: created.error });
+      return;
+    }
+    const space = created.workspace.spaces.find((s) => s.id === created.id);
+    notify({
+      variant: "success",
+      text: `Added ${space?.name ?? "a space"}`,
+    });
+    setWorkspaceRevision((n) => n + 1);
+  };
+
+  const cycleResponseView = () => {
+    const next =
+      RESPONSE_VIEWS[
+        (RESPONSE_VIEWS.indexOf(responseView) + 1) % RESPONSE_VIEWS.length
+      ]!;
+    if (!currentSpace) {
+      setLocalResponseView(next);
+      return;
+    }
+    const updated = updateWorkspaceItem("space", currentSpace.id, {
+      response_view: next,
+    });
+    if ("error" in updated) {
+      notify({ variant: "error", text: updated.error });
+      return;
+    }
+    setWorkspaceRevision((n) => n + 1);
+  };
+
+  // Raw is the body as received: no formatting, highlighting or preview.
+  const responseText =
+    responseView === "raw" ? response?.body ?? "" : responseBodyForView;
+  const showPreview = responseView === "preview" && !findOpen;
+
+  const findResults = useMemo(
+    () =>
+      findOpen && response ? findMatches(responseText, findQuery ?? "") : [],
+    [findOpen, response, responseText, findQuery]
+  );
+  const currentFind = Math.min(findIndex, Math.max(0, findResults.length - 1));
+
+  const openFind = () => {
+    if (!response) {
+      notify({ variant: "error", text: "No response to search" });
+      return;
+    }
+    focusSend({ type: "FOCUS_RESPONSE" });
+    focusSend({ type: "SET_RESPONSE_TAB", tab: "body" });
+    setFindQuery((q) => q ?? "");
+  };
+
+  const stepFind = (direction: -1 | 1) => {
+    if (findResults.length === 0) return;
+    setFindIndex(
+      (currentFind + direction + findResults.length) % findResults.length
+    );
+  };
 
   // Keep requestField consistent with the active request tab to avoid
   // multiple inputs being active at once.
@@ -265,17 +757,55 @@ export function App() {
       headers: normalizeHeaders(headers),
       body:
         body.trim().length > 0
-          ? {
-              contentType,
-              content: body,
-            }
+          ? isGraphql(contentType)
+            ? {
+                contentType: "application/json",
+                content: JSON.stringify({ query: body }),
+              }
+            : {
+                contentType,
+                content: body,
+              }
           : undefined,
     }),
     [method, url, headers, contentType, body]
   );
 
-  async function send() {
-    if (isLoading) return;
+  // What the jar would attach to the request; responses can change it too.
+  const resolvedUrl = useMemo(() => {
+    const preview = previewRequest(request);
+    return "error" in preview ? null : preview.request.url;
+  }, [request]);
+  const attachedCookies = useMemo(
+    () => (resolvedUrl ? cookiesForUrl(resolvedUrl) : []),
+    // eslint-disable-next-line react-hooks/exhaustive-deps
+    [resolvedUrl, response, cookieRevision]
+  );
+
+  // Sends the active tab's draft, or `target.request` into tab `target.tabId`.
+  async function send(target?: { tabId: number; request: FfiRequest }) {
+    if (!target && isLoading) return;
+    // The response belongs to this tab even if another is active by the time it arrives.
+    const tabId = target?.tabId ?? activeTabId;
+    const outgoing = target?.request ?? request;
+    // Sent anyway, in case the server expects it, but flagged first.
+    const bodyProblem =
+      outgoing.body && languageFor(outgoing.body.contentType) === "json"
+        ? jsonProblem(outgoing.body.content)
+        : null;
+    if (bodyProblem) {
+      notify({
+        variant: "info",
+        text: `Body isn't valid JSON (Ln ${bodyProblem.line}, Col ${bodyProblem.col}): ${bodyProblem.message}`,
+      });
+    }
+    updateTab(tabId, {
+      isLoading: true,
+      sentAt: Date.now(),
+      response: undefined,
+      error: undefined,
+      historyEntry: undefined,
+    });
 
     const transitionKey = startTransition(
       "request",
@@ -285,13 +815,16 @@ export function App() {
 
     try {
       // Prepare the request
-      requestSend({ type: "PREPARE_REQUEST", request });
+      requestSend({ type: "PREPARE_REQUEST", request: outgoing });
 
       // Send the request
       requestSend({ type: "SEND_REQUEST" });
 
-      const res = await sendRequestViaRust(request);
+      const res = await sendRequestViaRust(outgoing);
       requestSend({ type: "REQUEST_SUCCESS", response: res });
+      updateTab(tabId, { response: res, isLoading: false, responseScroll: 0 });
+      setLastResult(res);
+      if (activeTabIdRef.current !== tabId) return;
       focusSend({ type: "SET_RESPONSE_TAB", tab: "body" });
 
       // Navigate to response tabs based on current state
@@ -313,6 +846,9 @@ export function App() {
     } catch (e) {
       const errorMessage = e instanceof Error ? e.message : String(e);
       requestSend({ type: "REQUEST_ERROR", error: errorMessage });
+      updateTab(tabId, { error: errorMessage, isLoading: false });
+      setLastResult({ error: errorMessage });
+      notify({ variant: "error", text: `Request failed: ${errorMessage}` });
 
       // Enhanced error logging with full context
       logError(
@@ -320,7 +856,7 @@ export function App() {
         e as Error,
         { type: "SEND_REQUEST" }, // event that caused the error
         {
-          request,
+          request: outgoing,
           currentState: requestState.value,
           focusState: focusState.value,
         }, // context
@@ -329,6 +865,122 @@ export function App() {
     }
   }
 
+  // Stores the active draft and loads tab `id`'s.
+  const switchTab = (id: number) => {
+    const target = openTabs.find((t) => t.id === id);
+    if (!target || id === activeTabId) return;
+    updateTab(activeTabId, { method, url, headers, contentType, body });
+    setMethod(target.method);
+    setUrl(target.url);
+    setHeaders(target.headers);
+    setContentType(target.contentType);
+    setBody(target.body);
+    setActiveTabId(id);
+  };
+
+  const cycleTab = (direction: -1 | 1) => {
+    const index = openTabs.findIndex((t) => t.id === activeTabId);
+    const next =
+      openTabs[(index + direction + openTabs.length) % openTabs.length];
+    if (next) switchTab(next.id);
+  };
+
+  // Stores the active draft and adds `tab` after the others, active.
+  const addTab = (tab: OpenTab) => {
+    updateTab(activeTabId, { method, url, headers, contentType, body });
+    setOpenTabs((tabs) => [...tabs, tab]);
+    setMethod(tab.method);
+    setUrl(tab.url);
+    setHeaders(tab.headers);
+    setContentType(tab.contentType);
+    setBody(tab.body);
+    setActiveTabId(tab.id);
+  };
+
+  const openNewTab = () => {
+    addTab(newOpenTab(nextTabId.current++));
+    focusSend({ type: "FOCUS_URL" });
+  };
+
+  // Opens a history entry's request in a new tab, with its response marked
+  // as an older one, or sends the request again there.
+  const openHistoryEntry = (entry: HistoryEntry, resend: boolean) => {
+    const space = entry.spaceId ?? entry.request.space;
+    const sent = {
+      ...entry.request,
+      headers: withSecretValues(entry.request.headers, space),
+    };
+    const tab: OpenTab = {
+      ...newOpenTab(nextTabId.current++, asHttpMethod(sent.method), sent.url),
+      headers: sent.headers,
+      contentType: sent.body?.contentType ?? "application/json",
+      body: sent.body?.content ?? "",
+    };
+    if (!resend) {
+      tab.response = entry.response;
+      tab.historyEntry = { id: entry.id, timestamp: entry.timestamp };
+    }
+    addTab(tab);
+    setHistoryOpen(false);
+    setAllResponsesOpen(false);
+    focusSend({ type: "FOCUS_RESPONSE" });
+    focusSend({ type: "SET_RESPONSE_TAB", tab: "body" });
+    if (resend) {
+      void send({
+        tabId: tab.id,
+        request: { ...sent, space },
+      });
+    }
+  };
+
+  // From All responses: names the space the response came from, which is
+  // current again when it still selects the request's endpoint.
+  const openResponseInSpace = (entry: HistoryEntry) => {
+    openHistoryEntry(entry, false);
+    const space = workspace.spaces.find((s) => s.id === entry.spaceId);
+    const name = space?.name ?? entry.space;
+    notify({
+      variant: "info",
+      text: name ? `Opened a response from ${name}` : "Opened the response",
+    });
+  };
+
+  // Saved on quit so the next launch reopens these tabs, space and theme;
+  // the window size and library tab of windowed hosts are kept as they were.
+  const saveCurrentSession = () => {
+    const tabs = openTabs.map((t) =>
+      t.id === activeTabId
+        ? { ...t, method, url, headers, contentType, body }
+        : t
+    );
+    saveSession({
+      ...props.session,
+      space: currentSpace?.id ?? null,
+      tabs: tabs.map((t) => ({
+        method: t.method,
+        url: t.url,
+        // Secret values stay masked on disk; restoring refills them.
+        headers: t.headers.map((h) =>
+          h.secret ? { ...h, value: displayValue(h.value, true) } : h
+        ),
+        contentType: t.contentType,
+        body: t.body,
+      })),
+      activeTab: Math.max(0, tabs.findIndex((t) => t.id === activeTabId)),
+      theme: themeName,
+    });
+  };
+
+  // Closes the active tab; the last one stays open.
+  const closeTab = () => {
+    if (openTabs.length < 2) return;
+    const index = openTabs.findIndex((t) => t.id === activeTabId);
+    const neighbour = openTabs[index + 1] ?? openTabs[index - 1];
+    if (!neighbour) return;
+    switchTab(neighbour.id);
+    setOpenTabs((tabs) => tabs.filter((t) => t.id !== activeTabId));
+  };
+
   const tabNext = () => {
     try {
       focusSend({ type: "TAB_NEXT" });
@@ -357,6 +1009,238 @@ export function App() {
     }
   };
 
+  const runPaletteCommand = (name: string) => {
+    setPaletteCommands(null);
+    if (name === EXPORT_WORKSPACE_COMMAND) {
+      exportWorkspaceToFile();
+      return;
+    }
+    if (name === IMPORT_WORKSPACE_COMMAND) {
+      importWorkspaceFromFile();
+      return;
+    }
+    if (name === UNLOCK_SECRETS_COMMAND) {
+      setUnlockPrompt(secretsStatus().sealed);
+      return;
+    }
+    if (name === LOAD_SCHEMA_COMMAND) {
+      loadGraphqlSchema();
+      return;
+    }
+    if (name === SETTINGS_COMMAND) {
+      setSettingsOpen(true);
+      return;
+    }
+    if (name === ALL_RESPONSES_COMMAND) {
+      setAllResponsesOpen(true);
+      return;
+    }
+    if (name === COOKIES_COMMAND) {
+      setCookiesOpen(true);
+      return;
+    }
+    if (name === SCHEMA_EXPLORER_COMMAND) {
+      if (graphqlSchema) setSchemaOpen(true);
+      else {
+        notify({
+          variant: "error",
+          text: `Run "${LOAD_SCHEMA_COMMAND}" first`,
+        });
+      }
+      return;
+    }
+    const { result, error } = runCommand(name, {
+      request,
+      response: response ?? undefined,
+    });
+    if (error) {
+      // Full traceback goes to the Lua console; show just the message here.
+      notify({ variant: "error", text: error.split("\n")[0] });
+    } else if (typeof result === "string") {
+      notify({ variant: "success", text: result });
+    } else if (result === null || result === undefined) {
+      notify({ variant: "success", text: `Ran "${name}"` });
+    } else {
+      notify({ variant: "success", text: JSON.stringify(result) });
+    }
+  };
+
+  const exportWorkspaceToFile = () => {
+    const exported = exportWorkspace();
+    if ("error" in exported) {
+      notify({ variant: "error", text: exported.error });
+      return;
+    }
+    try {
+      writeFileSync(WORKSPACE_EXPORT_FILE, exported.data);
+      notify({
+        variant: "success",
+        text: `Exported workspace to ${WORKSPACE_EXPORT_FILE}`,
+      });
+    } catch (e) {
+      notify({ variant: "error", text: String(e) });
+    }
+  };
+
+  const importWorkspaceFromFile = () => {
+    let data: string;
+    try {
+      data = readFileSync(WORKSPACE_EXPORT_FILE, "utf8");
+    } catch (e) {
+      notify({ variant: "error", text: String(e) });
+      return;
+    }
+    const imported = importWorkspace(data);
+    if ("error" in imported) {
+      notify({ variant: "error", text: imported.error });
+      return;
+    }
+    const count = imported.workspace.endpoints.length;
+    notify({
+      variant: "success",
+      text: `Imported ${count} endpoint${count === 1 ? "" : "s"}`,
+    });
+    setWorkspaceRevision((n) => n + 1);
+  };
+
+  // Introspects the request bar's URL, sending the request's headers and auth.
+  const loadGraphqlSchema = () => {
+    notify({ variant: "info", text: `Loading schema from ${url}…` });
+    fetchGraphqlSchema(request).then(
+      (schema) => {
+        setGraphqlSchema(schema);
+        notify({
+          variant: "success",
+          text: `Loaded GraphQL schema: ${schema.types.length} types`,
+        });
+      },
+      (e) =>
+        notify({
+          variant: "error",
+          text: e instanceof Error ? e.message : String(e),
+        })
+    );
+  };
+
+  const unlockSecretsAndReport = (passphrase: string) => {
+    setUnlockPrompt(null);
+    const { error } = unlockSecrets(passphrase);
+    if (error) {
+      notify({ variant: "error", text: error });
+      return;
+    }
+    notify({ variant: "success", text: "Secrets unlocked" });
+    setWorkspaceRevision((n) => n + 1);
+  };
+
+  // Stars the library endpoint the request bar currently matches.
+  const toggleFavorite = () => {
+    const endpoint = workspace.endpoints.find(
+      (e) => e.method === method && e.url === url
+    );
+    if (!endpoint) {
+      notify({
+        variant: "error",
+        text: "The request isn't a library endpoint",
+      });
+      return;
+    }
+    const updated = updateWorkspaceItem("endpoint", endpoint.id, {
+      favorite: !endpoint.favorite,
+    });
+    if ("error" in updated) {
+      notify({ variant: "error", text: updated.error });
+      return;
+    }
+    notify({
+      variant: "success",
+      text: `${endpoint.favorite ? "Unstarred" : "Starred"} ${endpoint.name}`,
+    });
+    setWorkspaceRevision((n) => n + 1);
+  };
+
+  // The request bar's library endpoint and the spaces that select it.
+  const notesTargets = useMemo((): NotesTarget[] => {
+    const endpoint = workspace.endpoints.find(
+      (e) => e.method === method && e.url === url
+    );
+    if (!endpoint) return [];
+    return [
+      {
+        kind: "endpoint" as const,
+        id: endpoint.id,
+        name: endpoint.name,
+        notes: endpoint.notes ?? "",
+      },
+      ...workspace.spaces
+        .filter((s) => s.selected_endpoint_id === endpoint.id)
+        .map((s) => ({
+          kind: "space" as const,
+          id: s.id,
+          name: s.name,
+          notes: s.notes ?? "",
+        })),
+    ];
+  }, [workspace, method, url]);
+
+  const saveNotes = (target: NotesTarget, notes: string) => {
+    const updated = updateWorkspaceItem(target.kind, target.id, { notes });
+    if ("error" in updated) {
+      notify({ variant: "error", text: updated.error });
+      return;
+    }
+    notify({
+      variant: "success",
+      text: `Saved notes on ${target.name}`,
+    });
+    setWorkspaceRevision((n) => n + 1);
+  };
+
+  // Moves the current endpoint past its neighbour in the sidebar: the next one
+  // in the same folder with the same starred state.
+  const moveEndpoint = (direction: -1 | 1) => {
+    const endpoints = workspace.endpoints;
+    const index = endpoints.findIndex(
+      (e) => e.method === method && e.url === url
+    );
+    const endpoint = endpoints[index];
+    if (!endpoint) {
+      notify({
+        variant: "error",
+        text: "The request isn't a library endpoint",
+      });
+      return;
+    }
+    const isSibling = (i: number) =>
+      (endpoints[i]!.folder_id ?? null) === (endpoint.folder_id ?? null) &&
+      !!endpoints[i]!.favorite === !!endpoint.favorite;
+    let target = index + direction;
+    while (target >= 0 && target < endpoints.length && !isSibling(target)) {
+      target += direction;
+    }
+    if (target < 0 || target >= endpoints.length) return;
+
+    const moved = moveWorkspaceItem("endpoint", endpoint.id, target);
+    if ("error" in moved) {
+      notify({ variant: "error", text: moved.error });
+      return;
+    }
+    setWorkspaceRevision((n) => n + 1);
+  };
+
+  const stepWorkspaceAndReport = (direction: "undo" | "redo") => {
+    const step = stepWorkspace(direction);
+    if ("error" in step) {
+      notify({ variant: "error", text: step.error });
+      return;
+    }
+    notify({
+      variant: "success",
+      text: `${direction === "undo" ? "Undid" : "Redid"} ${step.label}`,
+    });
+    setWorkspaceRevision((n) => n + 1);
+  };
+
   const toggleDebugPanel = () => {
     setDebugPanelVisible(!debugPanelVisible);
   };
@@ -383,12 +1267,15 @@ export function App() {
     { id: "query", label: "Query" },
     { id: "auth", label: "Auth" },
     { id: "info", label: "Info" },
+    { id: "preview", label: "Preview" },
     { id: "options", label: "Options" },
   ] as const;
 
   const responseTabs = [
     { id: "body", label: "Body" },
     { id: "headers", label: "Headers" },
+    { id: "compare", label: "Compare" },
+    { id: "hex", label: "Hex" },
     { id: "trace", label: "Trace" },
   ] as const;
 
@@ -421,19 +1308,21 @@ export function App() {
       <Box flexDirection="column" flexGrow={1}>
         <Text dimColor>URL</Text>
         <TextInput
+          key={`url-${activeTabId}`}
           isDisabled={focus !== "topbar" || topbarField !== "url" || isLoading}
           defaultValue={url}
           onChange={setUrl}
         />
+        <VariableChips request={request} />
       </Box>
 
       <Box flexDirection="column" width={26} alignItems="flex-end">
         <Text dimColor>Send</Text>
         {isLoading ? (
-          <Spinner label="Sending..." />
+          <SendProgress since={activeTab.sentAt ?? Date.now()} />
         ) : response ? (
           <Text>
-            <Text color={response.status < 400 ? "green" : "red"} bold>
+            <Text color={statusColor(response.status)} bold>
               {response.status}
             </Text>{" "}
             <Text dimColor>{response.durationMs}ms</Text>
@@ -441,6 +1330,9 @@ export function App() {
         ) : (
           <Text dimColor>Ready</Text>
         )}
+        {currentSpace ? (
+          <ResponseSparkline space={currentSpace.id} refreshKey={response} />
+        ) : null}
       </Box>
     </Box>
   );
@@ -455,12 +1347,65 @@ export function App() {
     >
       <Box justifyContent="space-between">
         <Text bold>Collection</Text>
-        <Text dimColor>(placeholder)</Text>
+        <Text dimColor>
+          {libraryFilter
+            ? `${sidebarWorkspace.endpoints.length}/${workspace.endpoints.length}`
+            : workspace.endpoints.length}
+        </Text>
+      </Box>
+      {libraryFilter ? (
+        <Text color="cyan" wrap="truncate-end">
+          Filter: {libraryFilter}
+        </Text>
+      ) : null}
+      <EndpointTree
+        workspace={sidebarWorkspace}
+        expandAll={foldersExpanded || libraryFilter !== ""}
+        hideEmpty={libraryFilter !== ""}
+        isCurrent={(endpoint) =>
+          endpoint.method === method && endpoint.url === url
+        }
+        maxRows={20}
+      />
+      <Box justifyContent="space-between" marginTop={1}>
+        <Text bold>All responses</Text>
+        <Text dimColor>{formatChord(keymap.all_responses[0])}</Text>
       </Box>
-      <Text dimColor>- sample-collections</Text>
-      <Text>GET echo</Text>
-      <Text dimColor>GET get random user</Text>
-      <Text dimColor>POS echo post</Text>
+      {activeSpaces.length > 0 ? (
+        <Box flexDirection="column" marginTop={1}>
+          <Box justifyContent="space-between">
+            <Text bold>Spaces</Text>
+            <Text dimColor>
+              {spaceFilter
+                ? `${sidebarSpaces.length}/${activeSpaces.length}`
+                : `${formatChord(keymap.open_spaces[0])} edit`}
+            </Text>
+          </Box>
+          {spaceFilter ? (
+            <Text color="cyan" wrap="truncate-end">
+              Filter: {spaceFilter}
+            </Text>
+          ) : null}
+          {sidebarSpaces.length === 0 ? (
+            <Text dimColor>No spaces match.</Text>
+          ) : null}
+          {sidebarSpaces.slice(0, SIDEBAR_SPACES).map((space) => (
+            <Text
+              key={space.id}
+              dimColor={space.id !== currentSpace?.id}
+              wrap="truncate-end"
+            >
+              {space.id === currentSpace?.id ? "● " : "  "}
+              {space.name}
+            </Text>
+          ))}
+          {sidebarSpaces.length > SIDEBAR_SPACES ? (
+            <Text dimColor>
+              … {sidebarSpaces.length - SIDEBAR_SPACES} more
+            </Text>
+          ) : null}
+        </Box>
+      ) : null}
     </Box>
   );
 
@@ -475,7 +1420,15 @@ export function App() {
     >
       <Box justifyContent="space-between">
         <Text bold>Request</Text>
-        <Text dimColor>{formatHeaderPreview(headers)}</Text>
+        <Text wrap="truncate-end">
+          {attachedCookies.length > 0 ? (
+            <Text color={theme.keyHint}>
+              Cookie: {attachedCookies.map((c) => c.name).join(", ")}
+              {" · "}
+            </Text>
+          ) : null}
+          <Text dimColor>{formatHeaderPreview(headers)}</Text>
+        </Text>
       </Box>
       <TabBar
         tabs={requestTabs as any}
@@ -550,7 +1503,7 @@ export function App() {
                   .slice(0, 8)
                   .map((h, i) => (
                     <Text key={`${h.key}-${i}`}>
-                      {h.key}: {h.value}
+                      {h.key}: {displayValue(h.value, h.secret)}
                     </Text>
                   ))
               )}
@@ -561,6 +1514,7 @@ export function App() {
             <Box flexDirection="column">
               <Text dimColor>Content-Type</Text>
               <TextInput
+                key={`content-type-${activeTabId}`}
                 isDisabled={
                   focus !== "requestPane" ||
                   requestTab !== "body" ||
@@ -575,6 +1529,7 @@ export function App() {
               />
             </Box>
             <TextArea
+              key={`body-${activeTabId}`}
               title="Body"
               value={body}
               isActive={
@@ -584,8 +1539,31 @@ export function App() {
               }
               height={10}
               onChange={setBody}
+              language={bodyLanguage}
+              problem={
+                bodyIsGraphql
+                  ? checkGraphql
+                  : bodyLanguage === "json"
+                  ? jsonProblem
+                  : undefined
+              }
+              complete={bodyIsGraphql ? completeGraphql : undefined}
+              format={bodyLanguage === "json" ? formatJson : undefined}
+              matchBracket={
+                bodyLanguage === "json" || bodyIsGraphql
+                  ? matchingBracket
+                  : undefined
+              }
             />
           </Box>
+        ) : requestTab === "info" ? (
+          <NotesPane
+            targets={notesTargets}
+            isActive={focus === "requestPane" && requestTab === "info"}
+            onSave={saveNotes}
+          />
+        ) : requestTab === "preview" ? (
+          <RequestPreview request={request} />
         ) : (
           <Text dimColor>Not implemented yet.</Text>
         )}
@@ -605,33 +1583,65 @@ export function App() {
       <Box justifyContent="space-between">
         <Text bold>Response</Text>
         {response ? (
-          <Text dimColor>
-            {response.status} · {response.durationMs}ms
+          <Text>
+            <Text color={statusColor(response.status)} bold>
+              {response.status}
+            </Text>
+            <Text dimColor> · {response.durationMs}ms</Text>
           </Text>
         ) : (
           <Text dimColor>—</Text>
         )}
       </Box>
+      {activeTab.historyEntry ? (
+        <Text color="yellow" wrap="truncate-end">
+          Older response from {formatTime(activeTab.historyEntry.timestamp)}{" "}
+          (history #{activeTab.historyEntry.id}) ·{" "}
+          {formatChord(keymap.send_request[0])} re-send
+        </Text>
+      ) : null}
 
       <TabBar
         tabs={responseTabs as any}
         activeTab={responseTab}
-        isActive={focus === "responseTabs"}
+        isActive={
+          focus === "responseTabs" && !findOpen && !headersFiltering
+        }
         onChange={(t) => focusSend({ type: "SET_RESPONSE_TAB", tab: t })}
       />
 
       <Box marginTop={1} flexDirection="column">
         {!response ? (
-          <Text dimColor>No response yet. Press ctrl+j to send.</Text>
+          <Text dimColor>
+            No response yet. Press {keymap.send_request[0] ?? "send"} to send.
+          </Text>
+        ) : response.failure ? (
+          <ErrorPanel kind={response.failure} message={response.body} />
         ) : responseTab === "headers" ? (
-          <TextArea
-            title="Headers"
-            value={response.headers
-              .map(([k, v]: [string, string]) => `${k}: ${v}`)
-              .join("\n")}
+          <HeadersTable
+            key={`response-headers-${activeTabId}`}
+            headers={response.headers}
+            height={10}
             isActive={focus === "responseTabs"}
+            onCopy={(text) => notify({ variant: "success", text })}
+            onFilteringChange={setHeadersFiltering}
+          />
+        ) : responseTab === "compare" ? (
+          <ResponseDiff
+            request={{ method, url }}
+            response={response}
+            olderThan={
+              activeTab.historyEntry?.timestamp ??
+              new Date(activeTab.sentAt ?? 0).toISOString()
+            }
             height={10}
-            readOnly
+            isActive={focus === "responseTabs"}
+          />
+        ) : responseTab === "hex" ? (
+          <HexView
+            bytes={responseBytes!}
+            height={10}
+            isActive={focus === "responseTabs"}
           />
         ) : responseTab === "trace" ? (
           <TextArea
@@ -645,13 +1655,76 @@ export function App() {
           />
         ) : (
           <Box flexDirection="column" gap={1}>
-            <TextArea
-              title="Body"
-              value={responseBodyForView}
-              isActive={focus === "responseTabs"}
-              height={10}
-              readOnly
-            />
+            <Box gap={1}>
+              <Text dimColor>View</Text>
+              {RESPONSE_VIEWS.map((view) => (
+                <Text
+                  key={view}
+                  bold={view === responseView}
+                  color={view === responseView ? theme.focusBorder : undefined}
+                  dimColor={view !== responseView}
+                >
+                  {RESPONSE_VIEW_LABELS[view]}
+                </Text>
+              ))}
+              <Text dimColor>
+                ({formatChord(keymap.cycle_response_view[0])})
+              </Text>
+            </Box>
+            {findOpen ? (
+              <FindBar
+                query={findQuery ?? ""}
+                matchCount={findResults.length}
+                current={currentFind}
+                onChange={(query) => {
+                  setFindQuery(query);
+                  setFindIndex(0);
+                }}
+                onStep={stepFind}
+                onClose={() => setFindQuery(null)}
+              />
+            ) : null}
+            {/* Matches are shown in the text view, so previews step aside. */}
+            {responseImage && showPreview ? (
+              <ImagePreview
+                bytes={responseBytes!}
+                contentType={responseContentType}
+                height={10}
+                isActive={focus === "responseTabs"}
+                onMessage={(variant, text) => notify({ variant, text })}
+              />
+            ) : responseBinary && showPreview ? (
+              <HexView
+                bytes={responseBytes!}
+                height={10}
+                isActive={focus === "responseTabs"}
+              />
+            ) : responseJson !== undefined && showPreview ? (
+              <JsonTree
+                key={`response-tree-${activeTabId}`}
+                value={responseJson}
+                height={10}
+                isActive={focus === "responseTabs"}
+                onCopy={(text) => notify({ variant: "success", text })}
+              />
+            ) : (
+              <TextArea
+                key={`response-${activeTabId}`}
+                title="Body"
+                value={responseText}
+                isActive={focus === "responseTabs" && !findOpen}
+                height={10}
+                readOnly
+                wrap
+                language={responseView === "raw" ? null : responseLanguage}
+                matches={findResults}
+                currentMatch={currentFind}
+                initialScrollTop={activeTab.responseScroll}
+                onScrollTopChange={(top) =>
+                  updateTab(activeTabId, { responseScroll: top })
+                }
+              />
+            )}
           </Box>
         )}
       </Box>
@@ -671,6 +1744,7 @@ export function App() {
   return (
     <Box flexDirection="column" padding={1} width="100%" position="relative">
       <KeyboardShortcuts
+        keymap={keymap}
         onExit={quit}
         onSend={() => {
           void send();
@@ -704,39 +1778,235 @@ export function App() {
           }
         }}
         onToggleDebug={toggleDebugPanel}
-        onClearDebugLogs={clearLogs}
+        onClearDebugLogs={() => {
+          clearLogs();
+          clearConsole();
+          setConsoleNonce((n) => n + 1);
+        }}
+        onToggleConsole={() => setConsoleVisible((v) => !v)}
+        onPickTheme={() => setThemePickerOpen(true)}
+        onCycleTheme={() => setThemeName(cycleTheme())}
+        onCommandPalette={() => {
+          clearToasts();
+          setPaletteCommands([
+            ...listCommands(),
+            EXPORT_WORKSPACE_COMMAND,
+            IMPORT_WORKSPACE_COMMAND,
+            UNLOCK_SECRETS_COMMAND,
+            LOAD_SCHEMA_COMMAND,
+            SCHEMA_EXPLORER_COMMAND,
+            COOKIES_COMMAND,
+            SETTINGS_COMMAND,
+            ALL_RESPONSES_COMMAND,
+          ]);
+        }}
+        onToggleFolders={() => setFoldersExpanded((v) => !v)}
+        onSearchLibrary={() => setLibrarySearchOpen(true)}
+        onOpenHistory={() => setHistoryOpen(true)}
+        onAllResponses={() => setAllResponsesOpen(true)}
+        onOpenSpaces={() => setSpacesOpen(true)}
+        onNewSpace={addSpace}
+        onFind={openFind}
+        onCycleResponseView={cycleResponseView}
+        onUndo={() => stepWorkspaceAndReport("undo")}
+        onRedo={() => stepWorkspaceAndReport("redo")}
+        onToggleFavorite={toggleFavorite}
+        onMove={moveEndpoint}
+        onNewTab={openNewTab}
+        onCloseTab={closeTab}
+        onCycleTab={cycleTab}
         canHandleGlobalShortcut={canHandleGlobalShortcut}
+        isActive={
+          paletteCommands === null &&
+          !librarySearchOpen &&
+          !historyOpen &&
+          !allResponsesOpen &&
+          !spacesOpen &&
+          !themePickerOpen &&
+          unlockPrompt === null &&
+          !onboarding &&
+          !schemaOpen &&
+          !cookiesOpen &&
+          !settingsOpen
+        }
       />
 
       <Box justifyContent="space-between" marginBottom={1}>
         <Text bold>Posting-like Pigeon</Text>
-        <Text dimColor>Focus: {focus}</Text>
+        <Text dimColor>
+          Theme: {themeName} · Focus: {focus}
+        </Text>
       </Box>
 
+      {props.configError ? (
+        <StatusMessage variant="warning">
+          Config not loaded: {props.configError}
+        </StatusMessage>
+      ) : null}
+      {secrets.locked && secrets.sealed > 0 ? (
+        <StatusMessage variant="warning">
+          {secrets.sealed} secret value{secrets.sealed === 1 ? " is" : "s are"}{" "}
+          encrypted; run "{UNLOCK_SECRETS_COMMAND}" from the command palette
+        </StatusMessage>
+      ) : null}
       {error ? <StatusMessage variant="error">{error}</StatusMessage> : null}
+      <Toasts toasts={toasts} />
 
       <Box flexDirection="column" gap={1} width="100%" position="relative">
-        {TopBar}
+        {/* Overlays take over the panes so their inputs don't receive the overlay's keys. */}
+        {onboarding ? (
+          <Onboarding
+            onDone={(imported) => {
+              setOnboarding(false);
+              notify({
+                variant: "success",
+                text:
+                  imported > 0
+                    ? `Workspace ready: imported ${imported} endpoint${
+                        imported === 1 ? "" : "s"
+                      }`
+                    : "Workspace ready",
+              });
+              setWorkspaceRevision((n) => n + 1);
+            }}
+            onSkip={() => setOnboarding(false)}
+          />
+        ) : paletteCommands ? (
+          <CommandPalette
+            commands={paletteCommands}
+            onRun={runPaletteCommand}
+            onClose={() => setPaletteCommands(null)}
+          />
+        ) : librarySearchOpen ? (
+          <LibrarySearch
+            initialQuery={libraryFilter}
+            spaces={activeSpaces}
+            folders={workspace.folders}
+            onApply={(query) => {
+              setLibraryFilter(query);
+              setLibrarySearchOpen(false);
+            }}
+            onClose={() => setLibrarySearchOpen(false)}
+            onChange={() => setWorkspaceRevision((n) => n + 1)}
+          />
+        ) : historyOpen ? (
+          <HistoryPanel
+            onClose={() => setHistoryOpen(false)}
+            onOpen={(entry) => openHistoryEntry(entry, false)}
+            onResend={(entry) => openHistoryEntry(entry, true)}
+          />
+        ) : allResponsesOpen ? (
+          <AllResponses
+            workspace={workspace}
+            onOpen={openResponseInSpace}
+            onClose={() => setAllResponsesOpen(false)}
+          />
+        ) : spacesOpen ? (
+          <SpacesPanel
+            spaces={workspace.spaces}
+            currentId={currentSpace?.id}
+            filter={spaceFilter}
+            onFilterChange={setSpaceFilter}
+            onChange={() => setWorkspaceRevision((n) => n + 1)}
+            onClose={() => setSpacesOpen(false)}
+          />
+        ) : themePickerOpen ? (
+          <ThemePicker
+            onApply={setThemeName}
+            onClose={() => setThemePickerOpen(false)}
+          />
+        ) : schemaOpen && graphqlSchema ? (
+          <SchemaExplorer
+            schema={graphqlSchema}
+            onClose={() => setSchemaOpen(false)}
+          />
+        ) : cookiesOpen ? (
+          <CookieManager
+            url={resolvedUrl}
+            onChange={() => setCookieRevision((n) => n + 1)}
+            onClose={() => setCookiesOpen(false)}
+          />
+        ) : settingsOpen ? (
+          <SettingsView
+            onChange={() => {
+              // Apply right away rather than on the next generation poll.
+              applyConfigThemes();
+              applyConfigLocale();
+              setThemeName(currentThemeName());
+              setConfigGeneration(getConfigGeneration());
+            }}
+            onClose={() => setSettingsOpen(false)}
+          />
+        ) : unlockPrompt !== null ? (
+          <PassphrasePrompt
+            sealed={unlockPrompt}
+            onSubmit={unlockSecretsAndReport}
+            onClose={() => setUnlockPrompt(null)}
+          />
+        ) : (
+          <>
+            {openTabs.length > 1 ? (
+              <OpenTabBar
+                tabs={openTabs.map((t) =>
+                  t.id === activeTabId ? { ...t, method, url } : t
+                )}
+                activeId={activeTabId}
+                hint={`${formatChord(keymap.prev_tab.at(-1))}/${formatChord(
+                  keymap.next_tab.at(-1)
+                )} switch · ${formatChord(keymap.close_tab[0])} close`}
+              />
+            ) : null}
+            {TopBar}
 
-        <HSplit left={Sidebar} right={RightPane} leftWidth={32} gap={1} />
+            <HSplit left={Sidebar} right={RightPane} leftWidth={32} gap={1} />
+          </>
+        )}
 
         {debugPanelVisible && <DebugPanel isVisible={debugPanelVisible} />}
+        {consoleVisible && <LuaConsole key={consoleNonce} />}
 
         <Box borderStyle="round" paddingX={1} paddingY={0}>
           <KeyHints
             items={[
-              { key: "^j", label: "Send" },
-              { key: "^t", label: "Method" },
-              { key: "^l", label: "URL" },
-              { key: "tab", label: "Focus next" },
-              { key: "shift+tab", label: "Focus prev" },
+              { key: formatChord(keymap.send_request[0]), label: "Send" },
+              { key: formatChord(keymap.focus_method[0]), label: "Method" },
+              { key: formatChord(keymap.focus_url[0]), label: "URL" },
+              { key: formatChord(keymap.focus_next[0]), label: "Focus next" },
+              { key: formatChord(keymap.focus_prev[0]), label: "Focus prev" },
               { key: "←/→", label: "Switch tab" },
-              { key: "d", label: "Debug" },
-              { key: "^i", label: "Clear logs" },
-              { key: "q", label: "Quit" },
+              { key: formatChord(keymap.toggle_debug[0]), label: "Debug" },
+              {
+                key: formatChord(keymap.clear_debug_logs[0]),
+                label: "Clear logs",
+              },
+              { key: formatChord(keymap.toggle_console[0]), label: "Console" },
+              { key: formatChord(keymap.pick_theme[0]), label: "Theme" },
+              {
+                key: formatChord(keymap.command_palette[0]),
+                label: "Commands",
+              },
+              { key: formatChord(keymap.toggle_folders[0]), label: "Folders" },
+              { key: formatChord(keymap.search_library[0]), label: "Search" },
+              { key: formatChord(keymap.open_history[0]), label: "History" },
+              {
+                key: formatChord(keymap.all_responses[0]),
+                label: "All responses",
+              },
+              { key: formatChord(keymap.open_spaces[0]), label: "Spaces" },
+              { key: formatChord(keymap.new_space[0]), label: "New space" },
+              { key: formatChord(keymap.find_in_response[0]), label: "Find" },
+              {
+                key: formatChord(keymap.cycle_response_view[0]),
+                label: "View",
+              },
+              { key: formatChord(keymap.new_tab[0]), label: "New tab" },
+              { key: formatChord(keymap.undo[0]), label: "Undo" },
+              { key: formatChord(keymap.redo[0]), label: "Redo" },
+              { key: formatChord(keymap.quit[0]), label: "Quit" },
             ]}
           />
         </Box>
+        <StatusBar last={lastResult} />
       </Box>
 
       {/* Method dropdown overlay - rendered at app level to ensure proper layering */}
@@ -0,0 +1,8 @@
+/**
+ * Put `text` on the system clipboard through the terminal (OSC 52), which
+ * works over SSH too. Terminals that don't support it ignore the sequence.
+ */
+export function copyToClipboard(text: string): void {
+  const encoded = Buffer.from(text, "utf8").toString("base64");
+  process.stdout.write(`\x1b]52;c;${encoded}\x07`);
+}
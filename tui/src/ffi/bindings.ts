@@ -3,6 +3,7 @@ import { getCoreLib } from "./core";
 
 export type RustLib = {
   sendRequestPtr: (reqJsonPtr: Buffer) => Pointer | null;
+  graphqlSchemaPtr: (reqJsonPtr: Buffer) => Pointer | null;
   freeString: (ptr: Pointer) => void;
 };
 
@@ -13,6 +14,9 @@ export function loadRustLib(): RustLib {
     sendRequestPtr: (reqJsonBuf: Buffer) => {
       return core.pigeon_send_request(reqJsonBuf);
     },
+    graphqlSchemaPtr: (reqJsonBuf: Buffer) => {
+      return core.pigeon_graphql_schema(reqJsonBuf);
+    },
     freeString: (ptr: Pointer) => {
       core.pigeon_free_string(ptr);
     },
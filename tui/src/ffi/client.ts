@@ -1,15 +1,26 @@
 import type { FfiRequest, FfiResponse } from "@/types";
+import type { GraphqlSchema } from "@/graphql";
+import { getCoreLib } from "./core";
+import { readCStringAndFree } from "./bindings";
 
-type WorkerSendMessage = { type: "send"; id: number; request: FfiRequest };
+type WorkerSendMessage = {
+  type: "send" | "schema";
+  id: number;
+  request: FfiRequest;
+};
 type WorkerResultMessage =
   | { type: "result"; id: number; ok: true; response: FfiResponse }
+  | { type: "result"; id: number; ok: true; schema: GraphqlSchema }
   | { type: "result"; id: number; ok: false; error: string };
 
 let worker: Worker | null = null;
 let nextId = 1;
 const inflight = new Map<
   number,
-  { resolve: (r: FfiResponse) => void; reject: (e: Error) => void }
+  {
+    resolve: (r: FfiResponse | GraphqlSchema) => void;
+    reject: (e: Error) => void;
+  }
 >();
 
 export function terminateRustWorker(reason = "terminated"): void {
@@ -24,6 +35,12 @@ export function terminateRustWorker(reason = "terminated"): void {
   worker = null;
 }
 
+/** Persist cookies and pending workspace changes, then stop the core. Call on exit. */
+export function shutdownCore(): void {
+  const core = getCoreLib();
+  readCStringAndFree(core.pigeon_shutdown(), core.pigeon_free_string);
+}
+
 function getWorker(): Worker {
   if (worker) return worker;
 
@@ -41,8 +58,8 @@ function getWorker(): Worker {
       if (!pending) return;
       inflight.delete(msg.id);
 
-      if (msg.ok) pending.resolve(msg.response);
-      else pending.reject(new Error(msg.error));
+      if (!msg.ok) pending.reject(new Error(msg.error));
+      else pending.resolve("schema" in msg ? msg.schema : msg.response);
     }
   );
 
@@ -69,7 +86,31 @@ export async function sendRequestViaRust(
   const msg: WorkerSendMessage = { type: "send", id, request: req };
 
   return await new Promise<FfiResponse>((resolve, reject) => {
-    inflight.set(id, { resolve, reject });
+    inflight.set(id, {
+      resolve: (r) => resolve(r as FfiResponse),
+      reject,
+    });
+    w.postMessage(msg);
+  });
+}
+
+/**
+ * The schema of the GraphQL endpoint at `req.url`, sent `req`'s headers and
+ * auth. Runs on the worker like a send; rejects when introspection fails.
+ */
+export async function fetchGraphqlSchema(
+  req: FfiRequest
+): Promise<GraphqlSchema> {
+  const w = getWorker();
+  const id = nextId++;
+
+  const msg: WorkerSendMessage = { type: "schema", id, request: req };
+
+  return await new Promise<GraphqlSchema>((resolve, reject) => {
+    inflight.set(id, {
+      resolve: (r) => resolve(r as GraphqlSchema),
+      reject,
+    });
     w.postMessage(msg);
   });
 }
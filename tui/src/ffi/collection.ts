@@ -0,0 +1,62 @@
+import { getCoreLib } from "./core";
+import { readCStringAndFree } from "./bindings";
+import type { FfiRequest } from "@/types";
+
+/** `request` as a curl command line, from the core's cURL collection export. */
+export function curlCommand(
+  request: FfiRequest
+): { command: string } | { error: string } {
+  const core = getCoreLib();
+  const collection = { name: "", requests: [{ name: "", ...request }] };
+  const result = readCStringAndFree(
+    core.pigeon_export_collection(
+      Buffer.from("curl\0", "utf8"),
+      Buffer.from(JSON.stringify(collection) + "\0", "utf8")
+    ),
+    core.pigeon_free_string
+  );
+
+  try {
+    const parsed = JSON.parse(result);
+    if (typeof parsed?.error === "string") return { error: parsed.error };
+    // Each exported request starts with a `# name` comment line.
+    const data = String(parsed?.data ?? "");
+    return { command: data.replace(/^#.*\n/, "") };
+  } catch {
+    return { error: "invalid response from core" };
+  }
+}
+
+/**
+ * `request` as it would go on the wire, from the core: placeholders resolved
+ * (secrets kept as written), disabled headers dropped and the body's
+ * Content-Type added. Hooks and auth signing aren't applied.
+ */
+export function previewRequest(request: FfiRequest):
+  | {
+      request: FfiRequest;
+      /** Values by placeholder name; secrets are never resolved. */
+      resolved: Record<string, string>;
+      unresolved: string[];
+    }
+  | { error: string } {
+  const core = getCoreLib();
+  const result = readCStringAndFree(
+    core.pigeon_request_preview(
+      Buffer.from(JSON.stringify(request) + "\0", "utf8")
+    ),
+    core.pigeon_free_string
+  );
+
+  try {
+    const parsed = JSON.parse(result);
+    if (typeof parsed?.error === "string") return { error: parsed.error };
+    return {
+      request: parsed.request,
+      resolved: parsed.resolved ?? {},
+      unresolved: parsed.unresolved ?? [],
+    };
+  } catch {
+    return { error: "invalid response from core" };
+  }
+}
@@ -0,0 +1,49 @@
+import { getCoreLib } from "./core";
+import { readCStringAndFree } from "./bindings";
+import type { FfiRequest, FfiResponse } from "@/types";
+
+export type CommandContext = {
+  request: FfiRequest;
+  response?: FfiResponse;
+};
+
+/** Names of the commands plugins registered with `pigeon.command`. */
+export function listCommands(): string[] {
+  const core = getCoreLib();
+  const result = readCStringAndFree(
+    core.pigeon_commands(),
+    core.pigeon_free_string
+  );
+
+  try {
+    const parsed = JSON.parse(result);
+    return Array.isArray(parsed?.commands) ? parsed.commands : [];
+  } catch {
+    return [];
+  }
+}
+
+/** Run a `pigeon.command` with the current request/response. */
+export function runCommand(
+  name: string,
+  context: CommandContext
+): { result?: unknown; error?: string } {
+  const core = getCoreLib();
+  const result = readCStringAndFree(
+    core.pigeon_run_command(
+      Buffer.from(name + "\0", "utf8"),
+      Buffer.from(JSON.stringify(context) + "\0", "utf8")
+    ),
+    core.pigeon_free_string
+  );
+
+  try {
+    const parsed = JSON.parse(result);
+    if (parsed && typeof parsed === "object" && parsed.error) {
+      return { error: String(parsed.error) };
+    }
+    return { result: parsed?.result ?? null };
+  } catch {
+    return { error: `Unexpected response from core: ${result}` };
+  }
+}
@@ -1,9 +1,21 @@
 import { type Pointer } from "bun:ffi";
 import { getCoreLib } from "./core";
 import { readCStringAndFree } from "./bindings";
+import { getSettings } from "./settings";
+import { setLocale } from "@/locale";
+import {
+  applyTheme,
+  currentThemeName,
+  registerThemes,
+  type ThemeDefinition,
+} from "@/ui/theme";
 
 export type ConfigLib = {
-  loadConfigPtr: () => Pointer | null;
+  loadConfigPtr: (profilesBuf: Buffer | null) => Pointer | null;
+  configGetPtr: (pathBuf: Buffer | null) => Pointer | null;
+  keymapPtr: () => Pointer | null;
+  themesPtr: () => Pointer | null;
+  generationPtr: () => Pointer | null;
   freeString: (ptr: Pointer) => void;
 };
 
@@ -13,9 +25,25 @@ export function loadConfigLib(): ConfigLib {
   if (configLib) return configLib;
 
   configLib = {
-    loadConfigPtr: () => {
+    loadConfigPtr: (profilesBuf: Buffer | null) => {
       const core = getCoreLib();
-      return core.pigeon_load_config();
+      return core.pigeon_load_config_with_profiles(profilesBuf);
+    },
+    configGetPtr: (pathBuf: Buffer | null) => {
+      const core = getCoreLib();
+      return core.pigeon_config_get(pathBuf);
+    },
+    keymapPtr: () => {
+      const core = getCoreLib();
+      return core.pigeon_keymap();
+    },
+    themesPtr: () => {
+      const core = getCoreLib();
+      return core.pigeon_themes();
+    },
+    generationPtr: () => {
+      const core = getCoreLib();
+      return core.pigeon_config_generation();
     },
     freeString: (ptr: Pointer) => {
       const core = getCoreLib();
@@ -26,9 +54,15 @@ export function loadConfigLib(): ConfigLib {
   return configLib;
 }
 
-export function loadConfig(): { error?: string } {
+/**
+ * Load config.lua and plugins, with `profiles` (e.g. "work,ci") layered on top.
+ * Without profiles the core falls back to the PIGEON_PROFILE env var.
+ */
+export function loadConfig(profiles?: string): { error?: string } {
   const lib = loadConfigLib();
-  const ptr = lib.loadConfigPtr();
+  const buf =
+    profiles === undefined ? null : Buffer.from(profiles + "\0", "utf8");
+  const ptr = lib.loadConfigPtr(buf);
   const result = readCStringAndFree(ptr, lib.freeString);
 
   // Handle empty or null responses
@@ -48,3 +82,104 @@ export function loadConfig(): { error?: string } {
     return { error: `Failed to parse config response: ${result}` };
   }
 }
+
+/**
+ * Read a value defined by the Lua config (e.g. "theme.mode").
+ * Returns `undefined` when the key is unset or the runtime isn't loaded.
+ */
+export function getConfigValue<T = unknown>(path: string): T | undefined {
+  const lib = loadConfigLib();
+  const buf = Buffer.from(path + "\0", "utf8");
+  const result = readCStringAndFree(lib.configGetPtr(buf), lib.freeString);
+
+  try {
+    const parsed = JSON.parse(result);
+    if (parsed && typeof parsed === "object" && "value" in parsed) {
+      return (parsed.value ?? undefined) as T | undefined;
+    }
+    return undefined;
+  } catch {
+    return undefined;
+  }
+}
+
+/**
+ * Key bindings overridden in the Lua config (`pigeon.keymap.set`), as
+ * `action -> chords`. Empty when nothing is overridden or the runtime isn't loaded.
+ */
+export function getKeymapOverrides(): Record<string, string[]> {
+  const lib = loadConfigLib();
+  const result = readCStringAndFree(lib.keymapPtr(), lib.freeString);
+
+  try {
+    const parsed = JSON.parse(result);
+    if (parsed && typeof parsed === "object" && parsed.keymap) {
+      return parsed.keymap as Record<string, string[]>;
+    }
+    return {};
+  } catch {
+    return {};
+  }
+}
+
+/**
+ * Themes declared in the Lua config (`pigeon.theme.register`) and the one
+ * selected with `pigeon.theme.use`, if any.
+ */
+export function getConfigThemes(): {
+  themes: Record<string, ThemeDefinition>;
+  active?: string;
+} {
+  const lib = loadConfigLib();
+  const result = readCStringAndFree(lib.themesPtr(), lib.freeString);
+
+  try {
+    const parsed = JSON.parse(result);
+    if (parsed && typeof parsed === "object" && parsed.themes) {
+      return { themes: parsed.themes, active: parsed.active ?? undefined };
+    }
+    return { themes: {} };
+  } catch {
+    return { themes: {} };
+  }
+}
+
+/**
+ * Register the config's themes and switch to the one it selects, either with
+ * `pigeon.theme.use` or by name in `theme.mode` (e.g. "solarized"), or
+ * refresh the current theme in case its colors changed. A theme picked in
+ * the settings view wins over both.
+ */
+export function applyConfigThemes(): void {
+  const configThemes = getConfigThemes();
+  registerThemes(configThemes.themes);
+  const chosen = getSettings().find(
+    (s) => s.path === "theme.mode" && s.overridden
+  );
+  const selected =
+    (typeof chosen?.value === "string" ? chosen.value : undefined) ??
+    configThemes.active ??
+    getConfigValue<string>("theme.mode");
+  if (!selected || !applyTheme(selected)) applyTheme(currentThemeName());
+}
+
+/** Format dates and numbers for `ui.locale`, or the system's locale. */
+export function applyConfigLocale(): void {
+  setLocale(getConfigValue<string>("ui.locale"));
+}
+
+/**
+ * Counter bumped by the core on every config (re)load, including automatic
+ * reloads when config.lua or a plugin changes on disk.
+ */
+export function getConfigGeneration(): number {
+  const lib = loadConfigLib();
+  const result = readCStringAndFree(lib.generationPtr(), lib.freeString);
+
+  try {
+    const parsed = JSON.parse(result);
+    return typeof parsed?.generation === "number" ? parsed.generation : 0;
+  } catch {
+    return 0;
+  }
+}
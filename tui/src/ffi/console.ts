@@ -0,0 +1,32 @@
+import { getCoreLib } from "./core";
+import { readCStringAndFree } from "./bindings";
+
+export type ConsoleEntry = {
+  id: number;
+  timestamp: string;
+  level: "info" | "warn" | "error";
+  /** `print`, `log`, or the event that reported a script error. */
+  source: string;
+  message: string;
+};
+
+/** Lua console entries newer than `afterId` (0 for everything still buffered). */
+export function getConsoleEntries(afterId = 0): ConsoleEntry[] {
+  const core = getCoreLib();
+  const result = readCStringAndFree(
+    core.pigeon_lua_console(afterId),
+    core.pigeon_free_string
+  );
+
+  try {
+    const parsed = JSON.parse(result);
+    return Array.isArray(parsed?.entries) ? parsed.entries : [];
+  } catch {
+    return [];
+  }
+}
+
+export function clearConsole(): void {
+  const core = getCoreLib();
+  readCStringAndFree(core.pigeon_lua_console_clear(), core.pigeon_free_string);
+}
@@ -0,0 +1,107 @@
+import { getCoreLib } from "./core";
+import { readCStringAndFree } from "./bindings";
+
+export type Cookie = {
+  name: string;
+  value: string;
+  domain: string;
+  path: string;
+  secure: boolean;
+  httpOnly: boolean;
+  /** Sent only to `domain` itself, not its subdomains. */
+  hostOnly: boolean;
+  /** RFC 3339; null for session cookies. */
+  expires: string | null;
+};
+
+export type CookieKey = Pick<Cookie, "domain" | "path" | "name">;
+
+function cstring(value: string): Buffer {
+  return Buffer.from(value + "\0", "utf8");
+}
+
+function parseCookies(result: string): Cookie[] {
+  try {
+    const parsed = JSON.parse(result);
+    return Array.isArray(parsed) ? parsed : [];
+  } catch {
+    return [];
+  }
+}
+
+function parseSuccess(result: string, fallback: string): { error?: string } {
+  try {
+    const parsed = JSON.parse(result);
+    if (parsed?.success || parsed?.removed !== undefined) return {};
+    return { error: String(parsed?.error ?? fallback) };
+  } catch {
+    return { error: "invalid response from core" };
+  }
+}
+
+/** Every unexpired cookie in the jar; errors read as no cookies. */
+export function listCookies(): Cookie[] {
+  const core = getCoreLib();
+  return parseCookies(
+    readCStringAndFree(core.pigeon_cookies_list(null), core.pigeon_free_string)
+  );
+}
+
+/** Cookies the jar would attach to a request to `url`; [] if it isn't valid. */
+export function cookiesForUrl(url: string): Cookie[] {
+  const core = getCoreLib();
+  return parseCookies(
+    readCStringAndFree(
+      core.pigeon_cookies_for_url(cstring(url)),
+      core.pigeon_free_string
+    )
+  );
+}
+
+/**
+ * Add a cookie, or replace the one with the same domain, path and name. A
+ * host-only cookie is set without a Domain attribute.
+ */
+export function setCookie(cookie: Cookie): { error?: string } {
+  const core = getCoreLib();
+  const result = readCStringAndFree(
+    core.pigeon_cookies_set(
+      cstring(
+        JSON.stringify({
+          url: `https://${cookie.domain}${cookie.path}`,
+          name: cookie.name,
+          value: cookie.value,
+          domain: cookie.hostOnly ? undefined : cookie.domain,
+          path: cookie.path,
+          secure: cookie.secure,
+          httpOnly: cookie.httpOnly,
+          expires: cookie.expires ?? undefined,
+        })
+      )
+    ),
+    core.pigeon_free_string
+  );
+  return parseSuccess(result, "setting the cookie failed");
+}
+
+export function removeCookie(key: CookieKey): { error?: string } {
+  const core = getCoreLib();
+  const result = readCStringAndFree(
+    core.pigeon_cookies_remove(
+      cstring(
+        JSON.stringify({ domain: key.domain, path: key.path, name: key.name })
+      )
+    ),
+    core.pigeon_free_string
+  );
+  return parseSuccess(result, "removing the cookie failed");
+}
+
+export function clearCookies(): { error?: string } {
+  const core = getCoreLib();
+  const result = readCStringAndFree(
+    core.pigeon_cookies_clear(),
+    core.pigeon_free_string
+  );
+  return parseSuccess(result, "clearing cookies failed");
+}
@@ -3,7 +3,66 @@ import { fileURLToPath } from "url";
 
 export type CoreLibSymbols = {
   pigeon_send_request: (buf: Buffer) => Pointer | null;
+  pigeon_graphql_schema: (buf: Buffer) => Pointer | null;
   pigeon_load_config: () => Pointer | null;
+  pigeon_load_config_with_profiles: (profiles: Buffer | null) => Pointer | null;
+  pigeon_config_get: (buf: Buffer | null) => Pointer | null;
+  pigeon_keymap: () => Pointer | null;
+  pigeon_themes: () => Pointer | null;
+  pigeon_config_generation: () => Pointer | null;
+  pigeon_lua_console: (afterId: number) => Pointer | null;
+  pigeon_lua_console_clear: () => Pointer | null;
+  pigeon_workspace: () => Pointer | null;
+  pigeon_status: () => Pointer | null;
+  pigeon_workspace_search: (query: Buffer | null) => Pointer | null;
+  pigeon_workspace_move: (
+    kind: Buffer,
+    key: Buffer,
+    index: number
+  ) => Pointer | null;
+  pigeon_workspace_remove: (kind: Buffer, key: Buffer) => Pointer | null;
+  pigeon_workspace_bulk: (ops: Buffer) => Pointer | null;
+  pigeon_workspace_duplicate: (kind: Buffer, key: Buffer) => Pointer | null;
+  pigeon_space_request: (space: Buffer) => Pointer | null;
+  pigeon_request_preview: (request: Buffer) => Pointer | null;
+  pigeon_space_create: (fields: Buffer | null) => Pointer | null;
+  pigeon_export_collection: (
+    kind: Buffer,
+    collection: Buffer
+  ) => Pointer | null;
+  pigeon_workspace_undo: () => Pointer | null;
+  pigeon_workspace_redo: () => Pointer | null;
+  pigeon_workspace_export: () => Pointer | null;
+  pigeon_workspace_import: (data: Buffer) => Pointer | null;
+  pigeon_workspace_first_run: () => Pointer | null;
+  pigeon_workspace_setup: (setup: Buffer) => Pointer | null;
+  pigeon_secrets_unlock: (passphrase: Buffer) => Pointer | null;
+  pigeon_secrets_status: () => Pointer | null;
+  pigeon_workspace_update: (
+    kind: Buffer,
+    key: Buffer,
+    changesJson: Buffer
+  ) => Pointer | null;
+  pigeon_history: (queryJson: Buffer | null) => Pointer | null;
+  pigeon_history_pin: (id: number, pinned: boolean) => Pointer | null;
+  pigeon_history_delete: (id: number) => Pointer | null;
+  pigeon_history_clear: (space: Buffer | null) => Pointer | null;
+  pigeon_settings: () => Pointer | null;
+  pigeon_settings_set: (changeJson: Buffer) => Pointer | null;
+  pigeon_session: () => Pointer | null;
+  pigeon_session_save: (sessionJson: Buffer) => Pointer | null;
+  pigeon_cookies_list: (domain: Buffer | null) => Pointer | null;
+  pigeon_cookies_for_url: (url: Buffer) => Pointer | null;
+  pigeon_cookies_set: (cookieJson: Buffer) => Pointer | null;
+  pigeon_cookies_remove: (keyJson: Buffer) => Pointer | null;
+  pigeon_cookies_clear: () => Pointer | null;
+  pigeon_commands: () => Pointer | null;
+  pigeon_run_command: (
+    name: Buffer,
+    contextJson: Buffer | null
+  ) => Pointer | null;
+  pigeon_format_response: (responseJson: Buffer) => Pointer | null;
+  pigeon_shutdown: () => Pointer | null;
   pigeon_free_string: (ptr: Pointer) => void;
 };
 
@@ -21,10 +80,190 @@ export function getCoreLib(): CoreLibSymbols {
       args: [FFIType.cstring],
       returns: FFIType.ptr,
     },
+    pigeon_graphql_schema: {
+      args: [FFIType.cstring],
+      returns: FFIType.ptr,
+    },
     pigeon_load_config: {
       args: [],
       returns: FFIType.ptr,
     },
+    pigeon_load_config_with_profiles: {
+      args: [FFIType.cstring],
+      returns: FFIType.ptr,
+    },
+    pigeon_config_get: {
+      args: [FFIType.cstring],
+      returns: FFIType.ptr,
+    },
+    pigeon_keymap: {
+      args: [],
+      returns: FFIType.ptr,
+    },
+    pigeon_themes: {
+      args: [],
+      returns: FFIType.ptr,
+    },
+    pigeon_config_generation: {
+      args: [],
+      returns: FFIType.ptr,
+    },
+    pigeon_lua_console: {
+      args: [FFIType.u64],
+      returns: FFIType.ptr,
+    },
+    pigeon_lua_console_clear: {
+      args: [],
+      returns: FFIType.ptr,
+    },
+    pigeon_workspace: {
+      args: [],
+      returns: FFIType.ptr,
+    },
+    pigeon_status: {
+      args: [],
+      returns: FFIType.ptr,
+    },
+    pigeon_workspace_search: {
+      args: [FFIType.cstring],
+      returns: FFIType.ptr,
+    },
+    pigeon_workspace_move: {
+      args: [FFIType.cstring, FFIType.cstring, FFIType.u32],
+      returns: FFIType.ptr,
+    },
+    pigeon_workspace_remove: {
+      args: [FFIType.cstring, FFIType.cstring],
+      returns: FFIType.ptr,
+    },
+    pigeon_workspace_bulk: {
+      args: [FFIType.cstring],
+      returns: FFIType.ptr,
+    },
+    pigeon_workspace_duplicate: {
+      args: [FFIType.cstring, FFIType.cstring],
+      returns: FFIType.ptr,
+    },
+    pigeon_space_request: {
+      args: [FFIType.cstring],
+      returns: FFIType.ptr,
+    },
+    pigeon_request_preview: {
+      args: [FFIType.cstring],
+      returns: FFIType.ptr,
+    },
+    pigeon_space_create: {
+      args: [FFIType.cstring],
+      returns: FFIType.ptr,
+    },
+    pigeon_export_collection: {
+      args: [FFIType.cstring, FFIType.cstring],
+      returns: FFIType.ptr,
+    },
+    pigeon_workspace_undo: {
+      args: [],
+      returns: FFIType.ptr,
+    },
+    pigeon_workspace_redo: {
+      args: [],
+      returns: FFIType.ptr,
+    },
+    pigeon_workspace_export: {
+      args: [],
+      returns: FFIType.ptr,
+    },
+    pigeon_workspace_import: {
+      args: [FFIType.cstring],
+      returns: FFIType.ptr,
+    },
+    pigeon_workspace_first_run: {
+      args: [],
+      returns: FFIType.ptr,
+    },
+    pigeon_workspace_setup: {
+      args: [FFIType.cstring],
+      returns: FFIType.ptr,
+    },
+    pigeon_secrets_unlock: {
+      args: [FFIType.cstring],
+      returns: FFIType.ptr,
+    },
+    pigeon_secrets_status: {
+      args: [],
+      returns: FFIType.ptr,
+    },
+    pigeon_workspace_update: {
+      args: [FFIType.cstring, FFIType.cstring, FFIType.cstring],
+      returns: FFIType.ptr,
+    },
+    pigeon_history: {
+      args: [FFIType.cstring],
+      returns: FFIType.ptr,
+    },
+    pigeon_history_pin: {
+      args: [FFIType.i64, FFIType.bool],
+      returns: FFIType.ptr,
+    },
+    pigeon_history_delete: {
+      args: [FFIType.i64],
+      returns: FFIType.ptr,
+    },
+    pigeon_history_clear: {
+      args: [FFIType.cstring],
+      returns: FFIType.ptr,
+    },
+    pigeon_settings: {
+      args: [],
+      returns: FFIType.ptr,
+    },
+    pigeon_settings_set: {
+      args: [FFIType.cstring],
+      returns: FFIType.ptr,
+    },
+    pigeon_session: {
+      args: [],
+      returns: FFIType.ptr,
+    },
+    pigeon_session_save: {
+      args: [FFIType.cstring],
+      returns: FFIType.ptr,
+    },
+    pigeon_cookies_list: {
+      args: [FFIType.cstring],
+      returns: FFIType.ptr,
+    },
+    pigeon_cookies_for_url: {
+      args: [FFIType.cstring],
+      returns: FFIType.ptr,
+    },
+    pigeon_cookies_set: {
+      args: [FFIType.cstring],
+      returns: FFIType.ptr,
+    },
+    pigeon_cookies_remove: {
+      args: [FFIType.cstring],
+      returns: FFIType.ptr,
+    },
+    pigeon_cookies_clear: {
+      args: [],
+      returns: FFIType.ptr,
+    },
+    pigeon_commands: {
+      args: [],
+      returns: FFIType.ptr,
+    },
+    pigeon_run_command: {
+      args: [FFIType.cstring, FFIType.cstring],
+      returns: FFIType.ptr,
+    },
+    pigeon_format_response: {
+      args: [FFIType.cstring],
+      returns: FFIType.ptr,
+    },
+    pigeon_shutdown: {
+      args: [],
+      returns: FFIType.ptr,
+    },
     pigeon_free_string: {
       args: [FFIType.ptr],
       returns: FFIType.void,
@@ -33,7 +272,53 @@ export function getCoreLib(): CoreLibSymbols {
 
   coreLib = {
     pigeon_send_request: lib.symbols.pigeon_send_request,
+    pigeon_graphql_schema: lib.symbols.pigeon_graphql_schema,
     pigeon_load_config: lib.symbols.pigeon_load_config,
+    pigeon_load_config_with_profiles:
+      lib.symbols.pigeon_load_config_with_profiles,
+    pigeon_config_get: lib.symbols.pigeon_config_get,
+    pigeon_keymap: lib.symbols.pigeon_keymap,
+    pigeon_themes: lib.symbols.pigeon_themes,
+    pigeon_config_generation: lib.symbols.pigeon_config_generation,
+    pigeon_lua_console: lib.symbols.pigeon_lua_console,
+    pigeon_lua_console_clear: lib.symbols.pigeon_lua_console_clear,
+    pigeon_workspace: lib.symbols.pigeon_workspace,
+    pigeon_status: lib.symbols.pigeon_status,
+    pigeon_workspace_search: lib.symbols.pigeon_workspace_search,
+    pigeon_workspace_move: lib.symbols.pigeon_workspace_move,
+    pigeon_workspace_remove: lib.symbols.pigeon_workspace_remove,
+    pigeon_workspace_bulk: lib.symbols.pigeon_workspace_bulk,
+    pigeon_workspace_duplicate: lib.symbols.pigeon_workspace_duplicate,
+    pigeon_space_request: lib.symbols.pigeon_space_request,
+    pigeon_request_preview: lib.symbols.pigeon_request_preview,
+    pigeon_space_create: lib.symbols.pigeon_space_create,
+    pigeon_export_collection: lib.symbols.pigeon_export_collection,
+    pigeon_workspace_undo: lib.symbols.pigeon_workspace_undo,
+    pigeon_workspace_redo: lib.symbols.pigeon_workspace_redo,
+    pigeon_workspace_export: lib.symbols.pigeon_workspace_export,
+    pigeon_workspace_import: lib.symbols.pigeon_workspace_import,
+    pigeon_workspace_first_run: lib.symbols.pigeon_workspace_first_run,
+    pigeon_workspace_setup: lib.symbols.pigeon_workspace_setup,
+    pigeon_secrets_unlock: lib.symbols.pigeon_secrets_unlock,
+    pigeon_secrets_status: lib.symbols.pigeon_secrets_status,
+    pigeon_workspace_update: lib.symbols.pigeon_workspace_update,
+    pigeon_history: lib.symbols.pigeon_history,
+    pigeon_history_pin: lib.symbols.pigeon_history_pin,
+    pigeon_history_delete: lib.symbols.pigeon_history_delete,
+    pigeon_history_clear: lib.symbols.pigeon_history_clear,
+    pigeon_settings: lib.symbols.pigeon_settings,
+    pigeon_settings_set: lib.symbols.pigeon_settings_set,
+    pigeon_session: lib.symbols.pigeon_session,
+    pigeon_session_save: lib.symbols.pigeon_session_save,
+    pigeon_cookies_list: lib.symbols.pigeon_cookies_list,
+    pigeon_cookies_for_url: lib.symbols.pigeon_cookies_for_url,
+    pigeon_cookies_set: lib.symbols.pigeon_cookies_set,
+    pigeon_cookies_remove: lib.symbols.pigeon_cookies_remove,
+    pigeon_cookies_clear: lib.symbols.pigeon_cookies_clear,
+    pigeon_commands: lib.symbols.pigeon_commands,
+    pigeon_run_command: lib.symbols.pigeon_run_command,
+    pigeon_format_response: lib.symbols.pigeon_format_response,
+    pigeon_shutdown: lib.symbols.pigeon_shutdown,
     pigeon_free_string: lib.symbols.pigeon_free_string,
   };
 
@@ -0,0 +1,25 @@
+import { getCoreLib } from "./core";
+import { readCStringAndFree } from "./bindings";
+import type { FfiResponse } from "@/types";
+
+/**
+ * Body rendered by the `pigeon.formatter` registered for the response's
+ * Content-Type, or null when there is none (or it failed) so callers fall back
+ * to their default rendering.
+ */
+export function formatResponse(response: FfiResponse): string | null {
+  const core = getCoreLib();
+  const result = readCStringAndFree(
+    core.pigeon_format_response(
+      Buffer.from(JSON.stringify(response) + "\0", "utf8")
+    ),
+    core.pigeon_free_string
+  );
+
+  try {
+    const parsed = JSON.parse(result);
+    return typeof parsed?.body === "string" ? parsed.body : null;
+  } catch {
+    return null;
+  }
+}
@@ -0,0 +1,104 @@
+import { getCoreLib } from "./core";
+import { readCStringAndFree } from "./bindings";
+import type { FfiRequest, FfiResponse } from "@/types";
+
+export type HistoryEntry = {
+  id: number;
+  spaceId: string | null;
+  space: string | null;
+  timestamp: string;
+  request: FfiRequest;
+  /** As sent, with variables filled in; null for old entries. */
+  sent: FfiRequest | null;
+  response: FfiResponse;
+  /** Saved response, kept regardless of the history limits. */
+  pinned: boolean;
+};
+
+export type HistoryQuery = {
+  space?: string;
+  since?: string;
+  until?: string;
+  /** 404, a class such as "4xx", or "error" for failures and 4xx/5xx. */
+  status?: number | string;
+  method?: string;
+  url?: string;
+  /** Found in the URL, request or response body, ignoring ASCII case. */
+  text?: string;
+  pinned?: boolean;
+  limit?: number;
+};
+
+/** History entries matching `query`, newest first; errors read as no entries. */
+export function getHistory(query: HistoryQuery = {}): HistoryEntry[] {
+  const core = getCoreLib();
+  const result = readCStringAndFree(
+    core.pigeon_history(Buffer.from(JSON.stringify(query) + "\0", "utf8")),
+    core.pigeon_free_string
+  );
+
+  try {
+    const parsed = JSON.parse(result);
+    return Array.isArray(parsed?.entries) ? parsed.entries : [];
+  } catch {
+    return [];
+  }
+}
+
+/** Keep entry `id` as a saved response, or release it. */
+export function pinHistoryEntry(
+  id: number,
+  pinned: boolean
+): { error?: string } {
+  const core = getCoreLib();
+  const result = readCStringAndFree(
+    core.pigeon_history_pin(id, pinned),
+    core.pigeon_free_string
+  );
+
+  try {
+    const parsed = JSON.parse(result);
+    if (parsed?.success) return {};
+    return { error: String(parsed?.error ?? "pinning failed") };
+  } catch {
+    return { error: "invalid response from core" };
+  }
+}
+
+/** Delete entry `id`, pinned or not. */
+export function deleteHistoryEntry(id: number): { error?: string } {
+  const core = getCoreLib();
+  const result = readCStringAndFree(
+    core.pigeon_history_delete(id),
+    core.pigeon_free_string
+  );
+
+  try {
+    const parsed = JSON.parse(result);
+    if (parsed?.success) return {};
+    return { error: String(parsed?.error ?? "deleting failed") };
+  } catch {
+    return { error: "invalid response from core" };
+  }
+}
+
+/** Delete a space's entries (every space's if unset), keeping pinned ones. */
+export function clearHistory(
+  space?: string
+): { deleted: number } | { error: string } {
+  const core = getCoreLib();
+  const result = readCStringAndFree(
+    core.pigeon_history_clear(
+      space === undefined ? null : Buffer.from(space + "\0", "utf8")
+    ),
+    core.pigeon_free_string
+  );
+
+  try {
+    const parsed = JSON.parse(result);
+    if (typeof parsed?.deleted === "number") return { deleted: parsed.deleted };
+    return { error: String(parsed?.error ?? "clearing failed") };
+  } catch {
+    return { error: "invalid response from core" };
+  }
+}
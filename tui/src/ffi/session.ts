@@ -0,0 +1,69 @@
+import { getCoreLib } from "./core";
+import { readCStringAndFree } from "./bindings";
+import type { RequestHeader } from "@/types";
+
+/**
+ * An open tab's request draft, with secret header values masked; responses
+ * aren't kept.
+ */
+export type SessionTab = {
+  method: string;
+  url: string;
+  headers: RequestHeader[];
+  contentType: string;
+  body: string;
+};
+
+/**
+ * Where the app was when it closed. `window` and `libraryTab` belong to
+ * windowed hosts; they are kept as saved, since the terminal has neither.
+ */
+export type Session = {
+  window?: unknown;
+  /** Id of the space that was current. */
+  space?: string | null;
+  tabs: SessionTab[];
+  activeTab: number;
+  libraryTab?: string | null;
+  theme?: string | null;
+};
+
+/** The session saved on the last quit; null when there is none. */
+export function loadSession(): Session | null {
+  const core = getCoreLib();
+  const result = readCStringAndFree(
+    core.pigeon_session(),
+    core.pigeon_free_string
+  );
+
+  try {
+    const session = JSON.parse(result)?.session;
+    if (!session || typeof session !== "object") return null;
+    return {
+      ...session,
+      tabs: Array.isArray(session.tabs) ? session.tabs : [],
+      activeTab: typeof session.activeTab === "number" ? session.activeTab : 0,
+    };
+  } catch {
+    return null;
+  }
+}
+
+/** Save `session` for the next launch, replacing the saved one. */
+export function saveSession(session: Session): { error?: string } {
+  const core = getCoreLib();
+  const result = readCStringAndFree(
+    core.pigeon_session_save(
+      Buffer.from(JSON.stringify(session) + "\0", "utf8")
+    ),
+    core.pigeon_free_string
+  );
+
+  try {
+    const parsed = JSON.parse(result);
+    if (parsed?.success) return {};
+    return { error: String(parsed?.error ?? "saving the session failed") };
+  } catch {
+    return { error: "invalid response from core" };
+  }
+}
@@ -0,0 +1,55 @@
+import { getCoreLib } from "./core";
+import { readCStringAndFree } from "./bindings";
+
+export type SettingValue = number | string | boolean | null;
+
+/** A setting the settings view can change, as the core reports it. */
+export type Setting = {
+  /** Dotted config path, e.g. "http.timeout". */
+  path: string;
+  type: "number" | "string" | "boolean" | "choice";
+  choices?: string[];
+  /** In effect: the override if there is one, else `config`. */
+  value: SettingValue;
+  /** What config.lua sets; null when it doesn't. */
+  config: SettingValue;
+  overridden: boolean;
+};
+
+function parseSettings(result: string): Setting[] | { error: string } {
+  try {
+    const parsed = JSON.parse(result);
+    if (typeof parsed?.error === "string") return { error: parsed.error };
+    return Array.isArray(parsed?.settings) ? parsed.settings : [];
+  } catch {
+    return { error: "invalid response from core" };
+  }
+}
+
+/** The editable settings; errors read as none. */
+export function getSettings(): Setting[] {
+  const core = getCoreLib();
+  const settings = parseSettings(
+    readCStringAndFree(core.pigeon_settings(), core.pigeon_free_string)
+  );
+  return Array.isArray(settings) ? settings : [];
+}
+
+/**
+ * Override `path` over config.lua, saved across launches; null goes back to
+ * the config's value. Returns the settings as they are afterwards.
+ */
+export function setSetting(
+  path: string,
+  value: SettingValue
+): Setting[] | { error: string } {
+  const core = getCoreLib();
+  return parseSettings(
+    readCStringAndFree(
+      core.pigeon_settings_set(
+        Buffer.from(JSON.stringify({ path, value }) + "\0", "utf8")
+      ),
+      core.pigeon_free_string
+    )
+  );
+}
@@ -0,0 +1,39 @@
+import { getCoreLib } from "./core";
+import { readCStringAndFree } from "./bindings";
+
+/** What the core is busy with, from `pigeon_status`. */
+export type CoreStatus = {
+  /** Requests being sent, from the TUI, Lua or a collection run. */
+  inFlight: number;
+  /** Body bytes those requests have received so far. */
+  received: number;
+  /** `pigeon.schedule` jobs. */
+  jobs: number;
+  run: { name: string; done: number; total: number } | null;
+  /** The mock server's base URL while it runs. */
+  mock: string | null;
+  profiles: string[];
+};
+
+const idle: CoreStatus = {
+  inFlight: 0,
+  received: 0,
+  jobs: 0,
+  run: null,
+  mock: null,
+  profiles: [],
+};
+
+export function getStatus(): CoreStatus {
+  const core = getCoreLib();
+  const result = readCStringAndFree(
+    core.pigeon_status(),
+    core.pigeon_free_string
+  );
+
+  try {
+    return { ...idle, ...JSON.parse(result) };
+  } catch {
+    return idle;
+  }
+}
@@ -1,13 +1,17 @@
 import type { FfiRequest, FfiResponse } from "@/types";
+import type { GraphqlSchema } from "@/graphql";
 import { loadRustLib, readCStringAndFree } from "./bindings";
 
 // `self` is the worker global in this module context.
 declare const self: any;
 
-type SendMessage = { type: "send"; id: number; request: FfiRequest };
+type SendMessage =
+  | { type: "send"; id: number; request: FfiRequest }
+  | { type: "schema"; id: number; request: FfiRequest };
 
 type ResultMessage =
   | { type: "result"; id: number; ok: true; response: FfiResponse }
+  | { type: "result"; id: number; ok: true; schema: GraphqlSchema }
   | { type: "result"; id: number; ok: false; error: string };
 
 const lib = loadRustLib();
@@ -23,12 +27,48 @@ function toRequestJson(req: FfiRequest): string {
           content: req.body.content ?? "",
         }
       : undefined,
+    auth: req.auth,
   });
 }
 
+/** Introspect `request`'s endpoint; runs here since it waits on the network. */
+function loadSchema(msg: SendMessage): ResultMessage {
+  const json = JSON.stringify({
+    url: msg.request.url,
+    headers: msg.request.headers ?? [],
+    auth: msg.request.auth,
+  });
+  const ptr = lib.graphqlSchemaPtr(Buffer.from(json + "\0", "utf8"));
+  const parsed = JSON.parse(readCStringAndFree(ptr, lib.freeString));
+  if (parsed?.schema) {
+    return { type: "result", id: msg.id, ok: true, schema: parsed.schema };
+  }
+  return {
+    type: "result",
+    id: msg.id,
+    ok: false,
+    error: String(parsed?.error ?? "introspection failed"),
+  };
+}
+
 self.onmessage = (event: MessageEvent<SendMessage>) => {
   const msg = event.data;
-  if (!msg || msg.type !== "send") return;
+  if (!msg) return;
+  if (msg.type === "schema") {
+    try {
+      self.postMessage(loadSchema(msg));
+    } catch (e) {
+      const result: ResultMessage = {
+        type: "result",
+        id: msg.id,
+        ok: false,
+        error: e instanceof Error ? e.message : String(e),
+      };
+      self.postMessage(result);
+    }
+    return;
+  }
+  if (msg.type !== "send") return;
 
   try {
     const json = toRequestJson(msg.request);
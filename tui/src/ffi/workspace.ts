@@ -0,0 +1,523 @@
+import { getCoreLib } from "./core";
+import { readCStringAndFree } from "./bindings";
+import type { FfiRequest, RequestHeader } from "@/types";
+
+// Field names follow the core's workspace model (snake_case).
+export type WorkspaceEndpoint = {
+  id: string;
+  name: string;
+  url: string;
+  method: string;
+  folder_id?: string | null;
+  tags?: string[];
+  /** Starred: listed first and under Favorites in the sidebar. */
+  favorite?: boolean;
+  /** Markdown, shown in the request's Info tab. */
+  notes?: string;
+};
+
+export type WorkspaceFolder = {
+  id: string;
+  name: string;
+  parent_id?: string | null;
+  collapsed?: boolean;
+};
+
+export type WorkspaceKeyValue = {
+  key: string;
+  value: string;
+  enabled: boolean;
+};
+
+/** A saved request: a method and url plus the params, headers, body and auth sent with them. */
+export type WorkspaceRequest = {
+  id: string;
+  name: string;
+  endpoint_id?: string | null;
+  method: string;
+  url: string;
+  params: WorkspaceKeyValue[];
+  headers: WorkspaceKeyValue[];
+  body?: { content_type: string; content: string } | null;
+  auth?: { type: string; params: Record<string, string> } | null;
+  folder_id?: string | null;
+};
+
+export type WorkspaceHeader = {
+  id: string;
+  name: string;
+  key: string;
+  value: string;
+  enabled: boolean;
+  tags?: string[];
+  /** Stored encrypted; show it masked. */
+  secret?: boolean;
+};
+
+export type WorkspaceBody = {
+  id: string;
+  name: string;
+  content_type: string;
+  content: string;
+  tags?: string[];
+};
+
+export type WorkspaceSpace = {
+  id: string;
+  name: string;
+  selected_endpoint_id: string | null;
+  selected_header_ids: string[];
+  selected_body_id: string | null;
+  /** Changes to the selected items that apply to this space only. */
+  overrides?: {
+    method?: string | null;
+    url?: string | null;
+    host?: string | null;
+    /** Header values by header id. */
+    headers?: Record<string, string>;
+    body?: string | null;
+  };
+  /** One-off headers, merged over the selected library headers when sent. */
+  headers?: WorkspaceKeyValue[];
+  /** Markdown, shown in the Info tab of requests to its endpoint. */
+  notes?: string;
+  /** How responses are shown while it's the current space. */
+  response_view?: ResponseView;
+  /** Hidden from the sidebar and never the default space. */
+  archived?: boolean;
+};
+
+export type ResponseView = "raw" | "pretty" | "preview";
+
+export type WorkspaceEnvironment = {
+  id: string;
+  name: string;
+  variables: Record<string, string>;
+  /** Names of variables stored encrypted. */
+  secrets?: string[];
+};
+
+export type Workspace = {
+  endpoints: WorkspaceEndpoint[];
+  headers: WorkspaceHeader[];
+  bodies: WorkspaceBody[];
+  spaces: WorkspaceSpace[];
+  environments: WorkspaceEnvironment[];
+  folders: WorkspaceFolder[];
+  requests: WorkspaceRequest[];
+};
+
+const emptyWorkspace: Workspace = {
+  endpoints: [],
+  headers: [],
+  bodies: [],
+  spaces: [],
+  environments: [],
+  folders: [],
+  requests: [],
+};
+
+/** The core's workspace, including endpoints the config added via `pigeon.workspace`. */
+export function getWorkspace(): Workspace {
+  const core = getCoreLib();
+  const result = readCStringAndFree(
+    core.pigeon_workspace(),
+    core.pigeon_free_string
+  );
+
+  try {
+    const parsed = JSON.parse(result);
+    return { ...emptyWorkspace, ...(parsed?.workspace ?? {}) };
+  } catch {
+    return emptyWorkspace;
+  }
+}
+
+export type WorkspaceItemKind = "endpoint" | "header" | "body" | "space";
+
+/**
+ * Edit a library item or space (by id or name) in place; fields left out are kept.
+ * Spaces refer to items by id, so renames carry over to them.
+ */
+export function updateWorkspaceItem(
+  kind: WorkspaceItemKind,
+  key: string,
+  changes: Record<string, unknown>
+): { workspace: Workspace } | { error: string } {
+  const core = getCoreLib();
+  const result = readCStringAndFree(
+    core.pigeon_workspace_update(
+      Buffer.from(kind + "\0", "utf8"),
+      Buffer.from(key + "\0", "utf8"),
+      Buffer.from(JSON.stringify(changes) + "\0", "utf8")
+    ),
+    core.pigeon_free_string
+  );
+
+  try {
+    const parsed = JSON.parse(result);
+    if (typeof parsed?.error === "string") return { error: parsed.error };
+    return { workspace: { ...emptyWorkspace, ...(parsed?.workspace ?? {}) } };
+  } catch {
+    return { error: "invalid response from core" };
+  }
+}
+
+/**
+ * Move an endpoint, header, body, space or saved request (by id or name) to
+ * `index` in its list; the order is saved and front ends list items in it.
+ */
+export function moveWorkspaceItem(
+  kind: WorkspaceItemKind | "space" | "request",
+  key: string,
+  index: number
+): { workspace: Workspace } | { error: string } {
+  const core = getCoreLib();
+  const result = readCStringAndFree(
+    core.pigeon_workspace_move(
+      Buffer.from(kind + "\0", "utf8"),
+      Buffer.from(key + "\0", "utf8"),
+      index
+    ),
+    core.pigeon_free_string
+  );
+
+  try {
+    const parsed = JSON.parse(result);
+    if (typeof parsed?.error === "string") return { error: parsed.error };
+    return { workspace: { ...emptyWorkspace, ...(parsed?.workspace ?? {}) } };
+  } catch {
+    return { error: "invalid response from core" };
+  }
+}
+
+/**
+ * Remove an endpoint, header, body, space or saved request (by id or name).
+ * Spaces stop selecting a removed item; undo brings it back.
+ */
+export function removeWorkspaceItem(
+  kind: WorkspaceItemKind | "request",
+  key: string
+): { workspace: Workspace } | { error: string } {
+  const core = getCoreLib();
+  const result = readCStringAndFree(
+    core.pigeon_workspace_remove(
+      Buffer.from(kind + "\0", "utf8"),
+      Buffer.from(key + "\0", "utf8")
+    ),
+    core.pigeon_free_string
+  );
+
+  try {
+    const parsed = JSON.parse(result);
+    if (typeof parsed?.error === "string") return { error: parsed.error };
+    return { workspace: { ...emptyWorkspace, ...(parsed?.workspace ?? {}) } };
+  } catch {
+    return { error: "invalid response from core" };
+  }
+}
+
+export type BulkOp =
+  | { op: "remove"; kind: WorkspaceItemKind | "request"; key: string }
+  | {
+      op: "update";
+      kind: WorkspaceItemKind;
+      key: string;
+      changes: Record<string, unknown>;
+    };
+
+/**
+ * Remove or edit several items as one change that a single undo reverts.
+ * Nothing changes if any step fails.
+ */
+export function bulkEditWorkspace(
+  ops: BulkOp[]
+): { workspace: Workspace } | { error: string } {
+  const core = getCoreLib();
+  const result = readCStringAndFree(
+    core.pigeon_workspace_bulk(
+      Buffer.from(JSON.stringify(ops) + "\0", "utf8")
+    ),
+    core.pigeon_free_string
+  );
+
+  try {
+    const parsed = JSON.parse(result);
+    if (typeof parsed?.error === "string") return { error: parsed.error };
+    return { workspace: { ...emptyWorkspace, ...(parsed?.workspace ?? {}) } };
+  } catch {
+    return { error: "invalid response from core" };
+  }
+}
+
+/**
+ * Copy an endpoint, header, body, space or saved request (by id or name); the
+ * copy is listed right after it as "<name> copy".
+ */
+export function duplicateWorkspaceItem(
+  kind: WorkspaceItemKind | "request",
+  key: string
+): { workspace: Workspace } | { error: string } {
+  const core = getCoreLib();
+  const result = readCStringAndFree(
+    core.pigeon_workspace_duplicate(
+      Buffer.from(kind + "\0", "utf8"),
+      Buffer.from(key + "\0", "utf8")
+    ),
+    core.pigeon_free_string
+  );
+
+  try {
+    const parsed = JSON.parse(result);
+    if (typeof parsed?.error === "string") return { error: parsed.error };
+    return { workspace: { ...emptyWorkspace, ...(parsed?.workspace ?? {}) } };
+  } catch {
+    return { error: "invalid response from core" };
+  }
+}
+
+/**
+ * Add a space with `fields` set (e.g. `selected_endpoint_id`); without a name
+ * it's called "Space 1", "Space 2", ...
+ */
+export function createSpace(
+  fields: Partial<WorkspaceSpace> = {}
+): { id: string; workspace: Workspace } | { error: string } {
+  const core = getCoreLib();
+  const result = readCStringAndFree(
+    core.pigeon_space_create(
+      Buffer.from(JSON.stringify(fields) + "\0", "utf8")
+    ),
+    core.pigeon_free_string
+  );
+
+  try {
+    const parsed = JSON.parse(result);
+    if (typeof parsed?.error === "string") return { error: parsed.error };
+    return {
+      id: String(parsed?.id),
+      workspace: { ...emptyWorkspace, ...(parsed?.workspace ?? {}) },
+    };
+  } catch {
+    return { error: "invalid response from core" };
+  }
+}
+
+/** What a space (by id or name) sends, with its overrides and headers applied. */
+export function spaceRequest(
+  space: string
+): { request: FfiRequest } | { error: string } {
+  const core = getCoreLib();
+  const result = readCStringAndFree(
+    core.pigeon_space_request(Buffer.from(space + "\0", "utf8")),
+    core.pigeon_free_string
+  );
+
+  try {
+    const parsed = JSON.parse(result);
+    if (typeof parsed?.error === "string") return { error: parsed.error };
+    return { request: parsed.request };
+  } catch {
+    return { error: "invalid response from core" };
+  }
+}
+
+export type LibraryMatches = {
+  endpoints: WorkspaceEndpoint[];
+  headers: WorkspaceHeader[];
+  bodies: WorkspaceBody[];
+};
+
+/**
+ * Library items matching every word of `query`: `#tag` by tag, other words by
+ * name, URL, method or key (case-insensitive substrings).
+ */
+export function searchWorkspace(query: string): LibraryMatches {
+  const core = getCoreLib();
+  const result = readCStringAndFree(
+    core.pigeon_workspace_search(Buffer.from(query + "\0", "utf8")),
+    core.pigeon_free_string
+  );
+
+  try {
+    const parsed = JSON.parse(result);
+    return {
+      endpoints: parsed?.endpoints ?? [],
+      headers: parsed?.headers ?? [],
+      bodies: parsed?.bodies ?? [],
+    };
+  } catch {
+    return { endpoints: [], headers: [], bodies: [] };
+  }
+}
+
+/** Revert (`"undo"`) or reapply (`"redo"`) the last edit; returns its label. */
+export function stepWorkspace(
+  direction: "undo" | "redo"
+): { label: string } | { error: string } {
+  const core = getCoreLib();
+  const result = readCStringAndFree(
+    direction === "undo"
+      ? core.pigeon_workspace_undo()
+      : core.pigeon_workspace_redo(),
+    core.pigeon_free_string
+  );
+
+  try {
+    const parsed = JSON.parse(result);
+    if (typeof parsed?.label === "string") return { label: parsed.label };
+    return { error: String(parsed?.error ?? `nothing to ${direction}`) };
+  } catch {
+    return { error: "invalid response from core" };
+  }
+}
+
+/** The whole workspace as a versioned JSON document, for `importWorkspace`. */
+export function exportWorkspace(): { data: string } | { error: string } {
+  const core = getCoreLib();
+  const result = readCStringAndFree(
+    core.pigeon_workspace_export(),
+    core.pigeon_free_string
+  );
+
+  try {
+    const parsed = JSON.parse(result);
+    if (typeof parsed?.data === "string") return { data: parsed.data };
+    return { error: String(parsed?.error ?? "export failed") };
+  } catch {
+    return { error: "invalid response from core" };
+  }
+}
+
+/** Replace the workspace with an `exportWorkspace` document (undoable). */
+export function importWorkspace(
+  data: string
+): { workspace: Workspace } | { error: string } {
+  const core = getCoreLib();
+  const result = readCStringAndFree(
+    core.pigeon_workspace_import(Buffer.from(data + "\0", "utf8")),
+    core.pigeon_free_string
+  );
+
+  try {
+    const parsed = JSON.parse(result);
+    if (parsed?.workspace) return { workspace: parsed.workspace };
+    return { error: String(parsed?.error ?? "import failed") };
+  } catch {
+    return { error: "invalid response from core" };
+  }
+}
+
+/** Whether nothing had been saved yet, so the workspace is the sample one. */
+export function isFirstRun(): boolean {
+  const core = getCoreLib();
+  const result = readCStringAndFree(
+    core.pigeon_workspace_first_run(),
+    core.pigeon_free_string
+  );
+
+  try {
+    return JSON.parse(result)?.firstRun === true;
+  } catch {
+    return false;
+  }
+}
+
+export type WorkspaceSetup = {
+  /** Start from the sample endpoints instead of an empty library. */
+  sample: boolean;
+  import?: { kind: "curl" | "postman" | "openapi"; data: string };
+  /** First space's name; the core picks one when left out. */
+  space?: string;
+};
+
+/**
+ * Replace the workspace with a fresh one set up as chosen on first launch
+ * (undoable). `imported` is the number of endpoints the import added.
+ */
+export function setupWorkspace(
+  setup: WorkspaceSetup
+): { workspace: Workspace; imported: number } | { error: string } {
+  const core = getCoreLib();
+  const result = readCStringAndFree(
+    core.pigeon_workspace_setup(
+      Buffer.from(JSON.stringify(setup) + "\0", "utf8")
+    ),
+    core.pigeon_free_string
+  );
+
+  try {
+    const parsed = JSON.parse(result);
+    if (parsed?.workspace) {
+      return {
+        workspace: parsed.workspace,
+        imported: Number(parsed.imported ?? 0),
+      };
+    }
+    return { error: String(parsed?.error ?? "setup failed") };
+  } catch {
+    return { error: "invalid response from core" };
+  }
+}
+
+/** `value` as it may be shown on screen: masked when it is a secret. */
+export function displayValue(value: string, secret?: boolean): string {
+  return secret && value.length > 0 ? "••••••••" : value;
+}
+
+/**
+ * `headers` with the values of secret ones, which history and the session keep
+ * masked, filled in from what `space` sends now. Secret headers the space no
+ * longer has are dropped rather than sent masked.
+ */
+export function withSecretValues(
+  headers: RequestHeader[],
+  space?: string | null
+): RequestHeader[] {
+  if (!headers.some((h) => h.secret)) return headers;
+  const resolved = space ? spaceRequest(space) : null;
+  const current =
+    resolved && "request" in resolved ? resolved.request.headers : [];
+  return headers.flatMap((header) => {
+    if (!header.secret) return [header];
+    const key = header.key.toLowerCase();
+    const found = current.find((h) => h.secret && h.key.toLowerCase() === key);
+    return found ? [{ ...header, value: found.value }] : [];
+  });
+}
+
+/** Whether secrets are locked, and how many values are still encrypted. */
+export function secretsStatus(): { locked: boolean; sealed: number } {
+  const core = getCoreLib();
+  const result = readCStringAndFree(
+    core.pigeon_secrets_status(),
+    core.pigeon_free_string
+  );
+
+  try {
+    const parsed = JSON.parse(result);
+    return {
+      locked: parsed?.locked !== false,
+      sealed: Number(parsed?.sealed ?? 0),
+    };
+  } catch {
+    return { locked: true, sealed: 0 };
+  }
+}
+
+/** Unlock secret values with the master passphrase. */
+export function unlockSecrets(passphrase: string): { error?: string } {
+  const core = getCoreLib();
+  const result = readCStringAndFree(
+    core.pigeon_secrets_unlock(Buffer.from(passphrase + "\0", "utf8")),
+    core.pigeon_free_string
+  );
+
+  try {
+    const parsed = JSON.parse(result);
+    if (parsed?.success) return {};
+    return { error: String(parsed?.error ?? "unlock failed") };
+  } catch {
+    return { error: "invalid response from core" };
+  }
+}
@@ -0,0 +1,518 @@
+/**
+ * GraphQL query checks and completion for the request body editor, against a
+ * schema from `fetchGraphqlSchema`. Without one only the syntax is checked.
+ */
+
+export type GraphqlSchema = {
+  queryType: string | null;
+  mutationType: string | null;
+  subscriptionType: string | null;
+  types: GraphqlType[];
+};
+
+export type GraphqlType = {
+  name: string;
+  /** `OBJECT`, `INTERFACE`, `UNION`, `ENUM`, `INPUT_OBJECT` or `SCALAR`. */
+  kind: string;
+  description: string | null;
+  fields: GraphqlField[];
+  inputFields: GraphqlInput[];
+  enumValues: string[];
+  possibleTypes: string[];
+};
+
+export type GraphqlField = {
+  name: string;
+  /** As written in GraphQL, e.g. `[User!]!`. */
+  type: string;
+  description: string | null;
+  args: GraphqlInput[];
+  deprecated: boolean;
+};
+
+export type GraphqlInput = {
+  name: string;
+  type: string;
+  description: string | null;
+  defaultValue: string | null;
+};
+
+/** What is wrong with a query and where; `line` and `col` count from 1. */
+export type GraphqlProblem = {
+  index: number;
+  line: number;
+  col: number;
+  message: string;
+};
+
+/** The type a reference names, without list and non-null wrappers. */
+export function namedType(ref: string): string {
+  return ref.replace(/[[\]!]/g, "");
+}
+
+/** Whether fields are selected on `type` (objects, interfaces and unions). */
+export function hasSelection(type: GraphqlType): boolean {
+  return ["OBJECT", "INTERFACE", "UNION"].includes(type.kind);
+}
+
+type Token = {
+  kind: "name" | "punct" | "string" | "number" | "variable";
+  text: string;
+  index: number;
+};
+
+/** What the parser wanted next when the text ran out; drives completion. */
+type Expectation =
+  | { kind: "definition" }
+  | { kind: "field"; type: GraphqlType | null }
+  | { kind: "argument"; field: GraphqlField | null }
+  | { kind: "value"; type: string | null }
+  | { kind: "type"; within: GraphqlType | null }
+  | { kind: "other" };
+
+class ProblemAt extends Error {
+  index: number;
+
+  constructor(index: number, message: string) {
+    super(message);
+    this.index = index;
+  }
+}
+
+class EndOfInput extends Error {
+  expectation: Expectation;
+
+  constructor(expectation: Expectation) {
+    super("Unexpected end of the query");
+    this.expectation = expectation;
+  }
+}
+
+const NAME = /[_A-Za-z][_0-9A-Za-z]*/y;
+const NUMBER = /-?\d+(?:\.\d+)?(?:[eE][+-]?\d+)?/y;
+const VARIABLE = /\$[_A-Za-z][_0-9A-Za-z]*/y;
+
+function tokenize(text: string): Token[] {
+  const tokens: Token[] = [];
+  let pos = 0;
+  const sticky = (re: RegExp) => {
+    re.lastIndex = pos;
+    return re.exec(text)?.[0];
+  };
+  while (pos < text.length) {
+    const ch = text[pos]!;
+    // Commas are insignificant in GraphQL, like whitespace.
+    if (/[\s,]/.test(ch)) {
+      pos++;
+    } else if (ch === "#") {
+      const end = text.indexOf("\n", pos);
+      pos = end < 0 ? text.length : end;
+    } else if (text.startsWith("...", pos)) {
+      tokens.push({ kind: "punct", text: "...", index: pos });
+      pos += 3;
+    } else if ("{}()[]:=@!|&".includes(ch)) {
+      tokens.push({ kind: "punct", text: ch, index: pos });
+      pos++;
+    } else if (ch === '"') {
+      const block = text.startsWith('"""', pos);
+      let end = block ? text.indexOf('"""', pos + 3) : pos + 1;
+      if (!block) {
+        while (end < text.length && text[end] !== '"' && text[end] !== "\n") {
+          end += text[end] === "\\" ? 2 : 1;
+        }
+        if (text[end] !== '"') end = -1;
+      }
+      if (end < 0) throw new ProblemAt(pos, "Unterminated string");
+      end += block ? 3 : 1;
+      tokens.push({ kind: "string", text: text.slice(pos, end), index: pos });
+      pos = end;
+    } else {
+      const variable = sticky(VARIABLE);
+      const number = variable ? undefined : sticky(NUMBER);
+      const name = variable || number ? undefined : sticky(NAME);
+      const match = variable ?? number ?? name;
+      if (!match) throw new ProblemAt(pos, `Unexpected "${ch}"`);
+      tokens.push({
+        kind: variable ? "variable" : number ? "number" : "name",
+        text: match,
+        index: pos,
+      });
+      pos += match.length;
+    }
+  }
+  return tokens;
+}
+
+const schemaTypes = new WeakMap<GraphqlSchema, Map<string, GraphqlType>>();
+
+function typesOf(schema: GraphqlSchema): Map<string, GraphqlType> {
+  let types = schemaTypes.get(schema);
+  if (!types) {
+    types = new Map(schema.types.map((t) => [t.name, t]));
+    schemaTypes.set(schema, types);
+  }
+  return types;
+}
+
+/**
+ * Recursive descent over a document. Syntax errors throw; schema mismatches
+ * are noted (the first one kept) and parsing goes on. Running out of tokens
+ * throws `EndOfInput` with what was expected there.
+ */
+class Parser {
+  private i = 0;
+  private readonly tokens: Token[];
+  private readonly schema: GraphqlSchema | null;
+  private readonly types: Map<string, GraphqlType> | null;
+  problem: { index: number; message: string } | null = null;
+
+  constructor(text: string, schema: GraphqlSchema | null) {
+    this.tokens = tokenize(text);
+    this.schema = schema;
+    this.types = schema ? typesOf(schema) : null;
+  }
+
+  private peek(expect: Expectation): Token {
+    const token = this.tokens[this.i];
+    if (!token) throw new EndOfInput(expect);
+    return token;
+  }
+
+  private next(expect: Expectation): Token {
+    const token = this.peek(expect);
+    this.i++;
+    return token;
+  }
+
+  private is(token: Token, punct: string): boolean {
+    return token.kind === "punct" && token.text === punct;
+  }
+
+  private expect(punct: string, expect: Expectation) {
+    const token = this.next(expect);
+    if (!this.is(token, punct)) {
+      throw new ProblemAt(token.index, `Expected "${punct}"`);
+    }
+  }
+
+  private report(index: number, message: string) {
+    this.problem ??= { index, message };
+  }
+
+  private type(name: string | null): GraphqlType | null {
+    return name ? this.types?.get(name) ?? null : null;
+  }
+
+  document() {
+    while (this.i < this.tokens.length) {
+      const token = this.next({ kind: "definition" });
+      if (this.is(token, "{")) {
+        this.i--;
+        this.selectionSet(this.type(this.rootType("query")));
+      } else if (
+        token.text === "query" ||
+        token.text === "mutation" ||
+        token.text === "subscription"
+      ) {
+        const root = this.rootType(token.text);
+        if (this.schema && !root) {
+          this.report(token.index, `The schema has no ${token.text} type`);
+        }
+        if (this.peek({ kind: "other" }).kind === "name") this.i++;
+        if (this.is(this.peek({ kind: "other" }), "(")) this.skipGroup();
+        this.directives({ kind: "other" });
+        this.selectionSet(this.type(root));
+      } else if (token.text === "fragment") {
+        this.next({ kind: "other" });
+        const on = this.next({ kind: "other" });
+        if (on.text !== "on") throw new ProblemAt(on.index, 'Expected "on"');
+        const type = this.typeCondition(null);
+        this.directives({ kind: "other" });
+        this.selectionSet(type);
+      } else {
+        throw new ProblemAt(
+          token.index,
+          'Expected "query", "mutation", "subscription", "fragment" or "{"'
+        );
+      }
+    }
+  }
+
+  private rootType(operation: string): string | null {
+    if (!this.schema) return null;
+    if (operation === "mutation") return this.schema.mutationType;
+    if (operation === "subscription") return this.schema.subscriptionType;
+    return this.schema.queryType;
+  }
+
+  /** The type named next, after `on`. */
+  private typeCondition(within: GraphqlType | null): GraphqlType | null {
+    const name = this.next({ kind: "type", within });
+    if (name.kind !== "name") {
+      throw new ProblemAt(name.index, "Expected a type name");
+    }
+    const type = this.type(name.text);
+    if (this.types && !type) {
+      this.report(name.index, `Unknown type "${name.text}"`);
+    }
+    return type;
+  }
+
+  private selectionSet(type: GraphqlType | null) {
+    this.expect("{", { kind: "other" });
+    for (;;) {
+      const token = this.peek({ kind: "field", type });
+      if (this.is(token, "}")) {
+        this.i++;
+        return;
+      }
+      if (this.is(token, "...")) {
+        this.i++;
+        this.fragment(type);
+      } else if (token.kind === "name") {
+        this.field(type);
+      } else {
+        throw new ProblemAt(token.index, "Expected a field");
+      }
+    }
+  }
+
+  private fragment(parent: GraphqlType | null) {
+    const token = this.peek({ kind: "other" });
+    if (token.text === "on") {
+      this.i++;
+      const type = this.typeCondition(parent);
+      this.directives({ kind: "other" });
+      this.selectionSet(type);
+    } else if (token.kind === "name") {
+      this.i++;
+      this.directives({ kind: "field", type: parent });
+    } else {
+      this.directives({ kind: "other" });
+      this.selectionSet(parent);
+    }
+  }
+
+  private field(parent: GraphqlType | null) {
+    const after: Expectation = { kind: "field", type: parent };
+    let name = this.next(after);
+    if (this.is(this.peek(after), ":")) {
+      this.i++;
+      name = this.next(after);
+      if (name.kind !== "name") {
+        throw new ProblemAt(name.index, "Expected a field");
+      }
+    }
+    const field = parent?.fields.find((f) => f.name === name.text) ?? null;
+    if (parent && !field && name.text !== "__typename") {
+      this.report(
+        name.index,
+        `No field "${name.text}" on type "${parent.name}"`
+      );
+    }
+
+    const given = new Set<string>();
+    if (this.is(this.peek(after), "(")) {
+      this.i++;
+      for (;;) {
+        const arg = this.next({ kind: "argument", field });
+        if (this.is(arg, ")")) break;
+        if (arg.kind !== "name") {
+          throw new ProblemAt(arg.index, "Expected an argument");
+        }
+        given.add(arg.text);
+        const input = field?.args.find((a) => a.name === arg.text);
+        if (field && !input) {
+          this.report(
+            arg.index,
+            `No argument "${arg.text}" on field "${field.name}"`
+          );
+        }
+        this.expect(":", { kind: "other" });
+        this.value(input?.type ?? null);
+      }
+    }
+    this.directives(after);
+
+    for (const arg of field?.args ?? []) {
+      if (arg.type.endsWith("!") && !arg.defaultValue && !given.has(arg.name)) {
+        this.report(
+          name.index,
+          `Field "${name.text}" needs argument "${arg.name}: ${arg.type}"`
+        );
+      }
+    }
+    const type = field ? this.type(namedType(field.type)) : null;
+    if (this.i < this.tokens.length && this.is(this.peek(after), "{")) {
+      if (type && !hasSelection(type)) {
+        this.report(
+          name.index,
+          `Field "${name.text}" of type ${type.name} has no fields`
+        );
+      }
+      this.selectionSet(type && hasSelection(type) ? type : null);
+    } else if (type && hasSelection(type)) {
+      this.report(
+        name.index,
+        `Field "${name.text}" of type ${type.name} needs a field selection`
+      );
+    }
+  }
+
+  private value(ref: string | null) {
+    const token = this.next({ kind: "value", type: ref });
+    const inner = ref?.replace(/!$/, "") ?? null;
+    if (this.is(token, "[")) {
+      const item = inner?.startsWith("[") ? inner.slice(1, -1) : null;
+      while (!this.is(this.peek({ kind: "value", type: item }), "]")) {
+        this.value(item);
+      }
+      this.i++;
+    } else if (this.is(token, "{")) {
+      const input = this.type(inner && namedType(inner));
+      for (;;) {
+        const key = this.next({ kind: "other" });
+        if (this.is(key, "}")) break;
+        if (key.kind !== "name") {
+          throw new ProblemAt(key.index, "Expected a field");
+        }
+        const field = input?.inputFields.find((f) => f.name === key.text);
+        if (input && !field) {
+          this.report(
+            key.index,
+            `No field "${key.text}" on input type "${input.name}"`
+          );
+        }
+        this.expect(":", { kind: "other" });
+        this.value(field?.type ?? null);
+      }
+    } else if (token.kind === "name") {
+      const type = this.type(inner && namedType(inner));
+      const literal = ["true", "false", "null"].includes(token.text);
+      if (type?.kind === "ENUM" && !type.enumValues.includes(token.text)) {
+        this.report(
+          token.index,
+          `"${token.text}" is not a value of enum "${type.name}"`
+        );
+      } else if (type && type.kind !== "ENUM" && !literal) {
+        this.report(token.index, `Expected a value of type ${type.name}`);
+      }
+    } else if (token.kind === "punct") {
+      throw new ProblemAt(token.index, "Expected a value");
+    }
+  }
+
+  private directives(after: Expectation) {
+    while (this.i < this.tokens.length && this.is(this.peek(after), "@")) {
+      this.i++;
+      this.next({ kind: "other" });
+      if (this.i < this.tokens.length && this.is(this.peek(after), "(")) {
+        this.skipGroup();
+      }
+    }
+  }
+
+  /** Skip a balanced `( ... )`, e.g. variable definitions. */
+  private skipGroup() {
+    let depth = 0;
+    do {
+      const token = this.next({ kind: "other" });
+      if (this.is(token, "(")) depth++;
+      else if (this.is(token, ")")) depth--;
+    } while (depth > 0);
+  }
+}
+
+function problemAt(
+  text: string,
+  index: number,
+  message: string
+): GraphqlProblem {
+  const before = text.slice(0, index).split("\n");
+  return {
+    index,
+    line: before.length,
+    col: before.at(-1)!.length + 1,
+    message,
+  };
+}
+
+/** The first thing wrong with `query`, checked against `schema` if given. */
+export function graphqlProblem(
+  query: string,
+  schema: GraphqlSchema | null
+): GraphqlProblem | null {
+  if (!query.trim()) return null;
+  let parser: Parser | null = null;
+  let syntax: { index: number; message: string } | null = null;
+  try {
+    parser = new Parser(query, schema);
+    parser.document();
+  } catch (e) {
+    if (e instanceof ProblemAt) syntax = { index: e.index, message: e.message };
+    else if (e instanceof EndOfInput) {
+      syntax = { index: query.trimEnd().length, message: e.message };
+    } else throw e;
+  }
+  const found = [parser?.problem ?? null, syntax]
+    .filter((p): p is { index: number; message: string } => p !== null)
+    .sort((a, b) => a.index - b.index)[0];
+  return found ? problemAt(query, found.index, found.message) : null;
+}
+
+/**
+ * Completions for the word being typed at `index`: field, argument, enum
+ * value or type names that fit there, replacing the text from `from`.
+ */
+export function graphqlCompletions(
+  query: string,
+  index: number,
+  schema: GraphqlSchema
+): { from: number; items: string[] } | null {
+  const prefix = /[_A-Za-z][_0-9A-Za-z]*$/.exec(query.slice(0, index))?.[0];
+  const from = index - (prefix?.length ?? 0);
+  if (query[from - 1] === "$") return null;
+
+  let expectation: Expectation = { kind: "definition" };
+  try {
+    new Parser(query.slice(0, from), schema).document();
+  } catch (e) {
+    if (!(e instanceof EndOfInput)) return null;
+    expectation = e.expectation;
+  }
+
+  const types = typesOf(schema);
+  let names: string[] = [];
+  switch (expectation.kind) {
+    case "definition":
+      names = ["query", "mutation", "subscription", "fragment"];
+      break;
+    case "field":
+      names = [
+        ...(expectation.type?.fields ?? []).map((f) => f.name),
+        "__typename",
+      ];
+      break;
+    case "argument":
+      names = (expectation.field?.args ?? []).map((a) => a.name);
+      break;
+    case "value": {
+      const type = types.get(namedType(expectation.type ?? ""));
+      if (type?.kind === "ENUM") names = type.enumValues;
+      else if (type?.name === "Boolean") names = ["true", "false"];
+      break;
+    }
+    case "type":
+      names =
+        expectation.within && expectation.within.possibleTypes.length > 0
+          ? expectation.within.possibleTypes
+          : schema.types.filter(hasSelection).map((t) => t.name);
+      break;
+  }
+
+  const typed = (prefix ?? "").toLowerCase();
+  const items = names.filter(
+    (name) => name.toLowerCase().startsWith(typed) && name !== prefix
+  );
+  return items.length > 0 ? { from, items } : null;
+}
@@ -0,0 +1,118 @@
+/**
+ * Line-at-a-time syntax highlighting for the response viewer. Each language
+ * is a list of sticky regexes tried in order at every position; text no rule
+ * matches is left plain. Constructs spanning lines (block comments, multi-line
+ * strings) are only colored on the line they start.
+ */
+
+export type Language = "json" | "xml" | "html" | "javascript" | "yaml";
+
+export type Token = {
+  text: string;
+  color?: string;
+  dim?: boolean;
+  bold?: boolean;
+};
+
+type Style = Omit<Token, "text">;
+type Rule = [RegExp, Style];
+
+const STRING: Style = { color: "green" };
+const NUMBER: Style = { color: "cyan" };
+const LITERAL: Style = { color: "yellow" };
+const KEY: Style = { color: "magenta", bold: true };
+const TAG: Style = { color: "blue" };
+const KEYWORD: Style = { color: "magenta" };
+const COMMENT: Style = { dim: true };
+
+const markup: Rule[] = [
+  [/<!--.*?(?:-->|$)/y, COMMENT],
+  [/<!\[CDATA\[.*?(?:\]\]>|$)/y, COMMENT],
+  [/<[?!][\w-]*/y, TAG],
+  [/<\/?[\w:.-]+/y, TAG],
+  [/\??\/?>/y, TAG],
+  [/[\w:.-]+(?==)/y, LITERAL],
+  [/(?<==\s*)(?:"[^"]*"?|'[^']*'?)/y, STRING],
+  [/&(?:#x?[\da-f]+|\w+);/iy, NUMBER],
+];
+
+const rules: Record<Language, Rule[]> = {
+  json: [
+    [/"(?:[^"\\]|\\.)*"(?=\s*:)/y, KEY],
+    [/"(?:[^"\\]|\\.)*"?/y, STRING],
+    [/-?\d+(?:\.\d+)?(?:[eE][+-]?\d+)?/y, NUMBER],
+    [/\b(?:true|false)\b/y, LITERAL],
+    [/\bnull\b/y, COMMENT],
+  ],
+  xml: markup,
+  html: markup,
+  javascript: [
+    [/\/\/.*/y, COMMENT],
+    [/\/\*.*?(?:\*\/|$)/y, COMMENT],
+    [/"(?:[^"\\]|\\.)*"?|'(?:[^'\\]|\\.)*'?|`(?:[^`\\]|\\.)*`?/y, STRING],
+    [
+      /\b(?:async|await|break|case|catch|class|const|continue|default|delete|do|else|export|extends|finally|for|function|if|import|in|instanceof|let|new|of|return|switch|this|throw|try|typeof|var|void|while|yield)\b/y,
+      KEYWORD,
+    ],
+    [/\b(?:true|false|null|undefined|NaN|Infinity)\b/y, LITERAL],
+    [/\b(?:0x[\da-f]+|\d+(?:\.\d+)?(?:e[+-]?\d+)?)\b/iy, NUMBER],
+    [/[A-Za-z_$][\w$]*/y, {}],
+  ],
+  yaml: [
+    [/(?<=^|\s)#.*/y, COMMENT],
+    [/^(?:---|\.\.\.)(?=\s|$)/y, TAG],
+    [/(?<=^\s*(?:-\s+)*)[^\s:#'"-][^:#]*?(?=:(?:\s|$))/y, KEY],
+    [/(?<=^\s*(?:-\s+)*)"(?:[^"\\]|\\.)*"(?=:(?:\s|$))/y, KEY],
+    [/"(?:[^"\\]|\\.)*"?|'[^']*'?/y, STRING],
+    [/[&*][\w-]+/y, TAG],
+    [/!!?[\w-]*/y, TAG],
+    [/\b(?:true|false|yes|no|on|off)\b/iy, LITERAL],
+    [/(?:\bnull\b|~)/y, COMMENT],
+    [/-?\b\d+(?:\.\d+)?(?:e[+-]?\d+)?\b/iy, NUMBER],
+    [/[\w.-]+/y, {}],
+  ],
+};
+
+/** Pick a language from a Content-Type header value; null for anything else. */
+export function languageFor(contentType: string | undefined): Language | null {
+  const mime = (contentType ?? "").split(";")[0]!.trim().toLowerCase();
+  if (!mime) return null;
+  if (mime.includes("json")) return "json";
+  if (mime.includes("html")) return "html";
+  if (mime.includes("xml")) return "xml";
+  if (mime.includes("javascript") || mime.includes("ecmascript")) {
+    return "javascript";
+  }
+  if (mime.includes("yaml") || mime.includes("yml")) return "yaml";
+  return null;
+}
+
+/** Split one line into styled tokens; concatenated they give back `line`. */
+export function highlightLine(line: string, language: Language): Token[] {
+  const tokens: Token[] = [];
+  let plain = "";
+  let pos = 0;
+  outer: while (pos < line.length) {
+    for (const [re, style] of rules[language]) {
+      re.lastIndex = pos;
+      const match = re.exec(line);
+      if (!match || match[0].length === 0) continue;
+      pos += match[0].length;
+      // Unstyled rules only keep words whole (`in` inside `index`).
+      if (Object.keys(style).length === 0) {
+        plain += match[0];
+        continue outer;
+      }
+      if (plain) {
+        tokens.push({ text: plain });
+        plain = "";
+      }
+      tokens.push({ text: match[0], ...style });
+      continue outer;
+    }
+    plain += line[pos];
+    pos += 1;
+  }
+  if (plain) tokens.push({ text: plain });
+  return tokens;
+}
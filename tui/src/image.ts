@@ -0,0 +1,210 @@
+import { inflateSync } from "node:zlib";
+
+export type ImageInfo = { format: string; width: number; height: number };
+
+/** Decoded pixels, 4 bytes (RGBA) per pixel, row by row. */
+export type Bitmap = { width: number; height: number; rgba: Uint8Array };
+
+const PNG_SIGNATURE = [0x89, 0x50, 0x4e, 0x47, 0x0d, 0x0a, 0x1a, 0x0a];
+
+// Samples per pixel by PNG color type.
+const PNG_CHANNELS: Record<number, number> = { 0: 1, 2: 3, 3: 1, 4: 2, 6: 4 };
+
+// Bigger images aren't worth decoding for a terminal preview.
+const MAX_DECODED_PIXELS = 16_000_000;
+
+function startsWith(bytes: Uint8Array, prefix: number[]): boolean {
+  return prefix.every((b, i) => bytes[i] === b);
+}
+
+/** Format and size from the file header, for PNG, GIF, JPEG, WebP and BMP. */
+export function imageInfo(bytes: Uint8Array): ImageInfo | null {
+  const view = new DataView(bytes.buffer, bytes.byteOffset, bytes.byteLength);
+  const ascii = (from: number, to: number) =>
+    String.fromCharCode(...bytes.subarray(from, to));
+  try {
+    if (startsWith(bytes, PNG_SIGNATURE)) {
+      return {
+        format: "PNG",
+        width: view.getUint32(16),
+        height: view.getUint32(20),
+      };
+    }
+    if (ascii(0, 4) === "GIF8") {
+      return {
+        format: "GIF",
+        width: view.getUint16(6, true),
+        height: view.getUint16(8, true),
+      };
+    }
+    if (ascii(0, 2) === "BM") {
+      return {
+        format: "BMP",
+        width: view.getInt32(18, true),
+        height: Math.abs(view.getInt32(22, true)),
+      };
+    }
+    if (ascii(0, 4) === "RIFF" && ascii(8, 12) === "WEBP") {
+      const chunk = ascii(12, 16);
+      if (chunk === "VP8X") {
+        return {
+          format: "WebP",
+          width: 1 + (view.getUint32(24, true) & 0xffffff),
+          height: 1 + (view.getUint32(27, true) & 0xffffff),
+        };
+      }
+      if (chunk === "VP8L") {
+        const bits = view.getUint32(21, true);
+        return {
+          format: "WebP",
+          width: 1 + (bits & 0x3fff),
+          height: 1 + ((bits >> 14) & 0x3fff),
+        };
+      }
+      return {
+        format: "WebP",
+        width: view.getUint16(26, true) & 0x3fff,
+        height: view.getUint16(28, true) & 0x3fff,
+      };
+    }
+    if (bytes[0] === 0xff && bytes[1] === 0xd8) {
+      // Walk the segments to the first start-of-frame marker.
+      let pos = 2;
+      while (pos + 9 < bytes.length) {
+        if (bytes[pos] !== 0xff) return null;
+        const marker = bytes[pos + 1]!;
+        const isFrame =
+          marker >= 0xc0 &&
+          marker <= 0xcf &&
+          marker !== 0xc4 &&
+          marker !== 0xc8 &&
+          marker !== 0xcc;
+        if (isFrame) {
+          return {
+            format: "JPEG",
+            width: view.getUint16(pos + 7),
+            height: view.getUint16(pos + 5),
+          };
+        }
+        pos += 2 + view.getUint16(pos + 2);
+      }
+    }
+  } catch {
+    // Truncated header.
+  }
+  return null;
+}
+
+function paeth(a: number, b: number, c: number): number {
+  const p = a + b - c;
+  const pa = Math.abs(p - a);
+  const pb = Math.abs(p - b);
+  const pc = Math.abs(p - c);
+  return pa <= pb && pa <= pc ? a : pb <= pc ? b : c;
+}
+
+/**
+ * Decode a non-interlaced PNG (any color type and bit depth). Null for other
+ * formats, interlaced images and corrupt data.
+ */
+export function decodePng(bytes: Uint8Array): Bitmap | null {
+  if (!startsWith(bytes, PNG_SIGNATURE)) return null;
+  const view = new DataView(bytes.buffer, bytes.byteOffset, bytes.byteLength);
+  let width = 0;
+  let height = 0;
+  let depth = 0;
+  let colorType = 0;
+  let palette: Uint8Array | null = null;
+  let transparency: Uint8Array | null = null;
+  const data: Uint8Array[] = [];
+
+  try {
+    for (let pos = 8; pos + 8 <= bytes.length; ) {
+      const length = view.getUint32(pos);
+      const type = String.fromCharCode(...bytes.subarray(pos + 4, pos + 8));
+      const chunk = bytes.subarray(pos + 8, pos + 8 + length);
+      if (type === "IHDR") {
+        width = view.getUint32(pos + 8);
+        height = view.getUint32(pos + 12);
+        depth = chunk[8]!;
+        colorType = chunk[9]!;
+        if (chunk[12] !== 0) return null; // interlaced
+      } else if (type === "PLTE") palette = chunk;
+      else if (type === "tRNS") transparency = chunk;
+      else if (type === "IDAT") data.push(chunk);
+      else if (type === "IEND") break;
+      pos += 12 + length;
+    }
+
+    const channels = PNG_CHANNELS[colorType];
+    if (!channels || !width || !height) return null;
+    if (width * height > MAX_DECODED_PIXELS) return null;
+    const bitsPerPixel = channels * depth;
+    const stride = Math.ceil((width * bitsPerPixel) / 8);
+    const step = Math.max(1, bitsPerPixel >> 3);
+    const raw = inflateSync(Buffer.concat(data));
+
+    // Undo the per-line filters in place.
+    const lines = new Uint8Array(height * stride);
+    for (let y = 0; y < height; y++) {
+      const filter = raw[y * (stride + 1)]!;
+      const src = raw.subarray(y * (stride + 1) + 1, (y + 1) * (stride + 1));
+      const out = y * stride;
+      for (let x = 0; x < stride; x++) {
+        const a = x >= step ? lines[out + x - step]! : 0;
+        const b = y > 0 ? lines[out - stride + x]! : 0;
+        const c = x >= step && y > 0 ? lines[out - stride + x - step]! : 0;
+        const predictor =
+          filter === 1
+            ? a
+            : filter === 2
+            ? b
+            : filter === 3
+            ? (a + b) >> 1
+            : filter === 4
+            ? paeth(a, b, c)
+            : 0;
+        lines[out + x] = (src[x]! + predictor) & 0xff;
+      }
+    }
+
+    // Channel `i` of pixel `x` on line `y`, scaled to 0-255.
+    const max = (1 << depth) - 1;
+    const sample = (y: number, x: number, i: number, scale = true) => {
+      const bit = (x * channels + i) * depth;
+      const at = y * stride + (bit >> 3);
+      // 16-bit samples are big-endian; the high byte is enough here.
+      const value =
+        depth >= 8
+          ? lines[at]!
+          : (lines[at]! >> (8 - depth - (bit & 7))) & max;
+      return scale && depth < 8 ? Math.round((value * 255) / max) : value;
+    };
+
+    const rgba = new Uint8Array(width * height * 4);
+    for (let y = 0; y < height; y++) {
+      for (let x = 0; x < width; x++) {
+        const o = (y * width + x) * 4;
+        if (colorType === 3) {
+          const index = sample(y, x, 0, false);
+          rgba[o] = palette?.[index * 3] ?? 0;
+          rgba[o + 1] = palette?.[index * 3 + 1] ?? 0;
+          rgba[o + 2] = palette?.[index * 3 + 2] ?? 0;
+          rgba[o + 3] = transparency?.[index] ?? 255;
+        } else if (colorType === 0 || colorType === 4) {
+          const gray = sample(y, x, 0);
+          rgba[o] = rgba[o + 1] = rgba[o + 2] = gray;
+          rgba[o + 3] = colorType === 4 ? sample(y, x, 1) : 255;
+        } else {
+          rgba[o] = sample(y, x, 0);
+          rgba[o + 1] = sample(y, x, 1);
+          rgba[o + 2] = sample(y, x, 2);
+          rgba[o + 3] = colorType === 6 ? sample(y, x, 3) : 255;
+        }
+      }
+    }
+    return { width, height, rgba };
+  } catch {
+    return null;
+  }
+}
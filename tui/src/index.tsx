@@ -2,9 +2,34 @@ import React from "react";
 import { render } from "ink";
 import { ThemeProvider, defaultTheme } from "@inkjs/ui";
 import { App } from "@/app";
+import {
+  applyConfigLocale,
+  applyConfigThemes,
+  loadConfig,
+} from "@/ffi/confi";
+import { loadSession } from "@/ffi/session";
+import { applyTheme } from "@/ui/theme";
+
+/** `--profile work` or `--profile=work,ci`; undefined when not given. */
+function profileArg(argv: string[]): string | undefined {
+  for (let i = 0; i < argv.length; i++) {
+    const arg = argv[i]!;
+    if (arg === "--profile") return argv[i + 1] ?? "";
+    if (arg.startsWith("--profile=")) return arg.slice("--profile=".length);
+  }
+  return undefined;
+}
+
+// Load config.lua (and plugins) before the first render so keymap and theme overrides apply.
+const config = loadConfig(profileArg(process.argv.slice(2)));
+applyConfigThemes();
+applyConfigLocale();
+// Reopen where the last run quit, in the theme it had.
+const session = loadSession();
+if (session?.theme) applyTheme(session.theme);
 
 render(
   <ThemeProvider theme={defaultTheme}>
-    <App />
+    <App configError={config.error} session={session} />
   </ThemeProvider>
 );
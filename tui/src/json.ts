@@ -0,0 +1,241 @@
+/**
+ * JSON checks for the request body editor. `{{placeholders}}` are filled in
+ * when sending, so one may stand wherever a value can, quoted or not.
+ */
+
+type Token = {
+  kind: "punct" | "string" | "number" | "literal" | "placeholder";
+  text: string;
+  index: number;
+};
+
+/** What is wrong with a body and where; `line` and `col` count from 1. */
+export type JsonProblem = {
+  index: number;
+  line: number;
+  col: number;
+  message: string;
+};
+
+class ProblemAt extends Error {
+  index: number;
+
+  constructor(index: number, message: string) {
+    super(message);
+    this.index = index;
+  }
+}
+
+const NUMBER = /-?(?:0|[1-9]\d*)(?:\.\d+)?(?:[eE][+-]?\d+)?/y;
+const LITERAL = /(?:true|false|null)(?![\w$])/y;
+const ESCAPE = /\\(?:["\\/bfnrt]|u[\da-fA-F]{4})/y;
+
+function tokenize(text: string): Token[] {
+  const tokens: Token[] = [];
+  let pos = 0;
+  while (pos < text.length) {
+    const ch = text[pos]!;
+    if (/\s/.test(ch)) {
+      pos++;
+    } else if (text.startsWith("{{", pos)) {
+      const end = text.indexOf("}}", pos + 2);
+      if (end < 0) throw new ProblemAt(pos, "Unclosed {{placeholder");
+      tokens.push({
+        kind: "placeholder",
+        text: text.slice(pos, end + 2),
+        index: pos,
+      });
+      pos = end + 2;
+    } else if ("{}[]:,".includes(ch)) {
+      tokens.push({ kind: "punct", text: ch, index: pos });
+      pos++;
+    } else if (ch === '"') {
+      let end = pos + 1;
+      while (text[end] !== '"') {
+        if (end >= text.length || text[end] === "\n") {
+          throw new ProblemAt(pos, "Unterminated string");
+        }
+        if (text[end] === "\\") {
+          ESCAPE.lastIndex = end;
+          if (!ESCAPE.test(text)) throw new ProblemAt(end, "Invalid escape");
+          end = ESCAPE.lastIndex;
+        } else {
+          end++;
+        }
+      }
+      tokens.push({
+        kind: "string",
+        text: text.slice(pos, end + 1),
+        index: pos,
+      });
+      pos = end + 1;
+    } else {
+      let matched = false;
+      for (const [re, kind] of [
+        [NUMBER, "number"],
+        [LITERAL, "literal"],
+      ] as const) {
+        re.lastIndex = pos;
+        const match = re.exec(text);
+        if (match) {
+          tokens.push({ kind, text: match[0], index: pos });
+          pos += match[0].length;
+          matched = true;
+          break;
+        }
+      }
+      if (!matched) throw new ProblemAt(pos, `Unexpected "${ch}"`);
+    }
+  }
+  return tokens;
+}
+
+/** Check `tokens` form exactly one JSON value, throwing at the first misfit. */
+function parse(tokens: Token[], end: number) {
+  let i = 0;
+  const at = () => tokens[i]?.index ?? end;
+  const isPunct = (text: string) =>
+    tokens[i]?.kind === "punct" && tokens[i]!.text === text;
+
+  const value = (): void => {
+    const token = tokens[i];
+    if (!token) throw new ProblemAt(end, "Expected a value");
+    if (token.kind !== "punct") {
+      i++;
+    } else if (token.text === "{") {
+      i++;
+      let more = !isPunct("}");
+      while (more) {
+        const key = tokens[i];
+        if (key?.kind !== "string" && key?.kind !== "placeholder") {
+          throw new ProblemAt(at(), "Expected a quoted key");
+        }
+        i++;
+        if (!isPunct(":")) throw new ProblemAt(at(), 'Expected ":"');
+        i++;
+        value();
+        more = isPunct(",");
+        if (more) i++;
+        else if (!isPunct("}")) {
+          throw new ProblemAt(at(), 'Expected "," or "}"');
+        }
+      }
+      i++;
+    } else if (token.text === "[") {
+      i++;
+      let more = !isPunct("]");
+      while (more) {
+        value();
+        more = isPunct(",");
+        if (more) i++;
+        else if (!isPunct("]")) {
+          throw new ProblemAt(at(), 'Expected "," or "]"');
+        }
+      }
+      i++;
+    } else {
+      throw new ProblemAt(token.index, "Expected a value");
+    }
+  };
+
+  value();
+  if (i < tokens.length) {
+    throw new ProblemAt(at(), "Unexpected content after the value");
+  }
+}
+
+function problemAt(text: string, index: number, message: string): JsonProblem {
+  const before = text.slice(0, index).split("\n");
+  return {
+    index,
+    line: before.length,
+    col: before.at(-1)!.length + 1,
+    message,
+  };
+}
+
+function checked(text: string): Token[] | JsonProblem {
+  try {
+    const tokens = tokenize(text);
+    parse(tokens, text.trimEnd().length);
+    return tokens;
+  } catch (e) {
+    if (e instanceof ProblemAt) return problemAt(text, e.index, e.message);
+    throw e;
+  }
+}
+
+/** The first thing wrong with `text` as JSON; null when valid or blank. */
+export function jsonProblem(text: string): JsonProblem | null {
+  if (!text.trim()) return null;
+  const result = checked(text);
+  return Array.isArray(result) ? null : result;
+}
+
+/** `text` indented by two spaces per level; null when it isn't valid JSON. */
+export function formatJson(text: string): string | null {
+  if (!text.trim()) return null;
+  const tokens = checked(text);
+  if (!Array.isArray(tokens)) return null;
+
+  let out = "";
+  let depth = 0;
+  const newline = () => "\n" + "  ".repeat(depth);
+  tokens.forEach((token, i) => {
+    const next = tokens[i + 1]?.text;
+    if (token.text === "{" || token.text === "[") {
+      const empty = next === (token.text === "{" ? "}" : "]");
+      out += token.text;
+      if (!empty) {
+        depth++;
+        out += newline();
+      }
+    } else if (token.text === "}" || token.text === "]") {
+      const prev = tokens[i - 1]?.text;
+      if (prev !== "{" && prev !== "[") {
+        depth--;
+        out += newline();
+      }
+      out += token.text;
+    } else if (token.text === ",") {
+      out += "," + newline();
+    } else if (token.text === ":") {
+      out += ": ";
+    } else {
+      out += token.text;
+    }
+  });
+  return out;
+}
+
+/**
+ * Index of the bracket pairing with the one at `index`, skipping strings and
+ * placeholders; null when there is no bracket there or it is unbalanced.
+ */
+export function matchingBracket(text: string, index: number): number | null {
+  if (!"{}[]".includes(text[index] ?? " ")) return null;
+  const stack: number[] = [];
+  let pos = 0;
+  while (pos < text.length) {
+    const ch = text[pos]!;
+    if (ch === '"') {
+      pos++;
+      while (pos < text.length && text[pos] !== '"' && text[pos] !== "\n") {
+        pos += text[pos] === "\\" ? 2 : 1;
+      }
+    } else if (text.startsWith("{{", pos) && text.indexOf("}}", pos) > 0) {
+      pos = text.indexOf("}}", pos) + 1;
+    } else if (ch === "{" || ch === "[") {
+      stack.push(pos);
+    } else if (ch === "}" || ch === "]") {
+      const open = stack.pop();
+      if (open === index) return pos;
+      if (pos === index) {
+        const opener = ch === "}" ? "{" : "[";
+        return open !== undefined && text[open] === opener ? open : null;
+      }
+    }
+    pos++;
+  }
+  return null;
+}
@@ -0,0 +1,192 @@
+import type { Key } from "ink";
+
+export type KeyAction =
+  | "quit"
+  | "send_request"
+  | "focus_next"
+  | "focus_prev"
+  | "focus_method"
+  | "focus_url"
+  | "toggle_debug"
+  | "clear_debug_logs"
+  | "toggle_console"
+  | "pick_theme"
+  | "cycle_theme"
+  | "command_palette"
+  | "toggle_folders"
+  | "search_library"
+  | "open_history"
+  | "all_responses"
+  | "open_spaces"
+  | "new_space"
+  | "find_in_response"
+  | "cycle_response_view"
+  | "undo"
+  | "redo"
+  | "toggle_favorite"
+  | "move_up"
+  | "move_down"
+  | "new_tab"
+  | "close_tab"
+  | "next_tab"
+  | "prev_tab";
+
+export type Keymap = Record<KeyAction, string[]>;
+
+// Posting-like defaults; `pigeon.keymap.set(action, chords)` in config.lua replaces them per action.
+export const defaultKeymap: Keymap = {
+  quit: ["q", "ctrl+c"],
+  // ctrl+enter needs a terminal that reports modifiers on enter (e.g. kitty).
+  send_request: ["ctrl+j", "alt+enter", "ctrl+enter"],
+  focus_next: ["tab"],
+  focus_prev: ["shift+tab"],
+  focus_method: ["ctrl+t"],
+  focus_url: ["ctrl+l"],
+  toggle_debug: ["d"],
+  clear_debug_logs: ["ctrl+i"],
+  toggle_console: ["ctrl+o"],
+  pick_theme: ["ctrl+y"],
+  // Unbound by default; the picker replaced it, config.lua can still bind it.
+  cycle_theme: [],
+  command_palette: ["ctrl+p"],
+  toggle_folders: ["ctrl+e"],
+  search_library: ["ctrl+k"],
+  open_history: ["ctrl+r"],
+  all_responses: ["alt+r"],
+  open_spaces: ["ctrl+g"],
+  new_space: ["alt+n"],
+  find_in_response: ["ctrl+f"],
+  cycle_response_view: ["v"],
+  undo: ["ctrl+z"],
+  // Most terminals can't report shift with ctrl+letter, hence alt+z.
+  redo: ["alt+z", "ctrl+shift+z"],
+  toggle_favorite: ["ctrl+b"],
+  move_up: ["alt+up"],
+  move_down: ["alt+down"],
+  new_tab: ["ctrl+n"],
+  close_tab: ["ctrl+w"],
+  // Few terminals report ctrl+tab; alt+arrows work everywhere.
+  next_tab: ["ctrl+tab", "alt+right"],
+  prev_tab: ["ctrl+shift+tab", "alt+left"],
+};
+
+// When several actions share a chord, the first one listed here wins.
+export const keyActions = Object.keys(defaultKeymap) as KeyAction[];
+
+export function resolveKeymap(
+  overrides: Record<string, string[]> | undefined
+): Keymap {
+  const keymap = { ...defaultKeymap };
+  for (const action of keyActions) {
+    const chords = overrides?.[action];
+    if (Array.isArray(chords)) keymap[action] = chords;
+  }
+  return keymap;
+}
+
+type Chord = {
+  ctrl: boolean;
+  alt: boolean;
+  shift: boolean;
+  key: string;
+};
+
+function parseChord(chord: string): Chord {
+  const parts = chord.endsWith("++")
+    ? [...chord.slice(0, -2).split("+"), "+"]
+    : chord.split("+");
+  const key = parts.pop() ?? "";
+  const mods = new Set(parts);
+  return {
+    ctrl: mods.has("ctrl"),
+    // Ink reports alt (and most terminals' meta) as `key.meta`.
+    alt: mods.has("alt") || mods.has("meta"),
+    shift: mods.has("shift"),
+    key,
+  };
+}
+
+function namedKeyPressed(name: string, key: Key): boolean | undefined {
+  switch (name) {
+    case "enter":
+      return key.return;
+    case "escape":
+      return key.escape;
+    case "tab":
+      return key.tab;
+    case "backspace":
+      return key.backspace;
+    case "delete":
+      return key.delete;
+    case "up":
+      return key.upArrow;
+    case "down":
+      return key.downArrow;
+    case "left":
+      return key.leftArrow;
+    case "right":
+      return key.rightArrow;
+    case "pageup":
+      return key.pageUp;
+    case "pagedown":
+      return key.pageDown;
+    default:
+      return undefined;
+  }
+}
+
+export function matchesChord(chord: string, input: string, key: Key): boolean {
+  const c = parseChord(chord);
+
+  // Many terminals encode Ctrl+J as LF (\n) instead of reporting ctrl+j.
+  // Enter usually comes as `\r` (key.return === true), Ctrl+J as `\n` (key.return === false).
+  if (c.ctrl && !c.alt && !c.shift && c.key === "j") {
+    if (input === "\n" && !key.return) return true;
+  }
+
+  if (c.ctrl !== key.ctrl || c.alt !== key.meta) return false;
+
+  const named = namedKeyPressed(c.key, key);
+  if (named !== undefined) {
+    return named && c.shift === key.shift;
+  }
+
+  if (c.key === "space") return input === " " && c.shift === key.shift;
+
+  // Ink sets `shift` for uppercase letters, so compare case-insensitively and
+  // only require shift when the chord asks for it. Ctrl chords match shift
+  // exactly, so ctrl+z doesn't also fire for ctrl+shift+z.
+  if (input.toLowerCase() !== c.key) return false;
+  if (c.ctrl) return c.shift === key.shift;
+  return !c.shift || key.shift;
+}
+
+/**
+ * Plain character chords (like `q`) would swallow typing, so they only fire
+ * when no text input is focused.
+ */
+export function isTypingChord(chord: string): boolean {
+  const c = parseChord(chord);
+  return !c.ctrl && !c.alt && c.key.length === 1;
+}
+
+export function findAction(
+  keymap: Keymap,
+  input: string,
+  key: Key,
+  canHandleGlobal: boolean
+): KeyAction | undefined {
+  return keyActions.find((action) =>
+    keymap[action].some(
+      (chord) =>
+        (canHandleGlobal || !isTypingChord(chord)) &&
+        matchesChord(chord, input, key)
+    )
+  );
+}
+
+/** Compact label for key hints, e.g. `ctrl+j` -> `^j`. */
+export function formatChord(chord: string | undefined): string {
+  if (!chord) return "—";
+  return chord.replace(/^ctrl\+/, "^");
+}
@@ -0,0 +1,16 @@
+// BCP 47 tag for dates and numbers, from `ui.locale`; undefined uses the
+// system's.
+let current: string | undefined;
+
+export function currentLocale(): string | undefined {
+  return current;
+}
+
+/** Use `tag` from now on; an empty or unknown tag means the system's. */
+export function setLocale(tag: string | undefined): void {
+  try {
+    current = tag ? Intl.getCanonicalLocales(tag)[0] : undefined;
+  } catch {
+    current = undefined;
+  }
+}
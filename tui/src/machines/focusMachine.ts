@@ -15,8 +15,14 @@ export type RequestTab =
   | "query"
   | "auth"
   | "info"
+  | "preview"
   | "options";
-export type ResponseTab = "body" | "headers" | "trace";
+export type ResponseTab =
+  | "body"
+  | "headers"
+  | "compare"
+  | "hex"
+  | "trace";
 
 // Context interface
 export interface FocusContext {
@@ -31,6 +37,7 @@ export type FocusEvent =
   | { type: "TAB_PREV" }
   | { type: "FOCUS_METHOD" }
   | { type: "FOCUS_URL" }
+  | { type: "FOCUS_RESPONSE" }
   | { type: "SET_REQUEST_TAB"; tab: RequestTab }
   | { type: "SET_RESPONSE_TAB"; tab: ResponseTab }
   | { type: "SET_REQUEST_FIELD"; field: RequestField };
@@ -62,6 +69,7 @@ const baseFocusMachine = createMachine({
         currentField: "url" as TopbarField,
       }),
     },
+    FOCUS_RESPONSE: ".responseTabs",
   },
 
   states: {
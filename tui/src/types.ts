@@ -11,6 +11,8 @@ export type RequestHeader = {
   key: string;
   value: string;
   enabled?: boolean;
+  /** Holds a secret value: shown masked, and kept masked in history. */
+  secret?: boolean;
 };
 
 export type FfiRequest = {
@@ -21,6 +23,13 @@ export type FfiRequest = {
     contentType?: string;
     content?: string;
   };
+  /** Resolved against auth providers registered from Lua (`pigeon.register_auth`). */
+  auth?: {
+    type: string;
+    params?: Record<string, string>;
+  };
+  /** Space (id or name) whose history keeps the response; the first space if unset. */
+  space?: string;
 };
 
 export type FfiResponse = {
@@ -28,5 +37,19 @@ export type FfiResponse = {
   statusText: string;
   headers: Array<[string, string]>;
   body: string;
+  /** Raw body, base64-encoded, when it isn't valid UTF-8 (e.g. images). */
+  bodyBase64?: string;
   durationMs: number;
+  /** Why no response came back, on `status: 0` errors. */
+  failure?: FailureKind;
 };
+
+export type FailureKind =
+  | "dns"
+  | "connection_refused"
+  | "connect"
+  | "tls"
+  | "timeout"
+  | "invalid_url"
+  | "cancelled"
+  | "other";
@@ -0,0 +1,176 @@
+import { useMemo, useState } from "react";
+import { Box, Text, useInput } from "ink";
+import { getHistory, type HistoryEntry } from "@/ffi/history";
+import type { Workspace } from "@/ffi/workspace";
+import { formatTime } from "@/ui/HistoryPanel";
+import { statusColor, theme } from "@/ui/theme";
+
+// Entries read from the history; older ones are left to the History panel.
+const LIMIT = 200;
+
+// Rows shown at once; the list scrolls to keep the selection in view.
+const VISIBLE_ROWS = 15;
+
+type SortKey = "time" | "status" | "duration";
+
+const SORT_KEYS: Record<string, SortKey> = {
+  t: "time",
+  s: "status",
+  d: "duration",
+};
+
+type Row = {
+  entry: HistoryEntry;
+  space: string;
+  endpoint: string;
+};
+
+/**
+ * Every space's responses in one list, with the space and library endpoint
+ * each came from. t, s and d sort by time, status or duration (the same key
+ * again reverses the order), ↑/↓ pick a response and Enter opens it in a new
+ * tab, naming the space it came from. Esc closes.
+ */
+export function AllResponses(props: {
+  workspace: Workspace;
+  onOpen: (entry: HistoryEntry) => void;
+  onClose: () => void;
+}) {
+  const [sort, setSort] = useState<{ key: SortKey; descending: boolean }>({
+    key: "time",
+    descending: true,
+  });
+  const [highlighted, setHighlighted] = useState(0);
+  const entries = useMemo(() => getHistory({ limit: LIMIT }), []);
+
+  const rows = useMemo(() => {
+    const { spaces, endpoints } = props.workspace;
+    const rows: Row[] = entries.map((entry) => {
+      const method = entry.request.method.toUpperCase();
+      const endpoint = endpoints.find(
+        (e) => e.method === method && e.url === entry.request.url
+      );
+      const space = spaces.find((s) => s.id === entry.spaceId);
+      return {
+        entry,
+        space: space?.name ?? entry.space ?? "—",
+        endpoint: endpoint?.name ?? entry.request.url,
+      };
+    });
+    const value = (row: Row): number =>
+      sort.key === "status"
+        ? row.entry.response.status
+        : sort.key === "duration"
+        ? row.entry.response.durationMs
+        : new Date(row.entry.timestamp).getTime() || 0;
+    // Ties keep the history's order, newest first.
+    return rows.sort((a, b) =>
+      sort.descending ? value(b) - value(a) : value(a) - value(b)
+    );
+  }, [entries, props.workspace, sort]);
+  const selected = Math.min(highlighted, Math.max(rows.length - 1, 0));
+
+  useInput((input, key) => {
+    const sortKey = SORT_KEYS[input];
+    if (key.escape) {
+      props.onClose();
+    } else if (sortKey) {
+      setSort((s) => ({
+        key: sortKey,
+        descending: s.key === sortKey ? !s.descending : true,
+      }));
+      setHighlighted(0);
+    } else if (key.upArrow) {
+      setHighlighted(selected > 0 ? selected - 1 : rows.length - 1);
+    } else if (key.downArrow) {
+      setHighlighted(selected < rows.length - 1 ? selected + 1 : 0);
+    } else if (key.return) {
+      const row = rows[selected];
+      if (row) props.onOpen(row.entry);
+    }
+  });
+
+  const top = Math.max(
+    0,
+    Math.min(
+      selected - Math.floor(VISIBLE_ROWS / 2),
+      rows.length - VISIBLE_ROWS
+    )
+  );
+  const heading = (key: SortKey, label: string, width: number) => (
+    <Box width={width}>
+      <Text bold={sort.key === key} color={theme.keyHint}>
+        {label}
+        {sort.key === key ? (sort.descending ? " ↓" : " ↑") : ""}
+      </Text>
+    </Box>
+  );
+
+  return (
+    <Box
+      flexDirection="column"
+      borderStyle="round"
+      borderColor={theme.focusBorder}
+      paddingX={1}
+    >
+      <Box justifyContent="space-between" gap={1}>
+        <Text bold>
+          All responses
+          <Text dimColor>
+            {" "}
+            {entries.length === LIMIT ? `latest ${LIMIT}` : entries.length}
+          </Text>
+        </Text>
+        <Text dimColor>enter open · t/s/d sort · esc</Text>
+      </Box>
+
+      <Box flexDirection="column" marginTop={1}>
+        <Box gap={1}>
+          {heading("time", "Time", 14)}
+          {heading("status", "Status", 7)}
+          {heading("duration", "Duration", 9)}
+          <Box width={16}>
+            <Text color={theme.keyHint}>Space</Text>
+          </Box>
+          <Text color={theme.keyHint}>Endpoint</Text>
+        </Box>
+        {rows.length === 0 ? (
+          <Text dimColor>Nothing sent yet.</Text>
+        ) : null}
+        {rows.slice(top, top + VISIBLE_ROWS).map((row, i) => (
+          <Box key={row.entry.id} gap={1}>
+            <Box width={14}>
+              <Text inverse={top + i === selected} wrap="truncate-end">
+                {formatTime(row.entry.timestamp)}
+              </Text>
+            </Box>
+            <Box width={7}>
+              <Text color={statusColor(row.entry.response.status)}>
+                {row.entry.response.status}
+              </Text>
+            </Box>
+            <Box width={9}>
+              <Text dimColor>{row.entry.response.durationMs}ms</Text>
+            </Box>
+            <Box width={16}>
+              <Text wrap="truncate-end">{row.space}</Text>
+            </Box>
+            <Box flexGrow={1}>
+              <Text wrap="truncate-end">
+                <Text dimColor>
+                  {row.entry.request.method.toUpperCase().padEnd(7)}
+                </Text>
+                {row.endpoint}
+              </Text>
+            </Box>
+          </Box>
+        ))}
+        {rows.length > VISIBLE_ROWS ? (
+          <Text dimColor>
+            {selected + 1}/{rows.length}
+          </Text>
+        ) : null}
+      </Box>
+    </Box>
+  );
+}
@@ -0,0 +1,97 @@
+import { useMemo, useState } from "react";
+import { Box, Text, useInput } from "ink";
+import { TextInput } from "@inkjs/ui";
+import { theme } from "@/ui/theme";
+
+// Rows shown at once; the filter narrows longer lists.
+const VISIBLE_COMMANDS = 10;
+
+/** Fuzzy-ish filter: every word of the query must appear in the name. */
+function matches(name: string, query: string): boolean {
+  const haystack = name.toLowerCase();
+  return query
+    .toLowerCase()
+    .split(/\s+/)
+    .filter(Boolean)
+    .every((word) => haystack.includes(word));
+}
+
+/** Lists `pigeon.command`s; type to filter, ↑/↓ to pick, Enter to run, Esc to close. */
+export function CommandPalette(props: {
+  commands: string[];
+  onRun: (name: string) => void;
+  onClose: () => void;
+}) {
+  const [query, setQuery] = useState("");
+  const [highlighted, setHighlighted] = useState(0);
+
+  const filtered = useMemo(
+    () => props.commands.filter((name) => matches(name, query)),
+    [props.commands, query]
+  );
+  const selected = Math.min(highlighted, Math.max(filtered.length - 1, 0));
+
+  useInput((_input, key) => {
+    if (key.escape) {
+      props.onClose();
+    } else if (key.upArrow) {
+      setHighlighted(selected > 0 ? selected - 1 : filtered.length - 1);
+    } else if (key.downArrow) {
+      setHighlighted(selected < filtered.length - 1 ? selected + 1 : 0);
+    }
+  });
+
+  // Keep the highlighted row in view.
+  const start = Math.max(0, selected - VISIBLE_COMMANDS + 1);
+
+  return (
+    <Box
+      flexDirection="column"
+      borderStyle="round"
+      borderColor={theme.focusBorder}
+      paddingX={1}
+    >
+      <Box justifyContent="space-between">
+        <Text bold>Commands</Text>
+        <Text dimColor>
+          {filtered.length}/{props.commands.length}
+        </Text>
+      </Box>
+      <TextInput
+        placeholder="Type to filter…"
+        onChange={(value) => {
+          setQuery(value);
+          setHighlighted(0);
+        }}
+        onSubmit={() => {
+          const name = filtered[selected];
+          if (name) props.onRun(name);
+        }}
+      />
+      <Box flexDirection="column" marginTop={1}>
+        {props.commands.length === 0 ? (
+          <Text dimColor>
+            No commands. Plugins add them with pigeon.command(name, fn).
+          </Text>
+        ) : filtered.length === 0 ? (
+          <Text dimColor>No matching commands.</Text>
+        ) : (
+          filtered.slice(start, start + VISIBLE_COMMANDS).map((name, i) => {
+            const active = start + i === selected;
+            return (
+              <Text
+                key={name}
+                color={active ? theme.focusBorder : undefined}
+                bold={active}
+                wrap="truncate-end"
+              >
+                {active ? "› " : "  "}
+                {name}
+              </Text>
+            );
+          })
+        )}
+      </Box>
+    </Box>
+  );
+}
@@ -0,0 +1,68 @@
+import { useState } from "react";
+import { Box, Text, useInput } from "ink";
+import { theme } from "@/ui/theme";
+
+export type MenuAction = {
+  /** Picks the action directly while the menu is open. */
+  key: string;
+  label: string;
+  run: () => void;
+  /** Shown in red, e.g. for deletes. */
+  destructive?: boolean;
+};
+
+/**
+ * Actions for one item, opened with m from a list. ↑/↓ and Enter or the
+ * action's key pick one, Esc closes. The list should ignore input while it's
+ * open.
+ */
+export function ContextMenu(props: {
+  title: string;
+  actions: MenuAction[];
+  onClose: () => void;
+}) {
+  const [highlighted, setHighlighted] = useState(0);
+
+  const pick = (action: MenuAction | undefined) => {
+    if (!action) return;
+    props.onClose();
+    action.run();
+  };
+
+  useInput((input, key) => {
+    if (key.escape || input === "m") {
+      props.onClose();
+    } else if (key.upArrow) {
+      setHighlighted((i) => (i > 0 ? i - 1 : props.actions.length - 1));
+    } else if (key.downArrow) {
+      setHighlighted((i) => (i < props.actions.length - 1 ? i + 1 : 0));
+    } else if (key.return) {
+      pick(props.actions[highlighted]);
+    } else {
+      pick(props.actions.find((a) => a.key === input));
+    }
+  });
+
+  return (
+    <Box
+      flexDirection="column"
+      borderStyle="round"
+      borderColor={theme.focusBorder}
+      paddingX={1}
+      alignSelf="flex-start"
+      minWidth={28}
+    >
+      <Text bold wrap="truncate-end">
+        {props.title}
+      </Text>
+      {props.actions.map((action, i) => (
+        <Text key={action.key} inverse={i === highlighted}>
+          <Text color={theme.keyHint}>{action.key}</Text>{" "}
+          <Text color={action.destructive ? "red" : undefined}>
+            {action.label}
+          </Text>
+        </Text>
+      ))}
+    </Box>
+  );
+}
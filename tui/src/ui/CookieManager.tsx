@@ -0,0 +1,330 @@
+import { useMemo, useState } from "react";
+import { Box, Text, useInput } from "ink";
+import { TextInput } from "@inkjs/ui";
+import {
+  clearCookies,
+  cookiesForUrl,
+  listCookies,
+  removeCookie,
+  setCookie,
+  type Cookie,
+} from "@/ffi/cookies";
+import { theme } from "@/ui/theme";
+
+// Rows shown at once; the list scrolls to keep the selection in view.
+const VISIBLE_ROWS = 14;
+
+const FIELDS = ["name", "value", "domain", "path"] as const;
+type Field = (typeof FIELDS)[number];
+
+type Form = {
+  /** The cookie being edited; null when adding one. */
+  original: Cookie | null;
+  values: Record<Field, string>;
+  active: number;
+};
+
+type Row =
+  | { kind: "domain"; domain: string }
+  | { kind: "cookie"; cookie: Cookie; index: number };
+
+/**
+ * The cookie jar grouped by domain, with the cookies the current request
+ * would send marked ●. ↑/↓ pick a cookie, Enter or e edits it, n adds one
+ * for the request's host, x deletes it and X clears the jar (both after a
+ * y/n confirmation). In the form a leading "." on the domain includes its
+ * subdomains; ↑/↓ move between fields, Enter saves and Esc cancels.
+ */
+export function CookieManager(props: {
+  /** The current request's URL with placeholders resolved, if it has one. */
+  url: string | null;
+  /** Called after the jar changes. */
+  onChange: () => void;
+  onClose: () => void;
+}) {
+  const [revision, setRevision] = useState(0);
+  const cookies = useMemo(
+    () => sortCookies(listCookies()),
+    // eslint-disable-next-line react-hooks/exhaustive-deps
+    [revision]
+  );
+  const attached = useMemo(
+    () =>
+      new Set(
+        (props.url ? cookiesForUrl(props.url) : []).map((c) => cookieKey(c))
+      ),
+    // eslint-disable-next-line react-hooks/exhaustive-deps
+    [props.url, revision]
+  );
+  const [highlighted, setHighlighted] = useState(0);
+  const [form, setForm] = useState<Form | null>(null);
+  const [confirm, setConfirm] = useState<Cookie | "all" | null>(null);
+  const [error, setError] = useState<string | null>(null);
+  const [notice, setNotice] = useState<string | null>(null);
+  const selected = Math.min(highlighted, Math.max(cookies.length - 1, 0));
+  const cookie = cookies[selected];
+
+  const changed = (message: string) => {
+    setError(null);
+    setNotice(message);
+    setRevision((n) => n + 1);
+    props.onChange();
+  };
+
+  const report = (result: { error?: string }, success: string): boolean => {
+    if (result.error) {
+      setError(result.error);
+      setNotice(null);
+      return false;
+    }
+    changed(success);
+    return true;
+  };
+
+  const openForm = (original: Cookie | null) => {
+    setError(null);
+    setNotice(null);
+    setForm({
+      original,
+      values: original
+        ? {
+            name: original.name,
+            value: original.value,
+            domain: (original.hostOnly ? "" : ".") + original.domain,
+            path: original.path,
+          }
+        : { name: "", value: "", domain: hostOf(props.url), path: "/" },
+      active: original ? 1 : 0,
+    });
+  };
+
+  const save = (form: Form) => {
+    const name = form.values.name.trim();
+    const domainField = form.values.domain.trim().toLowerCase();
+    const domain = domainField.replace(/^\.+/, "");
+    const path = form.values.path.trim() || "/";
+    const problem = !name
+      ? "A cookie needs a name"
+      : !domain
+      ? "A cookie needs a domain"
+      : !path.startsWith("/")
+      ? 'The path must start with "/"'
+      : null;
+    if (problem) {
+      setError(problem);
+      return;
+    }
+
+    const next: Cookie = {
+      name,
+      value: form.values.value,
+      domain,
+      path,
+      hostOnly: !domainField.startsWith("."),
+      secure: form.original?.secure ?? false,
+      httpOnly: form.original?.httpOnly ?? false,
+      expires: form.original?.expires ?? null,
+    };
+    if (!report(setCookie(next), `Saved ${name}`)) return;
+    // Domain, path and name identify a cookie, so changing one moves it.
+    if (form.original && cookieKey(form.original) !== cookieKey(next)) {
+      removeCookie(form.original);
+    }
+    setForm(null);
+  };
+
+  useInput((input, key) => {
+    if (confirm) {
+      if (input === "y") {
+        if (confirm === "all") report(clearCookies(), "Cleared the jar");
+        else report(removeCookie(confirm), `Deleted ${confirm.name}`);
+      }
+      setConfirm(null);
+      return;
+    }
+    if (form) {
+      if (key.escape) {
+        setForm(null);
+        setError(null);
+      } else if (key.return) {
+        save(form);
+      } else if (key.upArrow || key.downArrow) {
+        const step = key.upArrow ? FIELDS.length - 1 : 1;
+        setForm((f) =>
+          f ? { ...f, active: (f.active + step) % FIELDS.length } : f
+        );
+      }
+      return;
+    }
+    if (key.escape) {
+      props.onClose();
+    } else if (key.upArrow) {
+      setHighlighted(selected > 0 ? selected - 1 : cookies.length - 1);
+    } else if (key.downArrow) {
+      setHighlighted(selected < cookies.length - 1 ? selected + 1 : 0);
+    } else if (input === "n") {
+      openForm(null);
+    } else if (input === "X" && cookies.length > 0) {
+      setConfirm("all");
+    } else if (!cookie) {
+      return;
+    } else if (key.return || input === "e") {
+      openForm(cookie);
+    } else if (input === "x") {
+      setConfirm(cookie);
+    }
+  });
+
+  const rows = useMemo(() => {
+    const rows: Row[] = [];
+    cookies.forEach((c, index) => {
+      if (index === 0 || cookies[index - 1]!.domain !== c.domain) {
+        rows.push({ kind: "domain", domain: c.domain });
+      }
+      rows.push({ kind: "cookie", cookie: c, index });
+    });
+    return rows;
+  }, [cookies]);
+  const selectedRow = rows.findIndex(
+    (r) => r.kind === "cookie" && r.index === selected
+  );
+  const top = Math.max(
+    0,
+    Math.min(
+      selectedRow - Math.floor(VISIBLE_ROWS / 2),
+      rows.length - VISIBLE_ROWS
+    )
+  );
+
+  return (
+    <Box
+      flexDirection="column"
+      borderStyle="round"
+      borderColor={theme.focusBorder}
+      paddingX={1}
+    >
+      <Box justifyContent="space-between" gap={1}>
+        <Text bold>
+          Cookies
+          <Text dimColor>
+            {" "}
+            {cookies.length} · {attached.size} sent with this request
+          </Text>
+        </Text>
+        <Text dimColor>
+          {form
+            ? "enter save · ↑/↓ field · esc cancel"
+            : "enter edit · n new · x delete · X clear · esc"}
+        </Text>
+      </Box>
+      {confirm ? (
+        <Text color="yellow">
+          {confirm === "all"
+            ? `Delete all ${cookies.length} cookies?`
+            : `Delete ${confirm.name} from ${confirm.domain}?`}{" "}
+          <Text bold>y</Text>/n
+        </Text>
+      ) : error ? (
+        <Text color="red">{error}</Text>
+      ) : notice ? (
+        <Text color="green">{notice}</Text>
+      ) : null}
+
+      {form ? (
+        <Box flexDirection="column" marginTop={1}>
+          <Text dimColor>
+            {form.original ? `Edit ${form.original.name}` : "New cookie"}
+          </Text>
+          {FIELDS.map((field, i) => (
+            <Box key={field} gap={1}>
+              <Box width={7}>
+                <Text
+                  color={i === form.active ? theme.focusBorder : undefined}
+                  dimColor={i !== form.active}
+                >
+                  {field[0]!.toUpperCase() + field.slice(1)}
+                </Text>
+              </Box>
+              <TextInput
+                defaultValue={form.values[field]}
+                isDisabled={i !== form.active}
+                onChange={(value) =>
+                  setForm((f) =>
+                    f ? { ...f, values: { ...f.values, [field]: value } } : f
+                  )
+                }
+              />
+            </Box>
+          ))}
+          <Text dimColor>
+            A leading "." on the domain sends it to subdomains too.
+          </Text>
+        </Box>
+      ) : (
+        <Box flexDirection="column" marginTop={1}>
+          {cookies.length === 0 ? (
+            <Text dimColor>
+              The jar is empty. Responses fill it as you send.
+            </Text>
+          ) : null}
+          {rows.slice(top, top + VISIBLE_ROWS).map((row) =>
+            row.kind === "domain" ? (
+              <Text key={`domain-${row.domain}`} bold>
+                {row.domain}
+              </Text>
+            ) : (
+              <Text
+                key={cookieKey(row.cookie)}
+                wrap="truncate-end"
+                inverse={row.index === selected}
+              >
+                <Text color={theme.focusBorder}>
+                  {attached.has(cookieKey(row.cookie)) ? " ● " : "   "}
+                </Text>
+                {row.cookie.name}
+                <Text dimColor>=</Text>
+                {row.cookie.value}
+                <Text dimColor> {cookieDetails(row.cookie)}</Text>
+              </Text>
+            )
+          )}
+        </Box>
+      )}
+    </Box>
+  );
+}
+
+function cookieKey(cookie: Pick<Cookie, "domain" | "path" | "name">): string {
+  return `${cookie.domain}\t${cookie.path}\t${cookie.name}`;
+}
+
+/** Cookies grouped by domain, then by path and name. */
+function sortCookies(cookies: Cookie[]): Cookie[] {
+  return [...cookies].sort(
+    (a, b) =>
+      a.domain.localeCompare(b.domain) ||
+      a.path.localeCompare(b.path) ||
+      a.name.localeCompare(b.name)
+  );
+}
+
+function cookieDetails(cookie: Cookie): string {
+  return [
+    cookie.path,
+    cookie.hostOnly ? null : "+subdomains",
+    cookie.secure ? "secure" : null,
+    cookie.httpOnly ? "httpOnly" : null,
+    cookie.expires ? `until ${cookie.expires.slice(0, 10)}` : "session",
+  ]
+    .filter((part) => part !== null)
+    .join(" · ");
+}
+
+function hostOf(url: string | null): string {
+  if (!url) return "";
+  try {
+    return new URL(url).hostname;
+  } catch {
+    return "";
+  }
+}
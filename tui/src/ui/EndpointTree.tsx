@@ -0,0 +1,166 @@
+import { Text } from "ink";
+import type {
+  Workspace,
+  WorkspaceEndpoint,
+  WorkspaceFolder,
+  WorkspaceRequest,
+} from "@/ffi/workspace";
+
+type Row =
+  | { kind: "favorites"; count: number }
+  | {
+      kind: "folder";
+      folder: WorkspaceFolder;
+      depth: number;
+      count: number;
+      open: boolean;
+    }
+  | {
+      kind: "endpoint";
+      endpoint: WorkspaceEndpoint;
+      depth: number;
+      /** Listed in the Favorites group rather than its folder. */
+      pinned?: boolean;
+    }
+  | { kind: "request"; request: WorkspaceRequest; depth: number };
+
+/**
+ * Flatten folders, endpoints and saved requests into sidebar rows, skipping
+ * collapsed folders' contents. Favorite endpoints are also listed up front.
+ */
+function buildRows(
+  workspace: Workspace,
+  expandAll: boolean,
+  hideEmpty: boolean
+): Row[] {
+  const known = new Set(workspace.folders.map((f) => f.id));
+  const parentOf = (id: string | null | undefined) =>
+    id && known.has(id) ? id : null;
+
+  const rows: Row[] = [];
+  const favorites = workspace.endpoints.filter((e) => e.favorite);
+  if (favorites.length > 0) {
+    rows.push({ kind: "favorites", count: favorites.length });
+    for (const endpoint of favorites) {
+      rows.push({ kind: "endpoint", endpoint, depth: 1, pinned: true });
+    }
+  }
+  // Favorites first within each folder too.
+  const endpoints = [
+    ...favorites,
+    ...workspace.endpoints.filter((e) => !e.favorite),
+  ];
+  // Hosts may save arbitrary parent ids, so guard against cycles.
+  const visited = new Set<string>();
+
+  const countIn = (folderId: string, seen: Set<string>): number => {
+    if (seen.has(folderId)) return 0;
+    seen.add(folderId);
+    const direct =
+      workspace.endpoints.filter((e) => parentOf(e.folder_id) === folderId)
+        .length +
+      workspace.requests.filter((r) => parentOf(r.folder_id) === folderId)
+        .length;
+    return workspace.folders
+      .filter((f) => parentOf(f.parent_id) === folderId)
+      .reduce((total, f) => total + countIn(f.id, seen), direct);
+  };
+
+  const walk = (parent: string | null, depth: number) => {
+    for (const folder of workspace.folders) {
+      if (parentOf(folder.parent_id) !== parent || visited.has(folder.id)) {
+        continue;
+      }
+      visited.add(folder.id);
+      const open = expandAll || !folder.collapsed;
+      const count = countIn(folder.id, new Set());
+      if (hideEmpty && count === 0) continue;
+      rows.push({ kind: "folder", folder, depth, count, open });
+      if (open) walk(folder.id, depth + 1);
+    }
+    for (const endpoint of endpoints) {
+      if (parentOf(endpoint.folder_id) === parent) {
+        rows.push({ kind: "endpoint", endpoint, depth });
+      }
+    }
+    for (const request of workspace.requests) {
+      if (parentOf(request.folder_id) === parent) {
+        rows.push({ kind: "request", request, depth });
+      }
+    }
+  };
+  walk(null, 0);
+  return rows;
+}
+
+/** Endpoints and saved requests grouped by folder; collapsed folders show ▸ and their item count. */
+export function EndpointTree(props: {
+  workspace: Workspace;
+  expandAll: boolean;
+  /** Hide folders with nothing in them, e.g. while the sidebar is filtered. */
+  hideEmpty?: boolean;
+  isCurrent: (endpoint: WorkspaceEndpoint) => boolean;
+  maxRows: number;
+}) {
+  const rows = buildRows(
+    props.workspace,
+    props.expandAll,
+    props.hideEmpty ?? false
+  );
+  if (rows.length === 0) return <Text dimColor>No endpoints yet.</Text>;
+
+  return (
+    <>
+      {rows.slice(0, props.maxRows).map((row) => {
+        if (row.kind === "favorites") {
+          return (
+            <Text key="favorites" bold wrap="truncate-end">
+              <Text color="yellow">★ </Text>
+              Favorites
+              <Text dimColor> ({row.count})</Text>
+            </Text>
+          );
+        }
+        const indent = "  ".repeat(row.depth);
+        if (row.kind === "folder") {
+          return (
+            <Text key={row.folder.id} bold wrap="truncate-end">
+              {indent}
+              {row.open ? "▾ " : "▸ "}
+              {row.folder.name}
+              <Text dimColor> ({row.count})</Text>
+            </Text>
+          );
+        }
+        if (row.kind === "request") {
+          return (
+            <Text key={row.request.id} dimColor wrap="truncate-end">
+              {indent}
+              {row.request.method.slice(0, 3).padEnd(4)}
+              {row.request.name}
+              <Text color="cyan"> ◆</Text>
+            </Text>
+          );
+        }
+        const current = props.isCurrent(row.endpoint);
+        return (
+          <Text
+            key={`${row.pinned ? "fav-" : ""}${row.endpoint.id}`}
+            dimColor={!current}
+            wrap="truncate-end"
+          >
+            {indent}
+            {row.endpoint.method.slice(0, 3).padEnd(4)}
+            {row.endpoint.name}
+            {row.endpoint.favorite && !row.pinned ? (
+              <Text color="yellow"> ★</Text>
+            ) : null}
+          </Text>
+        );
+      })}
+      {rows.length > props.maxRows ? (
+        <Text dimColor>… {rows.length - props.maxRows} more</Text>
+      ) : null}
+    </>
+  );
+}
@@ -0,0 +1,82 @@
+import { Box, Text } from "ink";
+import type { FailureKind } from "@/types";
+
+const FAILURES: Record<FailureKind, { title: string; fixes: string[] }> = {
+  dns: {
+    title: "Couldn't resolve the host",
+    fixes: [
+      "Check the host name for typos.",
+      "If it comes from a variable, check the environment defines it.",
+      "Check your network, DNS or VPN connection.",
+    ],
+  },
+  connection_refused: {
+    title: "Connection refused",
+    fixes: [
+      "Check the server is running and listening on that port.",
+      "Check the port, and http:// versus https://.",
+    ],
+  },
+  connect: {
+    title: "Couldn't connect",
+    fixes: [
+      "Check the host is reachable from this machine.",
+      "A firewall or proxy may be blocking the connection.",
+    ],
+  },
+  tls: {
+    title: "TLS handshake failed",
+    fixes: [
+      "Check the certificate is valid for this host and hasn't expired.",
+      "For a plain HTTP server, use http:// instead of https://.",
+    ],
+  },
+  timeout: {
+    title: "Timed out",
+    fixes: [
+      "Check the server isn't stuck or overloaded.",
+      "Raise http.timeout in config.lua for slow endpoints.",
+    ],
+  },
+  invalid_url: {
+    title: "Invalid URL",
+    fixes: [
+      "Include the scheme, e.g. https://.",
+      "Check every {{variable}} in the URL resolves.",
+    ],
+  },
+  cancelled: {
+    title: "Cancelled",
+    fixes: ["The request was stopped before a response came back."],
+  },
+  other: {
+    title: "Request failed",
+    fixes: [],
+  },
+};
+
+/** A failed send: what went wrong, the core's message and what to try. */
+export function ErrorPanel(props: { kind: FailureKind; message: string }) {
+  const failure = FAILURES[props.kind] ?? FAILURES.other;
+  return (
+    <Box
+      flexDirection="column"
+      borderStyle="round"
+      borderColor="red"
+      paddingX={1}
+    >
+      <Text color="red" bold>
+        {failure.title}
+      </Text>
+      <Text wrap="wrap">{props.message}</Text>
+      {failure.fixes.length > 0 ? (
+        <Box flexDirection="column" marginTop={1}>
+          <Text dimColor>Try:</Text>
+          {failure.fixes.map((fix) => (
+            <Text key={fix}>• {fix}</Text>
+          ))}
+        </Box>
+      ) : null}
+    </Box>
+  );
+}
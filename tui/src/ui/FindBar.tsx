@@ -0,0 +1,78 @@
+import { Box, Text, useInput } from "ink";
+import { TextInput } from "@inkjs/ui";
+import { theme } from "@/ui/theme";
+
+/** One occurrence of the query: `line` and the column range within it. */
+export type TextMatch = { line: number; start: number; end: number };
+
+// Past this many the count reads "N+" and the rest aren't highlighted.
+const MAX_MATCHES = 10_000;
+
+/**
+ * Every occurrence of `query` in `text`, line by line. Case-insensitive unless
+ * the query has an uppercase letter.
+ */
+export function findMatches(text: string, query: string): TextMatch[] {
+  if (!query) return [];
+  const caseSensitive = query !== query.toLowerCase();
+  const needle = caseSensitive ? query : query.toLowerCase();
+  const matches: TextMatch[] = [];
+  const lines = text.split("\n");
+  for (let line = 0; line < lines.length; line++) {
+    const haystack = caseSensitive
+      ? lines[line]!
+      : lines[line]!.toLowerCase();
+    let from = 0;
+    for (;;) {
+      const start = haystack.indexOf(needle, from);
+      if (start < 0) break;
+      matches.push({ line, start, end: start + needle.length });
+      if (matches.length >= MAX_MATCHES) return matches;
+      from = start + needle.length;
+    }
+  }
+  return matches;
+}
+
+/**
+ * Query input over the response body. Enter or ↓ go to the next match, ↑ to
+ * the previous one (both wrap around), Esc closes.
+ */
+export function FindBar(props: {
+  query: string;
+  matchCount: number;
+  current: number;
+  onChange: (query: string) => void;
+  onStep: (direction: -1 | 1) => void;
+  onClose: () => void;
+}) {
+  useInput((_input, key) => {
+    if (key.escape) props.onClose();
+    else if (key.upArrow) props.onStep(-1);
+    else if (key.downArrow) props.onStep(1);
+  });
+
+  const count =
+    props.matchCount >= MAX_MATCHES ? `${MAX_MATCHES}+` : props.matchCount;
+
+  return (
+    <Box borderStyle="round" borderColor={theme.focusBorder} paddingX={1}>
+      <Text bold>Find: </Text>
+      <Box flexGrow={1}>
+        <TextInput
+          defaultValue={props.query}
+          placeholder="text in the body"
+          onChange={props.onChange}
+          onSubmit={() => props.onStep(1)}
+        />
+      </Box>
+      <Text
+        color={props.query && props.matchCount === 0 ? "red" : undefined}
+        dimColor={!props.query || props.matchCount > 0}
+      >
+        {props.matchCount > 0 ? `${props.current + 1}/${count}` : "0/0"}
+        {"  "}↑/↓ · esc
+      </Text>
+    </Box>
+  );
+}
@@ -0,0 +1,187 @@
+import { useEffect, useMemo, useState } from "react";
+import { Box, Text, useInput } from "ink";
+import { TextInput } from "@inkjs/ui";
+import { copyToClipboard } from "@/clipboard";
+import { theme } from "@/ui/theme";
+
+type SortKey = "received" | "name" | "value";
+
+const SORT_KEYS: SortKey[] = ["received", "name", "value"];
+
+// Long names are cut so values keep most of the width.
+const MAX_NAME_WIDTH = 32;
+
+/**
+ * Response headers as a Name / Value table. ↑/↓ select, / filters by name or
+ * value, s cycles the sort column and r reverses it, y copies the row and c
+ * the value.
+ */
+export function HeadersTable(props: {
+  headers: Array<[string, string]>;
+  height: number;
+  isActive: boolean;
+  onCopy?: (message: string) => void;
+  /** Called when the filter input opens or closes, as it takes typing. */
+  onFilteringChange?: (filtering: boolean) => void;
+}) {
+  const [filter, setFilter] = useState("");
+  const [filtering, setFiltering] = useState(false);
+  const [sortKey, setSortKey] = useState<SortKey>("received");
+  const [descending, setDescending] = useState(false);
+  const [cursor, setCursor] = useState(0);
+  const [scrollTop, setScrollTop] = useState(0);
+
+  useEffect(() => {
+    props.onFilteringChange?.(filtering);
+    // eslint-disable-next-line react-hooks/exhaustive-deps
+  }, [filtering]);
+  useEffect(
+    () => () => props.onFilteringChange?.(false),
+    // eslint-disable-next-line react-hooks/exhaustive-deps
+    []
+  );
+
+  const rows = useMemo(() => {
+    const needle = filter.toLowerCase();
+    const matching = props.headers.filter(
+      ([name, value]) =>
+        name.toLowerCase().includes(needle) ||
+        value.toLowerCase().includes(needle)
+    );
+    if (sortKey !== "received") {
+      const column = sortKey === "name" ? 0 : 1;
+      matching.sort((a, b) =>
+        a[column].localeCompare(b[column], undefined, { sensitivity: "base" })
+      );
+    }
+    if (descending) matching.reverse();
+    return matching;
+  }, [props.headers, filter, sortKey, descending]);
+
+  // Viewport is the inner height: total minus borders, header, column
+  // titles and footer.
+  const viewportHeight = Math.max(1, props.height - 5);
+  const selected = Math.min(cursor, Math.max(0, rows.length - 1));
+  const row = rows[selected];
+  const nameWidth = Math.min(
+    MAX_NAME_WIDTH,
+    Math.max(4, ...rows.map(([name]) => name.length))
+  );
+
+  const moveTo = (index: number) => {
+    const next = Math.max(0, Math.min(rows.length - 1, index));
+    setCursor(next);
+    if (next < scrollTop) setScrollTop(next);
+    else if (next >= scrollTop + viewportHeight) {
+      setScrollTop(next - viewportHeight + 1);
+    }
+  };
+
+  useInput(
+    (input, key) => {
+      if (filtering) {
+        if (key.escape || key.return) setFiltering(false);
+        return;
+      }
+      if (key.upArrow) moveTo(selected - 1);
+      else if (key.downArrow) moveTo(selected + 1);
+      else if (key.pageUp) moveTo(selected - viewportHeight);
+      else if (key.pageDown) moveTo(selected + viewportHeight);
+      else if (input === "/") setFiltering(true);
+      else if (key.escape && filter) setFilter("");
+      else if (input === "s") {
+        setSortKey(
+          (k) => SORT_KEYS[(SORT_KEYS.indexOf(k) + 1) % SORT_KEYS.length]!
+        );
+      } else if (input === "r") setDescending((d) => !d);
+      else if (row && input === "y") {
+        copyToClipboard(`${row[0]}: ${row[1]}`);
+        props.onCopy?.(`Copied header ${row[0]}`);
+      } else if (row && input === "c") {
+        copyToClipboard(row[1]);
+        props.onCopy?.(`Copied value of ${row[0]}`);
+      }
+    },
+    { isActive: props.isActive }
+  );
+
+  const displayed = rows.slice(scrollTop, scrollTop + viewportHeight);
+  const arrow = descending ? "↓" : "↑";
+
+  return (
+    <Box
+      flexDirection="column"
+      borderStyle="round"
+      paddingX={1}
+      paddingY={0}
+      height={props.height}
+    >
+      <Box justifyContent="space-between" gap={1}>
+        {filtering ? (
+          <Box flexGrow={1}>
+            <Text bold>Filter: </Text>
+            <TextInput
+              defaultValue={filter}
+              placeholder="name or value"
+              onChange={(next) => {
+                setFilter(next);
+                setCursor(0);
+                setScrollTop(0);
+              }}
+            />
+          </Box>
+        ) : (
+          <Text bold>
+            Headers
+            {filter ? <Text color={theme.focusBorder}> /{filter}</Text> : null}
+          </Text>
+        )}
+        <Text dimColor>/ filter · s sort · r reverse · y row · c value</Text>
+      </Box>
+
+      <Text bold wrap="truncate-end">
+        {"Name".padEnd(nameWidth)}
+        {sortKey === "name" ? arrow : " "} Value
+        {sortKey === "value" ? ` ${arrow}` : ""}
+      </Text>
+
+      <Box flexDirection="column">
+        {rows.length === 0 ? (
+          <Text dimColor>
+            {filter ? "No headers match the filter." : "No headers."}
+          </Text>
+        ) : null}
+        {displayed.map(([name, value], i) => {
+          const active = props.isActive && scrollTop + i === selected;
+          const shown =
+            name.length > nameWidth
+              ? `${name.slice(0, nameWidth - 1)}…`
+              : name.padEnd(nameWidth);
+          return (
+            <Text
+              key={`${scrollTop + i}-${name}`}
+              wrap="truncate-end"
+              inverse={active}
+            >
+              <Text color={theme.keyHint}>{shown}</Text>
+              {"  "}
+              {value}
+            </Text>
+          );
+        })}
+      </Box>
+
+      <Box justifyContent="space-between">
+        <Text dimColor>
+          sorted by {sortKey === "received" ? "arrival" : sortKey}
+        </Text>
+        <Text dimColor>
+          {rows.length === 0 ? 0 : selected + 1}/{rows.length}
+          {rows.length === props.headers.length
+            ? ""
+            : ` of ${props.headers.length}`}
+        </Text>
+      </Box>
+    </Box>
+  );
+}
@@ -0,0 +1,97 @@
+import { useEffect, useState } from "react";
+import { Box, Text, useInput } from "ink";
+import { formatBytes } from "@/ui/ImagePreview";
+
+const BYTES_PER_ROW = 16;
+
+function hexByte(b: number): string {
+  return b.toString(16).padStart(2, "0");
+}
+
+/** `offset  xx xx … xx  xx xx … xx  |ascii|` for the 16 bytes at `offset`. */
+function dumpRow(bytes: Uint8Array, offset: number): string {
+  const chunk = bytes.subarray(offset, offset + BYTES_PER_ROW);
+  let hex = "";
+  let ascii = "";
+  for (let i = 0; i < BYTES_PER_ROW; i++) {
+    const b = chunk[i];
+    hex += (b === undefined ? "  " : hexByte(b)) + (i === 7 ? "  " : " ");
+    if (b !== undefined) {
+      ascii += b >= 0x20 && b < 0x7f ? String.fromCharCode(b) : ".";
+    }
+  }
+  return `${offset.toString(16).padStart(8, "0")}  ${hex} |${ascii}|`;
+}
+
+/**
+ * Hex and ASCII dump of a body, 16 bytes a row. Rows are formatted as they
+ * scroll into view, so any size pages through quickly: ↑/↓ scroll, PgUp/PgDn
+ * page, g/G jump to the start/end.
+ */
+export function HexView(props: {
+  bytes: Uint8Array;
+  height: number;
+  isActive: boolean;
+}) {
+  const [scrollTop, setScrollTop] = useState(0);
+  useEffect(() => setScrollTop(0), [props.bytes]);
+
+  // Viewport is the inner height: total minus borders, header and footer.
+  const viewportHeight = Math.max(1, props.height - 4);
+  const rowCount = Math.ceil(props.bytes.length / BYTES_PER_ROW);
+  const maxTop = Math.max(0, rowCount - viewportHeight);
+  const scrollTo = (top: number) =>
+    setScrollTop(Math.max(0, Math.min(maxTop, top)));
+
+  useInput(
+    (input, key) => {
+      if (key.upArrow) scrollTo(scrollTop - 1);
+      else if (key.downArrow) scrollTo(scrollTop + 1);
+      else if (key.pageUp) scrollTo(scrollTop - viewportHeight);
+      else if (key.pageDown) scrollTo(scrollTop + viewportHeight);
+      else if (input === "g") scrollTo(0);
+      else if (input === "G") scrollTo(maxTop);
+    },
+    { isActive: props.isActive }
+  );
+
+  const rows: string[] = [];
+  for (let row = scrollTop; row < scrollTop + viewportHeight; row++) {
+    if (row >= rowCount) break;
+    rows.push(dumpRow(props.bytes, row * BYTES_PER_ROW));
+  }
+  const page = Math.floor(scrollTop / viewportHeight) + 1;
+  const pages = Math.max(1, Math.ceil(rowCount / viewportHeight));
+
+  return (
+    <Box
+      flexDirection="column"
+      borderStyle="round"
+      paddingX={1}
+      paddingY={0}
+      height={props.height}
+    >
+      <Box justifyContent="space-between">
+        <Text bold>Hex</Text>
+        <Text dimColor>pgup/pgdn page · g/G start/end</Text>
+      </Box>
+
+      <Box flexDirection="column">
+        {rows.length === 0 ? <Text dimColor>(empty body)</Text> : null}
+        {rows.map((row, i) => (
+          <Text key={scrollTop + i} wrap="truncate-end">
+            <Text dimColor>{row.slice(0, 8)}</Text>
+            {row.slice(8)}
+          </Text>
+        ))}
+      </Box>
+
+      <Box justifyContent="space-between">
+        <Text dimColor>
+          page {page}/{pages}
+        </Text>
+        <Text dimColor>{formatBytes(props.bytes.length)}</Text>
+      </Box>
+    </Box>
+  );
+}
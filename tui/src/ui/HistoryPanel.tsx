@@ -0,0 +1,323 @@
+import { useMemo, useState } from "react";
+import { Box, Text, useInput } from "ink";
+import { TextInput } from "@inkjs/ui";
+import {
+  clearHistory,
+  deleteHistoryEntry,
+  getHistory,
+  pinHistoryEntry,
+  type HistoryEntry,
+  type HistoryQuery,
+} from "@/ffi/history";
+import { curlCommand } from "@/ffi/collection";
+import { copyToClipboard } from "@/clipboard";
+import { currentLocale } from "@/locale";
+import { ContextMenu, type MenuAction } from "@/ui/ContextMenu";
+import { statusColor, theme } from "@/ui/theme";
+
+// Recent (unpinned) entries listed below the saved ones.
+const RECENT_LIMIT = 15;
+
+// What s and v cycle through; the first shows everything.
+const STATUS_FILTERS = [undefined, "error", "2xx", "3xx", "4xx", "5xx"];
+const METHOD_FILTERS = [undefined, "GET", "POST", "PUT", "PATCH", "DELETE"];
+
+function cycle<T>(options: T[], current: T): T {
+  return options[(options.indexOf(current) + 1) % options.length]!;
+}
+
+export function formatTime(timestamp: string): string {
+  const date = new Date(timestamp);
+  return Number.isNaN(date.getTime())
+    ? timestamp
+    : date.toLocaleString(currentLocale(), {
+        month: "short",
+        day: "numeric",
+        hour: "2-digit",
+        minute: "2-digit",
+      });
+}
+
+function EntryRow(props: { entry: HistoryEntry; active: boolean }) {
+  const { entry } = props;
+  const status = entry.response.status;
+  return (
+    <Text wrap="truncate-end" inverse={props.active}>
+      <Text color="yellow">{entry.pinned ? "★ " : "  "}</Text>
+      <Text color={statusColor(status)}>
+        {String(status).padEnd(4)}
+      </Text>
+      <Text dimColor>{entry.request.method.toUpperCase().padEnd(7)}</Text>
+      {entry.request.url}
+      <Text dimColor>
+        {"  "}
+        {entry.response.durationMs}ms · {formatTime(entry.timestamp)}
+        {entry.space ? ` · ${entry.space}` : ""}
+      </Text>
+    </Text>
+  );
+}
+
+/**
+ * Sent requests: saved (pinned) responses first, then the most recent ones.
+ * ↑/↓ pick an entry, Enter opens its response, r sends its request again,
+ * p pins or unpins it, c copies it as curl, x deletes it and X clears its
+ * space's history (after a y/n confirmation), m lists those actions.
+ *
+ * s cycles a status filter (errors, 2xx…5xx), v a method filter and / types
+ * text to find in the URL, request or response body; the whole history is
+ * searched, not just what's listed. Esc clears the filters, then closes.
+ */
+export function HistoryPanel(props: {
+  onClose: () => void;
+  onOpen: (entry: HistoryEntry) => void;
+  onResend: (entry: HistoryEntry) => void;
+}) {
+  const [revision, setRevision] = useState(0);
+  const [highlighted, setHighlighted] = useState(0);
+  const [error, setError] = useState<string | null>(null);
+  const [notice, setNotice] = useState<string | null>(null);
+  // A destructive action waiting for y; it returns an error or a notice.
+  const [confirm, setConfirm] = useState<{
+    question: string;
+    run: () => { error?: string; notice?: string };
+  } | null>(null);
+  const [menuOpen, setMenuOpen] = useState(false);
+  const [status, setStatus] = useState<string | undefined>(undefined);
+  const [method, setMethod] = useState<string | undefined>(undefined);
+  const [text, setText] = useState("");
+  const [searching, setSearching] = useState(false);
+  const filtered = status !== undefined || method !== undefined || text !== "";
+
+  const { saved, recent } = useMemo(() => {
+    const filter: HistoryQuery = {
+      status,
+      method,
+      text: text.trim() || undefined,
+    };
+    return {
+      saved: getHistory({ ...filter, pinned: true }),
+      recent: getHistory({ ...filter, pinned: false, limit: RECENT_LIMIT }),
+    };
+  }, [revision, status, method, text]);
+  const entries = [...saved, ...recent];
+  const selected = Math.min(highlighted, Math.max(entries.length - 1, 0));
+
+  const actions = (entry: HistoryEntry): MenuAction[] => [
+    { key: "o", label: "Open response", run: () => props.onOpen(entry) },
+    { key: "r", label: "Send again", run: () => props.onResend(entry) },
+    {
+      key: "p",
+      label: entry.pinned ? "Unpin" : "Pin",
+      run: () => {
+        const result = pinHistoryEntry(entry.id, !entry.pinned);
+        setError(result.error ?? null);
+        setRevision((n) => n + 1);
+      },
+    },
+    {
+      key: "c",
+      label: "Copy as cURL",
+      run: () => {
+        const curl = curlCommand(entry.sent ?? entry.request);
+        if ("error" in curl) {
+          setError(curl.error);
+          return;
+        }
+        copyToClipboard(curl.command);
+        setError(null);
+        setNotice("Copied the request as curl");
+      },
+    },
+    {
+      key: "x",
+      label: "Delete…",
+      destructive: true,
+      run: () =>
+        setConfirm({
+          question: entry.pinned
+            ? "Delete this saved response?"
+            : "Delete this entry?",
+          run: () => {
+            const result = deleteHistoryEntry(entry.id);
+            return result.error ? result : { notice: "Entry deleted" };
+          },
+        }),
+    },
+    {
+      key: "X",
+      label: "Clear space history…",
+      destructive: true,
+      run: () => {
+        const space = entry.spaceId ?? entry.space;
+        if (!space) {
+          setError("Pick an entry of the space to clear");
+          return;
+        }
+        setConfirm({
+          question: `Clear the history of ${entry.space ?? space}? Saved responses are kept.`,
+          run: () => {
+            const result = clearHistory(space);
+            if ("error" in result) return result;
+            return {
+              notice: `Deleted ${result.deleted} entr${
+                result.deleted === 1 ? "y" : "ies"
+              }`,
+            };
+          },
+        });
+      },
+    },
+  ];
+
+  useInput(
+    (input, key) => {
+      if (searching) {
+        if (key.escape || key.return) setSearching(false);
+        return;
+      }
+      if (confirm) {
+        if (input === "y") {
+          const result = confirm.run();
+          setError(result.error ?? null);
+          setNotice(result.notice ?? null);
+          setRevision((n) => n + 1);
+        }
+        setConfirm(null);
+        return;
+      }
+      const entry = entries[selected];
+      if (key.escape) {
+        if (!filtered) {
+          props.onClose();
+          return;
+        }
+        setStatus(undefined);
+        setMethod(undefined);
+        setText("");
+      } else if (input === "s") {
+        setStatus((s) => cycle(STATUS_FILTERS, s));
+        setHighlighted(0);
+      } else if (input === "v") {
+        setMethod((m) => cycle(METHOD_FILTERS, m));
+        setHighlighted(0);
+      } else if (input === "/") {
+        setSearching(true);
+      } else if (key.upArrow) {
+        setHighlighted(selected > 0 ? selected - 1 : entries.length - 1);
+      } else if (key.downArrow) {
+        setHighlighted(selected < entries.length - 1 ? selected + 1 : 0);
+      } else if (!entry) {
+        return;
+      } else if (key.return) {
+        props.onOpen(entry);
+      } else if (input === "m") {
+        setMenuOpen(true);
+      } else {
+        actions(entry)
+          .find((a) => a.key === input)
+          ?.run();
+      }
+    },
+    { isActive: !menuOpen }
+  );
+
+  const selectedEntry = entries[selected];
+  const menu = selectedEntry ? (
+    <ContextMenu
+      title={`${selectedEntry.request.method.toUpperCase()} ${
+        selectedEntry.request.url
+      }`}
+      actions={actions(selectedEntry)}
+      onClose={() => setMenuOpen(false)}
+    />
+  ) : null;
+
+  return (
+    <Box
+      flexDirection="column"
+      borderStyle="round"
+      borderColor={theme.focusBorder}
+      paddingX={1}
+    >
+      <Box justifyContent="space-between">
+        <Text bold>History</Text>
+        <Text dimColor>
+          enter open · r re-send · p pin · x delete · m actions · esc
+        </Text>
+      </Box>
+      <Box gap={1}>
+        <Text dimColor>s</Text>
+        <Text color={status ? theme.focusBorder : undefined}>
+          {status === "error" ? "errors" : (status ?? "any status")}
+        </Text>
+        <Text dimColor>· v</Text>
+        <Text color={method ? theme.focusBorder : undefined}>
+          {method ?? "any method"}
+        </Text>
+        <Text dimColor>· /</Text>
+        {searching ? (
+          <TextInput
+            defaultValue={text}
+            placeholder="text in URL or body"
+            onChange={(next) => {
+              setText(next);
+              setHighlighted(0);
+            }}
+          />
+        ) : (
+          <Text color={text ? theme.focusBorder : undefined}>
+            {text ? `"${text}"` : "any text"}
+          </Text>
+        )}
+      </Box>
+      {confirm ? (
+        <Text color="yellow">
+          {confirm.question} <Text bold>y</Text>/n
+        </Text>
+      ) : error ? (
+        <Text color="red">{error}</Text>
+      ) : notice ? (
+        <Text color="green">{notice}</Text>
+      ) : null}
+
+      <Box flexDirection="column" marginTop={1}>
+        <Text bold color={theme.focusBorder}>
+          Saved responses
+        </Text>
+        {saved.length === 0 ? (
+          <Text dimColor>
+            {filtered
+              ? "None match the filters."
+              : "None yet. Pin a response with p to keep it as a baseline."}
+          </Text>
+        ) : (
+          saved.map((entry, i) => (
+            <Box key={entry.id} flexDirection="column">
+              <EntryRow entry={entry} active={i === selected} />
+              {menuOpen && i === selected ? menu : null}
+            </Box>
+          ))
+        )}
+      </Box>
+
+      <Box flexDirection="column" marginTop={1}>
+        <Text bold color={theme.focusBorder}>
+          {filtered ? "Recent matches" : "Recent"}
+        </Text>
+        {recent.length === 0 ? (
+          <Text dimColor>
+            {filtered ? "None match the filters." : "Nothing sent yet."}
+          </Text>
+        ) : (
+          recent.map((entry, i) => (
+            <Box key={entry.id} flexDirection="column">
+              <EntryRow entry={entry} active={saved.length + i === selected} />
+              {menuOpen && saved.length + i === selected ? menu : null}
+            </Box>
+          ))
+        )}
+      </Box>
+    </Box>
+  );
+}
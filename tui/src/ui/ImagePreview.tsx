@@ -0,0 +1,166 @@
+import { useEffect, useMemo, useRef, useState } from "react";
+import { Box, Text, measureElement, useInput, type DOMElement } from "ink";
+import { spawn } from "node:child_process";
+import { writeFileSync } from "node:fs";
+import { tmpdir } from "node:os";
+import { join } from "node:path";
+import { decodePng, imageInfo, type Bitmap } from "@/image";
+
+export function formatBytes(n: number): string {
+  if (n < 1024) return `${n} B`;
+  if (n < 1024 * 1024) return `${(n / 1024).toFixed(1)} KB`;
+  return `${(n / 1024 / 1024).toFixed(1)} MB`;
+}
+
+type Cell = { char: string; color?: string; background?: string };
+
+function hex(rgba: Uint8Array, i: number): string | undefined {
+  // Mostly transparent pixels show the terminal background.
+  if (rgba[i + 3]! < 128) return undefined;
+  return `#${[rgba[i]!, rgba[i + 1]!, rgba[i + 2]!]
+    .map((v) => v.toString(16).padStart(2, "0"))
+    .join("")}`;
+}
+
+/**
+ * Scale `bitmap` into at most `cols` × `rows` cells, two pixels per cell
+ * stacked with a half block, which keeps pixels roughly square.
+ */
+function toCells(bitmap: Bitmap, cols: number, rows: number): Cell[][] {
+  const scale = Math.min(cols / bitmap.width, (rows * 2) / bitmap.height, 1);
+  const width = Math.max(1, Math.floor(bitmap.width * scale));
+  const height = Math.max(1, Math.floor(bitmap.height * scale));
+  const pixel = (x: number, y: number) => {
+    if (y >= height) return undefined;
+    const sx = Math.min(bitmap.width - 1, Math.floor(x / scale));
+    const sy = Math.min(bitmap.height - 1, Math.floor(y / scale));
+    return hex(bitmap.rgba, (sy * bitmap.width + sx) * 4);
+  };
+  const lines: Cell[][] = [];
+  for (let y = 0; y < height; y += 2) {
+    const line: Cell[] = [];
+    for (let x = 0; x < width; x++) {
+      const top = pixel(x, y);
+      const bottom = pixel(x, y + 1);
+      if (top) line.push({ char: "▀", color: top, background: bottom });
+      else if (bottom) line.push({ char: "▄", color: bottom });
+      else line.push({ char: " " });
+    }
+    lines.push(line);
+  }
+  return lines;
+}
+
+function extensionFor(contentType: string): string {
+  const subtype = contentType.split(";")[0]!.split("/")[1]?.trim() ?? "";
+  if (subtype === "jpeg") return "jpg";
+  if (subtype === "svg+xml") return "svg";
+  return subtype.replace(/[^a-z0-9]/gi, "") || "bin";
+}
+
+/** Write the image to a temp file and hand it to the system viewer. */
+function openExternally(bytes: Uint8Array, contentType: string): string {
+  const path = join(
+    tmpdir(),
+    `pigeon-preview-${Date.now()}.${extensionFor(contentType)}`
+  );
+  writeFileSync(path, bytes);
+  const [command, ...args] =
+    process.platform === "darwin"
+      ? ["open", path]
+      : process.platform === "win32"
+      ? ["cmd", "/c", "start", "", path]
+      : ["xdg-open", path];
+  spawn(command!, args, { detached: true, stdio: "ignore" }).unref();
+  return path;
+}
+
+/**
+ * An image response: PNGs drawn with colored half blocks, every format with
+ * its dimensions and size. o opens it in the system viewer.
+ */
+export function ImagePreview(props: {
+  bytes: Uint8Array;
+  contentType: string;
+  height: number;
+  isActive: boolean;
+  onMessage?: (variant: "success" | "error", text: string) => void;
+}) {
+  const info = useMemo(() => imageInfo(props.bytes), [props.bytes]);
+  const bitmap = useMemo(() => decodePng(props.bytes), [props.bytes]);
+
+  const contentRef = useRef<DOMElement>(null);
+  const [contentWidth, setContentWidth] = useState(0);
+  useEffect(() => {
+    if (!contentRef.current) return;
+    const { width } = measureElement(contentRef.current);
+    if (width !== contentWidth) setContentWidth(width);
+  });
+
+  // Viewport is the inner height: total minus borders, header and footer.
+  const viewportHeight = Math.max(1, props.height - 4);
+  const cells = useMemo(
+    () =>
+      bitmap && contentWidth > 0
+        ? toCells(bitmap, contentWidth, viewportHeight)
+        : null,
+    [bitmap, contentWidth, viewportHeight]
+  );
+
+  useInput(
+    (input) => {
+      if (input !== "o") return;
+      try {
+        const path = openExternally(props.bytes, props.contentType);
+        props.onMessage?.("success", `Opened ${path}`);
+      } catch (error) {
+        props.onMessage?.("error", `Could not open the image: ${error}`);
+      }
+    },
+    { isActive: props.isActive }
+  );
+
+  return (
+    <Box
+      flexDirection="column"
+      borderStyle="round"
+      paddingX={1}
+      paddingY={0}
+      height={props.height}
+    >
+      <Box justifyContent="space-between">
+        <Text bold>Image</Text>
+        <Text dimColor>o open in viewer</Text>
+      </Box>
+
+      <Box flexDirection="column" flexGrow={1} ref={contentRef}>
+        {cells ? (
+          cells.map((line, y) => (
+            <Text key={y}>
+              {line.map((cell, x) => (
+                <Text
+                  key={x}
+                  color={cell.color}
+                  backgroundColor={cell.background}
+                >
+                  {cell.char}
+                </Text>
+              ))}
+            </Text>
+          ))
+        ) : (
+          <Text dimColor>
+            {info
+              ? `No inline preview for ${info.format}; press o to view it.`
+              : "Not an image format pigeon recognizes; press o to open it."}
+          </Text>
+        )}
+      </Box>
+
+      <Text dimColor wrap="truncate-end">
+        {info ? `${info.format} ${info.width}×${info.height} · ` : ""}
+        {formatBytes(props.bytes.length)} · {props.contentType}
+      </Text>
+    </Box>
+  );
+}
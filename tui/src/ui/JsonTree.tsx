@@ -0,0 +1,216 @@
+import { useEffect, useMemo, useState } from "react";
+import { Box, Text, useInput } from "ink";
+import { copyToClipboard } from "@/clipboard";
+
+type Row = {
+  path: string;
+  depth: number;
+  label: string | null;
+  value: unknown;
+  /** Child count for objects and arrays; null for other values. */
+  size: number | null;
+  isArray: boolean;
+};
+
+const IDENTIFIER = /^[A-Za-z_$][\w$]*$/;
+
+/** `parent`'s child `key` as a path like `$.items[0]["content-type"]`. */
+export function childPath(parent: string, key: string | number): string {
+  if (typeof key === "number") return `${parent}[${key}]`;
+  return IDENTIFIER.test(key)
+    ? `${parent}.${key}`
+    : `${parent}[${JSON.stringify(key)}]`;
+}
+
+export function entriesOf(value: unknown): Array<[string | number, unknown]> | null {
+  if (Array.isArray(value)) return value.map((v, i) => [i, v]);
+  if (value !== null && typeof value === "object") {
+    return Object.entries(value as Record<string, unknown>);
+  }
+  return null;
+}
+
+/** Rows for every node whose ancestors are all expanded. */
+function visibleRows(root: unknown, expanded: Set<string>): Row[] {
+  const rows: Row[] = [];
+  const walk = (
+    value: unknown,
+    path: string,
+    depth: number,
+    label: string | null
+  ) => {
+    const entries = entriesOf(value);
+    rows.push({
+      path,
+      depth,
+      label,
+      value,
+      size: entries ? entries.length : null,
+      isArray: Array.isArray(value),
+    });
+    if (!entries || !expanded.has(path)) return;
+    for (const [key, child] of entries) {
+      walk(child, childPath(path, key), depth + 1, String(key));
+    }
+  };
+  walk(root, "$", 0, null);
+  return rows;
+}
+
+/** Containers down to `depth` levels, expanded initially. */
+function expandedTo(root: unknown, depth: number): Set<string> {
+  const paths = new Set<string>();
+  const walk = (value: unknown, path: string, level: number) => {
+    const entries = entriesOf(value);
+    if (!entries || level >= depth) return;
+    paths.add(path);
+    for (const [key, child] of entries) {
+      walk(child, childPath(path, key), level + 1);
+    }
+  };
+  walk(root, "$", 0);
+  return paths;
+}
+
+function allContainers(root: unknown): Set<string> {
+  return expandedTo(root, Infinity);
+}
+
+function Scalar(props: { value: unknown }) {
+  const { value } = props;
+  if (typeof value === "string") {
+    return <Text color="green">{JSON.stringify(value)}</Text>;
+  }
+  if (typeof value === "number") return <Text color="cyan">{value}</Text>;
+  if (typeof value === "boolean") {
+    return <Text color="yellow">{String(value)}</Text>;
+  }
+  return <Text dimColor>null</Text>;
+}
+
+/**
+ * A parsed JSON document as a collapsible tree. ↑/↓ move, Enter/Space expand
+ * or collapse a node, * expands and - collapses everything, y copies the
+ * node's path and c its value. (←/→ stay with the response tabs.)
+ */
+export function JsonTree(props: {
+  value: unknown;
+  height: number;
+  isActive: boolean;
+  onCopy?: (message: string) => void;
+}) {
+  const [expanded, setExpanded] = useState(() => expandedTo(props.value, 2));
+  const [cursor, setCursor] = useState(0);
+  const [scrollTop, setScrollTop] = useState(0);
+
+  // A new response starts folded again.
+  useEffect(() => {
+    setExpanded(expandedTo(props.value, 2));
+    setCursor(0);
+    setScrollTop(0);
+  }, [props.value]);
+
+  const rows = useMemo(
+    () => visibleRows(props.value, expanded),
+    [props.value, expanded]
+  );
+  // Viewport is the inner height: total minus borders, header and footer.
+  const viewportHeight = Math.max(1, props.height - 4);
+  const selected = Math.min(cursor, rows.length - 1);
+  const row = rows[selected];
+
+  const moveTo = (index: number) => {
+    const next = Math.max(0, Math.min(rows.length - 1, index));
+    setCursor(next);
+    if (next < scrollTop) setScrollTop(next);
+    else if (next >= scrollTop + viewportHeight) {
+      setScrollTop(next - viewportHeight + 1);
+    }
+  };
+
+  const setOpen = (path: string, open: boolean) =>
+    setExpanded((prev) => {
+      const next = new Set(prev);
+      if (open) next.add(path);
+      else next.delete(path);
+      return next;
+    });
+
+  useInput(
+    (input, key) => {
+      if (!row) return;
+      if (key.upArrow) moveTo(selected - 1);
+      else if (key.downArrow) moveTo(selected + 1);
+      else if (key.pageUp) moveTo(selected - viewportHeight);
+      else if (key.pageDown) moveTo(selected + viewportHeight);
+      else if (key.return || input === " ") {
+        if (row.size !== null) setOpen(row.path, !expanded.has(row.path));
+      } else if (input === "-") {
+        setExpanded(new Set(["$"]));
+        moveTo(0);
+      } else if (input === "*") {
+        setExpanded(allContainers(props.value));
+      } else if (input === "y") {
+        copyToClipboard(row.path);
+        props.onCopy?.(`Copied path ${row.path}`);
+      } else if (input === "c") {
+        copyToClipboard(
+          typeof row.value === "string"
+            ? row.value
+            : JSON.stringify(row.value, null, 2)
+        );
+        props.onCopy?.(`Copied value of ${row.path}`);
+      }
+    },
+    { isActive: props.isActive }
+  );
+
+  const displayed = rows.slice(scrollTop, scrollTop + viewportHeight);
+
+  return (
+    <Box
+      flexDirection="column"
+      borderStyle="round"
+      paddingX={1}
+      paddingY={0}
+      height={props.height}
+    >
+      <Box justifyContent="space-between">
+        <Text bold>Body</Text>
+        <Text dimColor>enter toggle · */- all · y path · c value</Text>
+      </Box>
+
+      <Box flexDirection="column">
+        {displayed.map((r, i) => {
+          const active = props.isActive && scrollTop + i === selected;
+          const open = expanded.has(r.path);
+          return (
+            <Text key={r.path} wrap="truncate-end" inverse={active}>
+              {"  ".repeat(r.depth)}
+              {r.size === null ? "  " : open ? "▾ " : "▸ "}
+              {r.label !== null ? <Text bold>{r.label}: </Text> : null}
+              {r.size === null ? (
+                <Scalar value={r.value} />
+              ) : (
+                <Text dimColor>
+                  {r.isArray ? (open ? "[" : "[…]") : open ? "{" : "{…}"}{" "}
+                  {r.size} {r.isArray ? "item" : "key"}
+                  {r.size === 1 ? "" : "s"}
+                </Text>
+              )}
+            </Text>
+          );
+        })}
+      </Box>
+
+      <Box justifyContent="space-between">
+        <Text dimColor wrap="truncate-end">
+          {row?.path ?? "$"}
+        </Text>
+        <Text dimColor>
+          {selected + 1}/{rows.length}
+        </Text>
+      </Box>
+    </Box>
+  );
+}
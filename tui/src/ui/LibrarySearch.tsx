@@ -0,0 +1,512 @@
+import { useMemo, useState } from "react";
+import { Box, Text, useInput } from "ink";
+import { TextInput } from "@inkjs/ui";
+import {
+  bulkEditWorkspace,
+  displayValue,
+  duplicateWorkspaceItem,
+  removeWorkspaceItem,
+  searchWorkspace,
+  updateWorkspaceItem,
+  type BulkOp,
+  type Workspace,
+  type WorkspaceFolder,
+  type WorkspaceItemKind,
+  type WorkspaceSpace,
+} from "@/ffi/workspace";
+import { curlCommand } from "@/ffi/collection";
+import { copyToClipboard } from "@/clipboard";
+import { ContextMenu, type MenuAction } from "@/ui/ContextMenu";
+import { QuickSend } from "@/ui/QuickSend";
+import { theme } from "@/ui/theme";
+
+// Rows shown at once; more matches are summarized.
+const VISIBLE_MATCHES = 12;
+
+type Match = {
+  id: string;
+  itemKind: WorkspaceItemKind;
+  name: string;
+  kind: string;
+  label: string;
+  tags: string[];
+  /** Method and url, for endpoints. */
+  request?: { method: string; url: string };
+};
+
+/**
+ * Searches endpoints, headers and bodies; Enter filters the sidebar by the
+ * query, ↑/↓ pick a match and Tab lists its actions (rename, duplicate, quick
+ * send, copy as curl, put into a space, delete), Esc closes. Shift+↑/↓ select
+ * several matches; Tab then acts on all of them (tag, move to a folder,
+ * delete).
+ */
+export function LibrarySearch(props: {
+  initialQuery: string;
+  /** Spaces an endpoint or header can be put into. */
+  spaces: WorkspaceSpace[];
+  /** Folders selected endpoints can be moved to. */
+  folders: WorkspaceFolder[];
+  onApply: (query: string) => void;
+  onClose: () => void;
+  /** Called after an item is changed, so the workspace is re-read. */
+  onChange?: () => void;
+}) {
+  const [query, setQuery] = useState(props.initialQuery);
+  const [revision, setRevision] = useState(0);
+  const [highlighted, setHighlighted] = useState(0);
+  const [menuOpen, setMenuOpen] = useState(false);
+  const [renaming, setRenaming] = useState<Match | null>(null);
+  const [confirm, setConfirm] = useState<Match | null>(null);
+  // An endpoint or header waiting for the space it goes into.
+  const [placing, setPlacing] = useState<Match | null>(null);
+  // An endpoint sent without a space, its result shown under it.
+  const [quickSending, setQuickSending] = useState<Match | null>(null);
+  // Matches picked for bulk actions, kept while the query changes.
+  const [selection, setSelection] = useState<Map<string, Match>>(new Map());
+  const [bulkMenuOpen, setBulkMenuOpen] = useState(false);
+  const [tagging, setTagging] = useState(false);
+  const [moving, setMoving] = useState(false);
+  const [bulkConfirm, setBulkConfirm] = useState(false);
+  const [error, setError] = useState<string | null>(null);
+  const [notice, setNotice] = useState<string | null>(null);
+
+  const matches = useMemo<Match[]>(() => {
+    const found = searchWorkspace(query);
+    return [
+      ...found.endpoints.map((e) => ({
+        id: e.id,
+        itemKind: "endpoint" as const,
+        name: e.name,
+        kind: e.method.slice(0, 3).padEnd(4),
+        label: `${e.name}  ${e.url}`,
+        tags: e.tags ?? [],
+        request: { method: e.method, url: e.url },
+      })),
+      ...found.headers.map((h) => ({
+        id: h.id,
+        itemKind: "header" as const,
+        name: h.name,
+        kind: "HDR ",
+        label: `${h.name}  ${h.key}: ${displayValue(h.value, h.secret)}`,
+        tags: h.tags ?? [],
+      })),
+      ...found.bodies.map((b) => ({
+        id: b.id,
+        itemKind: "body" as const,
+        name: b.name,
+        kind: "BDY ",
+        label: `${b.name}  ${b.content_type}`,
+        tags: b.tags ?? [],
+      })),
+    ];
+  }, [query, revision]);
+  const selected = Math.min(highlighted, Math.max(matches.length - 1, 0));
+  const match = matches[selected];
+  const top = Math.max(0, selected - VISIBLE_MATCHES + 1);
+  const shown = matches.slice(top, top + VISIBLE_MATCHES);
+
+  const report = (
+    result: { workspace: Workspace } | { error: string },
+    success: string
+  ): boolean => {
+    if ("error" in result) {
+      setError(result.error);
+      setNotice(null);
+      return false;
+    }
+    setError(null);
+    setNotice(success);
+    setRevision((n) => n + 1);
+    props.onChange?.();
+    return true;
+  };
+
+  const rename = (item: Match, name: string) => {
+    const trimmed = name.trim();
+    if (trimmed === "") {
+      setError("A name is required");
+      return;
+    }
+    if (
+      trimmed === item.name ||
+      report(
+        updateWorkspaceItem(item.itemKind, item.id, { name: trimmed }),
+        `Renamed to ${trimmed}`
+      )
+    ) {
+      setRenaming(null);
+    }
+  };
+
+  const actions = (item: Match): MenuAction[] => [
+    {
+      key: "e",
+      label: "Rename",
+      run: () => {
+        setError(null);
+        setNotice(null);
+        setRenaming(item);
+      },
+    },
+    {
+      key: "d",
+      label: "Duplicate",
+      run: () =>
+        report(
+          duplicateWorkspaceItem(item.itemKind, item.id),
+          `Duplicated ${item.name}`
+        ),
+    },
+    ...(item.request
+      ? [
+          {
+            key: "q",
+            label: "Quick send",
+            run: () => setQuickSending(item),
+          },
+          {
+            key: "c",
+            label: "Copy as cURL",
+            run: () => {
+              const curl = curlCommand({ ...item.request!, headers: [] });
+              if ("error" in curl) {
+                setError(curl.error);
+                return;
+              }
+              copyToClipboard(curl.command);
+              setError(null);
+              setNotice(`Copied ${item.name} as curl`);
+            },
+          },
+        ]
+      : []),
+    ...(item.itemKind === "endpoint" || item.itemKind === "header"
+      ? [
+          {
+            key: "s",
+            label:
+              item.itemKind === "endpoint"
+                ? "Use in space…"
+                : "Toggle in space…",
+            run: () => setPlacing(item),
+          },
+        ]
+      : []),
+    {
+      key: "v",
+      label: "Select",
+      run: () => toggleSelected(item),
+    },
+    {
+      key: "x",
+      label: "Delete…",
+      destructive: true,
+      run: () => setConfirm(item),
+    },
+  ];
+
+  const toggleSelected = (item: Match) =>
+    setSelection((current) => {
+      const next = new Map(current);
+      if (next.has(item.id)) next.delete(item.id);
+      else next.set(item.id, item);
+      return next;
+    });
+
+  const picked = [...selection.values()];
+  const count = `${picked.length} item${picked.length === 1 ? "" : "s"}`;
+
+  const runBulk = (ops: BulkOp[], success: string) => {
+    if (report(bulkEditWorkspace(ops), success)) setSelection(new Map());
+  };
+
+  const tagSelected = (tag: string) => {
+    const trimmed = tag.trim().replace(/^#/, "");
+    if (trimmed === "") {
+      setError("A tag is required");
+      return;
+    }
+    setTagging(false);
+    const ops: BulkOp[] = picked.map((m) => ({
+      op: "update",
+      kind: m.itemKind,
+      key: m.id,
+      changes: { tags: [...new Set([...m.tags, trimmed])] },
+    }));
+    runBulk(ops, `Tagged ${count} #${trimmed}`);
+  };
+
+  // Only endpoints live in folders; other selected items stay put.
+  const moveSelected = (folder: WorkspaceFolder | null) => {
+    const endpoints = picked.filter((m) => m.itemKind === "endpoint");
+    if (endpoints.length === 0) {
+      setError("Only endpoints can be moved to a folder");
+      return;
+    }
+    const ops: BulkOp[] = endpoints.map((m) => ({
+      op: "update",
+      kind: "endpoint",
+      key: m.id,
+      changes: { folder_id: folder?.id ?? null },
+    }));
+    const moved = `${endpoints.length} endpoint${
+      endpoints.length === 1 ? "" : "s"
+    }`;
+    runBulk(ops, `Moved ${moved} to ${folder?.name ?? "the top level"}`);
+  };
+
+  const bulkActions = (): MenuAction[] => [
+    {
+      key: "t",
+      label: "Tag…",
+      run: () => {
+        setError(null);
+        setNotice(null);
+        setTagging(true);
+      },
+    },
+    { key: "f", label: "Move to folder…", run: () => setMoving(true) },
+    ...(match
+      ? [
+          {
+            key: "v",
+            label: selection.has(match.id)
+              ? `Deselect ${match.name}`
+              : `Select ${match.name}`,
+            run: () => toggleSelected(match),
+          },
+        ]
+      : []),
+    { key: "u", label: "Clear selection", run: () => setSelection(new Map()) },
+    {
+      key: "x",
+      label: `Delete ${count}…`,
+      destructive: true,
+      run: () => setBulkConfirm(true),
+    },
+  ];
+
+  // Folders are picked by number, so only the first nine are offered.
+  const folderChoices = (): MenuAction[] => [
+    { key: "0", label: "No folder", run: () => moveSelected(null) },
+    ...props.folders.slice(0, 9).map((folder, i) => ({
+      key: String(i + 1),
+      label: folder.name,
+      run: () => moveSelected(folder),
+    })),
+  ];
+
+  // Endpoints become the space's endpoint; headers are added or removed.
+  // Spaces are picked by number, so only the first nine are offered.
+  const placements = (item: Match): MenuAction[] =>
+    props.spaces.slice(0, 9).map((space, i) => {
+      const key = String(i + 1);
+      if (item.itemKind === "endpoint") {
+        const current = space.selected_endpoint_id === item.id;
+        return {
+          key,
+          label: `${space.name}${current ? " (current)" : ""}`,
+          run: () =>
+            report(
+              updateWorkspaceItem("space", space.id, {
+                selected_endpoint_id: item.id,
+              }),
+              `${space.name} now uses ${item.name}`
+            ),
+        };
+      }
+      const ids = space.selected_header_ids;
+      const selected = ids.includes(item.id);
+      return {
+        key,
+        label: `${selected ? "Remove from" : "Add to"} ${space.name}`,
+        run: () =>
+          report(
+            updateWorkspaceItem("space", space.id, {
+              selected_header_ids: selected
+                ? ids.filter((id) => id !== item.id)
+                : [...ids, item.id],
+            }),
+            selected
+              ? `Removed ${item.name} from ${space.name}`
+              : `Added ${item.name} to ${space.name}`
+          ),
+      };
+    });
+
+  useInput(
+    (input, key) => {
+      if (confirm) {
+        if (input === "y") {
+          report(
+            removeWorkspaceItem(confirm.itemKind, confirm.id),
+            `Deleted ${confirm.name}`
+          );
+        }
+        setConfirm(null);
+        return;
+      }
+      if (bulkConfirm) {
+        if (input === "y") {
+          const ops: BulkOp[] = picked.map((m) => ({
+            op: "remove",
+            kind: m.itemKind,
+            key: m.id,
+          }));
+          runBulk(ops, `Deleted ${count}`);
+        }
+        setBulkConfirm(false);
+        return;
+      }
+      if (renaming || tagging) {
+        if (key.escape) {
+          setRenaming(null);
+          setTagging(false);
+          setError(null);
+        }
+        return;
+      }
+      if (key.shift && match && (key.upArrow || key.downArrow)) {
+        toggleSelected(match);
+      }
+      if (key.escape) props.onClose();
+      else if (key.upArrow) {
+        setHighlighted(selected > 0 ? selected - 1 : matches.length - 1);
+      } else if (key.downArrow) {
+        setHighlighted(selected < matches.length - 1 ? selected + 1 : 0);
+      } else if (key.tab && selection.size > 0) setBulkMenuOpen(true);
+      else if (key.tab && match) setMenuOpen(true);
+    },
+    {
+      isActive:
+        !menuOpen &&
+        !bulkMenuOpen &&
+        !moving &&
+        placing === null &&
+        quickSending === null,
+    }
+  );
+
+  const busy =
+    menuOpen ||
+    bulkMenuOpen ||
+    moving ||
+    tagging ||
+    bulkConfirm ||
+    placing !== null ||
+    quickSending !== null ||
+    renaming !== null ||
+    confirm !== null;
+
+  return (
+    <Box
+      flexDirection="column"
+      borderStyle="round"
+      borderColor={theme.focusBorder}
+      paddingX={1}
+    >
+      <Box justifyContent="space-between">
+        <Text bold>Search library</Text>
+        <Text dimColor>
+          {matches.length} matches
+          {selection.size > 0 ? ` · ${selection.size} selected` : ""} · tab
+          actions · enter filter
+        </Text>
+      </Box>
+      <TextInput
+        defaultValue={props.initialQuery}
+        placeholder="Name, URL, method or #tag…"
+        isDisabled={busy}
+        onChange={(next) => {
+          setQuery(next);
+          setHighlighted(0);
+        }}
+        onSubmit={() => props.onApply(query.trim())}
+      />
+      {renaming ? (
+        <Box>
+          <Text bold>Rename {renaming.name}: </Text>
+          <TextInput
+            defaultValue={renaming.name}
+            onSubmit={(name) => rename(renaming, name)}
+          />
+        </Box>
+      ) : tagging ? (
+        <Box>
+          <Text bold>Tag {count}: #</Text>
+          <TextInput placeholder="tag" onSubmit={tagSelected} />
+        </Box>
+      ) : confirm ? (
+        <Text color="yellow">
+          Delete {confirm.name}? Spaces using it lose it.{" "}
+          <Text bold>y</Text>/n
+        </Text>
+      ) : bulkConfirm ? (
+        <Text color="yellow">
+          Delete {count}? Spaces using them lose them. <Text bold>y</Text>/n
+        </Text>
+      ) : error ? (
+        <Text color="red">{error}</Text>
+      ) : notice ? (
+        <Text color="green">{notice}</Text>
+      ) : null}
+      {bulkMenuOpen ? (
+        <ContextMenu
+          title={`${count} selected`}
+          actions={bulkActions()}
+          onClose={() => setBulkMenuOpen(false)}
+        />
+      ) : moving ? (
+        <ContextMenu
+          title={`Move ${count} to`}
+          actions={folderChoices()}
+          onClose={() => setMoving(false)}
+        />
+      ) : null}
+      <Box flexDirection="column" marginTop={1}>
+        {matches.length === 0 ? (
+          <Text dimColor>Nothing matches.</Text>
+        ) : (
+          shown.map((m, i) => (
+            <Box key={m.id} flexDirection="column">
+              <Text wrap="truncate-end" inverse={top + i === selected}>
+                {selection.size > 0 ? (
+                  <Text color={theme.focusBorder}>
+                    {selection.has(m.id) ? "✓ " : "  "}
+                  </Text>
+                ) : null}
+                <Text dimColor>{m.kind}</Text>
+                {m.label}
+                {m.tags.length > 0 ? (
+                  <Text color="cyan"> #{m.tags.join(" #")}</Text>
+                ) : null}
+              </Text>
+              {menuOpen && top + i === selected ? (
+                <ContextMenu
+                  title={m.name}
+                  actions={actions(m)}
+                  onClose={() => setMenuOpen(false)}
+                />
+              ) : placing?.id === m.id ? (
+                <ContextMenu
+                  title={`${m.name} →`}
+                  actions={placements(m)}
+                  onClose={() => setPlacing(null)}
+                />
+              ) : quickSending?.id === m.id && m.request ? (
+                <QuickSend
+                  request={m.request}
+                  onClose={() => setQuickSending(null)}
+                />
+              ) : null}
+            </Box>
+          ))
+        )}
+        {matches.length > top + shown.length ? (
+          <Text dimColor>… {matches.length - top - shown.length} more</Text>
+        ) : null}
+      </Box>
+    </Box>
+  );
+}
@@ -0,0 +1,56 @@
+import { useEffect, useState } from "react";
+import { Box, Text } from "ink";
+import { getConsoleEntries, type ConsoleEntry } from "@/ffi/console";
+import { theme } from "@/ui/theme";
+
+// Entries kept on screen; the core buffers more.
+const VISIBLE_ENTRIES = 8;
+
+const levelColor: Record<ConsoleEntry["level"], string | undefined> = {
+  info: undefined,
+  warn: "yellow",
+  error: "red",
+};
+
+/** Output of `print`, `pigeon.log.*` and failing hooks/plugins, polled from the core. */
+export function LuaConsole() {
+  const [entries, setEntries] = useState<ConsoleEntry[]>(() =>
+    getConsoleEntries().slice(-VISIBLE_ENTRIES)
+  );
+
+  useEffect(() => {
+    const timer = setInterval(() => {
+      setEntries((current) => {
+        const fresh = getConsoleEntries(current.at(-1)?.id ?? 0);
+        if (fresh.length === 0) return current;
+        return [...current, ...fresh].slice(-VISIBLE_ENTRIES);
+      });
+    }, 500);
+    return () => clearInterval(timer);
+  }, []);
+
+  return (
+    <Box
+      flexDirection="column"
+      borderStyle="round"
+      borderColor={theme.focusBorder}
+      paddingX={1}
+      height={VISIBLE_ENTRIES + 3}
+    >
+      <Text bold>Lua console</Text>
+      {entries.length === 0 ? (
+        <Text dimColor>No output yet. Use print() or pigeon.log.info().</Text>
+      ) : (
+        entries.map((entry) => (
+          <Text key={entry.id} color={levelColor[entry.level]} wrap="truncate">
+            <Text dimColor>
+              [{entry.timestamp.split("T")[1]?.split(".")[0] ?? "??:??:??"}]{" "}
+              {entry.source}:
+            </Text>{" "}
+            {entry.message}
+          </Text>
+        ))
+      )}
+    </Box>
+  );
+}
@@ -0,0 +1,117 @@
+import React from "react";
+import { Box, Text } from "ink";
+import { theme } from "@/ui/theme";
+
+// `code`, **bold**, *italic* / _italic_ and [links](url), in source order.
+const INLINE = /`([^`]+)`|\*\*([^*]+)\*\*|\*([^*]+)\*|_([^_]+)_|\[([^\]]+)\]\(([^)]+)\)/g;
+
+function Inline(props: { text: string }) {
+  const parts: React.ReactNode[] = [];
+  let last = 0;
+  for (const m of props.text.matchAll(INLINE)) {
+    const at = m.index ?? 0;
+    if (at > last) parts.push(props.text.slice(last, at));
+    const key = `i-${at}`;
+    if (m[1] !== undefined) {
+      parts.push(
+        <Text key={key} color={theme.keyHint}>
+          {m[1]}
+        </Text>
+      );
+    } else if (m[2] !== undefined) {
+      parts.push(
+        <Text key={key} bold>
+          {m[2]}
+        </Text>
+      );
+    } else if (m[3] !== undefined || m[4] !== undefined) {
+      parts.push(
+        <Text key={key} italic>
+          {m[3] ?? m[4]}
+        </Text>
+      );
+    } else {
+      parts.push(
+        <Text key={key}>
+          <Text underline>{m[5]}</Text>
+          <Text dimColor> ({m[6]})</Text>
+        </Text>
+      );
+    }
+    last = at + m[0].length;
+  }
+  if (last < props.text.length) parts.push(props.text.slice(last));
+  return <>{parts}</>;
+}
+
+/**
+ * Renders the common subset of markdown used in notes: headings, lists,
+ * quotes, fenced code and inline emphasis, code and links. Shows at most
+ * `maxLines` source lines.
+ */
+export function Markdown(props: { source: string; maxLines?: number }) {
+  const lines = props.source.split("\n").slice(0, props.maxLines);
+  let inCode = false;
+
+  return (
+    <Box flexDirection="column">
+      {lines.map((line, i) => {
+        const key = `md-${i}`;
+        if (line.trimStart().startsWith("```")) {
+          inCode = !inCode;
+          return null;
+        }
+        if (inCode) {
+          return (
+            <Text key={key} color={theme.keyHint} wrap="truncate-end">
+              {"  "}
+              {line}
+            </Text>
+          );
+        }
+
+        const heading = /^(#{1,6})\s+(.*)$/.exec(line);
+        if (heading) {
+          return (
+            <Text
+              key={key}
+              bold
+              underline={heading[1]!.length === 1}
+              color={theme.focusBorder}
+            >
+              <Inline text={heading[2]!} />
+            </Text>
+          );
+        }
+        if (/^\s*([-*_])(\s*\1){2,}\s*$/.test(line)) {
+          return (
+            <Text key={key} dimColor>
+              ────────
+            </Text>
+          );
+        }
+        const bullet = /^(\s*)[-*+]\s+(.*)$/.exec(line);
+        if (bullet) {
+          return (
+            <Text key={key}>
+              {bullet[1]}• <Inline text={bullet[2]!} />
+            </Text>
+          );
+        }
+        const quote = /^>\s?(.*)$/.exec(line);
+        if (quote) {
+          return (
+            <Text key={key} dimColor>
+              │ <Inline text={quote[1]!} />
+            </Text>
+          );
+        }
+        return (
+          <Text key={key}>
+            {line.length === 0 ? " " : <Inline text={line} />}
+          </Text>
+        );
+      })}
+    </Box>
+  );
+}
@@ -0,0 +1,77 @@
+import React, { useEffect, useState } from "react";
+import { Box, Text, useInput } from "ink";
+import { Markdown } from "@/ui/Markdown";
+import { TextArea } from "@/ui/TextArea";
+
+export type NotesTarget = {
+  kind: "endpoint" | "space";
+  id: string;
+  name: string;
+  notes: string;
+};
+
+/**
+ * Details of the current endpoint and the spaces using it: their markdown
+ * notes rendered, with an editor below. ←/→ switch between them outside edit
+ * mode; ctrl+s in the editor saves.
+ */
+export function NotesPane(props: {
+  targets: NotesTarget[];
+  isActive: boolean;
+  onSave: (target: NotesTarget, notes: string) => void;
+}) {
+  const [index, setIndex] = useState(0);
+  const [isEditing, setIsEditing] = useState(false);
+  const count = props.targets.length;
+
+  useEffect(() => {
+    if (index >= count) setIndex(0);
+  }, [index, count]);
+
+  useInput(
+    (_input, key) => {
+      if (isEditing || count < 2) return;
+      if (key.leftArrow) setIndex((i) => (i + count - 1) % count);
+      else if (key.rightArrow) setIndex((i) => (i + 1) % count);
+    },
+    { isActive: props.isActive }
+  );
+
+  const target = props.targets[Math.min(index, count - 1)];
+  if (!target) {
+    return (
+      <Text dimColor>
+        The request isn't a library endpoint, so it has no notes.
+      </Text>
+    );
+  }
+
+  return (
+    <Box flexDirection="column" gap={1}>
+      <Box justifyContent="space-between">
+        <Text bold>
+          {target.kind === "space" ? "Space" : "Endpoint"} · {target.name}
+        </Text>
+        {count > 1 ? (
+          <Text dimColor>
+            {index + 1}/{count} (←/→)
+          </Text>
+        ) : null}
+      </Box>
+      {target.notes.trim() ? (
+        <Markdown source={target.notes} maxLines={12} />
+      ) : (
+        <Text dimColor>No notes yet.</Text>
+      )}
+      <TextArea
+        key={`${target.kind}-${target.id}`}
+        title="Notes (markdown)"
+        value={target.notes}
+        isActive={props.isActive}
+        height={8}
+        onChange={(notes) => props.onSave(target, notes)}
+        onEditingChange={setIsEditing}
+      />
+    </Box>
+  );
+}
@@ -0,0 +1,147 @@
+import { useState } from "react";
+import { readFileSync } from "node:fs";
+import { Box, Text, useInput } from "ink";
+import { TextInput } from "@inkjs/ui";
+import { setupWorkspace, type WorkspaceSetup } from "@/ffi/workspace";
+import { theme } from "@/ui/theme";
+
+type ImportKind = NonNullable<WorkspaceSetup["import"]>["kind"];
+
+type Step = "start" | "import" | "importData" | "space";
+
+const IMPORT_KINDS: Record<string, { kind: ImportKind; label: string }> = {
+  c: { kind: "curl", label: "a cURL command" },
+  p: { kind: "postman", label: "a Postman collection file" },
+  o: { kind: "openapi", label: "an OpenAPI file (JSON)" },
+};
+
+/**
+ * Guided setup on first launch: start empty or from the sample requests,
+ * optionally import a collection or cURL command, then name the first space.
+ * Esc on the first step keeps the sample workspace as it is.
+ */
+export function Onboarding(props: {
+  onDone: (imported: number) => void;
+  onSkip: () => void;
+}) {
+  const [step, setStep] = useState<Step>("start");
+  const [sample, setSample] = useState(false);
+  const [importKind, setImportKind] = useState<ImportKind | null>(null);
+  const [importData, setImportData] = useState<string | null>(null);
+  const [error, setError] = useState<string | null>(null);
+
+  useInput((input, key) => {
+    if (key.escape) {
+      setError(null);
+      if (step === "start") props.onSkip();
+      else if (step === "import") setStep("start");
+      else if (step === "importData") setStep("import");
+      else setStep(importData === null ? "import" : "importData");
+      return;
+    }
+    if (step === "start" && (input === "1" || input === "2")) {
+      setSample(input === "2");
+      setStep("import");
+    } else if (step === "import") {
+      const choice = IMPORT_KINDS[input];
+      if (choice) {
+        setImportKind(choice.kind);
+        setStep("importData");
+      } else if (key.return) {
+        setImportData(null);
+        setStep("space");
+      }
+    }
+  });
+
+  const submitImport = (value: string) => {
+    const text = value.trim();
+    if (!text) return;
+    if (importKind === "curl") {
+      setImportData(text);
+    } else {
+      try {
+        setImportData(readFileSync(text, "utf8"));
+      } catch (e) {
+        setError(String(e));
+        return;
+      }
+    }
+    setError(null);
+    setStep("space");
+  };
+
+  const finish = (space: string) => {
+    const result = setupWorkspace({
+      sample,
+      import:
+        importKind && importData !== null
+          ? { kind: importKind, data: importData }
+          : undefined,
+      space: space.trim() || undefined,
+    });
+    if ("error" in result) {
+      setError(result.error);
+      if (result.error.startsWith("import:")) setStep("importData");
+      return;
+    }
+    props.onDone(result.imported);
+  };
+
+  return (
+    <Box
+      flexDirection="column"
+      borderStyle="round"
+      borderColor={theme.focusBorder}
+      paddingX={1}
+    >
+      <Text bold>Welcome to Pigeon</Text>
+      {step === "start" ? (
+        <>
+          <Text>Set up your workspace:</Text>
+          <Text>
+            <Text color={theme.keyHint}>1</Text> Start empty
+          </Text>
+          <Text>
+            <Text color={theme.keyHint}>2</Text> Start with sample requests
+          </Text>
+          <Text dimColor>Esc keep the sample workspace and skip setup</Text>
+        </>
+      ) : step === "import" ? (
+        <>
+          <Text>Import requests? (optional)</Text>
+          {Object.entries(IMPORT_KINDS).map(([key, { label }]) => (
+            <Text key={key}>
+              <Text color={theme.keyHint}>{key}</Text> From {label}
+            </Text>
+          ))}
+          <Text dimColor>Enter skip · Esc back</Text>
+        </>
+      ) : step === "importData" ? (
+        <>
+          <Text>
+            {importKind === "curl"
+              ? "Paste the cURL command:"
+              : "Path to the file:"}
+          </Text>
+          <TextInput
+            placeholder={
+              importKind === "curl"
+                ? "curl https://api.example.com/users"
+                : "./collection.json"
+            }
+            onSubmit={submitImport}
+          />
+          <Text dimColor>Enter next · Esc back</Text>
+        </>
+      ) : (
+        <>
+          <Text>Name your first space:</Text>
+          <TextInput placeholder="Space 1" onSubmit={finish} />
+          <Text dimColor>Enter finish · Esc back</Text>
+        </>
+      )}
+      {error ? <Text color="red">{error}</Text> : null}
+    </Box>
+  );
+}
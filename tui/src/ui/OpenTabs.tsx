@@ -0,0 +1,80 @@
+import { Box, Text } from "ink";
+import type { FfiResponse, HttpMethod, RequestHeader } from "@/types";
+import { theme } from "@/ui/theme";
+
+/** One open request: its draft, its own send state and where its response was scrolled to. */
+export type OpenTab = {
+  id: number;
+  method: HttpMethod;
+  url: string;
+  headers: RequestHeader[];
+  contentType: string;
+  body: string;
+  response?: FfiResponse;
+  error?: string;
+  isLoading: boolean;
+  /** When the pending send started, in ms since the epoch. */
+  sentAt?: number;
+  responseScroll: number;
+  /** Set while `response` is an older one loaded from the history. */
+  historyEntry?: { id: number; timestamp: string };
+};
+
+export function newOpenTab(
+  id: number,
+  method: HttpMethod = "GET",
+  url = ""
+): OpenTab {
+  return {
+    id,
+    method,
+    url,
+    headers: [],
+    contentType: "application/json",
+    body: "",
+    isLoading: false,
+    responseScroll: 0,
+  };
+}
+
+function tabLabel(tab: OpenTab): string {
+  const path = tab.url.replace(/^[a-z]+:\/\/[^/]*/i, "") || tab.url || "new";
+  return `${tab.method} ${path.length > 18 ? `${path.slice(0, 17)}…` : path}`;
+}
+
+/** The open requests, one per tab; the active one is highlighted. */
+export function OpenTabBar(props: {
+  tabs: OpenTab[];
+  activeId: number;
+  hint: string;
+}) {
+  return (
+    <Box flexDirection="row" gap={1}>
+      {props.tabs.map((tab, i) => {
+        const active = tab.id === props.activeId;
+        const status = tab.isLoading
+          ? "…"
+          : tab.error
+          ? "!"
+          : tab.response
+          ? String(tab.response.status)
+          : "";
+        return (
+          <Text
+            key={tab.id}
+            color={active ? theme.focusBorder : undefined}
+            dimColor={!active}
+            bold={active}
+            wrap="truncate-end"
+          >
+            {active ? "[" : " "}
+            {i + 1} {tabLabel(tab)}
+            {status ? ` ${status}` : ""}
+            {active ? "]" : " "}
+          </Text>
+        );
+      })}
+      <Text dimColor>{props.hint}</Text>
+    </Box>
+  );
+}
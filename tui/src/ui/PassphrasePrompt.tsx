@@ -0,0 +1,34 @@
+import { Box, Text, useInput } from "ink";
+import { PasswordInput } from "@inkjs/ui";
+import { theme } from "@/ui/theme";
+
+/** Asks for the master passphrase of secret values; Enter submits, Esc closes. */
+export function PassphrasePrompt(props: {
+  sealed: number;
+  onSubmit: (passphrase: string) => void;
+  onClose: () => void;
+}) {
+  useInput((_input, key) => {
+    if (key.escape) props.onClose();
+  });
+
+  return (
+    <Box
+      flexDirection="column"
+      borderStyle="round"
+      borderColor={theme.focusBorder}
+      paddingX={1}
+    >
+      <Text bold>Unlock secrets</Text>
+      <Text dimColor>
+        {props.sealed > 0
+          ? `${props.sealed} encrypted value${props.sealed === 1 ? "" : "s"}`
+          : "New secrets will be encrypted with this passphrase"}
+      </Text>
+      <PasswordInput
+        placeholder="Master passphrase"
+        onSubmit={(passphrase) => props.onSubmit(passphrase)}
+      />
+    </Box>
+  );
+}
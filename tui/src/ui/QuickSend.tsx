@@ -0,0 +1,106 @@
+import { useEffect, useState } from "react";
+import { Box, Text, useInput } from "ink";
+import { sendRequestViaRust } from "@/ffi/client";
+import type { FfiResponse } from "@/types";
+import { formatBytes } from "@/ui/ImagePreview";
+import { statusColor, theme } from "@/ui/theme";
+
+// Body lines shown under the status; the rest is summarized.
+const PREVIEW_LINES = 6;
+
+/**
+ * Sends an endpoint as the library has it, its method and URL with the
+ * environment's variables filled in and no space's headers, body or
+ * overrides, and shows the result under it. r sends it again, Esc or Enter
+ * closes. The list should ignore input while it's open.
+ */
+export function QuickSend(props: {
+  request: { method: string; url: string };
+  onClose: () => void;
+}) {
+  const { method, url } = props.request;
+  const [attempt, setAttempt] = useState(0);
+  const [result, setResult] = useState<
+    { response: FfiResponse } | { error: string } | null
+  >(null);
+
+  useEffect(() => {
+    let current = true;
+    setResult(null);
+    sendRequestViaRust({ method, url, headers: [] }).then(
+      (response) => current && setResult({ response }),
+      (e) =>
+        current &&
+        setResult({ error: e instanceof Error ? e.message : String(e) })
+    );
+    return () => {
+      current = false;
+    };
+  }, [method, url, attempt]);
+
+  useInput((input, key) => {
+    if (key.escape || key.return) props.onClose();
+    else if (input === "r" && result) setAttempt((n) => n + 1);
+  });
+
+  const response = result && "response" in result ? result.response : null;
+  const lines = response ? previewLines(response) : [];
+
+  return (
+    <Box
+      flexDirection="column"
+      borderStyle="round"
+      borderColor={theme.focusBorder}
+      paddingX={1}
+      alignSelf="flex-start"
+      minWidth={40}
+    >
+      <Box justifyContent="space-between" gap={2}>
+        <Text bold wrap="truncate-end">
+          {method.toUpperCase()} {url}
+        </Text>
+        <Text dimColor>r again · esc</Text>
+      </Box>
+      {result === null ? (
+        <Text dimColor>Sending…</Text>
+      ) : "error" in result ? (
+        <Text color="red">{result.error}</Text>
+      ) : (
+        <>
+          <Text>
+            <Text bold color={statusColor(result.response.status)}>
+              {result.response.status} {result.response.statusText}
+            </Text>
+            <Text dimColor>
+              {" "}
+              · {result.response.durationMs}ms ·{" "}
+              {formatBytes(result.response.body.length)}
+            </Text>
+          </Text>
+          {lines.slice(0, PREVIEW_LINES).map((line, i) => (
+            <Text key={i} wrap="truncate-end">
+              {line}
+            </Text>
+          ))}
+          {lines.length > PREVIEW_LINES ? (
+            <Text dimColor>… {lines.length - PREVIEW_LINES} more lines</Text>
+          ) : null}
+        </>
+      )}
+    </Box>
+  );
+}
+
+/** The body's first lines, JSON pretty-printed; binary bodies aren't shown. */
+function previewLines(response: FfiResponse): string[] {
+  if (response.bodyBase64) return [];
+  const text = response.body.trim();
+  try {
+    if (text.startsWith("{") || text.startsWith("[")) {
+      return JSON.stringify(JSON.parse(text), null, 2).split("\n");
+    }
+  } catch {
+    // Not JSON after all; shown as it came.
+  }
+  return text === "" ? [] : text.split("\n");
+}
@@ -0,0 +1,82 @@
+import { useMemo } from "react";
+import { Box, Text } from "ink";
+import type { FfiRequest } from "@/types";
+import { previewRequest } from "@/ffi/collection";
+import { cookiesForUrl } from "@/ffi/cookies";
+import { displayValue } from "@/ffi/workspace";
+import { theme } from "@/ui/theme";
+
+// Body lines shown; the rest is summarized.
+const BODY_LINES = 8;
+
+/**
+ * What Send would put on the wire for `request`: method, final URL, every
+ * header (including cookies from the jar) and the body, with placeholders
+ * nothing defines called out.
+ */
+export function RequestPreview(props: { request: FfiRequest }) {
+  const preview = useMemo(() => previewRequest(props.request), [props.request]);
+
+  if ("error" in preview) {
+    return <Text color="red">{preview.error}</Text>;
+  }
+  const { request, unresolved } = preview;
+  const cookies = cookiesForUrl(request.url);
+  const bodyLines = request.body?.content.split("\n") ?? [];
+
+  return (
+    <Box flexDirection="column">
+      <Text wrap="truncate-end">
+        <Text bold color={theme.keyHint}>
+          {request.method}
+        </Text>{" "}
+        {request.url}
+      </Text>
+      {unresolved.length > 0 ? (
+        <Text color="yellow" wrap="truncate-end">
+          Unresolved: {unresolved.map((name) => `{{${name}}}`).join(" ")}
+        </Text>
+      ) : null}
+
+      <Box flexDirection="column" marginTop={1}>
+        <Text dimColor>Headers</Text>
+        {request.headers.length === 0 && cookies.length === 0 ? (
+          <Text dimColor>None</Text>
+        ) : (
+          request.headers.map((h, i) => (
+            <Text key={`${h.key}-${i}`} wrap="truncate-end">
+              <Text color={theme.keyHint}>{h.key}</Text>:{" "}
+              {displayValue(h.value, h.secret)}
+            </Text>
+          ))
+        )}
+        {cookies.length > 0 ? (
+          <Text wrap="truncate-end">
+            <Text color={theme.keyHint}>Cookie</Text>:{" "}
+            {cookies.map((c) => `${c.name}=${c.value}`).join("; ")}
+            <Text dimColor> (from the jar)</Text>
+          </Text>
+        ) : null}
+        {request.auth ? (
+          <Text dimColor>Signed by {request.auth.type} when sent</Text>
+        ) : null}
+      </Box>
+
+      <Box flexDirection="column" marginTop={1}>
+        <Text dimColor>Body</Text>
+        {bodyLines.length === 0 ? (
+          <Text dimColor>None</Text>
+        ) : (
+          bodyLines.slice(0, BODY_LINES).map((line, i) => (
+            <Text key={i} wrap="truncate-end">
+              {line}
+            </Text>
+          ))
+        )}
+        {bodyLines.length > BODY_LINES ? (
+          <Text dimColor>… {bodyLines.length - BODY_LINES} more lines</Text>
+        ) : null}
+      </Box>
+    </Box>
+  );
+}
@@ -0,0 +1,198 @@
+import { useEffect, useMemo, useState } from "react";
+import { Box, Text, useInput } from "ink";
+import { getHistory, type HistoryEntry } from "@/ffi/history";
+import type { FfiResponse } from "@/types";
+import { formatTime } from "@/ui/HistoryPanel";
+import { childPath, entriesOf } from "@/ui/JsonTree";
+import { statusColor } from "@/ui/theme";
+
+type Change = {
+  path: string;
+  kind: "added" | "removed" | "changed";
+  before?: unknown;
+  after?: unknown;
+};
+
+const MARKS: Record<Change["kind"], { mark: string; color: string }> = {
+  added: { mark: "+", color: "green" },
+  removed: { mark: "-", color: "red" },
+  changed: { mark: "~", color: "yellow" },
+};
+
+/**
+ * Fields added, removed or changed from `before` to `after`. Objects are
+ * compared key by key and arrays index by index; anything else, or a
+ * container that became another kind of value, changes as a whole.
+ */
+function diffJson(before: unknown, after: unknown, path = "$"): Change[] {
+  const oldEntries = entriesOf(before);
+  const newEntries = entriesOf(after);
+  if (
+    !oldEntries ||
+    !newEntries ||
+    Array.isArray(before) !== Array.isArray(after)
+  ) {
+    return JSON.stringify(before) === JSON.stringify(after)
+      ? []
+      : [{ path, kind: "changed", before, after }];
+  }
+
+  const changes: Change[] = [];
+  const newByKey = new Map(newEntries);
+  const oldKeys = new Set(oldEntries.map(([key]) => key));
+  for (const [key, value] of oldEntries) {
+    const child = childPath(path, key);
+    if (newByKey.has(key)) {
+      changes.push(...diffJson(value, newByKey.get(key), child));
+    } else {
+      changes.push({ path: child, kind: "removed", before: value });
+    }
+  }
+  for (const [key, value] of newEntries) {
+    if (!oldKeys.has(key)) {
+      const child = childPath(path, key);
+      changes.push({ path: child, kind: "added", after: value });
+    }
+  }
+  return changes;
+}
+
+/** The body as JSON, or as its text when it isn't JSON. */
+function bodyValue(response: FfiResponse): unknown {
+  try {
+    return JSON.parse(response.body);
+  } catch {
+    return response.body;
+  }
+}
+
+function formatValue(value: unknown): string {
+  return value === undefined ? "" : JSON.stringify(value);
+}
+
+/**
+ * The response against the one received before it for the same method and
+ * URL, per the history: status and timing, then the body's added (+),
+ * removed (-) and changed (~) fields side by side. ↑/↓ scroll.
+ */
+export function ResponseDiff(props: {
+  request: { method: string; url: string };
+  response: FfiResponse;
+  /** When it was sent; only history entries from before are compared. */
+  olderThan: string;
+  height: number;
+  isActive: boolean;
+}) {
+  const { method, url } = props.request;
+  const previous = useMemo(
+    (): HistoryEntry | undefined =>
+      getHistory({ method, url, until: props.olderThan, limit: 20 }).find(
+        (e) =>
+          e.request.url === url &&
+          e.request.method.toUpperCase() === method.toUpperCase()
+      ),
+    [method, url, props.olderThan]
+  );
+  const changes = useMemo(
+    () =>
+      previous
+        ? diffJson(bodyValue(previous.response), bodyValue(props.response))
+        : [],
+    [previous, props.response]
+  );
+  const [scrollTop, setScrollTop] = useState(0);
+  // Viewport is the inner height: total minus borders and the two headings.
+  const viewportHeight = Math.max(1, props.height - 5);
+  const maxScroll = Math.max(0, changes.length - viewportHeight);
+
+  useEffect(() => setScrollTop(0), [changes]);
+
+  useInput(
+    (_input, key) => {
+      const step = key.pageUp || key.pageDown ? viewportHeight : 1;
+      if (key.upArrow || key.pageUp) {
+        setScrollTop((top) => Math.max(0, top - step));
+      } else if (key.downArrow || key.pageDown) {
+        setScrollTop((top) => Math.min(maxScroll, top + step));
+      }
+    },
+    { isActive: props.isActive }
+  );
+
+  return (
+    <Box
+      flexDirection="column"
+      borderStyle="round"
+      paddingX={1}
+      paddingY={0}
+      height={props.height}
+    >
+      {!previous ? (
+        <Text dimColor>
+          No earlier response to {method} {url} in the history.
+        </Text>
+      ) : (
+        <>
+          <Box justifyContent="space-between" gap={1}>
+            <Text wrap="truncate-end">
+              <Text bold>vs {formatTime(previous.timestamp)}</Text>
+              <Text dimColor> (history #{previous.id}) </Text>
+              <Text color={statusColor(previous.response.status)}>
+                {previous.response.status}
+              </Text>
+              {" → "}
+              <Text color={statusColor(props.response.status)}>
+                {props.response.status}
+              </Text>
+              <Text dimColor>
+                {" · "}
+                {previous.response.durationMs}ms →{" "}
+                {props.response.durationMs}ms
+              </Text>
+            </Text>
+            <Text dimColor>
+              {changes.length === 0
+                ? "same body"
+                : `${changes.length} change${changes.length === 1 ? "" : "s"}`}
+            </Text>
+          </Box>
+          <Box gap={1}>
+            <Box width="34%">
+              <Text dimColor>Field</Text>
+            </Box>
+            <Box width="33%">
+              <Text dimColor>Previous</Text>
+            </Box>
+            <Text dimColor>This response</Text>
+          </Box>
+          {changes
+            .slice(scrollTop, scrollTop + viewportHeight)
+            .map((change) => (
+              <Box key={change.path} gap={1}>
+                <Box width="34%">
+                  <Text wrap="truncate-end" color={MARKS[change.kind].color}>
+                    {MARKS[change.kind].mark} {change.path}
+                  </Text>
+                </Box>
+                <Box width="33%">
+                  <Text wrap="truncate-end" dimColor>
+                    {formatValue(change.before)}
+                  </Text>
+                </Box>
+                <Box flexGrow={1}>
+                  <Text wrap="truncate-end">{formatValue(change.after)}</Text>
+                </Box>
+              </Box>
+            ))}
+          {changes.length > viewportHeight ? (
+            <Text dimColor>
+              {scrollTop + 1}–
+              {Math.min(scrollTop + viewportHeight, changes.length)} of{" "}
+              {changes.length}
+            </Text>
+          ) : null}
+        </>
+      )}
+    </Box>
+  );
+}
@@ -0,0 +1,153 @@
+import { useMemo, useState } from "react";
+import { Box, Text, useInput } from "ink";
+import { namedType, type GraphqlSchema } from "@/graphql";
+import { theme } from "@/ui/theme";
+
+// Rows shown at once; the list scrolls to keep the selection in view.
+const VISIBLE_ROWS = 12;
+
+type Row = {
+  label: string;
+  /** Type reference shown after the label and opened with Enter. */
+  type?: string;
+  description?: string | null;
+  deprecated?: boolean;
+};
+
+/**
+ * Browse an introspected schema: a type's fields (with arguments), input
+ * fields, enum values or union members. Enter opens the selected item's
+ * type, ← goes back, Tab switches between the query, mutation and
+ * subscription roots.
+ */
+export function SchemaExplorer(props: {
+  schema: GraphqlSchema;
+  onClose: () => void;
+}) {
+  const { schema } = props;
+  const roots = [
+    schema.queryType,
+    schema.mutationType,
+    schema.subscriptionType,
+  ].filter((name): name is string => name !== null);
+  const [path, setPath] = useState<string[]>(roots.slice(0, 1));
+  const [selected, setSelected] = useState(0);
+
+  const current = path.at(-1);
+  const type = schema.types.find((t) => t.name === current);
+  const rows = useMemo<Row[]>(() => {
+    if (!type) return [];
+    if (type.kind === "ENUM") {
+      return type.enumValues.map((value) => ({ label: value }));
+    }
+    if (type.kind === "UNION") {
+      return type.possibleTypes.map((name) => ({ label: name, type: name }));
+    }
+    if (type.kind === "INPUT_OBJECT") {
+      return type.inputFields.map((f) => ({
+        label: f.name,
+        type: f.type,
+        description: f.description,
+      }));
+    }
+    return type.fields.map((f) => ({
+      label:
+        f.args.length > 0
+          ? `${f.name}(${f.args
+              .map((a) => `${a.name}: ${a.type}`)
+              .join(", ")})`
+          : f.name,
+      type: f.type,
+      description: f.description,
+      deprecated: f.deprecated,
+    }));
+  }, [type]);
+
+  const open = (name: string) => {
+    if (!schema.types.some((t) => t.name === name)) return;
+    setPath((p) => [...p, name]);
+    setSelected(0);
+  };
+
+  useInput((_input, key) => {
+    if (key.escape) {
+      props.onClose();
+    } else if (key.upArrow) {
+      setSelected((i) => Math.max(0, i - 1));
+    } else if (key.downArrow) {
+      setSelected((i) => Math.min(rows.length - 1, i + 1));
+    } else if (key.return || key.rightArrow) {
+      const target = rows[selected]?.type;
+      if (target) open(namedType(target));
+    } else if ((key.leftArrow || key.backspace) && path.length > 1) {
+      setPath((p) => p.slice(0, -1));
+      setSelected(0);
+    } else if (key.tab && roots.length > 1) {
+      const next = roots[(roots.indexOf(path[0] ?? "") + 1) % roots.length];
+      setPath([next!]);
+      setSelected(0);
+    }
+  });
+
+  const top = Math.max(
+    0,
+    Math.min(
+      selected - Math.floor(VISIBLE_ROWS / 2),
+      rows.length - VISIBLE_ROWS
+    )
+  );
+  const description = rows[selected]?.description ?? type?.description;
+
+  return (
+    <Box
+      flexDirection="column"
+      borderStyle="round"
+      borderColor={theme.focusBorder}
+      paddingX={1}
+    >
+      <Box justifyContent="space-between">
+        <Text bold>Schema</Text>
+        <Text dimColor>enter open · ← back · tab root · esc</Text>
+      </Box>
+      <Text wrap="truncate-end">
+        {path.join(" › ")}
+        <Text dimColor>
+          {" "}
+          {type ? type.kind.toLowerCase() : "not in schema"}
+        </Text>
+      </Text>
+
+      <Box flexDirection="column" marginTop={1}>
+        {rows.length === 0 ? (
+          <Text dimColor>Nothing to list.</Text>
+        ) : (
+          rows.slice(top, top + VISIBLE_ROWS).map((row, i) => (
+            <Text
+              key={row.label}
+              wrap="truncate-end"
+              inverse={top + i === selected}
+              dimColor={row.deprecated}
+            >
+              {row.label}
+              {row.type && row.type !== row.label ? (
+                <Text color={theme.keyHint}>: {row.type}</Text>
+              ) : null}
+            </Text>
+          ))
+        )}
+        {rows.length > VISIBLE_ROWS ? (
+          <Text dimColor>
+            {selected + 1}/{rows.length}
+          </Text>
+        ) : null}
+      </Box>
+      {description ? (
+        <Box marginTop={1}>
+          <Text dimColor wrap="wrap">
+            {description}
+          </Text>
+        </Box>
+      ) : null}
+    </Box>
+  );
+}
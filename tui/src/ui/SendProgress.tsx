@@ -0,0 +1,35 @@
+import { useEffect, useState } from "react";
+import { Spinner } from "@inkjs/ui";
+import { getStatus } from "@/ffi/status";
+import { formatBytes } from "@/ui/ImagePreview";
+
+// How often the elapsed time and byte count are refreshed.
+const TICK_MS = 100;
+
+/**
+ * Shown while a send is pending: time since `since` and, once the body starts
+ * arriving, the bytes received so far (summed over every request in flight).
+ */
+export function SendProgress(props: { since: number }) {
+  const [now, setNow] = useState(Date.now);
+  const [received, setReceived] = useState(0);
+
+  useEffect(() => {
+    const timer = setInterval(() => {
+      setNow(Date.now());
+      setReceived(getStatus().received);
+    }, TICK_MS);
+    return () => clearInterval(timer);
+  }, []);
+
+  const elapsed = `${((now - props.since) / 1000).toFixed(1)}s`;
+  return (
+    <Spinner
+      label={
+        received > 0
+          ? `Sending… ${elapsed} · ${formatBytes(received)}`
+          : `Sending… ${elapsed}`
+      }
+    />
+  );
+}
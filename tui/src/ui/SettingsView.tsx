@@ -0,0 +1,179 @@
+import { useState } from "react";
+import { Box, Text, useInput } from "ink";
+import { TextInput } from "@inkjs/ui";
+import {
+  getSettings,
+  setSetting,
+  type Setting,
+  type SettingValue,
+} from "@/ffi/settings";
+import { theme, themeNames } from "@/ui/theme";
+
+const LABELS: Record<string, string> = {
+  "http.timeout": "Request timeout (s)",
+  "http.proxy": "Proxy URL",
+  "tls.insecure": "Accept invalid certificates",
+  "tls.ca_file": "Extra CA certificate (PEM)",
+  "theme.mode": "Theme",
+  "history.max_entries": "History: keep entries",
+  "history.max_age_days": "History: keep days",
+  "ui.locale": "Locale",
+};
+
+// Shown for unset values, i.e. what the core does without one.
+const UNSET: Record<string, string> = {
+  "http.timeout": "none",
+  "http.proxy": "from environment",
+  "tls.insecure": "no",
+  "tls.ca_file": "none",
+  "theme.mode": "default",
+  "history.max_entries": "unlimited",
+  "history.max_age_days": "unlimited",
+  "ui.locale": "system",
+};
+
+/**
+ * Settings that config.lua can set, changed here without writing Lua. Values
+ * picked here are saved and win over the config; r goes back to its value.
+ * ↑/↓ pick a setting, Enter toggles or cycles it or edits it in place (Enter
+ * saves, an empty value resets, Esc cancels).
+ */
+export function SettingsView(props: {
+  /** Called after a setting changes, so the app takes it up. */
+  onChange: () => void;
+  onClose: () => void;
+}) {
+  const [settings, setSettings] = useState(getSettings);
+  const [highlighted, setHighlighted] = useState(0);
+  const [editing, setEditing] = useState<string | null>(null);
+  const [error, setError] = useState<string | null>(null);
+  const selected = Math.min(highlighted, Math.max(settings.length - 1, 0));
+  const setting = settings[selected];
+
+  const save = (path: string, value: SettingValue) => {
+    const result = setSetting(path, value);
+    if ("error" in result) {
+      setError(result.error);
+      return false;
+    }
+    setError(null);
+    setSettings(result);
+    props.onChange();
+    return true;
+  };
+
+  const submit = (setting: Setting, text: string) => {
+    const trimmed = text.trim();
+    if (trimmed === "") {
+      if (save(setting.path, null)) setEditing(null);
+      return;
+    }
+    const value = setting.type === "number" ? Number(trimmed) : trimmed;
+    if (typeof value === "number" && !Number.isFinite(value)) {
+      setError(`${LABELS[setting.path] ?? setting.path} must be a number`);
+      return;
+    }
+    if (save(setting.path, value)) setEditing(null);
+  };
+
+  const activate = (setting: Setting) => {
+    setError(null);
+    if (setting.type === "boolean") {
+      save(setting.path, !setting.value);
+    } else if (setting.type === "choice" || setting.path === "theme.mode") {
+      const choices =
+        setting.type === "choice" ? (setting.choices ?? []) : themeNames();
+      const current = choices.indexOf(String(setting.value));
+      const next = choices[(current + 1) % choices.length];
+      if (next !== undefined) save(setting.path, next);
+    } else {
+      setEditing(setting.value === null ? "" : String(setting.value));
+    }
+  };
+
+  useInput((input, key) => {
+    if (editing !== null) {
+      if (key.escape) {
+        setEditing(null);
+        setError(null);
+      } else if (key.return && setting) submit(setting, editing);
+      return;
+    }
+    if (key.escape) {
+      props.onClose();
+    } else if (key.upArrow) {
+      setHighlighted(selected > 0 ? selected - 1 : settings.length - 1);
+    } else if (key.downArrow) {
+      setHighlighted(selected < settings.length - 1 ? selected + 1 : 0);
+    } else if (!setting) {
+      return;
+    } else if (key.return) {
+      activate(setting);
+    } else if (input === "r" && setting.overridden) {
+      save(setting.path, null);
+    }
+  });
+
+  return (
+    <Box
+      flexDirection="column"
+      borderStyle="round"
+      borderColor={theme.focusBorder}
+      paddingX={1}
+    >
+      <Box justifyContent="space-between" gap={1}>
+        <Text bold>Settings</Text>
+        <Text dimColor>
+          {editing !== null
+            ? "enter save · empty resets · esc cancel"
+            : "enter change · r use config.lua · esc"}
+        </Text>
+      </Box>
+      {error ? <Text color="red">{error}</Text> : null}
+
+      <Box flexDirection="column" marginTop={1}>
+        {settings.length === 0 ? (
+          <Text dimColor>Settings aren't available.</Text>
+        ) : null}
+        {settings.map((s, i) => (
+          <Box key={s.path} gap={1}>
+            <Box width={30}>
+              <Text inverse={i === selected} wrap="truncate-end">
+                {LABELS[s.path] ?? s.path}
+              </Text>
+            </Box>
+            <Box flexGrow={1}>
+              {editing !== null && i === selected ? (
+                <TextInput
+                  defaultValue={editing}
+                  placeholder={UNSET[s.path]}
+                  onChange={setEditing}
+                />
+              ) : (
+                <Text wrap="truncate-end" dimColor={s.value === null}>
+                  {formatValue(s)}
+                </Text>
+              )}
+            </Box>
+            <Text
+              color={s.overridden ? theme.keyHint : undefined}
+              dimColor={!s.overridden}
+            >
+              {s.overridden
+                ? "set here"
+                : s.config !== null
+                ? "config.lua"
+                : "default"}
+            </Text>
+          </Box>
+        ))}
+      </Box>
+    </Box>
+  );
+}
+
+function formatValue(setting: Setting): string {
+  if (setting.value === null) return UNSET[setting.path] ?? "unset";
+  if (typeof setting.value === "boolean") return setting.value ? "yes" : "no";
+  return String(setting.value);
+}
@@ -0,0 +1,278 @@
+import { useMemo, useState } from "react";
+import { Box, Text, useInput } from "ink";
+import { TextInput } from "@inkjs/ui";
+import {
+  duplicateWorkspaceItem,
+  removeWorkspaceItem,
+  spaceRequest,
+  updateWorkspaceItem,
+  type Workspace,
+  type WorkspaceSpace,
+} from "@/ffi/workspace";
+import { curlCommand } from "@/ffi/collection";
+import { copyToClipboard } from "@/clipboard";
+import { ContextMenu, type MenuAction } from "@/ui/ContextMenu";
+import { theme } from "@/ui/theme";
+
+/**
+ * The workspace's spaces. ↑/↓ pick one, Enter or e renames it in place
+ * (Enter saves, Esc cancels), d duplicates it, a archives or restores it,
+ * c copies what it sends as curl, x deletes it after a y/n confirmation and
+ * m lists those actions. / filters by name, also in the sidebar; Esc clears
+ * the filter, then closes.
+ */
+export function SpacesPanel(props: {
+  spaces: WorkspaceSpace[];
+  currentId?: string;
+  filter: string;
+  onFilterChange: (filter: string) => void;
+  /** Called after a space is saved, so the workspace is re-read. */
+  onChange: () => void;
+  onClose: () => void;
+}) {
+  const spaces = useMemo(
+    () => filterSpaces(props.spaces, props.filter),
+    [props.spaces, props.filter]
+  );
+  const [highlighted, setHighlighted] = useState(() =>
+    Math.max(0, spaces.findIndex((s) => s.id === props.currentId))
+  );
+  const [filtering, setFiltering] = useState(false);
+  const [renaming, setRenaming] = useState<string | null>(null);
+  const [error, setError] = useState<string | null>(null);
+  const [notice, setNotice] = useState<string | null>(null);
+  // A delete waiting for y.
+  const [confirm, setConfirm] = useState<WorkspaceSpace | null>(null);
+  const [menuOpen, setMenuOpen] = useState(false);
+  const selected = Math.min(highlighted, Math.max(spaces.length - 1, 0));
+  const space = spaces[selected];
+
+  const report = (
+    result: { workspace: Workspace } | { error: string },
+    success: string
+  ): boolean => {
+    if ("error" in result) {
+      setError(result.error);
+      setNotice(null);
+      return false;
+    }
+    setError(null);
+    setNotice(success);
+    props.onChange();
+    return true;
+  };
+
+  const rename = (name: string) => {
+    if (!space) return;
+    const trimmed = name.trim();
+    if (trimmed === "") {
+      setError("A space needs a name");
+      return;
+    }
+    if (trimmed !== space.name) {
+      const result = updateWorkspaceItem("space", space.id, { name: trimmed });
+      if (!report(result, `Renamed to ${trimmed}`)) return;
+    }
+    setRenaming(null);
+  };
+
+  const actions = (space: WorkspaceSpace): MenuAction[] => [
+    {
+      key: "e",
+      label: "Rename",
+      run: () => {
+        setError(null);
+        setNotice(null);
+        setRenaming(space.name);
+      },
+    },
+    {
+      key: "d",
+      label: "Duplicate",
+      run: () =>
+        report(
+          duplicateWorkspaceItem("space", space.id),
+          `Duplicated ${space.name}`
+        ),
+    },
+    {
+      key: "c",
+      label: "Copy as cURL",
+      run: () => {
+        const resolved = spaceRequest(space.id);
+        const curl =
+          "error" in resolved ? resolved : curlCommand(resolved.request);
+        if ("error" in curl) {
+          setError(curl.error);
+          return;
+        }
+        copyToClipboard(curl.command);
+        setError(null);
+        setNotice(`Copied ${space.name} as curl`);
+      },
+    },
+    {
+      key: "a",
+      label: space.archived ? "Restore" : "Archive",
+      run: () => {
+        const archived = !space.archived;
+        report(
+          updateWorkspaceItem("space", space.id, { archived }),
+          `${archived ? "Archived" : "Restored"} ${space.name}`
+        );
+      },
+    },
+    {
+      key: "x",
+      label: "Delete…",
+      destructive: true,
+      run: () => setConfirm(space),
+    },
+  ];
+
+  useInput(
+    (input, key) => {
+      if (confirm) {
+        if (input === "y") {
+          report(
+            removeWorkspaceItem("space", confirm.id),
+            `Deleted ${confirm.name}`
+          );
+        }
+        setConfirm(null);
+        return;
+      }
+      if (renaming !== null) {
+        if (key.escape) {
+          setRenaming(null);
+          setError(null);
+        } else if (key.return) rename(renaming);
+        return;
+      }
+      if (filtering) {
+        if (key.escape || key.return) setFiltering(false);
+        return;
+      }
+      if (key.escape) {
+        if (props.filter) props.onFilterChange("");
+        else props.onClose();
+      } else if (input === "/") {
+        setFiltering(true);
+      } else if (key.upArrow) {
+        setHighlighted(selected > 0 ? selected - 1 : spaces.length - 1);
+      } else if (key.downArrow) {
+        setHighlighted(selected < spaces.length - 1 ? selected + 1 : 0);
+      } else if (!space) {
+        return;
+      } else if (key.return) {
+        actions(space)[0]!.run();
+      } else if (input === "m") {
+        setMenuOpen(true);
+      } else {
+        actions(space)
+          .find((a) => a.key === input)
+          ?.run();
+      }
+    },
+    { isActive: !menuOpen }
+  );
+
+  return (
+    <Box
+      flexDirection="column"
+      borderStyle="round"
+      borderColor={theme.focusBorder}
+      paddingX={1}
+    >
+      <Box justifyContent="space-between" gap={1}>
+        {filtering ? (
+          <Box flexGrow={1}>
+            <Text bold>Filter: </Text>
+            <TextInput
+              defaultValue={props.filter}
+              placeholder="space name"
+              onChange={(next) => {
+                props.onFilterChange(next);
+                setHighlighted(0);
+              }}
+            />
+          </Box>
+        ) : (
+          <Text bold>
+            Spaces
+            {props.filter ? (
+              <>
+                <Text color={theme.focusBorder}> /{props.filter}</Text>
+                <Text dimColor>
+                  {" "}
+                  {spaces.length} of {props.spaces.length}
+                </Text>
+              </>
+            ) : null}
+          </Text>
+        )}
+        <Text dimColor>
+          {renaming !== null
+            ? "enter save · esc cancel"
+            : "enter rename · m actions · / filter · esc"}
+        </Text>
+      </Box>
+      {confirm ? (
+        <Text color="yellow">
+          Delete {confirm.name}? Its history is kept. <Text bold>y</Text>/n
+        </Text>
+      ) : error ? (
+        <Text color="red">{error}</Text>
+      ) : notice ? (
+        <Text color="green">{notice}</Text>
+      ) : null}
+
+      <Box flexDirection="column" marginTop={1}>
+        {spaces.length === 0 ? (
+          <Text dimColor>
+            {props.filter ? "No spaces match the filter." : "No spaces yet."}
+          </Text>
+        ) : null}
+        {spaces.map((s, i) => (
+          <Box key={s.id} flexDirection="column">
+            {renaming !== null && i === selected ? (
+              <Box>
+                <Text color={theme.focusBorder}>› </Text>
+                <TextInput defaultValue={s.name} onChange={setRenaming} />
+              </Box>
+            ) : (
+              <Text
+                wrap="truncate-end"
+                inverse={i === selected}
+                dimColor={s.archived}
+              >
+                <Text color={theme.focusBorder}>
+                  {s.id === props.currentId ? "● " : "  "}
+                </Text>
+                {s.name}
+                {s.archived ? <Text dimColor> (archived)</Text> : null}
+              </Text>
+            )}
+            {menuOpen && i === selected ? (
+              <ContextMenu
+                title={s.name}
+                actions={actions(s)}
+                onClose={() => setMenuOpen(false)}
+              />
+            ) : null}
+          </Box>
+        ))}
+      </Box>
+    </Box>
+  );
+}
+
+/** Spaces whose name contains `filter`, ignoring case. */
+export function filterSpaces(
+  spaces: WorkspaceSpace[],
+  filter: string
+): WorkspaceSpace[] {
+  const needle = filter.trim().toLowerCase();
+  if (needle === "") return spaces;
+  return spaces.filter((s) => s.name.toLowerCase().includes(needle));
+}
@@ -0,0 +1,42 @@
+import { useMemo } from "react";
+import { Text } from "ink";
+import { getHistory } from "@/ffi/history";
+import { statusColor } from "@/ui/theme";
+
+// Sends charted, most recent last.
+const POINTS = 16;
+
+const BARS = "▁▂▃▄▅▆▇█";
+
+/**
+ * Response times of the space's latest sends, one bar each scaled to the
+ * slowest and colored by status class, with that slowest time after it.
+ * `refreshKey` re-reads history, e.g. when a new response arrives.
+ */
+export function ResponseSparkline(props: {
+  space: string;
+  refreshKey?: unknown;
+}) {
+  const entries = useMemo(
+    () => getHistory({ space: props.space, limit: POINTS }).reverse(),
+    // eslint-disable-next-line react-hooks/exhaustive-deps
+    [props.space, props.refreshKey]
+  );
+  if (entries.length < 2) return null;
+
+  const max = Math.max(1, ...entries.map((e) => e.response.durationMs));
+  return (
+    <Text>
+      {entries.map((entry) => {
+        const { status, durationMs } = entry.response;
+        const level = Math.round((durationMs / max) * (BARS.length - 1));
+        return (
+          <Text key={entry.id} color={statusColor(status)}>
+            {BARS[level]}
+          </Text>
+        );
+      })}
+      <Text dimColor> ≤{max}ms</Text>
+    </Text>
+  );
+}
@@ -0,0 +1,75 @@
+import { useEffect, useState, type ReactNode } from "react";
+import { Box, Text } from "ink";
+import { getStatus, type CoreStatus } from "@/ffi/status";
+import { statusColor } from "@/ui/theme";
+
+export type LastResult =
+  | { status: number; statusText: string; durationMs: number }
+  | { error: string };
+
+// How often the core's status is polled.
+const POLL_MS = 1000;
+
+/**
+ * One line at the bottom: requests in flight, the last result, active
+ * profiles and background work (collection runs, scheduled jobs, the mock
+ * server).
+ */
+export function StatusBar(props: { last: LastResult | null }) {
+  const [status, setStatus] = useState<CoreStatus>(getStatus);
+  useEffect(() => {
+    const timer = setInterval(() => setStatus(getStatus()), POLL_MS);
+    return () => clearInterval(timer);
+  }, []);
+
+  const { last } = props;
+  const parts: ReactNode[] = [];
+  if (status.run) {
+    parts.push(
+      <Text key="run" color="cyan">
+        Running {status.run.name || "collection"} {status.run.done}/
+        {status.run.total}
+      </Text>
+    );
+  }
+  if (status.jobs > 0) {
+    parts.push(
+      <Text key="jobs">
+        {status.jobs} scheduled job{status.jobs === 1 ? "" : "s"}
+      </Text>
+    );
+  }
+  if (status.mock) parts.push(<Text key="mock">Mock {status.mock}</Text>);
+
+  return (
+    <Box justifyContent="space-between" paddingX={1} gap={2}>
+      <Box gap={2}>
+        {status.inFlight > 0 ? (
+          <Text color="yellow">● {status.inFlight} in flight</Text>
+        ) : (
+          <Text dimColor>○ idle</Text>
+        )}
+        {last === null ? (
+          <Text dimColor>No requests yet</Text>
+        ) : "error" in last ? (
+          <Text color="red" wrap="truncate-end">
+            Last: {last.error}
+          </Text>
+        ) : (
+          <Text>
+            Last:{" "}
+            <Text color={statusColor(last.status)} bold>
+              {last.status} {last.statusText}
+            </Text>{" "}
+            <Text dimColor>{last.durationMs}ms</Text>
+          </Text>
+        )}
+        {parts}
+      </Box>
+      <Text dimColor>
+        Profile:{" "}
+        {status.profiles.length > 0 ? status.profiles.join(", ") : "default"}
+      </Text>
+    </Box>
+  );
+}
@@ -1,5 +1,9 @@
-import React, { useEffect, useMemo, useState } from "react";
-import { Box, Text, useInput } from "ink";
+import React, { useEffect, useMemo, useRef, useState } from "react";
+import { Box, Text, measureElement, useInput, type DOMElement } from "ink";
+import stringWidth from "string-width";
+import { highlightLine, type Language } from "@/highlight";
+import type { TextMatch } from "@/ui/FindBar";
+import { currentLocale } from "@/locale";
 
 function clamp(n: number, min: number, max: number) {
   return Math.max(min, Math.min(max, n));
@@ -11,6 +15,86 @@ function splitLines(s: string): string[] {
   return parts;
 }
 
+/**
+ * A screen row: a whole line, or one piece of a wrapped one that fits the
+ * width, starting at index `start` of the line.
+ */
+type Row = { line: number; start: number; text: string; continued: boolean };
+
+// Printable ASCII takes one column per character, so it can be cut by length.
+const PLAIN_ASCII = /^[\x20-\x7e]*$/;
+
+const graphemes = new Intl.Segmenter();
+
+/**
+ * Split lines into rows at most `width` terminal columns wide. Wide (CJK,
+ * emoji) characters count as two columns and are never split.
+ */
+function wrapLines(lines: string[], width: number): Row[] {
+  const rows: Row[] = [];
+  lines.forEach((text, line) => {
+    const plain = PLAIN_ASCII.test(text);
+    if (width <= 0 || (plain && text.length <= width)) {
+      rows.push({ line, start: 0, text, continued: false });
+      return;
+    }
+    if (plain) {
+      for (let start = 0; start < text.length; start += width) {
+        rows.push({
+          line,
+          start,
+          text: text.slice(start, start + width),
+          continued: start > 0,
+        });
+      }
+      return;
+    }
+    let start = 0;
+    let columns = 0;
+    for (const { segment, index } of graphemes.segment(text)) {
+      const segmentWidth = stringWidth(segment);
+      if (columns + segmentWidth > width && index > start) {
+        rows.push({
+          line,
+          start,
+          text: text.slice(start, index),
+          continued: start > 0,
+        });
+        start = index;
+        columns = 0;
+      }
+      columns += segmentWidth;
+    }
+    rows.push({ line, start, text: text.slice(start), continued: start > 0 });
+  });
+  return rows;
+}
+
+/** Split a row into runs outside and inside matches (`current` is the selected one). */
+function markMatches(
+  row: Row,
+  matches: Array<{ match: TextMatch; current: boolean }>
+): Array<{ text: string; mark?: "match" | "current" }> {
+  const parts: Array<{ text: string; mark?: "match" | "current" }> = [];
+  const end = row.start + row.text.length;
+  let col = row.start;
+  for (const { match, current } of matches) {
+    const from = Math.max(match.start, col);
+    const to = Math.min(match.end, end);
+    if (to <= from) continue;
+    if (from > col) {
+      parts.push({ text: row.text.slice(col - row.start, from - row.start) });
+    }
+    parts.push({
+      text: row.text.slice(from - row.start, to - row.start),
+      mark: current ? "current" : "match",
+    });
+    col = to;
+  }
+  if (col < end) parts.push({ text: row.text.slice(col - row.start) });
+  return parts;
+}
+
 function indexToLineCol(
   s: string,
   index: number
@@ -71,6 +155,38 @@ export function TextArea(props: {
   height: number;
   isActive: boolean;
   readOnly?: boolean;
+  /** Called when edit mode is entered or left. */
+  onEditingChange?: (editing: boolean) => void;
+  /** Scroll position to start at, e.g. one reported by `onScrollTopChange`. */
+  initialScrollTop?: number;
+  onScrollTopChange?: (top: number) => void;
+  /**
+   * Soft-wrap long lines outside edit mode so nothing is cut off at the edge.
+   * Only the rows in view are rendered either way, so any size scrolls.
+   */
+  wrap?: boolean;
+  /** Color the text outside edit mode; see `languageFor`. */
+  language?: Language | null;
+  /** Find results to highlight outside edit mode; see `findMatches`. */
+  matches?: TextMatch[];
+  /** Index into `matches` to mark and scroll into view. */
+  currentMatch?: number;
+  /** Checked as the text changes; what it finds is shown in the footer. */
+  problem?: (
+    text: string
+  ) => { line: number; col: number; message: string } | null;
+  /** Rewrites the text when `f` is pressed outside edit mode; null keeps it. */
+  format?: (text: string) => string | null;
+  /** Index of the bracket pairing with one at `index`, marked while editing. */
+  matchBracket?: (text: string, index: number) => number | null;
+  /**
+   * Words that may go at `index` while editing, replacing the text from
+   * `from`; listed in the footer, ctrl+a inserts the first.
+   */
+  complete?: (
+    text: string,
+    index: number
+  ) => { from: number; items: string[] } | null;
 }) {
   const readOnly = props.readOnly ?? false;
 
@@ -78,7 +194,17 @@ export function TextArea(props: {
   const [isEditing, setIsEditing] = useState(false);
   const [draft, setDraft] = useState(props.value);
   const [cursor, setCursor] = useState(0);
-  const [scrollTop, setScrollTop] = useState(0);
+  const [scrollTop, setScrollTop] = useState(props.initialScrollTop ?? 0);
+
+  useEffect(() => {
+    props.onScrollTopChange?.(scrollTop);
+    // eslint-disable-next-line react-hooks/exhaustive-deps
+  }, [scrollTop]);
+
+  useEffect(() => {
+    props.onEditingChange?.(isEditing);
+    // eslint-disable-next-line react-hooks/exhaustive-deps
+  }, [isEditing]);
 
   // Keep draft in sync when not editing
   useEffect(() => {
@@ -94,10 +220,66 @@ export function TextArea(props: {
     [draft, props.value, isEditing]
   );
 
+  // Content width, measured after layout, for wrapping.
+  const contentRef = useRef<DOMElement>(null);
+  const [contentWidth, setContentWidth] = useState(0);
+  useEffect(() => {
+    if (!contentRef.current) return;
+    const { width } = measureElement(contentRef.current);
+    if (width !== contentWidth) setContentWidth(width);
+  });
+
+  const gutter = Math.max(3, String(lines.length).length);
+  const rows = useMemo(
+    () =>
+      props.wrap && !isEditing
+        ? wrapLines(lines, contentWidth - gutter - 1)
+        : lines.map((text, line) => ({
+            line,
+            start: 0,
+            text,
+            continued: false,
+          })),
+    [lines, props.wrap, isEditing, contentWidth, gutter]
+  );
+
   // Viewport is the inner content height:
   // total height - top border - bottom border - header - footer
   const viewportHeight = Math.max(1, props.height - 4);
 
+  const matchesByLine = useMemo(() => {
+    const byLine = new Map<
+      number,
+      Array<{ match: TextMatch; current: boolean }>
+    >();
+    (props.matches ?? []).forEach((match, i) => {
+      const list = byLine.get(match.line) ?? [];
+      list.push({ match, current: i === props.currentMatch });
+      byLine.set(match.line, list);
+    });
+    return byLine;
+  }, [props.matches, props.currentMatch]);
+
+  // Bring the current match to the middle of the view.
+  useEffect(() => {
+    const match = props.matches?.[props.currentMatch ?? -1];
+    if (!match || isEditing) return;
+    const row = rows.findIndex(
+      (r) =>
+        r.line === match.line &&
+        match.start < r.start + Math.max(1, r.text.length)
+    );
+    if (row < 0) return;
+    const top = row - Math.floor(viewportHeight / 2);
+    setScrollTop(clamp(top, 0, Math.max(0, rows.length - viewportHeight)));
+  }, [props.matches, props.currentMatch, rows, viewportHeight, isEditing]);
+
+  const completion = useMemo(
+    () => (isEditing ? props.complete?.(draft, cursor) ?? null : null),
+    // eslint-disable-next-line react-hooks/exhaustive-deps
+    [isEditing, draft, cursor, props.complete]
+  );
+
   const ensureCursorVisible = (nextCursor: number, nextDraft: string) => {
     const { line } = indexToLineCol(nextDraft, nextCursor);
     const top = scrollTop;
@@ -131,6 +313,17 @@ export function TextArea(props: {
           setDraft(props.value);
           return;
         }
+        if (key.ctrl && input === "a") {
+          const word = completion?.items[0];
+          if (!completion || word === undefined) return;
+          const next =
+            draft.slice(0, completion.from) + word + draft.slice(cursor);
+          const nextIdx = completion.from + word.length;
+          setDraft(next);
+          setCursor(nextIdx);
+          ensureCursorVisible(nextIdx, next);
+          return;
+        }
 
         // Navigation
         if (key.leftArrow) {
@@ -202,18 +395,18 @@ export function TextArea(props: {
       // View mode (read-only scrolling)
       if (key.upArrow) {
         setScrollTop((t) =>
-          clamp(t - 1, 0, Math.max(0, lines.length - viewportHeight))
+          clamp(t - 1, 0, Math.max(0, rows.length - viewportHeight))
         );
       } else if (key.downArrow) {
         setScrollTop((t) =>
-          clamp(t + 1, 0, Math.max(0, lines.length - viewportHeight))
+          clamp(t + 1, 0, Math.max(0, rows.length - viewportHeight))
         );
       } else if (key.pageUp) {
         setScrollTop((t) =>
           clamp(
             t - viewportHeight,
             0,
-            Math.max(0, lines.length - viewportHeight)
+            Math.max(0, rows.length - viewportHeight)
           )
         );
       } else if (key.pageDown) {
@@ -221,22 +414,27 @@ export function TextArea(props: {
           clamp(
             t + viewportHeight,
             0,
-            Math.max(0, lines.length - viewportHeight)
+            Math.max(0, rows.length - viewportHeight)
           )
         );
       } else if (!readOnly && input === "e") {
         setIsEditing(true);
         setDraft(props.value);
+      } else if (!readOnly && input === "f" && props.format) {
+        const formatted = props.format(props.value);
+        if (formatted !== null) props.onChange?.(formatted);
       }
     },
     { isActive: props.isActive }
   );
 
-  const displayed = lines.slice(scrollTop, scrollTop + viewportHeight);
+  const displayed = rows.slice(scrollTop, scrollTop + viewportHeight);
   const headerRight = readOnly
     ? "read-only"
     : isEditing
     ? "editing (ctrl+s apply, esc cancel)"
+    : props.format
+    ? "view (e edit, f format)"
     : "view (press e to edit)";
 
   // Cursor rendering: show a block at cursor position on the active line.
@@ -244,6 +442,24 @@ export function TextArea(props: {
     ? indexToLineCol(draft, cursor)
     : { line: -1, col: -1 };
 
+  const text = isEditing ? draft : props.value;
+  const problem = useMemo(
+    () => props.problem?.(text) ?? null,
+    // eslint-disable-next-line react-hooks/exhaustive-deps
+    [text, props.problem]
+  );
+
+  // The bracket at (or just before) the cursor and its partner.
+  const bracketPos = useMemo(() => {
+    if (!isEditing || !props.matchBracket) return null;
+    for (const at of [cursor, cursor - 1]) {
+      const partner = at >= 0 ? props.matchBracket(draft, at) : null;
+      if (partner !== null) return indexToLineCol(draft, partner);
+    }
+    return null;
+    // eslint-disable-next-line react-hooks/exhaustive-deps
+  }, [isEditing, draft, cursor, props.matchBracket]);
+
   return (
     <Box
       flexDirection="column"
@@ -260,44 +476,121 @@ export function TextArea(props: {
         </Text>
       </Box>
 
-      <Box flexDirection="column">
-        {displayed.map((line, i) => {
-          const lineIndex = scrollTop + i;
+      <Box flexDirection="column" ref={contentRef}>
+        {displayed.map((row, i) => {
+          const line = row.text;
+          const lineIndex = row.line;
           const isCursorLine = isEditing && lineIndex === cursorPos.line;
+          const bracketCol =
+            bracketPos?.line === lineIndex ? bracketPos.col : -1;
+          const number = row.continued
+            ? " ".repeat(gutter)
+            : String(lineIndex + 1).padStart(gutter, " ");
+          const gutterText =
+            problem?.line === lineIndex + 1 && !row.continued ? (
+              <Text color="red">{number} </Text>
+            ) : (
+              <Text dimColor>{number} </Text>
+            );
 
           if (!isCursorLine) {
             return (
-              <Text key={`l-${lineIndex}`} wrap="truncate-end">
-                <Text dimColor>{String(lineIndex + 1).padStart(3, " ")} </Text>
-                {line.length === 0 ? <Text dimColor>·</Text> : line}
+              <Text key={`r-${scrollTop + i}`} wrap="truncate-end">
+                {gutterText}
+                {bracketCol >= 0 ? (
+                  <>
+                    {line.slice(0, bracketCol)}
+                    <Text backgroundColor="gray">{line[bracketCol]}</Text>
+                    {line.slice(bracketCol + 1)}
+                  </>
+                ) : line.length === 0 ? (
+                  <Text dimColor>·</Text>
+                ) : matchesByLine.has(lineIndex) && !isEditing ? (
+                  markMatches(row, matchesByLine.get(lineIndex)!).map(
+                    (part, j) => (
+                      <Text
+                        key={j}
+                        color={part.mark === "current" ? "black" : undefined}
+                        backgroundColor={
+                          part.mark === "current"
+                            ? "yellow"
+                            : part.mark
+                            ? "gray"
+                            : undefined
+                        }
+                      >
+                        {part.text}
+                      </Text>
+                    )
+                  )
+                ) : props.language && !isEditing ? (
+                  highlightLine(line, props.language).map((token, j) => (
+                    <Text
+                      key={j}
+                      color={token.color}
+                      dimColor={token.dim}
+                      bold={token.bold}
+                    >
+                      {token.text}
+                    </Text>
+                  ))
+                ) : (
+                  line
+                )}
               </Text>
             );
           }
 
           const col = clamp(cursorPos.col, 0, line.length);
-          const before = line.slice(0, col);
-          const ch = col < line.length ? line[col] : " ";
-          const after = col < line.length ? line.slice(col + 1) : "";
+          // Marked columns in order: the cursor and a matching bracket.
+          const marks = [col, bracketCol]
+            .filter((c, j, all) => c >= 0 && all.indexOf(c) === j)
+            .sort((a, b) => a - b);
+          let from = 0;
 
           return (
-            <Text key={`l-${lineIndex}`} wrap="truncate-end">
-              <Text dimColor>{String(lineIndex + 1).padStart(3, " ")} </Text>
-              {before}
-              <Text inverse>{ch}</Text>
-              {after}
+            <Text key={`r-${scrollTop + i}`} wrap="truncate-end">
+              {gutterText}
+              {marks.map((c) => {
+                const before = line.slice(from, c);
+                from = c + 1;
+                return (
+                  <React.Fragment key={c}>
+                    {before}
+                    <Text
+                      inverse={c === col}
+                      backgroundColor={c === col ? undefined : "gray"}
+                    >
+                      {line[c] ?? " "}
+                    </Text>
+                  </React.Fragment>
+                );
+              })}
+              {line.slice(from)}
             </Text>
           );
         })}
       </Box>
 
       <Box justifyContent="space-between">
+        {completion ? (
+          <Text wrap="truncate-end">
+            <Text dimColor>^a </Text>
+            <Text bold>{completion.items[0]}</Text>
+            <Text dimColor> {completion.items.slice(1, 6).join(" ")}</Text>
+          </Text>
+        ) : problem ? (
+          <Text color="red" wrap="truncate-end">
+            Ln {problem.line}, Col {problem.col}: {problem.message}
+          </Text>
+        ) : (
+          <Text dimColor>
+            {(displayed[0]?.line ?? 0) + 1}/{lines.length}
+          </Text>
+        )}
         <Text dimColor>
-          {scrollTop + 1}:{Math.max(1, displayed.length)}
-        </Text>
-        <Text dimColor>
-          {Math.min(
-            lines.join("\n").length,
-            isEditing ? draft.length : props.value.length
+          {(isEditing ? draft.length : props.value.length).toLocaleString(
+            currentLocale()
           )}{" "}
           chars
         </Text>
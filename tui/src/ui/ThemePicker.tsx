@@ -0,0 +1,100 @@
+import { useState } from "react";
+import { Box, Text, useInput } from "ink";
+import {
+  applyTheme,
+  currentThemeName,
+  isConfigTheme,
+  theme,
+  themeNames,
+  themePalette,
+} from "@/ui/theme";
+
+/**
+ * Built-in themes and those registered in config.lua. ↑/↓ preview one,
+ * Enter keeps it and Esc goes back to the theme that was active on open.
+ */
+export function ThemePicker(props: {
+  /** Called with each theme applied, so the app re-renders with it. */
+  onApply: (name: string) => void;
+  onClose: () => void;
+}) {
+  const [original] = useState(currentThemeName);
+  // Built-ins first, then the config's, each in registration order.
+  const [names] = useState(() => {
+    const all = themeNames();
+    return [
+      ...all.filter((n) => !isConfigTheme(n)),
+      ...all.filter((n) => isConfigTheme(n)),
+    ];
+  });
+  const [highlighted, setHighlighted] = useState(() =>
+    Math.max(0, names.indexOf(original))
+  );
+
+  const preview = (index: number) => {
+    const name = names[index];
+    if (!name) return;
+    setHighlighted(index);
+    applyTheme(name);
+    props.onApply(name);
+  };
+
+  useInput((_input, key) => {
+    if (key.escape) {
+      applyTheme(original);
+      props.onApply(original);
+      props.onClose();
+    } else if (key.return) {
+      props.onClose();
+    } else if (key.upArrow) {
+      preview(highlighted > 0 ? highlighted - 1 : names.length - 1);
+    } else if (key.downArrow) {
+      preview(highlighted < names.length - 1 ? highlighted + 1 : 0);
+    }
+  });
+
+  const firstConfig = names.findIndex((n) => isConfigTheme(n));
+
+  return (
+    <Box
+      flexDirection="column"
+      borderStyle="round"
+      borderColor={theme.focusBorder}
+      paddingX={1}
+    >
+      <Box justifyContent="space-between">
+        <Text bold>Theme</Text>
+        <Text dimColor>↑/↓ preview · enter keep · esc revert</Text>
+      </Box>
+      <Box flexDirection="column" marginTop={1}>
+        {names.map((name, i) => {
+          const palette = themePalette(name);
+          return (
+            <Box key={name} flexDirection="column">
+              {i === firstConfig ? (
+                <Text dimColor>From config</Text>
+              ) : i === 0 ? (
+                <Text dimColor>Built-in</Text>
+              ) : null}
+              <Text inverse={i === highlighted}>
+                <Text color={theme.focusBorder}>
+                  {name === original ? "● " : "  "}
+                </Text>
+                {palette
+                  ? [palette.c1, palette.c2, palette.c3, palette.c4].map(
+                      (color, slot) => (
+                        <Text key={slot} color={color}>
+                          ■
+                        </Text>
+                      )
+                    )
+                  : null}{" "}
+                {name}
+              </Text>
+            </Box>
+          );
+        })}
+      </Box>
+    </Box>
+  );
+}
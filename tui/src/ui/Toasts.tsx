@@ -0,0 +1,61 @@
+import { useCallback, useEffect, useRef, useState } from "react";
+import { Box } from "ink";
+import { StatusMessage } from "@inkjs/ui";
+
+export type Toast = {
+  variant: "success" | "error" | "info";
+  text: string;
+};
+
+// How long a toast stays up; errors linger so they can be read.
+const TOAST_MS: Record<Toast["variant"], number> = {
+  success: 3000,
+  info: 3000,
+  error: 8000,
+};
+// Older toasts are dropped once this many are up.
+const MAX_TOASTS = 3;
+
+/**
+ * Short-lived messages for things that just happened, e.g. a failed send or
+ * an import. `notify` shows one and it goes away on its own; `clear` drops
+ * them all.
+ */
+export function useToasts() {
+  const [toasts, setToasts] = useState<Array<Toast & { id: number }>>([]);
+  const nextId = useRef(1);
+  const timers = useRef(new Set<ReturnType<typeof setTimeout>>());
+
+  useEffect(() => {
+    const pending = timers.current;
+    return () => pending.forEach(clearTimeout);
+  }, []);
+
+  const notify = useCallback((toast: Toast) => {
+    const id = nextId.current++;
+    setToasts((shown) => [...shown, { ...toast, id }].slice(-MAX_TOASTS));
+    const timer = setTimeout(() => {
+      timers.current.delete(timer);
+      setToasts((shown) => shown.filter((t) => t.id !== id));
+    }, TOAST_MS[toast.variant]);
+    timers.current.add(timer);
+  }, []);
+
+  const clear = useCallback(() => setToasts([]), []);
+
+  return { toasts, notify, clear };
+}
+
+/** The toasts from `useToasts`, oldest first. */
+export function Toasts(props: { toasts: Array<Toast & { id: number }> }) {
+  if (props.toasts.length === 0) return null;
+  return (
+    <Box flexDirection="column">
+      {props.toasts.map((toast) => (
+        <StatusMessage key={toast.id} variant={toast.variant}>
+          {toast.text}
+        </StatusMessage>
+      ))}
+    </Box>
+  );
+}
@@ -0,0 +1,74 @@
+import { useMemo } from "react";
+import { Box, Text } from "ink";
+import type { FfiRequest } from "@/types";
+import { previewRequest } from "@/ffi/collection";
+
+// Resolved values are cut to this many characters.
+const MAX_VALUE = 24;
+
+const PLACEHOLDER = /\{\{\s*(.+?)\s*\}\}/g;
+
+/** Names of the `{{placeholders}}` in the URL, header values and body. */
+export function placeholderNames(request: FfiRequest): string[] {
+  const texts = [
+    request.url,
+    ...request.headers.map((h) => h.value),
+    request.body?.content ?? "",
+  ];
+  const names = new Set<string>();
+  for (const text of texts) {
+    for (const match of text.matchAll(PLACEHOLDER)) names.add(match[1]!);
+  }
+  return [...names];
+}
+
+/**
+ * The request's placeholders as chips: green with the value they resolve to,
+ * cyan for secrets (never shown) and yellow when nothing defines them.
+ */
+export function VariableChips(props: { request: FfiRequest }) {
+  const names = useMemo(() => placeholderNames(props.request), [props.request]);
+  const preview = useMemo(
+    () => (names.length > 0 ? previewRequest(props.request) : null),
+    [names, props.request]
+  );
+  if (!preview) return null;
+  if ("error" in preview) {
+    return (
+      <Text color="red" wrap="truncate-end">
+        {preview.error}
+      </Text>
+    );
+  }
+
+  return (
+    <Box gap={1} flexWrap="wrap">
+      {names.map((name) => {
+        const value = preview.resolved[name];
+        if (name.startsWith("secret:")) {
+          return (
+            <Text key={name} color="cyan">
+              [{name}]
+            </Text>
+          );
+        }
+        if (value === undefined) {
+          return (
+            <Text key={name} color="yellow">
+              [⚠ {name}]
+            </Text>
+          );
+        }
+        const shown =
+          value.length > MAX_VALUE
+            ? `${value.slice(0, MAX_VALUE - 1)}…`
+            : value;
+        return (
+          <Text key={name} color="green">
+            [{name} <Text dimColor>= {shown}</Text>]
+          </Text>
+        );
+      })}
+    </Box>
+  );
+}
@@ -29,21 +29,152 @@ export const palettes = {
 
 export type PaletteName = keyof typeof palettes;
 
-// Global selection (change here to switch theme everywhere)
-export const currentPaletteName: PaletteName = "sage";
+/**
+ * Colors a Lua theme (`pigeon.theme.register`) may set. Missing palette slots
+ * fall back to the default palette, missing tokens to their palette slot.
+ */
+export type ThemeDefinition = Partial<Palette> & {
+  focus_border?: string;
+  key_hint?: string;
+  tab_active_underline?: string;
+  tab_dot?: string;
+  border_idle?: string;
+  text_dim?: string;
+  status?: {
+    success?: string;
+    redirect?: string;
+    client_error?: string;
+    server_error?: string;
+    network_error?: string;
+  };
+};
+
+// Default selection; config.lua can pick another with `pigeon.theme.use(name)`.
+export const defaultPaletteName: PaletteName = "sage";
+
+// Full themes shipped alongside the palettes, for light and dark terminals.
+const builtinThemes: Record<string, ThemeDefinition> = {
+  dark: {
+    c1: "#61AFEF",
+    c2: "#E5C07B",
+    c3: "#98C379",
+    c4: "#C678DD",
+  },
+  light: {
+    c1: "#005F87",
+    c2: "#875F00",
+    c3: "#005F00",
+    c4: "#870087",
+    border_idle: "#8A8A8A",
+    text_dim: "#6C6C6C",
+  },
+  solarized: {
+    c1: "#268BD2",
+    c2: "#B58900",
+    c3: "#859900",
+    c4: "#D33682",
+    border_idle: "#586E75",
+    text_dim: "#657B83",
+  },
+  "high-contrast": {
+    c1: "#FFFF00",
+    c2: "#FFFFFF",
+    c3: "#00FFFF",
+    c4: "#FF00FF",
+    border_idle: "white",
+    text_dim: "white",
+  },
+};
+
+const themes: Record<string, ThemeDefinition> = {
+  ...palettes,
+  ...builtinThemes,
+};
+// Names registered by the Lua config, listed apart in the theme picker.
+const configThemeNames = new Set<string>();
+let currentName: string = defaultPaletteName;
+
+function tokensFor(def: ThemeDefinition) {
+  const base = palettes[defaultPaletteName];
+  const palette: Palette = {
+    c1: def.c1 ?? base.c1,
+    c2: def.c2 ?? base.c2,
+    c3: def.c3 ?? base.c3,
+    c4: def.c4 ?? base.c4,
+  };
+
+  return {
+    palette,
+
+    // UI tokens
+    focusBorder: def.focus_border ?? palette.c1,
+    keyHint: def.key_hint ?? palette.c1,
+    tabActiveUnderline: def.tab_active_underline ?? palette.c1,
+    tabDot: def.tab_dot ?? palette.c4,
+
+    borderIdle: def.border_idle ?? "gray",
+    textDim: def.text_dim ?? "gray",
+
+    // Response status classes
+    statusSuccess: def.status?.success ?? "green",
+    statusRedirect: def.status?.redirect ?? "blue",
+    statusClientError: def.status?.client_error ?? "#FF8700",
+    statusServerError: def.status?.server_error ?? "red",
+    statusNetworkError: def.status?.network_error ?? "gray",
+  };
+}
+
+// Semantic tokens (components should use these, not raw palette slots).
+// `applyTheme` updates them in place, so read them at render time.
+export const theme = tokensFor(palettes[defaultPaletteName]);
+
+/** Badge color for a response status: by class, or a transport failure (0). */
+export function statusColor(status: number): string {
+  if (status === 0) return theme.statusNetworkError;
+  if (status < 300) return theme.statusSuccess;
+  if (status < 400) return theme.statusRedirect;
+  if (status < 500) return theme.statusClientError;
+  return theme.statusServerError;
+}
+
+/** Add themes declared in the Lua config; a name clash replaces the built-in. */
+export function registerThemes(defs: Record<string, ThemeDefinition>): void {
+  Object.assign(themes, defs);
+  for (const name of Object.keys(defs)) configThemeNames.add(name);
+}
+
+/** Whether `name` came from the config rather than shipping with pigeon. */
+export function isConfigTheme(name: string): boolean {
+  return configThemeNames.has(name);
+}
+
+/** The palette slots `name` resolves to, e.g. for swatches in a picker. */
+export function themePalette(name: string): Palette | undefined {
+  const def = themes[name];
+  return def ? tokensFor(def).palette : undefined;
+}
 
-export const currentPalette: Palette = palettes[currentPaletteName];
+export function themeNames(): string[] {
+  return Object.keys(themes);
+}
 
-// Semantic tokens (components should use these, not raw palette slots)
-export const theme = {
-  palette: currentPalette,
+export function currentThemeName(): string {
+  return currentName;
+}
 
-  // UI tokens
-  focusBorder: currentPalette.c1,
-  keyHint: currentPalette.c1,
-  tabActiveUnderline: currentPalette.c1,
-  tabDot: currentPalette.c4,
+/** Switch every component to `name`. Returns false for unknown themes. */
+export function applyTheme(name: string): boolean {
+  const def = themes[name];
+  if (!def) return false;
+  Object.assign(theme, tokensFor(def));
+  currentName = name;
+  return true;
+}
 
-  borderIdle: "gray",
-  textDim: "gray",
-} as const;
+/** Move to the next theme in registration order, wrapping around. */
+export function cycleTheme(): string {
+  const names = themeNames();
+  const next = names[(names.indexOf(currentName) + 1) % names.length];
+  if (next) applyTheme(next);
+  return currentName;
+}
@@ -0,0 +1,102 @@
+type Token =
+  | { kind: "open"; text: string; name: string }
+  | { kind: "close"; text: string; name: string }
+  | { kind: "leaf"; text: string }
+  | { kind: "text"; text: string };
+
+/** End of the tag starting at `from`, skipping `>` inside quoted attribute values. */
+function tagEnd(xml: string, from: number): number {
+  let quote: string | null = null;
+  for (let i = from + 1; i < xml.length; i++) {
+    const ch = xml[i];
+    if (quote) {
+      if (ch === quote) quote = null;
+    } else if (ch === '"' || ch === "'") quote = ch;
+    else if (ch === ">") return i;
+  }
+  return -1;
+}
+
+function tokenize(xml: string): Token[] | null {
+  const tokens: Token[] = [];
+  let pos = 0;
+  while (pos < xml.length) {
+    const lt = xml.indexOf("<", pos);
+    if (lt < 0 || lt > pos) {
+      const text = xml.slice(pos, lt < 0 ? undefined : lt).trim();
+      if (text) tokens.push({ kind: "text", text });
+      if (lt < 0) break;
+    }
+    const special = [
+      ["<!--", "-->"],
+      ["<![CDATA[", "]]>"],
+      ["<?", "?>"],
+    ].find(([open]) => xml.startsWith(open!, lt));
+    let end: number;
+    if (special) {
+      end = xml.indexOf(special[1]!, lt);
+      if (end < 0) return null;
+      end += special[1]!.length - 1;
+    } else {
+      end = tagEnd(xml, lt);
+      if (end < 0) return null;
+    }
+    const raw = xml.slice(lt, end + 1);
+    // Attributes split over lines are joined; comments and CDATA stay as-is.
+    const text = special ? raw : raw.replace(/\s*\n\s*/g, " ");
+    const name = /^<\/?([^\s/>]+)/.exec(text)?.[1] ?? "";
+    if (special || text.startsWith("<!") || text.endsWith("/>")) {
+      tokens.push({ kind: "leaf", text });
+    } else if (text.startsWith("</")) {
+      tokens.push({ kind: "close", text, name });
+    } else {
+      tokens.push({ kind: "open", text, name });
+    }
+    pos = end + 1;
+  }
+  return tokens;
+}
+
+/**
+ * Indent an XML document two spaces per level. Elements holding only text
+ * stay on one line. Null when the input isn't well-formed enough to indent
+ * safely (unclosed or mismatched tags).
+ */
+export function formatXml(xml: string): string | null {
+  const tokens = tokenize(xml);
+  if (!tokens || !tokens.some((t) => t.kind === "open" || t.kind === "leaf")) {
+    return null;
+  }
+  const out: string[] = [];
+  const open: string[] = [];
+  const indent = () => "  ".repeat(open.length);
+  for (let i = 0; i < tokens.length; i++) {
+    const token = tokens[i]!;
+    if (token.kind === "open") {
+      const next = tokens[i + 1];
+      const after = tokens[i + 2];
+      if (
+        next?.kind === "text" &&
+        after?.kind === "close" &&
+        after.name === token.name
+      ) {
+        out.push(indent() + token.text + next.text + after.text);
+        i += 2;
+        continue;
+      }
+      if (next?.kind === "close" && next.name === token.name) {
+        out.push(indent() + token.text + next.text);
+        i += 1;
+        continue;
+      }
+      out.push(indent() + token.text);
+      open.push(token.name);
+    } else if (token.kind === "close") {
+      if (open.pop() !== token.name) return null;
+      out.push(indent() + token.text);
+    } else {
+      out.push(indent() + token.text);
+    }
+  }
+  return open.length === 0 ? out.join("\n") : null;
+}